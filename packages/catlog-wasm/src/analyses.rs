@@ -0,0 +1,154 @@
+//! Wasm bindings for analyses of models.
+
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use uuid::Uuid;
+use wasm_bindgen::prelude::*;
+
+use super::model::{DblModel, Ob};
+use catlog::stdlib::analyses::signed::{signed_adjacency_matrix, Sign, SignMatrix};
+
+/// Nonzero entry of a signed adjacency matrix.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct SignEntry {
+    /// Index of the object affected.
+    pub row: usize,
+
+    /// Index of the object having the effect.
+    pub col: usize,
+
+    /// Sign of the effect.
+    pub sign: Sign,
+}
+
+/// Signed adjacency matrix of a model, in sparse form.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct SignedAdjacency {
+    /// Objects indexing the rows and columns, in order.
+    pub objects: Vec<Uuid>,
+
+    /// Nonzero entries of the matrix.
+    pub entries: Vec<SignEntry>,
+}
+
+/// Qualitative response of an object to a press perturbation.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct PressResponse {
+    /// Object responding.
+    pub ob: Uuid,
+
+    /// Sign of the response.
+    pub sign: Sign,
+}
+
+impl DblModel {
+    fn sign_matrix(&self) -> Result<SignMatrix<Uuid>, String> {
+        signed_adjacency_matrix(self.checked_model()?).map_err(|err| err.to_string())
+    }
+}
+
+#[wasm_bindgen]
+impl DblModel {
+    /// Signed adjacency matrix of a model of the theory of signed categories.
+    #[wasm_bindgen(js_name = "signedAdjacencyMatrix")]
+    pub fn signed_adjacency_matrix(&self) -> Result<SignedAdjacency, String> {
+        let matrix = self.sign_matrix()?;
+        Ok(SignedAdjacency {
+            objects: matrix.objects().to_vec(),
+            entries: matrix
+                .entries()
+                .map(|(row, col, sign)| SignEntry { row, col, sign })
+                .collect(),
+        })
+    }
+
+    /// Is the signed adjacency matrix of the model sign stable?
+    #[wasm_bindgen(js_name = "isSignStable")]
+    pub fn is_sign_stable(&self) -> Result<bool, String> {
+        Ok(self.sign_matrix()?.is_sign_stable())
+    }
+
+    /// Predicts the responses of all objects to a press perturbation.
+    #[wasm_bindgen(js_name = "pressPerturbation")]
+    pub fn press_perturbation(&self, target: Ob) -> Result<Vec<PressResponse>, String> {
+        let matrix = self.sign_matrix()?;
+        let target: Uuid = target.try_into()?;
+        let signs = matrix
+            .predict_press_perturbation(&target)
+            .ok_or_else(|| format!("Object {} is not in the model", target))?;
+        Ok(matrix
+            .objects()
+            .iter()
+            .zip(signs)
+            .map(|(ob, sign)| PressResponse { ob: *ob, sign })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{MorDecl, ObDecl};
+    use crate::theories::*;
+    use crate::theory::{MorType, ObType};
+
+    #[test]
+    fn predator_prey() {
+        let th = ThSignedCategory::new().theory();
+        let mut model = DblModel::new(&th);
+        let (prey, predator) = (Uuid::now_v7(), Uuid::now_v7());
+        for id in [prey, predator] {
+            let ob_type = ObType::Basic("Object".into());
+            assert!(model
+                .add_ob(ObDecl {
+                    id,
+                    ob_type,
+                    provenance: None
+                })
+                .is_ok());
+        }
+        let object = || Box::new(ObType::Basic("Object".into()));
+        for (dom, cod, mor_type) in [
+            (prey, prey, MorType::Basic("Negative".into())),
+            (prey, predator, MorType::Hom(object())),
+            (predator, prey, MorType::Basic("Negative".into())),
+        ] {
+            assert!(model
+                .add_mor(MorDecl {
+                    id: Uuid::now_v7(),
+                    mor_type,
+                    dom: Some(Ob::Basic(dom)),
+                    cod: Some(Ob::Basic(cod)),
+                    provenance: None,
+                    confidence: None,
+                })
+                .is_ok());
+        }
+
+        // Analyses are only available after the model has been validated.
+        assert!(model.signed_adjacency_matrix().is_err());
+        assert!(model.validate().is_empty());
+        assert!(model.is_checked());
+        let matrix = model.signed_adjacency_matrix().unwrap();
+        assert_eq!(matrix.objects.len(), 2);
+        assert_eq!(matrix.entries.len(), 3);
+        assert_eq!(model.is_sign_stable(), Ok(true));
+        let responses = model.press_perturbation(Ob::Basic(predator)).unwrap();
+        let response = |ob| responses.iter().find(|r| r.ob == ob).unwrap().sign;
+        assert_eq!((response(prey), response(predator)), (Sign::Negative, Sign::Positive));
+
+        // Modifying the model marks it as dirty again.
+        let ob_type = ObType::Basic("Object".into());
+        let decl = ObDecl {
+            id: Uuid::now_v7(),
+            ob_type,
+            provenance: None,
+        };
+        assert!(model.add_ob(decl).is_ok());
+        assert!(!model.is_checked());
+        assert!(model.is_sign_stable().is_err());
+    }
+}
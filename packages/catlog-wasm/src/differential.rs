@@ -0,0 +1,387 @@
+/*! Differential tests of the JSON surface of the Wasm bindings.
+
+Theories and model documents cross into JavaScript through their `serde`
+implementations, which also determine the TypeScript types generated by Tsify.
+A bug in these implementations, or a mismatch between them and the Rust data
+structures, would go unnoticed by tests that stay on the Rust side. The tests
+here serialize randomized theories, model documents, and double diagrams to
+JSON text, parse them back, and check that the result is indistinguishable from
+the original: it must serialize to the same JSON, build the same model, and
+validate with the same errors, which must themselves survive a round trip.
+
+Besides the randomized cases, every file in the regression corpus directory
+`tests/corpus` is replayed on each test run. A corpus file is a JSON object
+with fields `theory` and `document`, and optionally `diagram`. When a randomized case fails, the failure
+message includes the case in this format, so that it can be saved to the
+corpus as is.
+ */
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use nonempty::NonEmpty;
+use proptest::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use ustr::{ustr, Ustr};
+use uuid::Uuid;
+
+use super::model::*;
+use super::theory::*;
+use catlog::dbl::diagram::SkelDblDiagram;
+use catlog::prelude::{
+    AuthorId, DiscreteDblModel, DiscreteDblTheory, FgCategory, FgDblModel, FinMor, Path,
+    Provenance, UstrDiscreteDblTheory, UstrFinCategory, Validate,
+};
+use catlog::stdlib::theories;
+
+/// A double diagram with labels as strings, independent of any theory.
+type Diagram = SkelDblDiagram<Ustr, Ustr, Ustr, Ustr>;
+
+/// A case in the regression corpus.
+#[derive(Serialize, Deserialize)]
+struct Case<Th> {
+    theory: Th,
+    document: ModelDocument,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    diagram: Option<Diagram>,
+}
+
+/// Round trips a value through JSON text, checking that nothing changes.
+fn round_trip<T: Serialize + DeserializeOwned>(what: &str, x: &T) -> Result<T, String> {
+    let text = serde_json::to_string(x).map_err(|err| format!("Cannot serialize {what}: {err}"))?;
+    let y: T = serde_json::from_str(&text)
+        .map_err(|err| format!("Cannot deserialize {what}: {err}\n{text}"))?;
+    ensure_eq(what, serde_json::to_value(x).unwrap(), serde_json::to_value(&y).unwrap())?;
+    Ok(y)
+}
+
+fn ensure_eq<T: PartialEq + Debug>(what: &str, x: T, y: T) -> Result<(), String> {
+    if x == y {
+        Ok(())
+    } else {
+        Err(format!("The {what} changed in a round trip:\n{x:?}\n{y:?}"))
+    }
+}
+
+/// Snapshot of a model as JSON, independent of the order of its generators.
+fn snapshot(model: &DblModel) -> Value {
+    let discrete: &DiscreteDblModel<Uuid, UstrFinCategory> = model.try_into().unwrap();
+    let obs: BTreeMap<_, _> = discrete
+        .object_generators()
+        .map(|x| (x.to_string(), json!(discrete.ob_gen_type(&x))))
+        .collect();
+    let mors: BTreeMap<_, _> = discrete
+        .morphism_generators()
+        .map(|f| {
+            let (dom, cod) = (discrete.get_dom(&f), discrete.get_cod(&f));
+            (f.to_string(), json!([discrete.mor_gen_type(&f), dom, cod]))
+        })
+        .collect();
+    json!({
+        "obs": obs,
+        "mors": mors,
+        "provenance": discrete.provenance(),
+        "confidence": model.confidence,
+    })
+}
+
+/// Validates a model, round tripping each error and sorting them as JSON text.
+fn validation_errors(model: &mut DblModel) -> Result<Vec<String>, String> {
+    let mut errs = Vec::new();
+    for err in model.validate() {
+        ensure_eq("validation error", round_trip("validation error", &err)?, err.clone())?;
+        errs.push(serde_json::to_string(&err).unwrap());
+    }
+    errs.sort();
+    Ok(errs)
+}
+
+/** Checks that a theory and a model document survive the JSON surface.
+
+Both sides of the round trip must describe the same theory and either fail to
+build a model with the same error or build the same model, which must then
+validate with the same errors.
+ */
+fn check(theory: &Arc<UstrDiscreteDblTheory>, doc: &ModelDocument) -> Result<(), String> {
+    let theory_rt = Arc::new(round_trip("theory", &**theory)?);
+    ensure_eq("theory description", theory.describe(), theory_rt.describe())?;
+    let doc_rt = round_trip("document", doc)?;
+
+    let built = DblModel::from_document(&theory.clone().into(), doc.clone());
+    let built_rt = DblModel::from_document(&theory_rt.into(), doc_rt);
+    match (built, built_rt) {
+        (Ok(mut model), Ok(mut model_rt)) => {
+            ensure_eq("model", snapshot(&model), snapshot(&model_rt))?;
+            let errs = validation_errors(&mut model)?;
+            ensure_eq("validation errors", errs, validation_errors(&mut model_rt)?)
+        }
+        (Err(err), Err(err_rt)) => ensure_eq("build error", err, err_rt),
+        (built, built_rt) => Err(format!(
+            "Only one side of the round trip built a model:\n{:?}\n{:?}",
+            built.err(),
+            built_rt.err()
+        )),
+    }
+}
+
+/// Validates a diagram, round tripping each error and sorting them as JSON text.
+fn diagram_errors(diagram: &Diagram) -> Result<Vec<String>, String> {
+    let mut errs = Vec::new();
+    for err in diagram.validate().err().map(Vec::from).unwrap_or_default() {
+        ensure_eq(
+            "diagram validation error",
+            round_trip("diagram validation error", &err)?,
+            err.clone(),
+        )?;
+        errs.push(serde_json::to_string(&err).unwrap());
+    }
+    errs.sort();
+    Ok(errs)
+}
+
+/// Checks that a double diagram survives the JSON surface.
+fn check_diagram(diagram: &Diagram) -> Result<(), String> {
+    let diagram_rt = round_trip("diagram", diagram)?;
+    ensure_eq(
+        "diagram validation errors",
+        diagram_errors(diagram)?,
+        diagram_errors(&diagram_rt)?,
+    )
+}
+
+/// Checks a case of the regression corpus, including its diagram, if any.
+fn check_case(
+    theory: &Arc<UstrDiscreteDblTheory>,
+    doc: &ModelDocument,
+    diagram: Option<&Diagram>,
+) -> Result<(), String> {
+    check(theory, doc)?;
+    diagram.map_or(Ok(()), check_diagram)
+}
+
+/// Names of types in a theory, including non-ASCII ones.
+fn arb_name() -> impl Strategy<Value = String> {
+    prop_oneof!["[A-Za-z][A-Za-z0-9_]{0,7}", "\\PC{1,6}"]
+}
+
+/** A randomized discrete double theory.
+
+Besides some theories from the standard library, the theories are free
+categories on bipartite graphs, which have no composable pairs of morphism
+generators and so are valid without any composition law.
+ */
+fn arb_theory() -> impl Strategy<Value = Arc<UstrDiscreteDblTheory>> {
+    let bipartite = (
+        prop::collection::btree_set(arb_name(), 2..6),
+        prop::collection::btree_map(
+            arb_name(),
+            any::<(prop::sample::Index, prop::sample::Index)>(),
+            0..5,
+        ),
+    )
+        .prop_map(|(obs, edges)| {
+            let obs: Vec<_> = obs.iter().map(|x| ustr(x)).collect();
+            let (srcs, tgts) = obs.split_at(obs.len() / 2);
+            let mut cat = UstrFinCategory::default();
+            for x in obs.iter() {
+                cat.add_ob_generator(*x);
+            }
+            for (e, (i, j)) in edges {
+                cat.add_mor_generator(ustr(&e), *i.get(srcs), *j.get(tgts));
+            }
+            Arc::new(DiscreteDblTheory::from(cat))
+        });
+    prop_oneof![
+        Just(Arc::new(theories::th_schema())),
+        Just(Arc::new(theories::th_signed_category())),
+        Just(Arc::new(theories::th_symmetric_links())),
+        bipartite,
+    ]
+}
+
+/// A randomized object, possibly nested in tabulators, over the given IDs.
+fn arb_ob(ids: Vec<Uuid>) -> impl Strategy<Value = Ob> {
+    let id = prop_oneof![
+        4 => prop::sample::select(ids),
+        1 => any::<u128>().prop_map(Uuid::from_u128),
+    ];
+    let basic = id.prop_map(Ob::Basic);
+    let nested = basic.clone().prop_recursive(4, 16, 3, |inner| {
+        let mor = prop_oneof![
+            any::<u128>().prop_map(|n| Mor::Basic(Uuid::from_u128(n))),
+            inner.clone().prop_map(|ob| Mor::Composite(Box::new(Path::Id(ob)))),
+        ];
+        prop_oneof![
+            inner.prop_map(|ob| Ob::Tabulated(Mor::Composite(Box::new(Path::Id(ob))))),
+            prop::collection::vec(mor, 1..3).prop_map(|mors| {
+                let path = Path::Seq(NonEmpty::from_vec(mors).unwrap());
+                Ob::Tabulated(Mor::Composite(Box::new(path)))
+            }),
+        ]
+    });
+    prop_oneof![8 => basic, 1 => nested]
+}
+
+fn arb_provenance() -> impl Strategy<Value = Option<Provenance>> {
+    // Timestamps are numbers in JavaScript, exact only up to 2^53.
+    let time = 0..(1u64 << 53);
+    prop::option::of((arb_name(), time.clone(), time).prop_map(|(author, t, s)| Provenance {
+        author_id: AuthorId(ustr(&author)),
+        created_at: t.min(s),
+        modified_at: t.max(s),
+    }))
+}
+
+/** A randomized model document over the given theory.
+
+Most types are taken from the theory and most endpoints are declared objects,
+but some are not, so that the documents also exercise the failure paths.
+ */
+fn arb_document(theory: &UstrDiscreteDblTheory) -> impl Strategy<Value = ModelDocument> {
+    let cat = theory.category();
+    let mut ob_types: Vec<Ustr> = cat.object_generators().collect();
+    ob_types.sort();
+    let mut mor_types: Vec<FinMor<Ustr, Ustr>> =
+        cat.morphism_generators().map(FinMor::Generator).collect();
+    mor_types.extend(ob_types.iter().map(|x| FinMor::Id(*x)));
+    mor_types.sort();
+    let ob_type = prop_oneof![
+        6 => prop::sample::select(ob_types).prop_map(ObType::from),
+        1 => arb_name().prop_map(|x| ObType::Basic(ustr(&x))),
+    ];
+    let mor_type = prop_oneof![
+        6 => prop::sample::select(mor_types).prop_map(MorType::from),
+        1 => arb_name().prop_map(|x| MorType::Basic(ustr(&x))),
+    ];
+
+    let obs = prop::collection::vec((any::<u128>(), ob_type, arb_provenance()), 1..6);
+    obs.prop_flat_map(move |obs| {
+        let obs: Vec<_> = obs
+            .into_iter()
+            .map(|(id, ob_type, provenance)| ObDecl {
+                id: Uuid::from_u128(id),
+                ob_type,
+                provenance,
+            })
+            .collect();
+        let ids: Vec<_> = obs.iter().map(|decl| decl.id).collect();
+        let endpoint = || prop::option::weighted(0.9, arb_ob(ids.clone()));
+        let confidence = prop::option::of((0..=100u32).prop_map(|n| f64::from(n) / 100.0));
+        let mor = (
+            any::<u128>(),
+            mor_type.clone(),
+            endpoint(),
+            endpoint(),
+            arb_provenance(),
+            confidence,
+        );
+        let mors = prop::collection::vec(mor, 0..6).prop_map(|mors| {
+            mors.into_iter()
+                .map(|(id, mor_type, dom, cod, provenance, confidence)| MorDecl {
+                    id: Uuid::from_u128(id),
+                    mor_type,
+                    dom,
+                    cod,
+                    provenance,
+                    confidence,
+                })
+                .collect()
+        });
+        (Just(obs), mors).prop_map(|(obs, mors)| ModelDocument { obs, mors })
+    })
+}
+
+/** A randomized double diagram with a skeletal indexing computad.
+
+Some boundaries refer to cells not in the diagram and most boundaries of squares
+are not compatible, so that the diagrams also exercise the validation errors.
+ */
+fn arb_diagram() -> impl Strategy<Value = Diagram> {
+    let label = || arb_name().prop_map(|x| ustr(&x));
+    prop::collection::vec(label(), 1..5).prop_flat_map(move |objects| {
+        let n = objects.len();
+        let vertex = move || prop_oneof![9 => 0..n, 1 => n..(n + 2)];
+        let edges = prop::collection::vec((label(), vertex(), vertex()), 0..4);
+        let proedges = prop::collection::vec((label(), vertex(), vertex()), 0..4);
+        (Just(objects), edges, proedges).prop_flat_map(move |(objects, edges, proedges)| {
+            let path = move |len: usize| {
+                prop_oneof![
+                    (0..=n).prop_map(Path::Id),
+                    prop::collection::vec(0..=len, 1..3)
+                        .prop_map(|es| Path::Seq(NonEmpty::from_vec(es).unwrap())),
+                ]
+            };
+            let (ne, np) = (edges.len(), proedges.len());
+            let cell = (label(), path(np), path(np), path(ne), path(ne));
+            let cells = prop::collection::vec(cell, 0..3);
+            (Just(objects), Just(edges), Just(proedges), cells).prop_map(
+                |(objects, edges, proedges, cells)| {
+                    let mut diagram = Diagram::default();
+                    for x in objects {
+                        diagram.add_object(x);
+                    }
+                    for (f, dom, cod) in edges {
+                        diagram.add_arrow(f, dom, cod);
+                    }
+                    for (m, src, tgt) in proedges {
+                        diagram.add_proarrow(m, src, tgt);
+                    }
+                    for (sq, dom, cod, src, tgt) in cells {
+                        diagram.add_cell(sq, dom, cod, src, tgt);
+                    }
+                    diagram
+                },
+            )
+        })
+    })
+}
+
+fn arb_case() -> impl Strategy<Value = (Arc<UstrDiscreteDblTheory>, ModelDocument, Option<Diagram>)>
+{
+    arb_theory().prop_flat_map(|theory| {
+        let doc = arb_document(&theory);
+        (Just(theory), doc, prop::option::of(arb_diagram()))
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        cases: 128,
+        failure_persistence: None,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn json_surface((theory, doc, diagram) in arb_case()) {
+        if let Err(msg) = check_case(&theory, &doc, diagram.as_ref()) {
+            let case = Case { theory: &*theory, document: doc, diagram };
+            let case = serde_json::to_string_pretty(&case).unwrap();
+            prop_assert!(false, "{}\n\nTo replay, add to the corpus:\n{}", msg, case);
+        }
+    }
+}
+
+#[test]
+fn replay_corpus() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    assert!(paths.len() >= 5);
+    for path in paths {
+        let text = fs::read_to_string(&path).unwrap();
+        let case: Case<UstrDiscreteDblTheory> =
+            serde_json::from_str(&text).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        let theory = Arc::new(case.theory);
+        if let Err(msg) = check_case(&theory, &case.document, case.diagram.as_ref()) {
+            panic!("{}: {msg}", path.display());
+        }
+    }
+}
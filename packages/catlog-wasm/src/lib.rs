@@ -1,10 +1,16 @@
+pub mod analyses;
 pub mod model;
 pub mod model_morphism;
+pub mod progress;
+pub mod quiver;
 pub mod theory;
 
 #[allow(clippy::new_without_default)]
 pub mod theories;
 
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod differential;
+
 use wasm_bindgen::prelude::*;
 
 /** Produce type defs for dependencies supporting `serde` but not `tsify`.
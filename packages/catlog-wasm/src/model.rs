@@ -1,6 +1,9 @@
 //! Wasm bindings for models of double theories.
 
 use all_the_same::all_the_same;
+use nonempty::NonEmpty;
+use std::collections::{HashMap, HashSet};
+use ustr::ustr;
 use uuid::Uuid;
 
 use serde::{Deserialize, Serialize};
@@ -8,14 +11,15 @@ use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
 use super::theory::*;
-use catlog::dbl::model::{self as dbl_model, InvalidDiscreteDblModel};
-use catlog::one::fin_category::UstrFinCategory;
-use catlog::one::Path;
-use catlog::one::{Category as _, FgCategory};
-use catlog::validate::{self, Validate};
+use catlog::prelude::{
+    unwrap_errors, Category as _, Checked, ClusterTree, ClusteringOptions, CombinationRule,
+    Confidence, ConfidenceColumn, DiscreteDblModel, EditStamp, FgCategory, FgDblModel,
+    InvalidDiscreteDblModel, InvalidKeyConstraint, KeyConstraint, KeyConstraints,
+    MissingConfidence, NamespaceTree, Path, Provenance, UstrFinCategory, Validate,
+};
 
 /// An object in a model of a double theory.
-#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
 #[serde(tag = "tag", content = "content")]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub enum Ob {
@@ -27,7 +31,7 @@ pub enum Ob {
 }
 
 /// A morphism in a model of a double theory.
-#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
 #[serde(tag = "tag", content = "content")]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub enum Mor {
@@ -84,7 +88,7 @@ impl TryFrom<Mor> for Path<Uuid, Uuid> {
 }
 
 /// Declaration of an object in a model of a double theory.
-#[derive(Serialize, Deserialize, Tsify)]
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, missing_as_null)]
 pub struct ObDecl {
     /// Globally unique identifier of object.
@@ -93,10 +97,15 @@ pub struct ObDecl {
     /// Object type in double theory.
     #[serde(rename = "obType")]
     pub ob_type: ObType,
+
+    /// Provenance of the object, absent in older documents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub provenance: Option<Provenance>,
 }
 
 /// Declaration of a morphism in a model of a double theory.
-#[derive(Serialize, Deserialize, Tsify)]
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, missing_as_null)]
 pub struct MorDecl {
     /// Globally unique identifier of morphism.
@@ -111,9 +120,228 @@ pub struct MorDecl {
 
     /// Codomain of morphism, if defined.
     pub cod: Option<Ob>,
+
+    /// Provenance of the morphism, absent in older documents.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub provenance: Option<Provenance>,
+
+    /// Confidence in the morphism, between 0 and 1, if annotated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub confidence: Option<f64>,
+}
+
+/// A morphism in a model of a double theory together with its type.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct TypedMor {
+    /// Morphism in the model.
+    pub mor: Mor,
+
+    /// Morphism type of the morphism.
+    #[serde(rename = "morType")]
+    pub mor_type: MorType,
+}
+
+/// Declarations of the objects and morphisms in a model document.
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct ModelDocument {
+    /// Object declarations.
+    pub obs: Vec<ObDecl>,
+
+    /// Morphism declarations.
+    pub mors: Vec<MorDecl>,
+}
+
+/// A declaration in a model document whose type could not be resolved.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct UnresolvedRef {
+    /// Identifier of the object or morphism declared.
+    pub id: Uuid,
+
+    /// Reason that the type could not be resolved.
+    pub message: String,
+}
+
+/// Result of migrating a model document to refer to types by ID.
+#[derive(Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct MigratedDocument {
+    /// Migrated document, containing only the resolved declarations.
+    pub document: ModelDocument,
+
+    /// Declarations that could not be resolved and were dropped.
+    pub unresolved: Vec<UnresolvedRef>,
+}
+
+impl ModelDocument {
+    /// IDs declared more than once in the document, in order of first repeat.
+    pub fn duplicate_ids(&self) -> Vec<Uuid> {
+        let mut seen = HashSet::new();
+        let mut dups = Vec::new();
+        let ids = self.obs.iter().map(|decl| decl.id).chain(self.mors.iter().map(|decl| decl.id));
+        for id in ids {
+            if !seen.insert(id) && !dups.contains(&id) {
+                dups.push(id);
+            }
+        }
+        dups
+    }
+
+    /** Repairs duplicate IDs in the document.
+
+    Each declaration whose ID was already declared earlier in the document,
+    objects before morphisms, is assigned a fresh ID. Every reference from a
+    morphism to a renamed ID is then rewritten or dropped. A reference is
+    rewritten when it unambiguously denotes a renamed declaration, which happens
+    when a single morphism shares its ID with objects, since references to
+    objects and to morphisms are distinguished. When two objects or two
+    morphisms share an ID, references to it are ambiguous, so the domain or
+    codomain containing the reference is dropped and the reference is reported.
+     */
+    pub fn repair(self) -> (ModelDocument, RepairReport) {
+        let mut report = RepairReport::default();
+        let (mut ob_ids, mut dup_obs) = (HashSet::new(), HashSet::new());
+        let mut obs = Vec::with_capacity(self.obs.len());
+        for mut decl in self.obs {
+            if !ob_ids.insert(decl.id) {
+                dup_obs.insert(decl.id);
+                decl.id = report.rename(decl.id);
+            }
+            obs.push(decl);
+        }
+        let (mut mor_ids, mut dup_mors, mut renamed_mors) =
+            (HashSet::new(), HashSet::new(), HashMap::new());
+        let mut mors = Vec::with_capacity(self.mors.len());
+        for mut decl in self.mors {
+            if !mor_ids.insert(decl.id) {
+                dup_mors.insert(decl.id);
+                decl.id = report.rename(decl.id);
+            } else if ob_ids.contains(&decl.id) {
+                let new = report.rename(decl.id);
+                renamed_mors.insert(decl.id, new);
+                decl.id = new;
+            }
+            mors.push(decl);
+        }
+        for decl in mors.iter_mut() {
+            let mor = decl.id;
+            let mut rewrite = |is_mor: bool, id: Uuid| {
+                let dups = if is_mor { &dup_mors } else { &dup_obs };
+                if dups.contains(&id) {
+                    report.ambiguous.push(AmbiguousRef { mor, reference: id });
+                    None
+                } else if is_mor {
+                    Some(renamed_mors.get(&id).copied().unwrap_or(id))
+                } else {
+                    Some(id)
+                }
+            };
+            decl.dom = decl.dom.take().and_then(|ob| ob.rewrite_ids(&mut rewrite));
+            decl.cod = decl.cod.take().and_then(|ob| ob.rewrite_ids(&mut rewrite));
+        }
+        (ModelDocument { obs, mors }, report)
+    }
+}
+
+impl Ob {
+    /** Rewrites the IDs referenced, flagging those referring to morphisms.
+
+    The object is dropped if any of its IDs is. Every ID is visited, so that all
+    dropped references are seen.
+     */
+    fn rewrite_ids(self, f: &mut impl FnMut(bool, Uuid) -> Option<Uuid>) -> Option<Self> {
+        match self {
+            Ob::Basic(id) => f(false, id).map(Ob::Basic),
+            Ob::Tabulated(mor) => mor.rewrite_ids(f).map(Ob::Tabulated),
+        }
+    }
+}
+
+impl Mor {
+    /// Rewrites the IDs referenced, flagging those referring to morphisms.
+    fn rewrite_ids(self, f: &mut impl FnMut(bool, Uuid) -> Option<Uuid>) -> Option<Self> {
+        match self {
+            Mor::Basic(id) => f(true, id).map(Mor::Basic),
+            Mor::Composite(path) => {
+                let path = match *path {
+                    Path::Id(ob) => Path::Id(ob.rewrite_ids(f)?),
+                    Path::Seq(mors) => {
+                        let mors: Vec<_> = mors.into_iter().map(|mor| mor.rewrite_ids(f)).collect();
+                        Path::Seq(NonEmpty::from_vec(mors.into_iter().collect::<Option<_>>()?)?)
+                    }
+                };
+                Some(Mor::Composite(Box::new(path)))
+            }
+        }
+    }
+}
+
+/// Report on the repair of duplicate IDs in a model document.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct RepairReport {
+    /// Declarations assigned fresh IDs, in document order.
+    pub renamed: Vec<RenamedId>,
+
+    /// References to duplicate IDs that could not be resolved automatically.
+    pub ambiguous: Vec<AmbiguousRef>,
+}
+
+impl RepairReport {
+    fn rename(&mut self, old: Uuid) -> Uuid {
+        let new = Uuid::now_v7();
+        self.renamed.push(RenamedId { old, new });
+        new
+    }
+}
+
+/// A declaration assigned a fresh ID because its ID was a duplicate.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct RenamedId {
+    /// Duplicate ID in the original document.
+    pub old: Uuid,
+
+    /// Fresh ID assigned.
+    pub new: Uuid,
+}
+
+/// A reference from a morphism to a duplicate ID that is ambiguous.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct AmbiguousRef {
+    /// Morphism containing the reference, by its repaired ID.
+    pub mor: Uuid,
+
+    /// Duplicate ID referenced.
+    pub reference: Uuid,
+}
+
+/// Result of repairing duplicate IDs in a model document.
+#[derive(Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct RepairedDocument {
+    /// Repaired document, in which all IDs are distinct.
+    pub document: ModelDocument,
+
+    /// Report on the repairs made.
+    pub report: RepairReport,
 }
 
-type UuidDiscreteDblModel = dbl_model::DiscreteDblModel<Uuid, UstrFinCategory>;
+pub(crate) type UuidDiscreteDblModel = DiscreteDblModel<Uuid, UstrFinCategory>;
+
+/// Checks that an ID is not yet declared in a model, as an object or morphism.
+fn check_undeclared(model: &UuidDiscreteDblModel, id: Uuid) -> Result<(), String> {
+    if model.has_ob(&id) || model.has_mor(&Path::single(id)) {
+        Err(format!("ID already declared in model: {}", id))
+    } else {
+        Ok(())
+    }
+}
 
 /** A box containing a model of a double theory of any kind.
 
@@ -127,7 +355,13 @@ pub enum DblModelBox {
 /// Converts from a model of a discrete double theory.
 impl From<UuidDiscreteDblModel> for DblModel {
     fn from(model: UuidDiscreteDblModel) -> Self {
-        DblModel(DblModelBox::Discrete(model))
+        DblModel {
+            model: DblModelBox::Discrete(model),
+            keys: Default::default(),
+            namespaces: Default::default(),
+            confidence: Default::default(),
+            checked: false,
+        }
     }
 }
 
@@ -136,7 +370,7 @@ impl<'a> TryFrom<&'a DblModel> for &'a UuidDiscreteDblModel {
     type Error = String;
 
     fn try_from(model: &'a DblModel) -> Result<Self, Self::Error> {
-        match &model.0 {
+        match &model.model {
             DblModelBox::Discrete(model) => Ok(model),
             //_ => Err("Cannot cast into a model of a discrete double theory".into()),
         }
@@ -145,44 +379,160 @@ impl<'a> TryFrom<&'a DblModel> for &'a UuidDiscreteDblModel {
 
 /// Wasm bindings for a model of a double theory.
 #[wasm_bindgen]
-pub struct DblModel(#[wasm_bindgen(skip)] pub DblModelBox);
+pub struct DblModel {
+    /// The underlying model.
+    #[wasm_bindgen(skip)]
+    pub model: DblModelBox,
+
+    /// Key constraints on the entities of the model.
+    #[wasm_bindgen(skip)]
+    pub keys: KeyConstraints<Uuid>,
+
+    /// Folders of the basic objects and morphisms in the model.
+    #[wasm_bindgen(skip)]
+    pub namespaces: NamespaceTree<Uuid>,
+
+    /// Confidence in the basic morphisms in the model.
+    #[wasm_bindgen(skip)]
+    pub confidence: ConfidenceColumn<Uuid>,
+
+    /** Whether the model has been validated since it was last modified.
+
+    Analyses and exporters can only be run on a [checked](Self::checked_model)
+    model.
+     */
+    #[wasm_bindgen(skip)]
+    pub checked: bool,
+}
 
 #[wasm_bindgen]
 impl DblModel {
     /// Creates an empty model of the given theory.
     #[wasm_bindgen(constructor)]
     pub fn new(theory: &DblTheory) -> Self {
-        Self(match &theory.0 {
-            DblTheoryBox::Discrete(th) => {
-                DblModelBox::Discrete(UuidDiscreteDblModel::new(th.clone()))
-            }
+        match &theory.theory {
+            DblTheoryBox::Discrete(th) => UuidDiscreteDblModel::new(th.clone()).into(),
             DblTheoryBox::DiscreteTab(_) => panic!("Not implemented"),
-        })
+        }
+    }
+
+    /** Creates a model of the given theory from a document.
+
+    It is an error if the document declares any ID more than once, since the
+    later declaration would otherwise silently overwrite the earlier one. Such
+    documents can be [repaired](ModelDocument::repair) first.
+     */
+    #[wasm_bindgen(js_name = "fromDocument")]
+    pub fn from_document(theory: &DblTheory, doc: ModelDocument) -> Result<DblModel, String> {
+        let dups = doc.duplicate_ids();
+        if !dups.is_empty() {
+            let dups: Vec<_> = dups.iter().map(|id| id.to_string()).collect();
+            return Err(format!("Duplicate IDs in document: {}", dups.join(", ")));
+        }
+        let mut model = DblModel::new(theory);
+        for decl in doc.obs {
+            model.add_ob(decl)?;
+        }
+        for decl in doc.mors {
+            model.add_mor(decl)?;
+        }
+        Ok(model)
+    }
+
+    /// Repairs duplicate IDs in a model document.
+    #[wasm_bindgen(js_name = "repairDocument")]
+    pub fn repair_document(doc: ModelDocument) -> RepairedDocument {
+        let (document, report) = doc.repair();
+        RepairedDocument { document, report }
+    }
+
+    /** Migrates a model document so that it refers to types by ID.
+
+    Older documents store the names that basic types had when the document was
+    saved. Each type in the document is [resolved](DblTheory::resolve_ob_type)
+    against the theory, and declarations whose types cannot be resolved are
+    reported and left out of the migrated document.
+     */
+    #[wasm_bindgen(js_name = "migrateDocument")]
+    pub fn migrate_document(theory: &DblTheory, doc: ModelDocument) -> MigratedDocument {
+        let mut unresolved = Vec::new();
+        let mut report = |id, message| unresolved.push(UnresolvedRef { id, message });
+        let obs = doc
+            .obs
+            .into_iter()
+            .filter_map(|decl| match theory.resolve_ob_type(decl.ob_type) {
+                Ok(ob_type) => Some(ObDecl { ob_type, ..decl }),
+                Err(message) => {
+                    report(decl.id, message);
+                    None
+                }
+            })
+            .collect();
+        let mors = doc
+            .mors
+            .into_iter()
+            .filter_map(|decl| match theory.resolve_mor_type(decl.mor_type) {
+                Ok(mor_type) => Some(MorDecl { mor_type, ..decl }),
+                Err(message) => {
+                    report(decl.id, message);
+                    None
+                }
+            })
+            .collect();
+        MigratedDocument {
+            document: ModelDocument { obs, mors },
+            unresolved,
+        }
     }
 
-    /// Adds an object to the model.
+    /** Adds an object to the model.
+
+    It is an error if the ID is already declared in the model, as an object or
+    as a morphism, since the declaration would otherwise silently overwrite the
+    earlier one.
+     */
     #[wasm_bindgen(js_name = "addOb")]
-    pub fn add_ob(&mut self, decl: ObDecl) -> Result<bool, String> {
-        all_the_same!(match &mut self.0 {
+    pub fn add_ob(&mut self, decl: ObDecl) -> Result<(), String> {
+        all_the_same!(match &mut self.model {
             DblModelBox::[Discrete](model) => {
                 let ob_type = decl.ob_type.try_into()?;
-                Ok(model.add_ob(decl.id, ob_type))
+                check_undeclared(model, decl.id)?;
+                self.checked = false;
+                model.add_ob(decl.id, ob_type);
+                if let Some(prov) = decl.provenance {
+                    model.provenance_mut().update(decl.id, prov);
+                }
+                Ok(())
             }
         })
     }
 
-    /// Adds a morphism to the model.
+    /** Adds a morphism to the model.
+
+    As with [objects](Self::add_ob), it is an error if the ID is already
+    declared in the model.
+     */
     #[wasm_bindgen(js_name = "addMor")]
-    pub fn add_mor(&mut self, decl: MorDecl) -> Result<bool, String> {
-        all_the_same!(match &mut self.0 {
+    pub fn add_mor(&mut self, decl: MorDecl) -> Result<(), String> {
+        all_the_same!(match &mut self.model {
             DblModelBox::[Discrete](model) => {
                 let mor_type = decl.mor_type.try_into()?;
-                let res = model.make_mor(decl.id, mor_type);
+                let confidence =
+                    decl.confidence.map(Confidence::new).transpose().map_err(|err| err.to_string())?;
                 let dom = decl.dom.map(|ob| ob.try_into()).transpose()?;
                 let cod = decl.cod.map(|ob| ob.try_into()).transpose()?;
+                check_undeclared(model, decl.id)?;
+                self.checked = false;
+                model.make_mor(decl.id, mor_type);
                 model.update_dom(decl.id, dom);
                 model.update_cod(decl.id, cod);
-                Ok(res)
+                if let Some(prov) = decl.provenance {
+                    model.provenance_mut().update(decl.id, prov);
+                }
+                if confidence.is_some() {
+                    self.confidence.set(decl.id, confidence);
+                }
+                Ok(())
             }
         })
     }
@@ -190,7 +540,7 @@ impl DblModel {
     /// Is the object contained in the model?
     #[wasm_bindgen(js_name = "hasOb")]
     pub fn has_ob(&self, ob: Ob) -> Result<bool, String> {
-        all_the_same!(match &self.0 {
+        all_the_same!(match &self.model {
             DblModelBox::[Discrete](model) => {
                 let ob = ob.try_into()?;
                 Ok(model.has_ob(&ob))
@@ -201,7 +551,7 @@ impl DblModel {
     /// Is the morphism contained in the model?
     #[wasm_bindgen(js_name = "hasMor")]
     pub fn has_mor(&self, mor: Mor) -> Result<bool, String> {
-        all_the_same!(match &self.0 {
+        all_the_same!(match &self.model {
             DblModelBox::[Discrete](model) => {
                 let mor = mor.try_into()?;
                 Ok(model.has_mor(&mor))
@@ -212,7 +562,7 @@ impl DblModel {
     /// Returns array of all basic objects in the model.
     #[wasm_bindgen]
     pub fn objects(&self) -> Vec<Ob> {
-        all_the_same!(match &self.0 {
+        all_the_same!(match &self.model {
             DblModelBox::[Discrete](model) => model.object_generators().map(|x| x.into()).collect()
         })
     }
@@ -220,18 +570,193 @@ impl DblModel {
     /// Returns array of all basic morphisms in the model.
     #[wasm_bindgen]
     pub fn morphisms(&self) -> Vec<Mor> {
-        all_the_same!(match &self.0 {
+        all_the_same!(match &self.model {
             DblModelBox::[Discrete](model) => model.morphism_generators().map(Mor::Basic).collect()
         })
     }
 
-    /// Validates that the model is well defined.
+    /** Finds a path of morphisms of least total weight between two objects.
+
+    Each basic morphism is weighted by the weight of its morphism type, which
+    defaults to one when not set. Weights must be nonnegative.
+     */
+    #[wasm_bindgen(js_name = "shortestPath")]
+    pub fn shortest_path(
+        &self,
+        src: Ob,
+        tgt: Ob,
+        weights: &MorTypeWeights,
+    ) -> Result<Option<TypedMor>, String> {
+        all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => {
+                let (src, tgt) = (src.try_into()?, tgt.try_into()?);
+                let weight = |f: &_| weights.get(&model.mor_gen_type(f).into()).unwrap_or(1.0);
+                let result = model.shortest_path(&src, &tgt, weight).map_err(|err| err.to_string())?;
+                Ok(result.map(|(path, mor_type)| TypedMor {
+                    mor: path.into(),
+                    mor_type: mor_type.into(),
+                }))
+            }
+        })
+    }
+
+    /** Clusters the basic objects of the model hierarchically, for layout.
+
+    Each node of the tree records the most common object type below it.
+     */
+    #[wasm_bindgen(js_name = "hierarchicalClustering")]
+    pub fn hierarchical_clustering(&self, options: ClusteringOptions) -> ClusterTree<Uuid, ObType> {
+        all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => {
+                model.hierarchical_clustering(&options).map(|x| x, |typ| typ.into())
+            }
+        })
+    }
+
+    /// Provenance of a basic object or morphism, if recorded.
+    #[wasm_bindgen]
+    pub fn provenance(&self, id: &str) -> Result<Option<Provenance>, String> {
+        let id = Uuid::parse_str(id).map_err(|err| err.to_string())?;
+        all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => Ok(model.provenance().get(&id).cloned())
+        })
+    }
+
+    /** Sets the author and time to record for subsequent edits.
+
+    See [`DiscreteDblModel::set_edit_stamp`]. Passing nothing stops recording.
+     */
+    #[wasm_bindgen(js_name = "setEditStamp")]
+    pub fn set_edit_stamp(&mut self, stamp: Option<EditStamp>) {
+        all_the_same!(match &mut self.model {
+            DblModelBox::[Discrete](model) => model.set_edit_stamp(stamp)
+        })
+    }
+
+    /// Confidence in a basic morphism, if annotated.
     #[wasm_bindgen]
-    pub fn validate(&self) -> Vec<InvalidDiscreteDblModel<Uuid>> {
-        all_the_same!(match &self.0 {
-            DblModelBox::[Discrete](model) => validate::unwrap_errors(model.validate())
+    pub fn confidence(&self, id: &str) -> Result<Option<f64>, String> {
+        let id = Uuid::parse_str(id).map_err(|err| err.to_string())?;
+        Ok(self.confidence.get(&id).copied().map(f64::from))
+    }
+
+    /// Sets or unsets the confidence in a basic morphism.
+    #[wasm_bindgen(js_name = "setConfidence")]
+    pub fn set_confidence(&mut self, id: &str, confidence: Option<f64>) -> Result<(), String> {
+        let id = Uuid::parse_str(id).map_err(|err| err.to_string())?;
+        let confidence =
+            confidence.map(Confidence::new).transpose().map_err(|err| err.to_string())?;
+        self.confidence.set(id, confidence);
+        Ok(())
+    }
+
+    /** Combined confidence in all the basic morphisms of the model.
+
+    For a feedback loop returned by a loop analysis, this is the confidence in
+    the loop. Returns `null` when a morphism is not annotated and missing
+    annotations are excluded.
+     */
+    #[wasm_bindgen(js_name = "combinedConfidence")]
+    pub fn combined_confidence(
+        &self,
+        rule: CombinationRule,
+        missing: MissingConfidence,
+    ) -> Option<f64> {
+        all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => {
+                let ids: Vec<_> = model.morphism_generators().collect();
+                self.confidence.combine(&ids, rule, missing).map(f64::from)
+            }
         })
     }
+
+    /** Moves basic objects and morphisms into a folder, empty for the root.
+
+    Fails without moving anything if any of the IDs is not in the model.
+     */
+    #[wasm_bindgen(js_name = "moveToFolder")]
+    pub fn move_to_folder(&mut self, ids: Vec<String>, path: Vec<String>) -> Result<(), String> {
+        let ids: Vec<_> = ids
+            .iter()
+            .map(|id| Uuid::parse_str(id).map_err(|err| err.to_string()))
+            .collect::<Result<_, _>>()?;
+        let path: Vec<_> = path.iter().map(|name| ustr(name)).collect();
+        all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => self.namespaces.move_generators(model, ids, &path)
+        })
+        .map_err(|err| err.to_string())
+    }
+
+    /// Basic objects and morphisms in a folder, optionally including subfolders.
+    #[wasm_bindgen(js_name = "folderContents")]
+    pub fn folder_contents(&self, path: Vec<String>, recursive: bool) -> Vec<String> {
+        let path: Vec<_> = path.iter().map(|name| ustr(name)).collect();
+        let ids = all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => self.namespaces.generators_in(model, &path, recursive)
+        });
+        ids.into_iter().map(|id| id.to_string()).collect()
+    }
+
+    /// Adds a key constraint, returning its index.
+    #[wasm_bindgen(js_name = "addKey")]
+    pub fn add_key(&mut self, key: KeyConstraint<Uuid>) -> usize {
+        self.keys.add(key)
+    }
+
+    /// Removes a key constraint by its index.
+    #[wasm_bindgen(js_name = "removeKey")]
+    pub fn remove_key(&mut self, index: usize) -> Option<KeyConstraint<Uuid>> {
+        self.keys.remove(index)
+    }
+
+    /// Key constraints on the model, in the order they were added.
+    #[wasm_bindgen]
+    pub fn keys(&self) -> Vec<KeyConstraint<Uuid>> {
+        self.keys.iter().cloned().collect()
+    }
+
+    /// Validates that the key constraints are well defined in the model.
+    #[wasm_bindgen(js_name = "validateKeys")]
+    pub fn validate_keys(&self) -> Vec<InvalidKeyConstraint<Uuid>> {
+        all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => unwrap_errors(self.keys.validate_in(model))
+        })
+    }
+
+    /** Validates that the model is well defined.
+
+    If the model is valid, it is marked as checked until it is next modified.
+     */
+    #[wasm_bindgen]
+    pub fn validate(&mut self) -> Vec<InvalidDiscreteDblModel<Uuid>> {
+        let errs = all_the_same!(match &self.model {
+            DblModelBox::[Discrete](model) => unwrap_errors(model.validate())
+        });
+        self.checked = errs.is_empty();
+        errs
+    }
+
+    /// Has the model been validated since it was last modified?
+    #[wasm_bindgen(js_name = "isChecked")]
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+}
+
+impl DblModel {
+    /** The model as checked to be valid, for analyses and exporters.
+
+    Fails if the model has been modified since it was last validated.
+     */
+    pub fn checked_model(&self) -> Result<&Checked<UuidDiscreteDblModel>, String> {
+        if !self.checked {
+            return Err("Model must be validated before it can be analyzed".into());
+        }
+        match &self.model {
+            DblModelBox::Discrete(model) => Checked::check_ref(model)
+                .map_err(|_| "Model is not valid and cannot be analyzed".to_string()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -248,12 +773,14 @@ mod tests {
             .add_ob(ObDecl {
                 id: x,
                 ob_type: ObType::Basic("Entity".into()),
+                provenance: None,
             })
             .is_ok());
         assert!(model
             .add_ob(ObDecl {
                 id: y,
                 ob_type: ObType::Basic("AttrType".into()),
+                provenance: None,
             })
             .is_ok());
         assert!(model
@@ -262,6 +789,8 @@ mod tests {
                 mor_type: MorType::Basic("Attr".into()),
                 dom: Some(Ob::Basic(x)),
                 cod: Some(Ob::Basic(y)),
+                provenance: None,
+                confidence: None,
             })
             .is_ok());
         assert_eq!(model.has_ob(Ob::Basic(x)), Ok(true));
@@ -270,4 +799,394 @@ mod tests {
         assert_eq!(model.morphisms().len(), 1);
         assert!(model.validate().is_empty());
     }
+
+    #[test]
+    fn folders() {
+        let th = ThSchema::new().theory();
+        let mut model = DblModel::new(&th);
+        let (x, y, a) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        for (id, ob_type) in [(x, "Entity"), (y, "AttrType")] {
+            assert!(model
+                .add_ob(ObDecl {
+                    id,
+                    ob_type: ObType::Basic(ob_type.into()),
+                    provenance: None,
+                })
+                .is_ok());
+        }
+        assert!(model
+            .add_mor(MorDecl {
+                id: a,
+                mor_type: MorType::Basic("Attr".into()),
+                dom: Some(Ob::Basic(x)),
+                cod: Some(Ob::Basic(y)),
+                provenance: None,
+                confidence: None,
+            })
+            .is_ok());
+
+        let (billing, invoices) =
+            (vec!["billing".to_string()], vec!["billing".into(), "invoices".into()]);
+        assert!(model.move_to_folder(vec![x.to_string()], billing.clone()).is_ok());
+        assert!(model.move_to_folder(vec![a.to_string()], invoices.clone()).is_ok());
+        assert!(model.move_to_folder(vec!["not a uuid".into()], billing.clone()).is_err());
+        let unknown = Uuid::now_v7().to_string();
+        assert!(model.move_to_folder(vec![y.to_string(), unknown], billing.clone()).is_err());
+        assert_eq!(model.folder_contents(billing.clone(), false), vec![x.to_string()]);
+        assert_eq!(model.folder_contents(billing, true), vec![x.to_string(), a.to_string()]);
+        assert_eq!(model.folder_contents(invoices, false), vec![a.to_string()]);
+        assert_eq!(model.folder_contents(vec![], false), vec![y.to_string()]);
+        assert_eq!(model.objects().len(), 2);
+    }
+
+    #[test]
+    fn empty_model() {
+        let th = ThSignedCategory::new().theory();
+        let mut model = DblModel::new(&th);
+        assert!(model.objects().is_empty());
+        assert!(model.morphisms().is_empty());
+        assert!(model.validate().is_empty());
+        assert!(model.validate_keys().is_empty());
+        assert!(model.signed_adjacency_matrix().is_ok_and(|m| m.objects.is_empty()));
+        assert_eq!(model.is_sign_stable(), Ok(true));
+        assert_eq!(crate::quiver::export_quiver(&model, vec![]), Ok("[0,0]".into()));
+    }
+
+    #[test]
+    fn shortest_path() {
+        let th = ThSchema::new().theory();
+        let mut model = DblModel::new(&th);
+        let (x, y, a) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let entity = ObType::Basic("Entity".into());
+        assert!(model
+            .add_ob(ObDecl {
+                id: x,
+                ob_type: entity.clone(),
+                provenance: None,
+            })
+            .is_ok());
+        assert!(model
+            .add_ob(ObDecl {
+                id: y,
+                ob_type: entity.clone(),
+                provenance: None,
+            })
+            .is_ok());
+        assert!(model
+            .add_mor(MorDecl {
+                id: a,
+                mor_type: MorType::Hom(Box::new(entity.clone())),
+                dom: Some(Ob::Basic(x)),
+                cod: Some(Ob::Basic(y)),
+                provenance: None,
+                confidence: None,
+            })
+            .is_ok());
+
+        let mut weights = MorTypeWeights::new();
+        let result = model.shortest_path(Ob::Basic(x), Ob::Basic(y), &weights).unwrap();
+        assert_eq!(
+            result.map(|typed| typed.mor_type),
+            Some(MorType::Hom(Box::new(entity.clone())))
+        );
+        let result = model.shortest_path(Ob::Basic(y), Ob::Basic(x), &weights);
+        assert!(result.unwrap().is_none());
+        weights.set(MorType::Hom(Box::new(entity)), -1.0);
+        assert!(model.shortest_path(Ob::Basic(x), Ob::Basic(y), &weights).is_err());
+    }
+
+    #[test]
+    fn provenance() {
+        let th = ThSchema::new().theory();
+        let mut model = DblModel::new(&th);
+        let x = Uuid::now_v7();
+        let json =
+            format!(r#"{{"id": "{}", "obType": {{"tag": "Basic", "content": "Entity"}}}}"#, x);
+        let decl: ObDecl = serde_json::from_str(&json).unwrap();
+        assert!(decl.provenance.is_none());
+        assert!(model.add_ob(decl).is_ok());
+        assert_eq!(model.provenance(&x.to_string()), Ok(None));
+
+        let prov = Provenance {
+            author_id: "alice".into(),
+            created_at: 10,
+            modified_at: 10,
+        };
+        let json = format!(
+            r#"{{"id": "{}", "obType": {{"tag": "Basic", "content": "Entity"}}, "provenance": {}}}"#,
+            x,
+            serde_json::to_string(&prov).unwrap()
+        );
+        let decl: ObDecl = serde_json::from_str(&json).unwrap();
+        assert_eq!(model.add_ob(decl.clone()), Err(format!("ID already declared in model: {x}")));
+        let mut model = DblModel::new(&th);
+        assert!(model.add_ob(decl).is_ok());
+        assert_eq!(model.provenance(&x.to_string()), Ok(Some(prov)));
+
+        // Edits made under a stamp are recorded by the model.
+        let y = Uuid::now_v7();
+        model.set_edit_stamp(Some(EditStamp {
+            author_id: "carol".into(),
+            at: 30,
+        }));
+        let decl = ObDecl {
+            id: y,
+            ob_type: ObType::Basic("Entity".into()),
+            provenance: None,
+        };
+        assert!(model.add_ob(decl).is_ok());
+        let recorded = model.provenance(&y.to_string()).unwrap().unwrap();
+        assert_eq!((recorded.author_id, recorded.created_at), ("carol".into(), 30));
+    }
+
+    #[test]
+    fn keys() {
+        let th = ThSchema::new().theory();
+        let mut model = DblModel::new(&th);
+        let (x, y, a) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        for (id, typ) in [(x, "Entity"), (y, "AttrType")] {
+            let ob_type = ObType::Basic(typ.into());
+            assert!(model
+                .add_ob(ObDecl {
+                    id,
+                    ob_type,
+                    provenance: None
+                })
+                .is_ok());
+        }
+        assert!(model
+            .add_mor(MorDecl {
+                id: a,
+                mor_type: MorType::Basic("Attr".into()),
+                dom: Some(Ob::Basic(x)),
+                cod: Some(Ob::Basic(y)),
+                provenance: None,
+                confidence: None,
+            })
+            .is_ok());
+
+        let json = format!(r#"{{"entity": "{}", "attributes": ["{}"], "kind": "Primary"}}"#, x, a);
+        let key: KeyConstraint<Uuid> = serde_json::from_str(&json).unwrap();
+        assert_eq!(model.add_key(key), 0);
+        assert!(model.validate_keys().is_empty());
+        let key = KeyConstraint {
+            entity: y,
+            attributes: vec![a],
+            kind: Default::default(),
+        };
+        assert_eq!(model.add_key(key), 1);
+        assert_eq!(model.validate_keys(), vec![InvalidKeyConstraint::Attribute(1, a)]);
+        assert!(model.remove_key(1).is_some());
+        assert_eq!(model.keys().len(), 1);
+    }
+
+    #[test]
+    fn migrate_renamed_types() {
+        let mut th = ThSchema::new().theory();
+        assert!(th.rename_ob_type("Entity", "Table".into()).is_ok());
+        assert!(th.rename_ob_type("Table", "Relation".into()).is_err());
+        assert!(th.rename_ob_type("Entity", "Relation".into()).is_ok());
+        assert_eq!(th.ob_type_name("Entity"), Some("Relation".into()));
+        assert_eq!(th.ob_type_name("AttrType"), Some("AttrType".into()));
+        assert_eq!(th.ob_type_name("Widget"), None);
+
+        // Names cannot be shared with another type, by ID or by alias.
+        assert!(th.rename_ob_type("AttrType", "Entity".into()).is_err());
+        assert!(th.rename_ob_type("AttrType", "Table".into()).is_err());
+        assert!(th.rename_ob_type("AttrType", "Relation".into()).is_err());
+        assert!(th.rename_ob_type("Entity", "Table".into()).is_ok());
+        assert!(th.rename_ob_type("Entity", "Relation".into()).is_ok());
+
+        // The names are saved and restored with the theory.
+        let json = serde_json::to_string(&th.type_names()).unwrap();
+        let mut restored = ThSchema::new().theory();
+        assert!(restored.load_type_names(serde_json::from_str(&json).unwrap()).is_ok());
+        assert_eq!(restored.type_names(), th.type_names());
+        let mut bad = th.type_names();
+        assert!(bad.ob_types.rename(ustr("Widget"), "Gadget".into()).is_ok());
+        assert!(restored.load_type_names(bad).is_err());
+
+        // A document saved when the object type was named "Table".
+        let (x, y, z, a) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let json = format!(
+            r#"{{
+                "obs": [
+                    {{"id": "{x}", "obType": {{"tag": "Basic", "content": "Table"}}}},
+                    {{"id": "{y}", "obType": {{"tag": "Basic", "content": "AttrType"}}}},
+                    {{"id": "{z}", "obType": {{"tag": "Basic", "content": "Widget"}}}}
+                ],
+                "mors": [
+                    {{"id": "{a}", "morType": {{"tag": "Basic", "content": "Attr"}},
+                      "dom": {{"tag": "Basic", "content": "{x}"}},
+                      "cod": {{"tag": "Basic", "content": "{y}"}}}}
+                ]
+            }}"#
+        );
+        let doc: ModelDocument = serde_json::from_str(&json).unwrap();
+        let migrated = DblModel::migrate_document(&th, doc);
+        assert_eq!(
+            migrated.unresolved,
+            vec![UnresolvedRef {
+                id: z,
+                message: "Cannot resolve object type: Widget".into(),
+            }]
+        );
+        let obs = migrated.document.obs;
+        assert_eq!(obs.len(), 2);
+        assert_eq!(obs[0].ob_type, ObType::Basic("Entity".into()));
+
+        let mut model = DblModel::new(&th);
+        for decl in obs {
+            assert!(model.add_ob(decl).is_ok());
+        }
+        for decl in migrated.document.mors {
+            assert!(model.add_mor(decl).is_ok());
+        }
+        assert!(model.validate().is_empty());
+    }
+
+    #[test]
+    fn duplicate_ids() {
+        let th = ThSchema::new().theory();
+        let (x, y, a) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        let ob_decl = |id, ob_type: &str| ObDecl {
+            id,
+            ob_type: ObType::Basic(ob_type.into()),
+            provenance: None,
+        };
+        let mor_decl = |id, dom, cod| MorDecl {
+            id,
+            mor_type: MorType::Basic("Attr".into()),
+            dom: Some(Ob::Basic(dom)),
+            cod: Some(Ob::Basic(cod)),
+            provenance: None,
+            confidence: None,
+        };
+        // The object `x` is declared twice and the morphism `y` shares its ID
+        // with an object.
+        let doc = || ModelDocument {
+            obs: vec![ob_decl(x, "Entity"), ob_decl(x, "AttrType"), ob_decl(y, "AttrType")],
+            mors: vec![mor_decl(a, x, y), mor_decl(y, x, y)],
+        };
+        assert_eq!(doc().duplicate_ids(), vec![x, y]);
+        let err = DblModel::from_document(&th, doc()).err().unwrap();
+        assert_eq!(err, format!("Duplicate IDs in document: {x}, {y}"));
+
+        let RepairedDocument { document, report } = DblModel::repair_document(doc());
+        let old: Vec<_> = report.renamed.iter().map(|r| r.old).collect();
+        assert_eq!(old, vec![x, y]);
+        assert_eq!(document.obs[1].id, report.renamed[0].new);
+        assert_eq!(document.mors[1].id, report.renamed[1].new);
+        assert_eq!(
+            report.ambiguous,
+            vec![
+                AmbiguousRef {
+                    mor: a,
+                    reference: x
+                },
+                AmbiguousRef {
+                    mor: report.renamed[1].new,
+                    reference: x
+                },
+            ]
+        );
+        assert!(document.duplicate_ids().is_empty());
+
+        // Ambiguous references are dropped, while the reference to the object
+        // `y`, which kept its ID, is not.
+        assert!(document.mors.iter().all(|decl| decl.dom.is_none()));
+        assert!(document
+            .mors
+            .iter()
+            .all(|decl| matches!(decl.cod, Some(Ob::Basic(id)) if id == y)));
+        let mut model = DblModel::from_document(&th, document).unwrap();
+        assert_eq!(model.objects().len(), 3);
+        assert_eq!(model.morphisms().len(), 2);
+        assert_eq!(model.validate().len(), 2);
+    }
+
+    #[test]
+    fn duplicate_mor_ids() {
+        let th = ThCategory::new().theory();
+        let (x, f) = (Uuid::now_v7(), Uuid::now_v7());
+        let hom = MorType::Hom(Box::new(ObType::Basic("Object".into())));
+        let mor_decl = |dom, cod| MorDecl {
+            id: f,
+            mor_type: hom.clone(),
+            dom,
+            cod,
+            provenance: None,
+            confidence: None,
+        };
+        // The morphism `f` is declared twice and also shares its ID with an
+        // object, while another morphism refers to `f` through a tabulator.
+        let link = MorDecl {
+            id: x,
+            mor_type: hom.clone(),
+            dom: Some(Ob::Basic(f)),
+            cod: Some(Ob::Tabulated(Mor::Basic(f))),
+            provenance: None,
+            confidence: None,
+        };
+        let doc = ModelDocument {
+            obs: vec![ObDecl {
+                id: f,
+                ob_type: ObType::Basic("Object".into()),
+                provenance: None,
+            }],
+            mors: vec![
+                mor_decl(Some(Ob::Basic(f)), None),
+                mor_decl(None, Some(Ob::Basic(f))),
+                link,
+            ],
+        };
+        assert_eq!(doc.duplicate_ids(), vec![f]);
+        assert!(DblModel::from_document(&th, doc.clone()).is_err());
+
+        let (document, report) = doc.repair();
+        assert!(document.duplicate_ids().is_empty());
+        let new: Vec<_> = report.renamed.iter().map(|r| (r.old, r.new)).collect();
+        assert_eq!(new.len(), 2);
+        assert!(new.iter().all(|(old, _)| *old == f));
+        let ids: Vec<_> = document.mors.iter().map(|decl| decl.id).collect();
+        assert_eq!(ids, vec![new[0].1, new[1].1, x]);
+
+        // References to the object `f` are kept, but the reference to the
+        // morphism `f` is ambiguous and its codomain is dropped.
+        assert!(matches!(document.mors[0].dom, Some(Ob::Basic(id)) if id == f));
+        assert!(matches!(document.mors[1].cod, Some(Ob::Basic(id)) if id == f));
+        assert!(matches!(document.mors[2].dom, Some(Ob::Basic(id)) if id == f));
+        assert!(document.mors[2].cod.is_none());
+        assert_eq!(
+            report.ambiguous,
+            vec![AmbiguousRef {
+                mor: x,
+                reference: f
+            }]
+        );
+    }
+
+    #[test]
+    fn strict_loading() {
+        let th = ThSchema::new().theory();
+        let mut model = DblModel::new(&th);
+        let x = Uuid::now_v7();
+        let ob_decl = ObDecl {
+            id: x,
+            ob_type: ObType::Basic("Entity".into()),
+            provenance: None,
+        };
+        assert!(model.add_ob(ob_decl.clone()).is_ok());
+        assert!(model.add_ob(ob_decl).is_err());
+        let mor_decl = MorDecl {
+            id: x,
+            mor_type: MorType::Basic("Attr".into()),
+            dom: None,
+            cod: None,
+            provenance: None,
+            confidence: None,
+        };
+        assert!(model.add_mor(mor_decl).is_err());
+        assert_eq!(model.morphisms().len(), 0);
+    }
 }
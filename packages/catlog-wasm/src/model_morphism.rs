@@ -1,23 +1,28 @@
 use std::hash::Hash;
 
 use super::model::DblModel;
-use catlog::dbl::model;
-use catlog::dbl::model_morphism::DiscreteDblModelMapping;
-use catlog::one::fin_category::UstrFinCategory;
-use catlog::one::FgCategory;
+use ustr::Ustr;
+
+use catlog::prelude::{
+    DblModel as _, DiscreteDblModel, DiscreteDblModelMapping, FgCategory, FinGraph, FinMor,
+    Progress, UstrFinCategory,
+};
 
 /// Find motifs in a model of a discrete double theory.
 pub fn motifs<Id>(
-    motif: &model::DiscreteDblModel<Id, UstrFinCategory>,
+    motif: &DiscreteDblModel<Id, UstrFinCategory>,
     model: &DblModel,
+    progress: &mut impl Progress,
 ) -> Result<Vec<DblModel>, String>
 where
     Id: Clone + Eq + Hash,
 {
-    let model: &model::DiscreteDblModel<_, _> = model.try_into()?;
+    let (confidence, namespaces) = (&model.confidence, &model.namespaces);
+    let model: &DiscreteDblModel<_, _> = model.try_into()?;
     let mut images: Vec<_> = DiscreteDblModelMapping::morphisms(motif, model)
         .monic()
-        .find_all()
+        .find_all_with_progress(progress)
+        .map_err(|err| err.to_string())?
         .into_iter()
         .map(|mapping| mapping.syntactic_image(model))
         .collect();
@@ -28,7 +33,54 @@ where
     // Remove duplicates: different morphisms can have the same image.
     retain_unique(&mut images);
 
-    Ok(images.into_iter().map(|im| im.into()).collect())
+    // Carry along the confidence in the morphisms of each motif and the folders
+    // of its generators.
+    Ok(images
+        .into_iter()
+        .map(|im| {
+            let mors: Vec<_> = im.morphism_generators().collect();
+            let gens: Vec<_> = im.object_generators().chain(mors.iter().copied()).collect();
+            let mut result: DblModel = im.into();
+            result.confidence = confidence.restrict(&mors);
+            result.namespaces = namespaces.restrict(&gens);
+            result
+        })
+        .collect())
+}
+
+/** Find loops in a model of a discrete double theory with a given composite type.
+
+Unlike finding a loop as a [motif](motifs), the loops are enumerated directly on
+the underlying typed graph of the model, which is much faster on large models.
+Each loop is returned as the submodel generated by its morphisms, from small to
+large.
+ */
+pub fn loops_with_type(
+    mor_type: &FinMor<Ustr, Ustr>,
+    model: &DblModel,
+    progress: &mut impl Progress,
+) -> Result<Vec<DblModel>, String> {
+    let (confidence, namespaces) = (&model.confidence, &model.namespaces);
+    let model: &DiscreteDblModel<_, _> = model.try_into()?;
+    let graph = model.underlying_typed_graph();
+    let mut loops = graph
+        .loops_with_progress(graph.vertex_count(), progress)
+        .map_err(|err| err.to_string())?;
+    loops.retain(|path| model.mor_type(path) == *mor_type);
+    loops.sort_by_key(|path| path.len());
+
+    Ok(loops
+        .into_iter()
+        .map(|path| {
+            let mors: Vec<_> = path.iter().copied().collect();
+            let submodel = model.induced_submodel(mors.iter().copied());
+            let gens: Vec<_> = submodel.object_generators().chain(mors.iter().copied()).collect();
+            let mut result: DblModel = submodel.into();
+            result.confidence = confidence.restrict(&mors);
+            result.namespaces = namespaces.restrict(&gens);
+            result
+        })
+        .collect())
 }
 
 /** Remove duplicate elements from a vector.
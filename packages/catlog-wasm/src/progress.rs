@@ -0,0 +1,77 @@
+//! Wasm bindings for progress reporting and cancellation.
+
+use std::ops::ControlFlow;
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use catlog::prelude::{Progress, Throttle};
+
+/// Number of items between progress reports forwarded to JavaScript.
+const REPORT_EVERY: usize = 1000;
+
+/** Progress receiver backed by an optional JavaScript callback.
+
+The callback is called with the phase, the number of items done, and the total
+number of items, if known. It cancels the operation by returning `true`. A
+callback that throws also stops the operation, and the error it threw is kept
+so that it can be returned instead of a cancellation.
+ */
+pub struct JsProgress {
+    callback: Option<js_sys::Function>,
+    error: Option<JsValue>,
+}
+
+impl JsProgress {
+    /// Reports progress to the callback, if any.
+    pub fn new(callback: Option<js_sys::Function>) -> Self {
+        Self {
+            callback,
+            error: None,
+        }
+    }
+}
+
+impl Progress for JsProgress {
+    fn report(&mut self, phase: &str, done: usize, total: Option<usize>) -> ControlFlow<()> {
+        let Some(callback) = &self.callback else {
+            return ControlFlow::Continue(());
+        };
+        match callback.call3(&JsValue::NULL, &phase.into(), &done.into(), &total.into()) {
+            Ok(cancel) if !cancel.is_truthy() => ControlFlow::Continue(()),
+            Ok(_) => ControlFlow::Break(()),
+            Err(error) => {
+                self.error = Some(error);
+                ControlFlow::Break(())
+            }
+        }
+    }
+}
+
+/** Runs an operation reporting progress to an optional JavaScript callback.
+
+Reports are throttled so that the callback is called at most once every
+[`REPORT_EVERY`] items of a phase, besides the start and end of the phase. If
+the callback throws, the error it threw is returned in place of the result of
+the operation.
+ */
+pub fn with_progress<T>(
+    callback: Option<js_sys::Function>,
+    op: impl FnOnce(&mut Throttle<JsProgress>) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut progress = Throttle::new(JsProgress::new(callback), REPORT_EVERY);
+    let result = op(&mut progress);
+    match progress.into_inner().error {
+        Some(error) => Err(error_message(&error)),
+        None => result,
+    }
+}
+
+fn error_message(error: &JsValue) -> String {
+    if let Some(error) = error.dyn_ref::<js_sys::Error>() {
+        error.message().into()
+    } else if let Some(message) = error.as_string() {
+        message
+    } else {
+        format!("{error:?}")
+    }
+}
@@ -0,0 +1,196 @@
+//! Wasm bindings for import and export of quiver diagrams.
+
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use uuid::Uuid;
+use wasm_bindgen::prelude::*;
+
+use super::model::DblModel;
+use catlog::export::quiver;
+use catlog::prelude::{FinGraph, Graph, Mapping};
+
+/// A vertex in a diagram imported from quiver.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct QuiverVertex {
+    /// Index of the vertex among the cells of the diagram.
+    pub index: usize,
+
+    /// Position of the vertex in quiver's grid.
+    pub position: (i64, i64),
+
+    /// Label of the vertex, if any.
+    pub label: Option<String>,
+}
+
+/// An edge in a diagram imported from quiver.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct QuiverEdge {
+    /// Index of the edge among the cells of the diagram.
+    pub index: usize,
+
+    /// Index of the source vertex.
+    pub src: usize,
+
+    /// Index of the target vertex.
+    pub tgt: usize,
+
+    /// Label of the edge, if any.
+    pub label: Option<String>,
+
+    /// Curvature of the edge, if curved.
+    pub curve: Option<i64>,
+}
+
+/// A diagram imported from quiver.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct QuiverDiagram {
+    /// Vertices in the diagram.
+    pub vertices: Vec<QuiverVertex>,
+
+    /// Edges in the diagram.
+    pub edges: Vec<QuiverEdge>,
+
+    /// Warnings about features of the diagram that were skipped.
+    pub warnings: Vec<String>,
+}
+
+/// Layout of a basic object or morphism for export to quiver.
+#[derive(Clone, Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, missing_as_null)]
+pub struct QuiverCell {
+    /// Identifier of the object or morphism.
+    pub id: Uuid,
+
+    /// Label of the object or morphism, if any.
+    pub label: Option<String>,
+
+    /// Position of the object in quiver's grid, if any.
+    #[serde(default)]
+    pub position: Option<(i64, i64)>,
+
+    /// Curvature of the morphism, if curved.
+    #[serde(default)]
+    pub curve: Option<i64>,
+}
+
+/// Imports a diagram from quiver's JSON format.
+#[wasm_bindgen(js_name = "importQuiver")]
+pub fn import_quiver(json: &str) -> Result<QuiverDiagram, String> {
+    let (graph, layout, warnings) = quiver::import_quiver(json)
+        .map_err(|errs| errs.iter().map(|err| err.to_string()).collect::<Vec<_>>().join("\n"))?;
+    let mut vertices: Vec<_> = graph
+        .vertices()
+        .map(|v| QuiverVertex {
+            index: v,
+            position: *layout.positions.apply(&v).unwrap(),
+            label: layout.vertex_labels.apply(&v).cloned(),
+        })
+        .collect();
+    vertices.sort_by_key(|v| v.index);
+    let mut edges: Vec<_> = graph
+        .edges()
+        .map(|e| QuiverEdge {
+            index: e,
+            src: graph.src(&e),
+            tgt: graph.tgt(&e),
+            label: layout.edge_labels.apply(&e).cloned(),
+            curve: layout.edge_curves.apply(&e).copied(),
+        })
+        .collect();
+    edges.sort_by_key(|e| e.index);
+    Ok(QuiverDiagram {
+        vertices,
+        edges,
+        warnings: warnings.iter().map(|warning| warning.to_string()).collect(),
+    })
+}
+
+/** Exports the generating graph of a model to quiver's JSON format.
+
+The model must be [checked](DblModel::checked_model).
+ */
+#[wasm_bindgen(js_name = "exportQuiver")]
+pub fn export_quiver(model: &DblModel, cells: Vec<QuiverCell>) -> Result<String, String> {
+    let model = model.checked_model()?;
+    let graph = model.generating_graph();
+    let mut layout: quiver::Layout<Uuid, Uuid> = Default::default();
+    for cell in cells {
+        if graph.has_vertex(&cell.id) {
+            layout.positions.update(cell.id, cell.position);
+            layout.vertex_labels.update(cell.id, cell.label);
+        } else if graph.has_edge(&cell.id) {
+            layout.edge_curves.update(cell.id, cell.curve);
+            layout.edge_labels.update(cell.id, cell.label);
+        }
+    }
+    Ok(quiver::export_model_quiver(model, &layout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{MorDecl, Ob, ObDecl};
+    use crate::theories::*;
+    use crate::theory::{MorType, ObType};
+
+    #[test]
+    fn quiver_round_trip() {
+        let json = r#"[0,2,[0,0,"x"],[1,0,"y"],[0,1,"f",0,{"curve":2}],[2,2,"",0,{"style":{"name":"corner"}}]]"#;
+        let diagram = import_quiver(json).unwrap();
+        assert_eq!(diagram.vertices.len(), 2);
+        assert_eq!(diagram.edges[0].curve, Some(2));
+        assert_eq!(diagram.warnings.len(), 1);
+
+        let th = ThCategory::new().theory();
+        let mut model = DblModel::new(&th);
+        let (x, y, f) = (Uuid::now_v7(), Uuid::now_v7(), Uuid::now_v7());
+        for (id, v) in [(x, &diagram.vertices[0]), (y, &diagram.vertices[1])] {
+            assert!(v.label.is_some());
+            let ob_type = ObType::Basic("Object".into());
+            assert!(model
+                .add_ob(ObDecl {
+                    id,
+                    ob_type,
+                    provenance: None
+                })
+                .is_ok());
+        }
+        assert!(model
+            .add_mor(MorDecl {
+                id: f,
+                mor_type: MorType::Hom(Box::new(ObType::Basic("Object".into()))),
+                dom: Some(Ob::Basic(x)),
+                cod: Some(Ob::Basic(y)),
+                provenance: None,
+                confidence: None,
+            })
+            .is_ok());
+        let cells = [(x, &diagram.vertices[0]), (y, &diagram.vertices[1])]
+            .into_iter()
+            .map(|(id, v)| QuiverCell {
+                id,
+                label: v.label.clone(),
+                position: Some(v.position),
+                curve: None,
+            })
+            .chain([QuiverCell {
+                id: f,
+                label: diagram.edges[0].label.clone(),
+                position: None,
+                curve: diagram.edges[0].curve,
+            }])
+            .collect::<Vec<_>>();
+        assert!(export_quiver(&model, cells.clone()).is_err());
+        assert!(model.validate().is_empty());
+        let result = export_quiver(&model, cells).unwrap();
+        let expected = if x < y {
+            r#"[0,2,[0,0,"x"],[1,0,"y"],[0,1,"f",0,{"curve":2}]]"#
+        } else {
+            r#"[0,2,[1,0,"y"],[0,0,"x"],[1,0,"f",0,{"curve":2}]]"#
+        };
+        assert_eq!(result, expected);
+    }
+}
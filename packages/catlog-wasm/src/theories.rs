@@ -5,18 +5,20 @@ theory-specific analysis methods.
  */
 
 use std::sync::Arc;
+use ustr::ustr;
 
 use wasm_bindgen::prelude::*;
 
 use super::model::DblModel;
-use super::model_morphism::motifs;
+use super::model_morphism::loops_with_type;
+use super::progress::with_progress;
 use super::theory::DblTheory;
-use catlog::dbl::theory;
-use catlog::stdlib::{models, theories};
+use catlog::prelude::{FinMor, UstrDiscreteDblTheory, UstrDiscreteTabTheory};
+use catlog::stdlib::theories;
 
 /// The theory of categories.
 #[wasm_bindgen]
-pub struct ThCategory(Arc<theory::UstrDiscreteDblTheory>);
+pub struct ThCategory(Arc<UstrDiscreteDblTheory>);
 
 #[wasm_bindgen]
 impl ThCategory {
@@ -33,7 +35,7 @@ impl ThCategory {
 
 /// The theory of database schemas with attributes.
 #[wasm_bindgen]
-pub struct ThSchema(Arc<theory::UstrDiscreteDblTheory>);
+pub struct ThSchema(Arc<UstrDiscreteDblTheory>);
 
 #[wasm_bindgen]
 impl ThSchema {
@@ -50,7 +52,7 @@ impl ThSchema {
 
 /// The theory of signed categories.
 #[wasm_bindgen]
-pub struct ThSignedCategory(Arc<theory::UstrDiscreteDblTheory>);
+pub struct ThSignedCategory(Arc<UstrDiscreteDblTheory>);
 
 #[wasm_bindgen]
 impl ThSignedCategory {
@@ -64,24 +66,38 @@ impl ThSignedCategory {
         self.0.clone().into()
     }
 
-    /// Find positive feedback loops in a model.
+    /** Find positive feedback loops in a model.
+
+    Progress of the search is reported to the optional callback, which is
+    called as `progress(phase, done, total)` and cancels the search by
+    returning `true`. A callback that throws also stops the search, and the
+    error it threw is returned.
+     */
     #[wasm_bindgen(js_name = "positiveLoops")]
-    pub fn positive_loops(&self, model: &DblModel) -> Result<Vec<DblModel>, String> {
-        let positive_loop = models::positive_loop(self.0.clone());
-        motifs(&positive_loop, model)
+    pub fn positive_loops(
+        &self,
+        model: &DblModel,
+        progress: Option<js_sys::Function>,
+    ) -> Result<Vec<DblModel>, String> {
+        let positive = FinMor::Id(ustr("Object"));
+        with_progress(progress, |progress| loops_with_type(&positive, model, progress))
     }
 
-    /// Find negative feedback loops in a model.
+    /// Find negative feedback loops in a model, like `positiveLoops`.
     #[wasm_bindgen(js_name = "negativeLoops")]
-    pub fn negative_loops(&self, model: &DblModel) -> Result<Vec<DblModel>, String> {
-        let negative_loop = models::negative_loop(self.0.clone());
-        motifs(&negative_loop, model)
+    pub fn negative_loops(
+        &self,
+        model: &DblModel,
+        progress: Option<js_sys::Function>,
+    ) -> Result<Vec<DblModel>, String> {
+        let negative = FinMor::Generator(ustr("Negative"));
+        with_progress(progress, |progress| loops_with_type(&negative, model, progress))
     }
 }
 
 /// The theory of nullable signed categories.
 #[wasm_bindgen]
-pub struct ThNullableSignedCategory(Arc<theory::UstrDiscreteDblTheory>);
+pub struct ThNullableSignedCategory(Arc<UstrDiscreteDblTheory>);
 
 #[wasm_bindgen]
 impl ThNullableSignedCategory {
@@ -96,9 +112,26 @@ impl ThNullableSignedCategory {
     }
 }
 
+/// The theory of categories with symmetric links.
+#[wasm_bindgen]
+pub struct ThSymmetricLinks(Arc<UstrDiscreteDblTheory>);
+
+#[wasm_bindgen]
+impl ThSymmetricLinks {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(Arc::new(theories::th_symmetric_links()))
+    }
+
+    #[wasm_bindgen]
+    pub fn theory(&self) -> DblTheory {
+        self.0.clone().into()
+    }
+}
+
 /// The theory of categories with links.
 #[wasm_bindgen]
-pub struct ThCategoryLinks(Arc<theory::UstrDiscreteTabTheory>);
+pub struct ThCategoryLinks(Arc<UstrDiscreteTabTheory>);
 
 #[wasm_bindgen]
 impl ThCategoryLinks {
@@ -116,8 +149,10 @@ impl ThCategoryLinks {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::*;
     use crate::theory::*;
-    use ustr::ustr;
+    use catlog::prelude::{CompositionNotation, Directionality, DiscreteDblModel, FgCategory};
+    use uuid::Uuid;
 
     #[test]
     fn discrete_dbl_theory() {
@@ -126,7 +161,12 @@ mod tests {
         let attr_type = ObType::Basic(ustr("AttrType"));
         let attr = MorType::Basic(ustr("Attr"));
         assert_eq!(th.src(attr.clone()), Ok(entity));
-        assert_eq!(th.tgt(attr), Ok(attr_type));
+        assert_eq!(th.tgt(attr.clone()), Ok(attr_type));
+        assert_eq!(th.directionality(attr), Ok(Directionality::Directed));
+
+        let th = ThSymmetricLinks::new().theory();
+        let link = MorType::Basic(ustr("Link"));
+        assert_eq!(th.directionality(link), Ok(Directionality::Symmetric));
     }
 
     #[test]
@@ -137,4 +177,148 @@ mod tests {
         assert_eq!(th.src(link.clone()), Ok(x));
         assert!(matches!(th.tgt(link), Ok(ObType::Tabulator(_))));
     }
+
+    #[test]
+    fn feedback_loops() {
+        let th = ThSignedCategory::new();
+        let mut model = DblModel::new(&th.theory());
+        let object = ObType::Basic(ustr("Object"));
+        let (x, y) = (Uuid::now_v7(), Uuid::now_v7());
+        for id in [x, y] {
+            let decl = ObDecl {
+                id,
+                ob_type: object.clone(),
+                provenance: None,
+            };
+            assert!(model.add_ob(decl).is_ok());
+        }
+        let positive = MorType::Hom(Box::new(object));
+        let negative = MorType::Basic(ustr("Negative"));
+        for (dom, cod, mor_type) in [
+            (x, y, positive.clone()),
+            (y, x, negative.clone()),
+            (y, y, negative),
+            (x, x, positive),
+        ] {
+            let decl = MorDecl {
+                id: Uuid::now_v7(),
+                mor_type,
+                dom: Some(Ob::Basic(dom)),
+                cod: Some(Ob::Basic(cod)),
+                provenance: None,
+                confidence: None,
+            };
+            assert!(model.add_mor(decl).is_ok());
+        }
+
+        let mor_count = |model: &DblModel| {
+            let model: &DiscreteDblModel<_, _> = model.try_into().unwrap();
+            model.morphism_generators().count()
+        };
+        let loops = th.negative_loops(&model, None).unwrap();
+        assert_eq!(loops.iter().map(mor_count).collect::<Vec<_>>(), vec![1, 2]);
+        let loops = th.positive_loops(&model, None).unwrap();
+        assert_eq!(loops.iter().map(mor_count).collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn try_compose_mor_types() {
+        let th = ThSignedCategory::new().theory();
+        let x = ObType::Basic(ustr("Object"));
+        let negative = MorType::Basic(ustr("Negative"));
+        let hom = MorType::Hom(Box::new(x));
+        assert_eq!(
+            th.try_compose_mor_types(vec![negative.clone(), negative.clone()]),
+            Ok(Some(hom.clone()))
+        );
+        assert_eq!(
+            th.try_compose_mor_types(vec![negative.clone(), hom.clone(), negative.clone()]),
+            Ok(Some(hom))
+        );
+
+        // Attributes cannot be composed with each other.
+        let th = ThSchema::new().theory();
+        let attr = MorType::Basic(ustr("Attr"));
+        assert_eq!(th.try_compose_mor_types(vec![attr.clone(), attr.clone()]), Ok(None));
+        assert_eq!(th.try_compose_mor_types(vec![attr.clone()]), Ok(Some(attr)));
+
+        assert!(th.try_compose_mor_types(vec![negative]).is_err());
+        let garbage =
+            MorType::Hom(Box::new(ObType::Tabulator(Box::new(MorType::Basic(ustr("Attr"))))));
+        assert!(th.try_compose_mor_types(vec![garbage]).is_err());
+        assert!(th.try_compose_mor_types(vec![]).is_err());
+
+        let th = ThCategoryLinks::new().theory();
+        let link = MorType::Basic(ustr("Link"));
+        assert_eq!(th.try_compose_mor_types(vec![link.clone(), link]), Ok(None));
+    }
+
+    #[test]
+    fn composites_involving() {
+        let th = ThSignedCategory::new().theory();
+        let negative = MorType::Basic(ustr("Negative"));
+        let usages = th.composites_involving(negative.clone()).unwrap();
+        assert_eq!(
+            usages,
+            vec![CompositeUsage {
+                first: negative.clone(),
+                second: negative,
+                composite: MorType::Hom(Box::new(ObType::Basic(ustr("Object")))),
+            }]
+        );
+        assert!(ThCategoryLinks::new()
+            .theory()
+            .composites_involving(MorType::Basic(ustr("Link")))
+            .is_err());
+    }
+
+    #[test]
+    fn mor_type_label() {
+        let mut th = ThSignedCategory::new().theory();
+        let classical = CompositionNotation::Classical;
+        let neg = MorType::Basic(ustr("Negative"));
+        assert_eq!(th.mor_type_label(neg.clone(), classical), Ok("Negative".into()));
+        let hom = MorType::Hom(Box::new(ObType::Basic(ustr("Object"))));
+        assert_eq!(th.mor_type_label(hom.clone(), classical), Ok("id(Object)".into()));
+        assert!(th.mor_type_label(MorType::Basic(ustr("Attr")), classical).is_err());
+
+        // Composites are labeled as written, using display names.
+        assert!(th.rename_mor_type("Negative", "Inhibits".into()).is_ok());
+        assert!(th.rename_ob_type("Object", "Species".into()).is_ok());
+        let path = vec![neg.clone(), hom.clone(), neg];
+        assert_eq!(th.composite_label(path, classical), Ok("Inhibits ∘ Inhibits".into()));
+        assert_eq!(th.mor_type_label(hom, classical), Ok("id(Species)".into()));
+        assert!(th.composite_label(vec![], classical).is_err());
+    }
+
+    #[test]
+    fn describe() {
+        let desc = ThSignedCategory::new().theory().describe();
+        assert_eq!(desc.ob_types, vec!["Object".to_string()]);
+        assert_eq!(desc.composites.len(), 1);
+        let desc = ThCategoryLinks::new().theory().describe();
+        assert_eq!(desc.mor_types[0].tgt, "Tab(Hom(Object))");
+    }
+
+    #[test]
+    fn auto_index() {
+        let mut th = ThNullableSignedCategory::new().theory();
+        assert_eq!(th.ob_type_index().get(&ObType::Basic(ustr("Object"))), None);
+        assert!(th.auto_index().is_ok());
+
+        let x = ObType::Basic(ustr("Object"));
+        assert_eq!(th.ob_type_index().get(&x), Some(0));
+        let mor_types = [
+            MorType::Hom(Box::new(x)),
+            MorType::Basic(ustr("Negative")),
+            MorType::Basic(ustr("Zero")),
+        ];
+        let mut indices: Vec<_> =
+            mor_types.iter().map(|m| th.mor_type_index().get(m).unwrap()).collect();
+        indices.sort();
+        assert_eq!(indices, vec![0, 1, 2]);
+
+        let mut th = ThCategoryLinks::new().theory();
+        assert!(th.auto_index().is_err());
+    }
 }
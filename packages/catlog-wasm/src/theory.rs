@@ -4,15 +4,17 @@ use all_the_same::all_the_same;
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::sync::Arc;
-use ustr::Ustr;
+use ustr::{ustr, Ustr};
 
 use serde::{Deserialize, Serialize};
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
-use catlog::dbl::theory;
-use catlog::dbl::theory::{DblTheory as BaseDblTheory, TabMorType, TabObType};
-use catlog::one::fin_category::*;
+use catlog::prelude::{
+    Category as _, CompositionMatrix, CompositionNotation, DblTheory as BaseDblTheory,
+    Directionality, EnumerableCategory, FinMor, Path, TabMorType, TabObType, TheoryDescription,
+    TheoryKind, UstrDiscreteDblTheory, UstrDiscreteTabTheory,
+};
 
 /// Object type in a double theory.
 #[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Tsify)]
@@ -38,6 +40,23 @@ pub enum MorType {
     Hom(Box<ObType>),
 }
 
+/** Usage of a morphism type in the composition law of a discrete theory.
+
+The composite of the `first` and `second` morphism types is `composite`.
+ */
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct CompositeUsage {
+    /// First morphism type in the composite.
+    pub first: MorType,
+
+    /// Second morphism type in the composite.
+    pub second: MorType,
+
+    /// Composite of the two morphism types.
+    pub composite: MorType,
+}
+
 /// Convert from object type in a discrete double theory.
 impl From<Ustr> for ObType {
     fn from(value: Ustr) -> Self {
@@ -131,26 +150,137 @@ underlying double theory, but `wasm-bindgen` does not support
 explicitly enumerate the supported kinds of double theories in this enum.
  */
 pub enum DblTheoryBox {
-    Discrete(Arc<theory::UstrDiscreteDblTheory>),
-    DiscreteTab(Arc<theory::UstrDiscreteTabTheory>),
+    Discrete(Arc<UstrDiscreteDblTheory>),
+    DiscreteTab(Arc<UstrDiscreteTabTheory>),
+}
+
+/** Display names of the basic object or morphism types in a theory.
+
+Basic types are identified by stable IDs, which are what models refer to. The
+names shown to users are kept separately, so that renaming a type does not
+break the models referring to it. A type without a name is displayed by its ID.
+Every name that a type has ever had is remembered, so that older documents
+storing names rather than IDs can still be resolved. A name, current or former,
+can therefore belong to only one type. The names are saved along with the
+theory in [`TypeNameTables`].
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+pub struct TypeNames {
+    names: HashMap<Ustr, String>,
+    aliases: HashMap<String, Ustr>,
+}
+
+impl TypeNames {
+    /// Gets the display name of a type.
+    pub fn name(&self, id: Ustr) -> String {
+        self.names.get(&id).cloned().unwrap_or_else(|| id.to_string())
+    }
+
+    /** Renames a type, remembering its old name.
+
+    Fails without changing anything if the name is, or once was, the name of a
+    different type.
+     */
+    pub fn rename(&mut self, id: Ustr, name: String) -> Result<(), String> {
+        if let Some(other) = self.resolve(&name).filter(|other| *other != id) {
+            return Err(format!("Name `{}` is already used by type: {}", name, other));
+        }
+        self.aliases.insert(self.name(id), id);
+        self.aliases.insert(name.clone(), id);
+        self.names.insert(id, name);
+        Ok(())
+    }
+
+    /** Checks that the names are consistent, as when loaded from storage.
+
+    Every type named must satisfy the `is_type` predicate, every current name
+    must be remembered as an alias of its type, and no name can be the ID of a
+    different type.
+     */
+    pub fn check(&self, is_type: impl Fn(Ustr) -> bool) -> Result<(), String> {
+        for (name, id) in self.aliases.iter() {
+            if !is_type(*id) {
+                return Err(format!("Named type not in theory: {}", id));
+            }
+            if ustr(name) != *id && is_type(ustr(name)) {
+                return Err(format!("Name `{}` is the ID of a different type", name));
+            }
+        }
+        for (id, name) in self.names.iter() {
+            if self.resolve(name) != Some(*id) {
+                return Err(format!("Name `{}` of type {} is not remembered", name, id));
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves a current or former name of a type to its ID.
+    pub fn resolve(&self, name: &str) -> Option<Ustr> {
+        self.aliases.get(name).copied()
+    }
+}
+
+/// Display names of the basic object and morphism types in a theory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct TypeNameTables {
+    /// Display names of the basic object types.
+    pub ob_types: TypeNames,
+
+    /// Display names of the basic morphism types.
+    pub mor_types: TypeNames,
 }
 
 /** Wasm bindings for a double theory.
  */
 #[wasm_bindgen]
-pub struct DblTheory(#[wasm_bindgen(skip)] pub DblTheoryBox);
+pub struct DblTheory {
+    /// The underlying double theory.
+    #[wasm_bindgen(skip)]
+    pub theory: DblTheoryBox,
+
+    /// Display names of the basic object types.
+    #[wasm_bindgen(skip)]
+    pub ob_type_names: TypeNames,
+
+    /// Display names of the basic morphism types.
+    #[wasm_bindgen(skip)]
+    pub mor_type_names: TypeNames,
+
+    /// Indices of the object types, assigned by [`auto_index`](Self::auto_index).
+    #[wasm_bindgen(skip)]
+    pub ob_type_index: ObTypeIndex,
+
+    /// Indices of the morphism types, assigned by [`auto_index`](Self::auto_index).
+    #[wasm_bindgen(skip)]
+    pub mor_type_index: MorTypeIndex,
+}
+
+/// Converts from a box containing a double theory.
+impl From<DblTheoryBox> for DblTheory {
+    fn from(theory: DblTheoryBox) -> Self {
+        Self {
+            theory,
+            ob_type_names: Default::default(),
+            mor_type_names: Default::default(),
+            ob_type_index: Default::default(),
+            mor_type_index: Default::default(),
+        }
+    }
+}
 
 /// Converts from a discrete double theory.
-impl From<Arc<theory::UstrDiscreteDblTheory>> for DblTheory {
-    fn from(theory: Arc<theory::UstrDiscreteDblTheory>) -> Self {
-        Self(DblTheoryBox::Discrete(theory))
+impl From<Arc<UstrDiscreteDblTheory>> for DblTheory {
+    fn from(theory: Arc<UstrDiscreteDblTheory>) -> Self {
+        DblTheoryBox::Discrete(theory).into()
     }
 }
 
 /// Converts from a discrete tabulator theory.
-impl From<Arc<theory::UstrDiscreteTabTheory>> for DblTheory {
-    fn from(theory: Arc<theory::UstrDiscreteTabTheory>) -> Self {
-        Self(DblTheoryBox::DiscreteTab(theory))
+impl From<Arc<UstrDiscreteTabTheory>> for DblTheory {
+    fn from(theory: Arc<UstrDiscreteTabTheory>) -> Self {
+        DblTheoryBox::DiscreteTab(theory).into()
     }
 }
 
@@ -158,19 +288,16 @@ impl From<Arc<theory::UstrDiscreteTabTheory>> for DblTheory {
 impl DblTheory {
     /// Kind of double theory ("double doctrine").
     #[wasm_bindgen(getter)]
-    pub fn kind(&self) -> String {
-        // TODO: Should return an enum so that we get type defs.
-        match &self.0 {
-            DblTheoryBox::Discrete(_) => "Discrete",
-            DblTheoryBox::DiscreteTab(_) => "DiscreteTab",
-        }
-        .into()
+    pub fn kind(&self) -> TheoryKind {
+        all_the_same!(match &self.theory {
+            DblTheoryBox::[Discrete, DiscreteTab](th) => th.kind()
+        })
     }
 
     /// Source of a morphism type.
     #[wasm_bindgen]
     pub fn src(&self, mor_type: MorType) -> Result<ObType, String> {
-        all_the_same!(match &self.0 {
+        all_the_same!(match &self.theory {
             DblTheoryBox::[Discrete, DiscreteTab](th) => {
                 let m = mor_type.try_into()?;
                 Ok(th.src(&m).into())
@@ -181,13 +308,349 @@ impl DblTheory {
     /// Target of a morphism type.
     #[wasm_bindgen]
     pub fn tgt(&self, mor_type: MorType) -> Result<ObType, String> {
-        all_the_same!(match &self.0 {
+        all_the_same!(match &self.theory {
             DblTheoryBox::[Discrete, DiscreteTab](th) => {
                 let m = mor_type.try_into()?;
                 Ok(th.tgt(&m).into())
             }
         })
     }
+
+    /** Directionality of a morphism type.
+
+    Only discrete double theories have symmetric morphism types.
+     */
+    #[wasm_bindgen]
+    pub fn directionality(&self, mor_type: MorType) -> Result<Directionality, String> {
+        match &self.theory {
+            DblTheoryBox::Discrete(th) => {
+                let m = mor_type.try_into()?;
+                Ok(th.directionality(&m))
+            }
+            DblTheoryBox::DiscreteTab(_) => Ok(Directionality::Directed),
+        }
+    }
+
+    /** Composes a sequence of morphism types, if possible.
+
+    Returns `None` when consecutive morphism types are not composable or their
+    composite is not defined in the theory. By contrast, an error is returned
+    when the input is invalid, such as an empty sequence or a morphism type not
+    belonging to the theory.
+     */
+    #[wasm_bindgen(js_name = "tryComposeMorTypes")]
+    pub fn try_compose_mor_types(&self, ms: Vec<MorType>) -> Result<Option<MorType>, String> {
+        if ms.is_empty() {
+            return Err("Cannot compose an empty sequence of morphism types".into());
+        }
+        match &self.theory {
+            DblTheoryBox::Discrete(th) => {
+                let ms = try_into_mor_types(th.as_ref(), ms)?;
+                let composite = ms.into_iter().try_fold(None, |acc, n| match acc {
+                    None => Some(Some(n)),
+                    Some(m) => th.category().compose2_lenient(m, n).ok().map(Some),
+                });
+                Ok(composite.flatten().map(|m| m.into()))
+            }
+            DblTheoryBox::DiscreteTab(th) => {
+                let ms = try_into_mor_types(th.as_ref(), ms)?;
+                let composite = ms.into_iter().try_fold(None, |acc, n| match acc {
+                    None => Some(Some(n)),
+                    Some(m) => th.try_compose2_types(m, n).map(Some),
+                });
+                Ok(composite.flatten().map(|m| m.into()))
+            }
+        }
+    }
+
+    /** Assigns sequential indices to all object and morphism types.
+
+    The indices are assigned in the order of the generators of the theory,
+    starting from zero, with the identity morphism types preceding the basic
+    ones. Only discrete theories are supported.
+     */
+    #[wasm_bindgen(js_name = "autoIndex")]
+    pub fn auto_index(&mut self) -> Result<(), String> {
+        let DblTheoryBox::Discrete(th) = &self.theory else {
+            return Err("Types can only be indexed automatically in discrete theories".into());
+        };
+        let cat = th.category();
+        self.ob_type_index = ObTypeIndex(cat.objects().map(ObType::Basic).zip(0..).collect());
+        self.mor_type_index = MorTypeIndex(cat.morphisms().map(MorType::from).zip(0..).collect());
+        Ok(())
+    }
+
+    /// Indices of the object types, empty until assigned.
+    #[wasm_bindgen(getter, js_name = "obTypeIndex")]
+    pub fn ob_type_index(&self) -> ObTypeIndex {
+        self.ob_type_index.clone()
+    }
+
+    /// Indices of the morphism types, empty until assigned.
+    #[wasm_bindgen(getter, js_name = "morTypeIndex")]
+    pub fn mor_type_index(&self) -> MorTypeIndex {
+        self.mor_type_index.clone()
+    }
+
+    /** Composition table of the theory, for display in documentation.
+
+    Only discrete theories are supported, since the morphism types of other
+    theories do not form a finite category.
+     */
+    #[wasm_bindgen(js_name = "compositionTable")]
+    pub fn composition_table(&self) -> Result<CompositionMatrix<MorType>, String> {
+        match &self.theory {
+            DblTheoryBox::Discrete(th) => Ok(th.category().composition_table().map(MorType::from)),
+            DblTheoryBox::DiscreteTab(_) => {
+                Err("Composition tables are only available for discrete theories".into())
+            }
+        }
+    }
+
+    /** Composites of morphism types that mention the given basic one.
+
+    A composite mentions a morphism type if it is one of the two types composed
+    or their composite. The usages are sorted by the types composed. Only
+    discrete theories are supported, as for the composition table.
+     */
+    #[wasm_bindgen(js_name = "compositesInvolving")]
+    pub fn composites_involving(&self, mor_type: MorType) -> Result<Vec<CompositeUsage>, String> {
+        let DblTheoryBox::Discrete(th) = &self.theory else {
+            return Err("Composites are only available for discrete theories".into());
+        };
+        let FinMor::Generator(m) = mor_type.try_into()? else {
+            return Err("Composites can only involve basic morphism types".into());
+        };
+        let mut usages: Vec<_> = th.composites_involving(&m).collect();
+        usages.sort_by(|((d1, e1), _), ((d2, e2), _)| (d1, e1).cmp(&(d2, e2)));
+        Ok(usages
+            .into_iter()
+            .map(|((d, e), f)| CompositeUsage {
+                first: MorType::Basic(d),
+                second: MorType::Basic(e),
+                composite: f.into(),
+            })
+            .collect())
+    }
+
+    /** Label of a morphism type, for display on arrows in diagrams.
+
+    Only discrete theories are supported. The label is as in
+    [`DiscreteDblTheory::mor_type_label`](catlog::dbl::theory::DiscreteDblTheory::mor_type_label),
+    except that basic types are shown by their display names.
+     */
+    #[wasm_bindgen(js_name = "morTypeLabel")]
+    pub fn mor_type_label(
+        &self,
+        mor_type: MorType,
+        notation: CompositionNotation,
+    ) -> Result<String, String> {
+        self.composite_label(vec![mor_type], notation)
+    }
+
+    /** Label of a composite of morphism types, such as `t ∘ i`.
+
+    Only discrete theories are supported. The label is as in
+    [`DiscreteDblTheory::composite_label`](catlog::dbl::theory::DiscreteDblTheory::composite_label),
+    except that basic types are shown by their display names.
+     */
+    #[wasm_bindgen(js_name = "compositeLabel")]
+    pub fn composite_label(
+        &self,
+        mor_types: Vec<MorType>,
+        notation: CompositionNotation,
+    ) -> Result<String, String> {
+        let DblTheoryBox::Discrete(th) = &self.theory else {
+            return Err("Labels are only available for discrete theories".into());
+        };
+        let path = Path::from_vec(try_into_mor_types(th.as_ref(), mor_types)?)
+            .ok_or("Composite must have at least one morphism type")?;
+        th.composite_label(path.clone(), notation).map_err(|err| err.to_string())?;
+        let path = path.map(|x| x, |m| th.category().canonical_name(&m).0).flatten();
+        let path = path.map(|x| self.ob_type_names.name(x), |e| self.mor_type_names.name(e));
+        Ok(path.display(notation).to_string())
+    }
+
+    /// Description of the theory, for generating reference documentation.
+    #[wasm_bindgen]
+    pub fn describe(&self) -> TheoryDescription {
+        all_the_same!(match &self.theory {
+            DblTheoryBox::[Discrete, DiscreteTab](th) => th.describe()
+        })
+    }
+
+    /// Basic morphism types with the given source.
+    #[wasm_bindgen(js_name = "morTypesFrom")]
+    pub fn mor_types_from(&self, ob_type: ObType) -> Result<Vec<MorType>, String> {
+        all_the_same!(match &self.theory {
+            DblTheoryBox::[Discrete, DiscreteTab](th) => {
+                let x = ob_type.try_into()?;
+                Ok(th.mor_types_from(&x).map(|m| m.into()).collect())
+            }
+        })
+    }
+
+    /// Display name of a basic object type, if it is in the theory.
+    #[wasm_bindgen(js_name = "obTypeName")]
+    pub fn ob_type_name(&self, id: &str) -> Option<String> {
+        self.has_ob_type(&ObType::Basic(ustr(id)))
+            .unwrap_or(false)
+            .then(|| self.ob_type_names.name(ustr(id)))
+    }
+
+    /// Display name of a basic morphism type, if it is in the theory.
+    #[wasm_bindgen(js_name = "morTypeName")]
+    pub fn mor_type_name(&self, id: &str) -> Option<String> {
+        self.has_mor_type(&MorType::Basic(ustr(id)))
+            .unwrap_or(false)
+            .then(|| self.mor_type_names.name(ustr(id)))
+    }
+
+    /** Renames a basic object type, without changing its ID.
+
+    The name cannot be the ID or a current or former name of another object
+    type, since then references by name would be ambiguous.
+     */
+    #[wasm_bindgen(js_name = "renameObType")]
+    pub fn rename_ob_type(&mut self, id: &str, name: String) -> Result<(), String> {
+        if !self.has_ob_type(&ObType::Basic(ustr(id)))? {
+            return Err(format!("Object type not in theory: {}", id));
+        }
+        if name != id && self.has_ob_type(&ObType::Basic(ustr(&name)))? {
+            return Err(format!("Name `{}` is the ID of another object type", name));
+        }
+        self.ob_type_names.rename(ustr(id), name)
+    }
+
+    /** Renames a basic morphism type, without changing its ID.
+
+    The name cannot be the ID or a current or former name of another morphism
+    type, since then references by name would be ambiguous.
+     */
+    #[wasm_bindgen(js_name = "renameMorType")]
+    pub fn rename_mor_type(&mut self, id: &str, name: String) -> Result<(), String> {
+        if !self.has_mor_type(&MorType::Basic(ustr(id)))? {
+            return Err(format!("Morphism type not in theory: {}", id));
+        }
+        if name != id && self.has_mor_type(&MorType::Basic(ustr(&name)))? {
+            return Err(format!("Name `{}` is the ID of another morphism type", name));
+        }
+        self.mor_type_names.rename(ustr(id), name)
+    }
+
+    /// Display names of the basic types, to be saved along with the theory.
+    #[wasm_bindgen(getter, js_name = "typeNames")]
+    pub fn type_names(&self) -> TypeNameTables {
+        TypeNameTables {
+            ob_types: self.ob_type_names.clone(),
+            mor_types: self.mor_type_names.clone(),
+        }
+    }
+
+    /** Restores display names of the basic types saved with the theory.
+
+    The names are checked against the theory and replace the current names
+    only if they are consistent.
+     */
+    #[wasm_bindgen(js_name = "loadTypeNames")]
+    pub fn load_type_names(&mut self, tables: TypeNameTables) -> Result<(), String> {
+        tables
+            .ob_types
+            .check(|id| self.has_ob_type(&ObType::Basic(id)).unwrap_or(false))?;
+        tables
+            .mor_types
+            .check(|id| self.has_mor_type(&MorType::Basic(id)).unwrap_or(false))?;
+        self.ob_type_names = tables.ob_types;
+        self.mor_type_names = tables.mor_types;
+        Ok(())
+    }
+
+    /** Resolves an object type that may refer to basic types by name.
+
+    Older documents refer to basic types by their names at the time, rather than
+    by their IDs. A reference that is the ID of a type in the theory is kept as
+    is; otherwise, it is resolved as a current or former name of a type.
+     */
+    #[wasm_bindgen(js_name = "resolveObType")]
+    pub fn resolve_ob_type(&self, ob_type: ObType) -> Result<ObType, String> {
+        match ob_type {
+            ObType::Basic(id) => {
+                if self.has_ob_type(&ObType::Basic(id))? {
+                    return Ok(ObType::Basic(id));
+                }
+                let resolved = self.ob_type_names.resolve(&id).map(ObType::Basic);
+                resolved.ok_or_else(|| format!("Cannot resolve object type: {}", id))
+            }
+            ObType::Tabulator(m) => Ok(ObType::Tabulator(Box::new(self.resolve_mor_type(*m)?))),
+        }
+    }
+
+    /// Resolves a morphism type that may refer to basic types by name.
+    #[wasm_bindgen(js_name = "resolveMorType")]
+    pub fn resolve_mor_type(&self, mor_type: MorType) -> Result<MorType, String> {
+        match mor_type {
+            MorType::Basic(id) => {
+                if self.has_mor_type(&MorType::Basic(id))? {
+                    return Ok(MorType::Basic(id));
+                }
+                let resolved = self.mor_type_names.resolve(&id).map(MorType::Basic);
+                resolved.ok_or_else(|| format!("Cannot resolve morphism type: {}", id))
+            }
+            MorType::Hom(x) => Ok(MorType::Hom(Box::new(self.resolve_ob_type(*x)?))),
+        }
+    }
+
+    /// Basic morphism types with the given target.
+    #[wasm_bindgen(js_name = "morTypesInto")]
+    pub fn mor_types_into(&self, ob_type: ObType) -> Result<Vec<MorType>, String> {
+        all_the_same!(match &self.theory {
+            DblTheoryBox::[Discrete, DiscreteTab](th) => {
+                let y = ob_type.try_into()?;
+                Ok(th.mor_types_into(&y).map(|m| m.into()).collect())
+            }
+        })
+    }
+}
+
+impl DblTheory {
+    /// Does the theory contain the object type?
+    fn has_ob_type(&self, ob_type: &ObType) -> Result<bool, String> {
+        all_the_same!(match &self.theory {
+            DblTheoryBox::[Discrete, DiscreteTab](th) => {
+                let x = ob_type.clone().try_into()?;
+                Ok(th.has_ob_type(&x))
+            }
+        })
+    }
+
+    /// Does the theory contain the morphism type?
+    fn has_mor_type(&self, mor_type: &MorType) -> Result<bool, String> {
+        all_the_same!(match &self.theory {
+            DblTheoryBox::[Discrete, DiscreteTab](th) => {
+                let m = mor_type.clone().try_into()?;
+                Ok(th.has_mor_type(&m))
+            }
+        })
+    }
+}
+
+/// Converts morphism types, checking that they belong to the theory.
+fn try_into_mor_types<Th>(th: &Th, ms: Vec<MorType>) -> Result<Vec<Th::MorType>, String>
+where
+    Th: BaseDblTheory,
+    Th::MorType: TryFrom<MorType, Error = String>,
+{
+    ms.into_iter()
+        .map(|m| {
+            let name = format!("{:?}", m);
+            let m: Th::MorType = m.try_into()?;
+            if th.has_mor_type(&m) {
+                Ok(m)
+            } else {
+                Err(format!("Morphism type not in theory: {}", name))
+            }
+        })
+        .collect()
 }
 
 /** Mapping from object types to numerical indices.
@@ -249,3 +712,33 @@ impl MorTypeIndex {
         self.0.insert(m, i);
     }
 }
+
+/** Mapping from morphism types to numerical weights.
+
+Like [`MorTypeIndex`], this struct just compensates for the lack of hash maps
+with arbitrary keys in JavaScript.
+ */
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct MorTypeWeights(HashMap<MorType, f64>);
+
+#[wasm_bindgen]
+impl MorTypeWeights {
+    /// Creates a new assignment of weights to morphism types.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Gets the weight of a morphism type, if set.
+    #[wasm_bindgen]
+    pub fn get(&self, m: &MorType) -> Option<f64> {
+        self.0.get(m).copied()
+    }
+
+    /// Sets the weight of a morphism type.
+    #[wasm_bindgen]
+    pub fn set(&mut self, m: MorType, weight: f64) {
+        self.0.insert(m, weight);
+    }
+}
@@ -0,0 +1,105 @@
+//! Benchmarks of bulk insertion into models and graphs.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use ustr::{ustr, Ustr};
+
+use catlog::prelude::{DiscreteDblModel, FinMor, MorSpec, UstrDiscreteDblModel, UstrGraph};
+use catlog::stdlib::theories::th_schema;
+
+type Batch = (Vec<(Ustr, Ustr)>, Vec<MorSpec<Ustr, FinMor<Ustr, Ustr>>>);
+
+/// A schema with `n` entities, each with an attribute of its own type.
+fn schema_batch(n: usize) -> Batch {
+    let (entity, attr_type) = (ustr("Entity"), ustr("AttrType"));
+    let obs = (0..n)
+        .flat_map(|i| [(ustr(&format!("e{i}")), entity), (ustr(&format!("t{i}")), attr_type)])
+        .collect();
+    let mors = (0..n)
+        .map(|i| MorSpec {
+            id: ustr(&format!("a{i}")),
+            dom: ustr(&format!("e{i}")),
+            cod: ustr(&format!("t{i}")),
+            mor_type: FinMor::Generator(ustr("Attr")),
+        })
+        .collect();
+    (obs, mors)
+}
+
+fn model_insertion(c: &mut Criterion) {
+    let th = Arc::new(th_schema());
+    let mut group = c.benchmark_group("model_insertion");
+    for n in [1_000, 10_000] {
+        let batch = schema_batch(n);
+        group.bench_with_input(BenchmarkId::new("one_at_a_time", n), &batch, |b, batch| {
+            b.iter_batched(
+                || batch.clone(),
+                |(obs, mors)| {
+                    let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(th.clone());
+                    for (x, typ) in obs {
+                        model.add_ob(x, typ);
+                    }
+                    for spec in mors {
+                        model.add_mor(spec.id, spec.dom, spec.cod, spec.mor_type);
+                    }
+                    model
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("bulk", n), &batch, |b, batch| {
+            b.iter_batched(
+                || batch.clone(),
+                |(obs, mors)| {
+                    let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(th.clone());
+                    model.add_obs(obs).unwrap();
+                    model.add_mors(mors).unwrap();
+                    model
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn graph_insertion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graph_insertion");
+    for n in [1_000, 10_000] {
+        let vertices: Vec<_> = (0..n).map(|i| ustr(&format!("v{i}"))).collect();
+        let edges: Vec<_> = (0..n)
+            .map(|i| (ustr(&format!("e{i}")), vertices[i], vertices[(i + 1) % n]))
+            .collect();
+        group.bench_with_input(BenchmarkId::new("one_at_a_time", n), &edges, |b, edges| {
+            b.iter_batched(
+                || edges.clone(),
+                |edges| {
+                    let mut graph = UstrGraph::default();
+                    graph.add_vertices(vertices.iter().copied());
+                    for (e, src, tgt) in edges {
+                        graph.add_edge(e, src, tgt);
+                    }
+                    graph
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("bulk", n), &edges, |b, edges| {
+            b.iter_batched(
+                || edges.clone(),
+                |edges| {
+                    let mut graph = UstrGraph::default();
+                    graph.add_vertices(vertices.iter().copied());
+                    graph.add_edges(edges).unwrap();
+                    graph
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, model_insertion, graph_insertion);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+//! Benchmarks of graph queries on models versus their underlying typed graphs.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ustr::{ustr, Ustr};
+
+use catlog::one::graph_algorithms::weakly_connected_components;
+use catlog::prelude::{
+    DiscreteDblModel, DiscreteDblModelMapping, FinMor, UstrDiscreteDblModel, UstrDiscreteDblTheory,
+};
+use catlog::stdlib::{models::positive_loop, theories::th_signed_category};
+
+/// A signed model with 20k morphisms: 5k disjoint squares of mixed signs.
+fn squares(th: &Arc<UstrDiscreteDblTheory>) -> UstrDiscreteDblModel {
+    let mut model = DiscreteDblModel::new(th.clone());
+    let object = ustr("Object");
+    let ob = |i: usize, j: usize| ustr(&format!("x{i}_{j}"));
+    for i in 0..5_000 {
+        for j in 0..4 {
+            model.add_ob(ob(i, j), object);
+        }
+        for j in 0..4 {
+            let sign = if (i + j) % 3 == 0 {
+                FinMor::Generator(ustr("Negative"))
+            } else {
+                FinMor::Id(object)
+            };
+            model.add_mor(ustr(&format!("f{i}_{j}")), ob(i, j), ob(i, (j + 1) % 4), sign);
+        }
+    }
+    model
+}
+
+fn loops(c: &mut Criterion) {
+    let th = Arc::new(th_signed_category());
+    let model = squares(&th);
+    let motif = positive_loop(th.clone());
+    let positive: FinMor<Ustr, Ustr> = FinMor::Id(ustr("Object"));
+
+    let mut group = c.benchmark_group("positive_loops");
+    group.sample_size(10);
+    group.bench_function("motif_search", |b| {
+        b.iter(|| DiscreteDblModelMapping::morphisms(&motif, &model).monic().find_all())
+    });
+    group.bench_function("model", |b| b.iter(|| model.loops_with_type(&positive, 4)));
+    let graph = model.underlying_typed_graph();
+    group.bench_function("cached_typed_graph", |b| b.iter(|| graph.loops(4)));
+    group.finish();
+}
+
+fn components(c: &mut Criterion) {
+    let th = Arc::new(th_signed_category());
+    let model = squares(&th);
+
+    let mut group = c.benchmark_group("connected_components");
+    group.sample_size(10);
+    group.bench_function("generating_graph", |b| {
+        b.iter(|| weakly_connected_components(model.generating_graph()))
+    });
+    group.bench_function("model", |b| b.iter(|| model.connected_components()));
+    let graph = model.underlying_typed_graph();
+    group.bench_function("cached_typed_graph", |b| b.iter(|| graph.connected_components()));
+    group.finish();
+}
+
+criterion_group!(benches, loops, components);
+criterion_main!(benches);
@@ -22,6 +22,9 @@ use ref_cast::RefCast;
 use std::hash::Hash;
 use thiserror::Error;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::one::graph::*;
 use crate::one::path::Path;
 use crate::validate::{self, Validate};
@@ -234,7 +237,9 @@ pub trait ColumnarDblComputad {
 }
 
 /// An invalid assignment in a double computad defined explicitly by data.
-#[derive(Debug, Error)]
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
 pub enum InvalidDblComputadData<E, ProE, Sq> {
     /// Edge assigned a domain that is not a vertex contained in the computad.
     #[error("Domain of edge `{0}` is not a vertex in the computad")]
@@ -0,0 +1,197 @@
+/*! Confidence in the generators of models.
+
+Models built from evidence, such as causal loop diagrams drawn from interviews
+or the literature, are more certain about some of their morphisms than others.
+The confidence in each morphism generator is a weight between zero and one,
+kept in a [`ConfidenceColumn`]. Generators need not be annotated.
+
+The confidence in a path of morphisms, such as a feedback loop found by motif
+finding, combines the confidences in the generators along it by a
+[`CombinationRule`] chosen for each analysis. Generators without an annotation
+are handled as specified by [`MissingConfidence`].
+ */
+
+use std::hash::Hash;
+
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+use super::generator_column::GeneratorColumn;
+use crate::one::path::Path;
+
+/// Confidence in a generator, a weight between zero and one inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "f64", into = "f64"))]
+pub struct Confidence(f64);
+
+impl Confidence {
+    /// Full confidence, the unit for every combination rule.
+    pub const CERTAIN: Self = Confidence(1.0);
+
+    /// Creates a confidence, provided the weight is between zero and one.
+    pub fn new(value: f64) -> Result<Self, InvalidConfidence> {
+        if (0.0..=1.0).contains(&value) {
+            Ok(Confidence(value))
+        } else {
+            Err(InvalidConfidence(value))
+        }
+    }
+
+    /// Gets the weight of the confidence.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Confidence {
+    type Error = InvalidConfidence;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Confidence> for f64 {
+    fn from(confidence: Confidence) -> Self {
+        confidence.0
+    }
+}
+
+/// A weight that is not a valid confidence.
+#[derive(Clone, Copy, Debug, Error, PartialEq)]
+#[error("Confidence should be between 0 and 1, got {0}")]
+pub struct InvalidConfidence(pub f64);
+
+/// Rule for combining the confidences in the generators along a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CombinationRule {
+    /// A path is as weak as its weakest link.
+    Min,
+
+    /// Confidences are multiplied, as for independent events.
+    Product,
+}
+
+impl CombinationRule {
+    /// Combines two confidences by the rule.
+    pub fn combine(self, a: Confidence, b: Confidence) -> Confidence {
+        match self {
+            CombinationRule::Min => Confidence(a.0.min(b.0)),
+            CombinationRule::Product => Confidence(a.0 * b.0),
+        }
+    }
+}
+
+/// How to treat generators without a confidence annotation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum MissingConfidence {
+    /// Treat the generator as certain, with confidence one.
+    #[default]
+    Certain,
+
+    /// Exclude any path through the generator, leaving it without confidence.
+    Exclude,
+}
+
+/// Confidence in the generators of a model.
+pub type ConfidenceColumn<Id> = GeneratorColumn<Id, Confidence>;
+
+impl<Id> ConfidenceColumn<Id>
+where
+    Id: Eq + Clone + Hash,
+{
+    /** Sets or unsets the confidence in a generator.
+
+    Returns the previous confidence, if any.
+     */
+    pub fn set(&mut self, id: Id, confidence: Option<Confidence>) -> Option<Confidence> {
+        match confidence {
+            Some(confidence) => self.insert(id, confidence),
+            None => self.remove(&id),
+        }
+    }
+
+    /** Combines the confidences in a collection of generators.
+
+    The combination of no generators is [certain](Confidence::CERTAIN). Returns
+    `None` when a generator is not annotated and missing annotations are
+    [excluded](MissingConfidence::Exclude).
+     */
+    pub fn combine<'a>(
+        &self,
+        ids: impl IntoIterator<Item = &'a Id>,
+        rule: CombinationRule,
+        missing: MissingConfidence,
+    ) -> Option<Confidence>
+    where
+        Id: 'a,
+    {
+        ids.into_iter().try_fold(Confidence::CERTAIN, |acc, id| {
+            let confidence = match (self.get(id).copied(), missing) {
+                (Some(confidence), _) => confidence,
+                (None, MissingConfidence::Certain) => Confidence::CERTAIN,
+                (None, MissingConfidence::Exclude) => return None,
+            };
+            Some(rule.combine(acc, confidence))
+        })
+    }
+
+    /// Computes the confidence in a path of morphism generators.
+    pub fn path_confidence<V>(
+        &self,
+        path: &Path<V, Id>,
+        rule: CombinationRule,
+        missing: MissingConfidence,
+    ) -> Option<Confidence> {
+        self.combine(path.iter(), rule, missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_edge_path() {
+        let mut col: ConfidenceColumn<char> = Default::default();
+        col.set('f', Some(Confidence::new(0.5).unwrap()));
+        col.set('g', Some(Confidence::new(0.8).unwrap()));
+        let path: Path<char, char> = Path::pair('f', 'g');
+        let confidence = |col: &ConfidenceColumn<_>, rule, missing| {
+            col.path_confidence(&path, rule, missing).map(f64::from)
+        };
+        assert_eq!(confidence(&col, CombinationRule::Min, MissingConfidence::Exclude), Some(0.5));
+        assert_eq!(
+            confidence(&col, CombinationRule::Product, MissingConfidence::Exclude),
+            Some(0.4)
+        );
+
+        // Now with one of the two confidences missing.
+        assert_eq!(col.set('g', None).map(f64::from), Some(0.8));
+        assert_eq!(confidence(&col, CombinationRule::Min, MissingConfidence::Certain), Some(0.5));
+        assert_eq!(
+            confidence(&col, CombinationRule::Product, MissingConfidence::Certain),
+            Some(0.5)
+        );
+        assert_eq!(confidence(&col, CombinationRule::Min, MissingConfidence::Exclude), None);
+        assert_eq!(confidence(&col, CombinationRule::Product, MissingConfidence::Exclude), None);
+
+        let empty: Path<char, char> = Path::empty('x');
+        assert_eq!(
+            col.path_confidence(&empty, CombinationRule::Product, MissingConfidence::Exclude),
+            Some(Confidence::CERTAIN)
+        );
+        assert!(Confidence::new(1.5).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+/*! Caches of data derived from models.
+
+Some analyses first derive an auxiliary structure from a model, such as its
+[underlying typed graph](super::model::DiscreteDblModel::underlying_typed_graph),
+and then query it many times. A [`DerivedCache`] keeps the derived data along
+with the [revision](super::model::DiscreteDblModel::revision) of the model it was
+derived from, recomputing it only when the model has changed since.
+
+Revisions are only comparable within a single model and its history, so a
+cache should be used with one model throughout. In particular, a model and its
+clone can reach the same revision with different contents.
+ */
+
+/// Data derived from a model, together with the revision it was derived at.
+#[derive(Clone, Debug)]
+pub struct DerivedCache<T> {
+    entry: Option<(u64, T)>,
+}
+
+impl<T> Default for DerivedCache<T> {
+    fn default() -> Self {
+        Self { entry: None }
+    }
+}
+
+impl<T> DerivedCache<T> {
+    /// Gets the cached data if it was derived at the given revision.
+    pub fn get(&self, revision: u64) -> Option<&T> {
+        self.entry.as_ref().filter(|(r, _)| *r == revision).map(|(_, value)| value)
+    }
+
+    /// Gets the data for the given revision, deriving it again if needed.
+    pub fn get_or_derive(&mut self, revision: u64, derive: impl FnOnce() -> T) -> &T {
+        if self.get(revision).is_none() {
+            self.entry = Some((revision, derive()));
+        }
+        &self.entry.as_ref().unwrap().1
+    }
+
+    /// Discards the cached data.
+    pub fn clear(&mut self) {
+        self.entry = None;
+    }
+}
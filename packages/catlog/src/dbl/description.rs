@@ -0,0 +1,188 @@
+/*! Descriptions of double theories for reference documentation.
+
+A [`TheoryDescription`] lists the basic types of a theory, the sources and
+targets of its morphism types, and the composites of morphism types, all in
+readable form. It is produced by the `describe` method of
+[discrete](DiscreteDblTheory::describe) and
+[tabulator](DiscreteTabTheory::describe) theories and can be rendered as a
+reference page by
+[`render_markdown`](crate::export::reference::render_markdown).
+
+Composites are recorded as pairs of morphism types rather than as formatted
+equations, so that the renderer can write them in either
+[notation](crate::one::path::CompositionNotation). Documentation is not part
+of a theory, so it is attached to the description by the caller.
+ */
+
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hash};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+use super::theory::{
+    DblTheory, Directionality, DiscreteDblTheory, DiscreteTabTheory, TabMorType, TheoryKind,
+};
+use crate::one::category::FgCategory;
+use crate::one::fin_category::{FinCategory, FinMor};
+
+/// Readable description of a double theory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct TheoryDescription {
+    /// Kind of the theory.
+    pub kind: TheoryKind,
+
+    /// Name of the theory, if attached.
+    pub name: Option<String>,
+
+    /// Documentation of the theory, if attached.
+    pub docs: Option<String>,
+
+    /// Basic object types, sorted.
+    pub ob_types: Vec<String>,
+
+    /// Basic morphism types, sorted.
+    pub mor_types: Vec<MorTypeDescription>,
+
+    /// Composites of basic morphism types, sorted by the types composed.
+    pub composites: Vec<CompositeDescription>,
+}
+
+/// Description of a basic morphism type in a [`TheoryDescription`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct MorTypeDescription {
+    /// The morphism type.
+    pub name: String,
+
+    /// Source of the morphism type.
+    pub src: String,
+
+    /// Target of the morphism type.
+    pub tgt: String,
+
+    /// Whether the morphism type is symmetric.
+    pub symmetric: bool,
+}
+
+/// Description of a composite in a [`TheoryDescription`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct CompositeDescription {
+    /// First morphism type composed.
+    pub first: String,
+
+    /// Second morphism type composed.
+    pub second: String,
+
+    /// Composite of the morphism types.
+    pub composite: String,
+}
+
+impl TheoryDescription {
+    fn new(kind: TheoryKind) -> Self {
+        Self {
+            kind,
+            name: None,
+            docs: None,
+            ob_types: Vec::new(),
+            mor_types: Vec::new(),
+            composites: Vec::new(),
+        }
+    }
+
+    /// Attaches a name and documentation to the description.
+    pub fn with_docs(mut self, name: impl Into<String>, docs: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self.docs = Some(docs.into());
+        self
+    }
+
+    fn sort(mut self) -> Self {
+        self.ob_types.sort();
+        self.mor_types.sort_by(|m, n| m.name.cmp(&n.name));
+        self.composites
+            .sort_by(|c, d| (&c.first, &c.second).cmp(&(&d.first, &d.second)));
+        self
+    }
+}
+
+impl<V, E, S> DiscreteDblTheory<FinCategory<V, E, S>>
+where
+    V: Eq + Clone + Hash + Display,
+    E: Eq + Clone + Hash + Display,
+    S: BuildHasher,
+{
+    /** Describes the theory for reference documentation.
+
+    A composite that is an identity morphism type is written as `id(x)`, as in
+    the [labels](DiscreteDblTheory::mor_type_label) of morphism types.
+     */
+    pub fn describe(&self) -> TheoryDescription {
+        let cat = self.category();
+        let label = |m: &FinMor<V, E>| cat.canonical_name(m).to_string();
+        let mut desc = TheoryDescription::new(self.kind());
+        desc.ob_types = cat.object_generators().map(|x| x.to_string()).collect();
+        desc.mor_types = cat
+            .morphism_generators()
+            .map(|e| MorTypeDescription {
+                name: e.to_string(),
+                src: cat.morphism_generator_dom(&e).to_string(),
+                tgt: cat.morphism_generator_cod(&e).to_string(),
+                symmetric: self.directionality(&FinMor::Generator(e)) == Directionality::Symmetric,
+            })
+            .collect();
+        desc.composites = cat
+            .composites()
+            .map(|((d, e), f)| CompositeDescription {
+                first: d.to_string(),
+                second: e.to_string(),
+                composite: label(&f),
+            })
+            .collect();
+        desc.sort()
+    }
+}
+
+impl<V, E, S> DiscreteTabTheory<V, E, S>
+where
+    V: Eq + Clone + Hash + Display,
+    E: Eq + Clone + Hash + Display,
+    S: BuildHasher,
+{
+    /// Describes the theory for reference documentation.
+    pub fn describe(&self) -> TheoryDescription {
+        let mut desc = TheoryDescription::new(self.kind());
+        desc.ob_types = self.basic_ob_types().map(|x| x.to_string()).collect();
+        desc.mor_types = self
+            .basic_mor_types()
+            .map(|e| {
+                let m = TabMorType::Basic(e.clone());
+                MorTypeDescription {
+                    name: e.to_string(),
+                    src: self.src(&m).to_string(),
+                    tgt: self.tgt(&m).to_string(),
+                    symmetric: false,
+                }
+            })
+            .collect();
+        desc.composites = self
+            .composites()
+            .map(|((d, e), m)| CompositeDescription {
+                first: d.to_string(),
+                second: e.to_string(),
+                composite: m.to_string(),
+            })
+            .collect();
+        desc.sort()
+    }
+}
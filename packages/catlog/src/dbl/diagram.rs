@@ -17,6 +17,9 @@ computad [mapping](DblComputadMapping).
 use derivative::Derivative;
 use nonempty::NonEmpty;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use super::computad::*;
 use crate::one::path::SkelPath;
 use crate::validate::{self, Validate};
@@ -47,7 +50,7 @@ pub trait DblDiagram: FinDblComputad {
 }
 
 /// A double diagram with a skeletal indexing computad.
-#[derive(Clone, Derivative)]
+#[derive(Clone, Debug, Derivative)]
 #[derivative(Default(bound = ""))]
 pub struct SkelDblDiagram<Ob, Arr, Pro, Cell> {
     objects: AttributedSkelSet<Ob>,
@@ -196,6 +199,95 @@ where
     }
 }
 
+/** Serialized form of a double diagram with a skeletal indexing computad.
+
+Each object, arrow, proarrow, and cell in the diagram is listed in the order of
+its indexing element, together with its boundary in the indexing computad.
+ */
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SkelDblDiagramData<Ob, Arr, Pro, Cell> {
+    objects: Vec<Ob>,
+    arrows: Vec<(Arr, usize, usize)>,
+    proarrows: Vec<(Pro, usize, usize)>,
+    cells: Vec<(Cell, SkelPath, SkelPath, SkelPath, SkelPath)>,
+}
+
+#[cfg(feature = "serde")]
+impl<Ob, Arr, Pro, Cell> Serialize for SkelDblDiagram<Ob, Arr, Pro, Cell>
+where
+    Ob: Eq + Clone + Serialize,
+    Arr: Eq + Clone + Serialize,
+    Pro: Eq + Clone + Serialize,
+    Cell: Eq + Clone + Serialize,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let boundary = |col: &VecColumn<usize>, x: usize| {
+            *col.apply(&x).expect("Boundary of indexing cell should be assigned")
+        };
+        let path = |col: &VecColumn<SkelPath>, x: usize| {
+            col.apply(&x).expect("Boundary of indexing square should be assigned").clone()
+        };
+        SkelDblDiagramData {
+            objects: self.objects.iter().map(|v| self.object(&v)).collect(),
+            arrows: (self.arrows.iter())
+                .map(|e| (self.arrow(&e), boundary(&self.dom_map, e), boundary(&self.cod_map, e)))
+                .collect(),
+            proarrows: (self.proarrows.iter())
+                .map(|p| {
+                    let (src, tgt) = (boundary(&self.src_map, p), boundary(&self.tgt_map, p));
+                    (self.proarrow(&p), src, tgt)
+                })
+                .collect(),
+            cells: (self.cells.iter())
+                .map(|α| {
+                    (
+                        self.cell(&α),
+                        path(&self.sq_dom_map, α),
+                        path(&self.sq_cod_map, α),
+                        path(&self.sq_src_map, α),
+                        path(&self.sq_tgt_map, α),
+                    )
+                })
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/** Deserializes a double diagram.
+
+Unlike a [finite category](crate::one::fin_category::FinCategory), a diagram is
+*not* validated when deserialized, so that it can be deserialized from data that
+is still being edited and then [validated](Validate) explicitly.
+ */
+#[cfg(feature = "serde")]
+impl<'de, Ob, Arr, Pro, Cell> Deserialize<'de> for SkelDblDiagram<Ob, Arr, Pro, Cell>
+where
+    Ob: Eq + Deserialize<'de>,
+    Arr: Eq + Deserialize<'de>,
+    Pro: Eq + Deserialize<'de>,
+    Cell: Eq + Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = SkelDblDiagramData::<Ob, Arr, Pro, Cell>::deserialize(deserializer)?;
+        let mut diagram = SkelDblDiagram::default();
+        for x in data.objects {
+            diagram.add_object(x);
+        }
+        for (f, dom, cod) in data.arrows {
+            diagram.add_arrow(f, dom, cod);
+        }
+        for (m, src, tgt) in data.proarrows {
+            diagram.add_proarrow(m, src, tgt);
+        }
+        for (α, dom, cod, src, tgt) in data.cells {
+            diagram.add_cell(α, dom, cod, src, tgt);
+        }
+        Ok(diagram)
+    }
+}
+
 impl<Ob, Arr, Pro, Cell> DblComputadMapping for SkelDblDiagram<Ob, Arr, Pro, Cell>
 where
     Ob: Eq + Clone,
@@ -272,4 +364,27 @@ mod tests {
         assert!(diag.validate().is_ok());
         assert!(DblComputadMorphism(&diag, &diag, &cptd).validate().is_ok());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut diag: SkelDblDiagram<&str, &str, &str, &str> = Default::default();
+        let (x, y) = (diag.add_object("x"), diag.add_object("y"));
+        let f = diag.add_arrow("f", x, y);
+        let m = diag.add_proarrow("m", x, y);
+        diag.add_cell("α", Path::single(m), Path::Id(y), Path::single(f), Path::Id(y));
+        let json = serde_json::to_string(&diag).unwrap();
+        let de: SkelDblDiagram<String, String, String, String> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&de).unwrap(), json);
+        assert_eq!(de.cell(&0), "α");
+
+        // Invalid diagrams are deserialized, to be validated afterwards.
+        diag.add_arrow("g", y, 2);
+        let json = serde_json::to_string(&diag).unwrap();
+        let de: SkelDblDiagram<String, String, String, String> =
+            serde_json::from_str(&json).unwrap();
+        let errs: Vec<_> = de.validate().unwrap_err().into();
+        assert_eq!(errs, vec![InvalidDblComputadData::Cod(1)]);
+    }
 }
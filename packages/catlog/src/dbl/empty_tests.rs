@@ -0,0 +1,133 @@
+//! Tests of operations on empty theories and models.
+
+use std::sync::Arc;
+
+use ustr::Ustr;
+
+use super::key::{KeyConstraints, SchemaInstance};
+use super::model::*;
+use super::model_morphism::*;
+use super::theory::*;
+use super::theory_morphism::*;
+use crate::one::fin_category::*;
+use crate::one::*;
+use crate::stdlib::analyses::signed::signed_adjacency_matrix;
+use crate::stdlib::theories::*;
+use crate::validate::{Checked, Validate};
+
+#[test]
+fn empty_theory() {
+    let th = UstrDiscreteDblTheory::from(UstrFinCategory::default());
+    assert!(th.validate().is_ok());
+    assert_eq!(th.category().object_generators().count(), 0);
+    assert_eq!(th.category().morphism_generators().count(), 0);
+    assert_eq!(th.symmetric_mor_types().count(), 0);
+    assert!(th.category().is_free());
+    assert!(th.category().audit_composites().is_empty());
+    assert_eq!(th.category().named_morphisms().count(), 0);
+
+    let mapping = th.unique_morphism_to_terminal();
+    assert!(DiscreteDblTheoryMorphism(&mapping, &th, &th_category()).validate().is_ok());
+
+    let model = DiscreteDblModel::<Ustr, _>::new(Arc::new(th));
+    assert!(model.validate().is_ok());
+    assert_eq!(model.minimal_theory().category().object_generators().count(), 0);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn empty_theory_serde() {
+    let th = UstrDiscreteDblTheory::from(UstrFinCategory::default());
+    let json = serde_json::to_string(&th).unwrap();
+    assert_eq!(json, r#"{"category":{"obs":[],"homs":[],"composites":[]},"symmetric":[]}"#);
+    let de: UstrDiscreteDblTheory = serde_json::from_str(&json).unwrap();
+    assert!(de.validate().is_ok());
+    assert_eq!(de.category().object_generators().count(), 0);
+    assert_eq!(de.category().morphism_generators().count(), 0);
+    assert_eq!(serde_json::to_string(&de).unwrap(), json);
+}
+
+#[test]
+fn empty_model() {
+    let th = Arc::new(th_signed_category());
+    let empty: UstrDiscreteDblModel = DiscreteDblModel::new(th.clone());
+    assert!(empty.validate().is_ok());
+    assert!(empty.is_free());
+    assert!(empty.connected_components().is_empty());
+    assert_eq!(empty.object_generators().count(), 0);
+    assert_eq!(empty.minimal_theory().category().object_generators().count(), 0);
+
+    // The empty model is initial: it has a unique morphism into any model.
+    let model = crate::stdlib::models::positive_loop(th.clone());
+    let morphisms = DiscreteDblModelMapping::morphisms(&empty, &model).find_all();
+    assert_eq!(morphisms.len(), 1);
+    assert!(DiscreteDblModelMorphism(&morphisms[0], &empty, &model).validate().is_ok());
+    assert_eq!(DiscreteDblModelMapping::morphisms(&empty, &empty).find_all().len(), 1);
+
+    // Gluing along the empty model is the disjoint union, with the empty model
+    // as a unit.
+    let span = ModelSpan::new(
+        empty.clone(),
+        Default::default(),
+        empty.clone(),
+        Default::default(),
+        empty.clone(),
+    );
+    assert!(span.validate().is_ok());
+    let cospan = span.pushout().unwrap();
+    assert_eq!(cospan.apex().object_generators().count(), 0);
+
+    let span = ModelSpan::new(
+        model.clone(),
+        Default::default(),
+        empty.clone(),
+        Default::default(),
+        empty.clone(),
+    );
+    let cospan = span.pushout().unwrap();
+    assert_eq!(cospan.apex().object_generators().count(), 1);
+    assert!(cospan.apex().validate().is_ok());
+
+    // Rewriting with the empty rule does nothing.
+    let rule = RewriteRule::new(ModelSpan::new(
+        empty.clone(),
+        Default::default(),
+        empty.clone(),
+        Default::default(),
+        empty.clone(),
+    ));
+    let matches = find_matches(&empty, &rule);
+    assert_eq!(matches.len(), 1);
+    let result = apply_rewrite(&empty, &rule, &matches[0]).unwrap();
+    assert_eq!(result.object_generators().count(), 0);
+
+    // Analyses produce empty results rather than errors.
+    let matrix = signed_adjacency_matrix(&Checked::new(empty.clone()).unwrap()).unwrap();
+    assert_eq!(matrix.size(), 0);
+    assert!(matrix.is_sign_stable());
+    assert_eq!(matrix.predict_press_perturbation(&Ustr::from("x")), None);
+}
+
+#[test]
+fn empty_keys() {
+    let th = Arc::new(th_schema());
+    let model: UstrDiscreteDblModel = DiscreteDblModel::new(th);
+    let keys: KeyConstraints<Ustr> = Default::default();
+    assert!(keys.validate_in(&model).is_ok());
+    let instance: SchemaInstance<Ustr, u32> = Default::default();
+    assert!(keys.check_instance(&instance).is_ok());
+}
+
+#[cfg(feature = "quiver")]
+#[test]
+fn empty_quiver() {
+    use crate::export::quiver::*;
+
+    let (graph, _, warnings) = import_quiver("[0,0]").unwrap();
+    assert_eq!(graph.vertex_count(), 0);
+    assert!(warnings.is_empty());
+
+    let model: UstrDiscreteDblModel = DiscreteDblModel::new(Arc::new(th_category()));
+    let layout: Layout<Ustr, Ustr> = Default::default();
+    assert_eq!(export_quiver(model.generating_graph(), &layout), "[0,0]");
+}
@@ -0,0 +1,148 @@
+/*! Columns of data attached to the generators of models.
+
+Editors and analyses often attach data to the basic objects and morphisms of a
+model that is not part of the model itself, such as the
+[confidence](super::confidence) in a morphism, the [provenance](super::provenance)
+of a generator, the [folder](super::namespace) it is filed under, the
+[cell](super::ownership) that introduced it, or its declared
+[inverse](super::inverse). Such data does not affect the model's semantics,
+equality, or validation, and each kind of data can be loaded, saved, and merged
+independently.
+
+Each kind of data is kept in a [`GeneratorColumn`] keyed by generator ID. Most
+columns are kept outside the model and do not observe it, so whoever mutates the
+model is responsible for updating them, for instance by
+[retaining](GeneratorColumn::retain) only the generators still in the model.
+Provenance is the exception: the [model](super::model::DiscreteDblModel::provenance)
+keeps it up to date itself. A column serializes as a plain map from IDs to
+values.
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use derivative::Derivative;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A column of values attached to generators of a model, keyed by generator ID.
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+#[derivative(PartialEq(bound = "Id: Eq + Hash, T: PartialEq"))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "Id: Serialize, T: Serialize",
+        deserialize = "Id: Deserialize<'de> + Eq + Hash, T: Deserialize<'de>"
+    ))
+)]
+pub struct GeneratorColumn<Id, T>(HashMap<Id, T>);
+
+impl<Id, T> GeneratorColumn<Id, T>
+where
+    Id: Eq + Clone + Hash,
+{
+    /// Gets the value attached to a generator, if any.
+    pub fn get(&self, id: &Id) -> Option<&T> {
+        self.0.get(id)
+    }
+
+    /// Gets a mutable reference to the value attached to a generator, if any.
+    pub fn get_mut(&mut self, id: &Id) -> Option<&mut T> {
+        self.0.get_mut(id)
+    }
+
+    /// Does the generator have an attached value?
+    pub fn contains(&self, id: &Id) -> bool {
+        self.0.contains_key(id)
+    }
+
+    /// Attaches a value to a generator, returning the previous value.
+    pub fn insert(&mut self, id: Id, value: T) -> Option<T> {
+        self.0.insert(id, value)
+    }
+
+    /// Detaches the value from a generator, returning it if there was one.
+    pub fn remove(&mut self, id: &Id) -> Option<T> {
+        self.0.remove(id)
+    }
+
+    /// Keeps only the generators, and their values, satisfying a predicate.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Id, &T) -> bool) {
+        self.0.retain(|id, value| keep(id, value))
+    }
+
+    /// Iterates over the generators with attached values, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Id, &T)> {
+        self.0.iter()
+    }
+
+    /// Number of generators with attached values.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Does no generator have an attached value?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Restricts the column to the given generators.
+    pub fn restrict<'a>(&self, ids: impl IntoIterator<Item = &'a Id>) -> Self
+    where
+        Id: 'a,
+        T: Clone,
+    {
+        Self(
+            ids.into_iter()
+                .filter_map(|id| Some((id.clone(), self.get(id)?.clone())))
+                .collect(),
+        )
+    }
+}
+
+impl<Id, T> IntoIterator for GeneratorColumn<Id, T> {
+    type Item = (Id, T);
+    type IntoIter = std::collections::hash_map::IntoIter<Id, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<Id, T> FromIterator<(Id, T)> for GeneratorColumn<Id, T>
+where
+    Id: Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item = (Id, T)>>(iter: I) -> Self {
+        Self(HashMap::from_iter(iter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column() {
+        let mut col: GeneratorColumn<char, usize> = [('x', 1), ('y', 2)].into_iter().collect();
+        assert_eq!(col.insert('z', 3), None);
+        assert_eq!(col.remove(&'x'), Some(1));
+        col.retain(|_, n| *n > 2);
+        assert_eq!(col.len(), 1);
+        assert_eq!(col.get(&'z'), Some(&3));
+        assert!(col.restrict(&['x', 'y']).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_as_map() {
+        let col: GeneratorColumn<char, usize> = [('x', 1)].into_iter().collect();
+        let json = serde_json::to_string(&col).unwrap();
+        assert_eq!(json, r#"{"x":1}"#);
+        assert_eq!(serde_json::from_str::<GeneratorColumn<char, usize>>(&json).unwrap(), col);
+    }
+}
@@ -0,0 +1,257 @@
+/*! Declared inverses of morphisms in models.
+
+A model can contain pairs of basic morphisms that are meant to be mutually
+inverse, such as the two directions of a bijective correspondence between
+entities. A model of a discrete double theory has no way to say so, since its
+morphisms are freely generated, so declared inverses are recorded in an
+[`InverseColumn`] instead. The column is an involution:
+whenever `g` is the inverse of `f`, `f` is the inverse of `g`.
+
+Declarations are checked when they are made, but the model can change
+afterwards, for instance by [rewriting](super::model::apply_rewrite). The
+column should then be [validated](InverseColumn::validate_in) again to find
+declarations that refer to morphisms no longer in the model.
+ */
+
+use std::hash::Hash;
+
+use nonempty::NonEmpty;
+use thiserror::Error;
+
+use super::generator_column::GeneratorColumn;
+use super::model::{DblModel, DiscreteDblModel, FgDblModel, ModelStore};
+use super::theory::{DblTheory, Directionality};
+use crate::one::{Category, FgCategory};
+use crate::validate;
+
+/// Declared inverses of the basic morphisms of a model.
+pub type InverseColumn<Id> = GeneratorColumn<Id, Id>;
+
+/// A failure to declare two morphisms to be inverse.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum InverseError<Id> {
+    /// Morphism is not a basic morphism in the model.
+    #[error("Morphism `{0}` is not in the model")]
+    NotInModel(Id),
+
+    /// Morphisms do not have opposite domains and codomains.
+    #[error("Morphisms `{0}` and `{1}` do not have opposite endpoints")]
+    Endpoints(Id, Id),
+
+    /// Morphisms have types that cannot be inverse in the theory.
+    #[error("Morphisms `{0}` and `{1}` have incompatible types")]
+    MorTypes(Id, Id),
+
+    /// Morphism already has a different declared inverse.
+    #[error("Morphism `{0}` already has inverse `{1}`")]
+    AlreadyDeclared(Id, Id),
+}
+
+/// A declared inverse that is not well defined in a model.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum InvalidInverse<Id> {
+    /// Morphism with a declared inverse is not in the model.
+    #[error("Morphism `{0}` has a declared inverse but is not in the model")]
+    Dangling(Id),
+
+    /// Morphism does not have endpoints opposite to its declared inverse.
+    #[error("Morphism `{0}` does not have endpoints opposite to its inverse")]
+    Endpoints(Id),
+}
+
+impl<Id> InverseColumn<Id>
+where
+    Id: Eq + Clone + Hash,
+{
+    /// Gets the declared inverse of a morphism, if any.
+    pub fn inverse(&self, f: &Id) -> Option<&Id> {
+        self.get(f)
+    }
+
+    /** Gets the declared inverse of a morphism, if it is still valid in a model.
+
+    Unlike [`inverse`](Self::inverse), the declaration is ignored unless both
+    morphisms are still in the model with opposite domains and codomains, as
+    they may not be after the model has changed. The model can be any
+    [store](ModelStore) of generators.
+     */
+    pub fn valid_inverse<S>(&self, store: &S, f: &Id) -> Option<&Id>
+    where
+        S: ModelStore<Id = Id>,
+    {
+        self.get(f)
+            .filter(|g| store.has_mor_generator(g) && has_opposite_endpoints(store, f, g))
+    }
+
+    /** Declares two basic morphisms of a model to be mutually inverse.
+
+    The morphisms must have opposite domains and codomains, and their types must
+    be opposite in the theory, with the same type when either is
+    [symmetric](Directionality::Symmetric). A morphism can be its own inverse
+    when it is an endomorphism. Declaring the same pair again does nothing.
+     */
+    pub fn declare<Cat>(
+        &mut self,
+        model: &DiscreteDblModel<Id, Cat>,
+        f: Id,
+        g: Id,
+    ) -> Result<(), InverseError<Id>>
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        for h in [&f, &g] {
+            if !model.has_mor(&h.clone().into()) {
+                return Err(InverseError::NotInModel(h.clone()));
+            }
+        }
+        if !has_opposite_endpoints(model, &f, &g) {
+            return Err(InverseError::Endpoints(f, g));
+        }
+        let theory = model.theory();
+        let (s, t) = (model.mor_gen_type(&f), model.mor_gen_type(&g));
+        let symmetric = [&s, &t]
+            .into_iter()
+            .any(|m| theory.directionality(m) == Directionality::Symmetric);
+        if theory.src(&s) != theory.tgt(&t)
+            || theory.tgt(&s) != theory.src(&t)
+            || (symmetric && s != t)
+        {
+            return Err(InverseError::MorTypes(f, g));
+        }
+        for (h, k) in [(&f, &g), (&g, &f)] {
+            if let Some(other) = self.get(h).filter(|other| *other != k) {
+                return Err(InverseError::AlreadyDeclared(h.clone(), other.clone()));
+            }
+        }
+        self.insert(f.clone(), g.clone());
+        self.insert(g, f);
+        Ok(())
+    }
+
+    /// Removes the declared inverse of a morphism, returning it if it existed.
+    pub fn undeclare(&mut self, f: &Id) -> Option<Id> {
+        let g = self.remove(f)?;
+        self.remove(&g);
+        Some(g)
+    }
+
+    /// Validates the declared inverses against a model.
+    pub fn validate_in<Cat>(
+        &self,
+        model: &DiscreteDblModel<Id, Cat>,
+    ) -> Result<(), NonEmpty<InvalidInverse<Id>>>
+    where
+        Id: Ord,
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        validate::wrap_errors(self.iter_invalid_in(model))
+    }
+
+    /** Iterates over failures of the declared inverses to be well defined.
+
+    Morphisms are considered in the order they were added to the model, except
+    for dangling morphisms, which come last in the order of their IDs.
+     */
+    pub fn iter_invalid_in<'a, Cat>(
+        &'a self,
+        model: &'a DiscreteDblModel<Id, Cat>,
+    ) -> impl Iterator<Item = InvalidInverse<Id>> + 'a
+    where
+        Id: Ord,
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        let endpoint_errors = model.morphism_generators().filter_map(|f| {
+            let g = self.get(&f)?;
+            (model.has_mor(&g.clone().into()) && !has_opposite_endpoints(model, &f, g))
+                .then_some(InvalidInverse::Endpoints(f))
+        });
+        let mut dangling: Vec<_> = self
+            .iter()
+            .map(|(f, _)| f)
+            .filter(|f| !model.has_mor(&(*f).clone().into()))
+            .collect();
+        dangling.sort();
+        let dangling_errors = dangling.into_iter().map(|f| InvalidInverse::Dangling(f.clone()));
+        endpoint_errors.chain(dangling_errors)
+    }
+}
+
+fn has_opposite_endpoints<S: ModelStore>(store: &S, f: &S::Id, g: &S::Id) -> bool {
+    let (dom, cod) = (store.lookup_dom(f), store.lookup_cod(f));
+    dom.is_some() && cod.is_some() && dom == store.lookup_cod(g) && cod == store.lookup_dom(g)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ustr::{ustr, Ustr};
+
+    use super::*;
+    use crate::dbl::model::UstrDiscreteDblModel;
+    use crate::one::fin_category::FinMor;
+    use crate::stdlib::theories::th_category;
+    use crate::zero::Set;
+
+    #[test]
+    fn reachability_through_inverses() {
+        let th = Arc::new(th_category());
+        let ob = ustr("Object");
+        let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(th);
+        for x in ["x", "y", "z"] {
+            model.add_ob(ustr(x), ob);
+        }
+        model.add_mor(ustr("f"), ustr("x"), ustr("y"), FinMor::Id(ob));
+        model.add_mor(ustr("g"), ustr("y"), ustr("x"), FinMor::Id(ob));
+        model.add_mor(ustr("h"), ustr("y"), ustr("z"), FinMor::Id(ob));
+
+        let mut inverses: InverseColumn<Ustr> = Default::default();
+        assert_eq!(
+            inverses.declare(&model, ustr("f"), ustr("h")),
+            Err(InverseError::Endpoints(ustr("f"), ustr("h")))
+        );
+        assert!(inverses.declare(&model, ustr("f"), ustr("g")).is_ok());
+        assert_eq!(inverses.inverse(&ustr("g")), Some(&ustr("f")));
+        assert!(inverses.validate_in(&model).is_ok());
+
+        // Following only `f` and `h`, the object `x` is reachable from `y` just
+        // when `f` can be traversed backwards through its inverse.
+        let follow = |f: &Ustr| *f != ustr("g");
+        let reached = model.reachable(&ustr("y"), follow, None);
+        assert!(!reached.contains(&ustr("x")));
+        let reached = model.reachable(&ustr("y"), follow, Some(&inverses));
+        assert!(reached.contains(&ustr("x")) && reached.contains(&ustr("z")));
+
+        // Removing the inverse from the model leaves a dangling declaration.
+        let sub = model.induced_submodel([ustr("f"), ustr("h")]);
+        assert_eq!(
+            inverses.validate_in(&sub).map_err(Vec::from),
+            Err(vec![InvalidInverse::Dangling(ustr("g"))])
+        );
+
+        let reached = sub.reachable(&ustr("y"), follow, Some(&inverses));
+        assert!(!reached.contains(&ustr("x")));
+
+        // Dangling declarations are reported in the order of their IDs.
+        let sub = model.induced_submodel([ustr("h")]);
+        assert_eq!(
+            inverses.validate_in(&sub).map_err(Vec::from),
+            Err(vec![InvalidInverse::Dangling(ustr("f")), InvalidInverse::Dangling(ustr("g"))])
+        );
+
+        // Moving an endpoint breaks the declaration.
+        model.update_cod(ustr("g"), Some(ustr("z")));
+        assert_eq!(
+            inverses.iter_invalid_in(&model).collect::<Vec<_>>(),
+            vec![InvalidInverse::Endpoints(ustr("f")), InvalidInverse::Endpoints(ustr("g"))]
+        );
+        let reached = model.reachable(&ustr("y"), follow, Some(&inverses));
+        assert!(!reached.contains(&ustr("x")));
+    }
+}
@@ -0,0 +1,488 @@
+/*! Key constraints on models of schemas.
+
+In a model of the [theory of schemas](crate::stdlib::theories::th_schema), an
+entity can be given keys: lists of attributes whose values, taken together,
+identify the rows of the entity. Like the [provenance](crate::prelude::ProvenanceColumn) of
+generators, key constraints are not part of the model itself and are kept in a
+separate [`KeyConstraints`] collection. The collection can be checked against a
+model, to ensure that the keys refer to attributes of the right entities, and
+against an [instance](SchemaInstance) of the model, to ensure that no two rows
+agree on a key.
+
+A model can also be read as a database in its own right, whose elements are
+the basic objects and whose attributes are the basic morphisms of a given type.
+Uniqueness of such an attribute is checked by
+[`check_unique`](DiscreteDblModel::check_unique).
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use derivative::Derivative;
+use nonempty::NonEmpty;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+use super::model::{DiscreteDblModel, FgDblModel};
+use crate::one::{FgCategory, FinGraph, Graph};
+use crate::validate;
+
+/// Kind of a key constraint, in the sense of SQL.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum KeyKind {
+    /// Primary key, whose attributes must be defined in every row.
+    Primary,
+
+    /// Uniqueness constraint, which ignores rows with undefined attributes.
+    #[default]
+    Unique,
+}
+
+/// A key on an entity, comprising one or more of its attributes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct KeyConstraint<Id> {
+    /// Entity that the key is on.
+    pub entity: Id,
+
+    /// Attributes of the entity comprising the key.
+    pub attributes: Vec<Id>,
+
+    /// Kind of key.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: KeyKind,
+}
+
+/// Key constraints on a model, in the order they were added.
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+#[derivative(PartialEq(bound = "Id: PartialEq"))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct KeyConstraints<Id>(Vec<KeyConstraint<Id>>);
+
+/** A key constraint that is not well defined in a model.
+
+Key constraints are referred to by their position in the collection.
+ */
+#[derive(Debug, Error, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum InvalidKeyConstraint<Id> {
+    /// Entity of key is not a basic object in the model.
+    #[error("Entity of key {0} is not an object in the model")]
+    Entity(usize),
+
+    /// Key has no attributes.
+    #[error("Key {0} has no attributes")]
+    Empty(usize),
+
+    /// Attribute of key is not a basic morphism out of the entity.
+    #[error("Attribute `{1}` of key {0} is not an attribute of its entity")]
+    Attribute(usize, Id),
+
+    /// Attribute occurs more than once in key.
+    #[error("Attribute `{1}` occurs more than once in key {0}")]
+    DuplicateAttribute(usize, Id),
+
+    /// Entity has more than one primary key.
+    #[error("Entity `{0}` has more than one primary key")]
+    MultiplePrimary(Id),
+}
+
+/// A violation of a key constraint by an instance.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum KeyViolation {
+    /// Row agrees on the key with an earlier row.
+    #[error("Row {row} duplicates row {first} on key {key}")]
+    Duplicate {
+        /// Index of the key constraint.
+        key: usize,
+        /// The offending row.
+        row: usize,
+        /// The earliest row with the same values on the key.
+        first: usize,
+    },
+
+    /// Row has an undefined attribute in a primary key.
+    #[error("Row {row} has undefined value in primary key {key}")]
+    Undefined {
+        /// Index of the key constraint.
+        key: usize,
+        /// The offending row.
+        row: usize,
+    },
+}
+
+/** Elements of a model sharing the same value under an attribute.
+
+Reported by [`check_unique`](DiscreteDblModel::check_unique).
+ */
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("{} elements have the same value `{value}`", .entities.len())]
+pub struct DuplicateKey<Id> {
+    /// The colliding elements, in the order their attributes were added.
+    pub entities: Vec<Id>,
+
+    /// The value shared by the elements.
+    pub value: Id,
+}
+
+impl<Id, Cat> DiscreteDblModel<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /** Checks that an attribute uniquely identifies the elements of the model.
+
+    The elements are the domains and the values are the codomains of the basic
+    morphisms of type `attr`. Each value shared by more than one element is
+    reported once, with all the elements having it. Morphisms without a domain
+    or codomain are ignored.
+     */
+    pub fn check_unique(&self, attr: &Cat::Mor) -> Result<(), Vec<DuplicateKey<Id>>> {
+        let mut values = Vec::new();
+        let mut entities: HashMap<&Id, Vec<Id>> = HashMap::new();
+        for f in self.morphism_generators().filter(|f| self.mor_gen_type(f) == *attr) {
+            let (Some(entity), Some(value)) = (self.get_dom(&f), self.get_cod(&f)) else {
+                continue;
+            };
+            let group = entities.entry(value).or_insert_with(|| {
+                values.push(value);
+                Vec::new()
+            });
+            if !group.contains(entity) {
+                group.push(entity.clone());
+            }
+        }
+        let dups: Vec<_> = values
+            .into_iter()
+            .filter_map(|value| {
+                let entities = entities.remove(value)?;
+                (entities.len() > 1).then(|| DuplicateKey {
+                    entities,
+                    value: value.clone(),
+                })
+            })
+            .collect();
+        if dups.is_empty() {
+            Ok(())
+        } else {
+            Err(dups)
+        }
+    }
+}
+
+/** Tabular data for an instance of a model of a schema.
+
+Each entity has a number of rows, and each attribute assigns a value to some or
+all of the rows of its entity.
+ */
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct SchemaInstance<Id, T> {
+    rows: HashMap<Id, usize>,
+    values: HashMap<Id, Vec<Option<T>>>,
+}
+
+impl<Id, T> SchemaInstance<Id, T>
+where
+    Id: Eq + Hash,
+{
+    /// Number of rows of an entity.
+    pub fn row_count(&self, entity: &Id) -> usize {
+        self.rows.get(entity).copied().unwrap_or(0)
+    }
+
+    /// Adds rows to an entity, returning the range of the new rows.
+    pub fn add_rows(&mut self, entity: Id, n: usize) -> std::ops::Range<usize> {
+        let count = self.rows.entry(entity).or_insert(0);
+        *count += n;
+        (*count - n)..*count
+    }
+
+    /// Gets the value of an attribute at a row, if defined.
+    pub fn get(&self, attr: &Id, row: usize) -> Option<&T> {
+        self.values.get(attr).and_then(|col| col.get(row)).and_then(|x| x.as_ref())
+    }
+
+    /// Sets the value of an attribute at a row.
+    pub fn set(&mut self, attr: Id, row: usize, value: T) {
+        let col = self.values.entry(attr).or_default();
+        if col.len() <= row {
+            col.resize_with(row + 1, || None);
+        }
+        col[row] = Some(value);
+    }
+}
+
+impl<Id> KeyConstraints<Id>
+where
+    Id: Eq + Clone + Hash,
+{
+    /// Adds a key constraint, returning its index.
+    pub fn add(&mut self, key: KeyConstraint<Id>) -> usize {
+        self.0.push(key);
+        self.0.len() - 1
+    }
+
+    /// Removes a key constraint by its index, if it exists.
+    pub fn remove(&mut self, index: usize) -> Option<KeyConstraint<Id>> {
+        (index < self.0.len()).then(|| self.0.remove(index))
+    }
+
+    /// Iterates over the key constraints.
+    pub fn iter(&self) -> impl Iterator<Item = &KeyConstraint<Id>> {
+        self.0.iter()
+    }
+
+    /// Iterates over the key constraints on an entity, with their indices.
+    pub fn keys_on<'a>(
+        &'a self,
+        entity: &'a Id,
+    ) -> impl Iterator<Item = (usize, &'a KeyConstraint<Id>)> + 'a {
+        self.0.iter().enumerate().filter(move |(_, key)| key.entity == *entity)
+    }
+
+    /// Validates the key constraints against a model.
+    pub fn validate_in<Cat>(
+        &self,
+        model: &DiscreteDblModel<Id, Cat>,
+    ) -> Result<(), NonEmpty<InvalidKeyConstraint<Id>>>
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        validate::wrap_errors(self.iter_invalid_in(model))
+    }
+
+    /// Iterates over failures of the key constraints to be well defined.
+    pub fn iter_invalid_in<'a, Cat>(
+        &'a self,
+        model: &'a DiscreteDblModel<Id, Cat>,
+    ) -> impl Iterator<Item = InvalidKeyConstraint<Id>> + 'a
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        let graph = model.generating_graph();
+        let key_errors = self.0.iter().enumerate().flat_map(move |(i, key)| {
+            if !graph.has_vertex(&key.entity) {
+                return vec![InvalidKeyConstraint::Entity(i)];
+            }
+            if key.attributes.is_empty() {
+                return vec![InvalidKeyConstraint::Empty(i)];
+            }
+            let mut errs = Vec::new();
+            for (j, attr) in key.attributes.iter().enumerate() {
+                if model.get_dom(attr) != Some(&key.entity) {
+                    errs.push(InvalidKeyConstraint::Attribute(i, attr.clone()));
+                } else if key.attributes[..j].contains(attr) {
+                    errs.push(InvalidKeyConstraint::DuplicateAttribute(i, attr.clone()));
+                }
+            }
+            errs
+        });
+        let primary_errors = graph.vertices().filter_map(move |entity| {
+            let n = self.keys_on(&entity).filter(|(_, key)| key.kind == KeyKind::Primary).count();
+            (n > 1).then_some(InvalidKeyConstraint::MultiplePrimary(entity))
+        });
+        key_errors.chain(primary_errors)
+    }
+
+    /** Checks that an instance satisfies the key constraints.
+
+    The key constraints are assumed to be well defined. Every row that agrees
+    with an earlier row on a key is reported, so that a group of `n` rows with
+    the same key produces `n-1` violations.
+     */
+    pub fn check_instance<T>(
+        &self,
+        instance: &SchemaInstance<Id, T>,
+    ) -> Result<(), NonEmpty<KeyViolation>>
+    where
+        T: Eq + Hash,
+    {
+        validate::wrap_errors(self.iter_violations(instance))
+    }
+
+    /// Iterates over violations of the key constraints by an instance.
+    pub fn iter_violations<'a, T>(
+        &'a self,
+        instance: &'a SchemaInstance<Id, T>,
+    ) -> impl Iterator<Item = KeyViolation> + 'a
+    where
+        T: Eq + Hash,
+    {
+        self.0.iter().enumerate().flat_map(|(key, constraint)| {
+            let mut first_rows: HashMap<Vec<&T>, usize> = HashMap::new();
+            let mut errs = Vec::new();
+            for row in 0..instance.row_count(&constraint.entity) {
+                let values: Option<Vec<_>> =
+                    constraint.attributes.iter().map(|attr| instance.get(attr, row)).collect();
+                match values {
+                    Some(values) => {
+                        if let Some(first) = first_rows.get(&values) {
+                            errs.push(KeyViolation::Duplicate {
+                                key,
+                                row,
+                                first: *first,
+                            });
+                        } else {
+                            first_rows.insert(values, row);
+                        }
+                    }
+                    None if constraint.kind == KeyKind::Primary => {
+                        errs.push(KeyViolation::Undefined { key, row })
+                    }
+                    None => (),
+                }
+            }
+            errs
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbl::model::UstrDiscreteDblModel;
+    use crate::model;
+    use crate::one::fin_category::FinMor;
+    use crate::stdlib::theories::th_schema;
+    use std::sync::Arc;
+    use ustr::{ustr, Ustr};
+
+    fn enrollment_model() -> UstrDiscreteDblModel {
+        model! {
+            theory: Arc::new(th_schema()),
+            obs: [
+                "Enrollment" => "Entity",
+                "Course" => "Entity",
+                "Text" => "AttrType",
+            ],
+            mors: [
+                "student" => ("Enrollment", "Text", ustr("Attr")),
+                "course" => ("Enrollment", "Text", ustr("Attr")),
+                "title" => ("Course", "Text", ustr("Attr")),
+            ],
+        }
+        .expect("Model should be valid")
+    }
+
+    #[test]
+    fn validate_keys() {
+        let model = enrollment_model();
+        let mut keys: KeyConstraints<Ustr> = Default::default();
+        keys.add(KeyConstraint {
+            entity: ustr("Enrollment"),
+            attributes: vec![ustr("student"), ustr("course")],
+            kind: KeyKind::Primary,
+        });
+        assert!(keys.validate_in(&model).is_ok());
+
+        keys.add(KeyConstraint {
+            entity: ustr("Enrollment"),
+            attributes: vec![ustr("title"), ustr("course"), ustr("course")],
+            kind: KeyKind::Primary,
+        });
+        keys.add(KeyConstraint {
+            entity: ustr("Student"),
+            attributes: vec![],
+            kind: KeyKind::Unique,
+        });
+        let errs: Vec<_> = keys.iter_invalid_in(&model).collect();
+        assert_eq!(
+            errs,
+            vec![
+                InvalidKeyConstraint::Attribute(1, ustr("title")),
+                InvalidKeyConstraint::DuplicateAttribute(1, ustr("course")),
+                InvalidKeyConstraint::Entity(2),
+                InvalidKeyConstraint::MultiplePrimary(ustr("Enrollment")),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_composite_key() {
+        let (enrollment, student, course) = (ustr("Enrollment"), ustr("student"), ustr("course"));
+        let mut keys: KeyConstraints<Ustr> = Default::default();
+        keys.add(KeyConstraint {
+            entity: enrollment,
+            attributes: vec![student, course],
+            kind: KeyKind::Primary,
+        });
+
+        let mut instance: SchemaInstance<Ustr, &str> = Default::default();
+        let rows = instance.add_rows(enrollment, 4);
+        assert_eq!(rows, 0..4);
+        for (row, (s, c)) in
+            [("alice", "math"), ("alice", "art"), ("bob", "math")].into_iter().enumerate()
+        {
+            instance.set(student, row, s);
+            instance.set(course, row, c);
+        }
+        instance.set(student, 3, "bob");
+        assert_eq!(
+            keys.check_instance(&instance).map_err(Vec::from),
+            Err(vec![KeyViolation::Undefined { key: 0, row: 3 }])
+        );
+
+        instance.set(course, 3, "math");
+        assert_eq!(
+            keys.check_instance(&instance).map_err(Vec::from),
+            Err(vec![KeyViolation::Duplicate {
+                key: 0,
+                row: 3,
+                first: 2
+            }])
+        );
+    }
+
+    #[test]
+    fn unique_attribute() {
+        let model: UstrDiscreteDblModel = model! {
+            theory: Arc::new(th_schema()),
+            obs: [
+                "alice" => "Entity",
+                "bob" => "Entity",
+                "carol" => "Entity",
+                "a@example.com" => "AttrType",
+                "c@example.com" => "AttrType",
+            ],
+            mors: [
+                "alice.email" => ("alice", "a@example.com", ustr("Attr")),
+                "bob.email" => ("bob", "c@example.com", ustr("Attr")),
+                "carol.email" => ("carol", "c@example.com", ustr("Attr")),
+            ],
+        }
+        .expect("Model should be valid");
+        let attr = FinMor::Generator(ustr("Attr"));
+        assert_eq!(
+            model.check_unique(&attr),
+            Err(vec![DuplicateKey {
+                entities: vec![ustr("bob"), ustr("carol")],
+                value: ustr("c@example.com"),
+            }])
+        );
+
+        // An element having the same value twice does not collide with itself.
+        let dups = enrollment_model().check_unique(&attr).unwrap_err();
+        assert_eq!(dups[0].entities, vec![ustr("Enrollment"), ustr("Course")]);
+    }
+}
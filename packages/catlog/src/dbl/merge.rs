@@ -0,0 +1,298 @@
+/*! Theory-specific validation of merged models.
+
+Merging models by a [pushout](super::model::ModelSpan::pushout) is a purely
+structural operation: it identifies the elements that the span says to identify
+and keeps everything else apart. A merge can succeed structurally and still
+produce a model that is nonsensical in the domain of the theory, such as a
+database schema in which an entity has two attributes with the same name.
+
+Such semantic conflicts are detected by [merge validators](MergeValidator),
+which inspect the merged model. Validators are collected in a
+[registry](MergeValidatorRegistry), so that each theory can contribute its own
+checks, and the registry is consulted by [`ModelSpan::pushout_checked`].
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use nonempty::NonEmpty;
+use thiserror::Error;
+use ustr::Ustr;
+
+use super::model::*;
+use super::theory::DiscreteDblTheory;
+use crate::one::fin_category::{FinMor, UstrFinCategory};
+use crate::one::*;
+use crate::validate;
+
+/** A validator of models produced by merging.
+
+A validator is specific to one or more theories, indicated by
+[`applies_to`](MergeValidator::applies_to). The validator is only run on models
+of the theories to which it applies.
+ */
+pub trait MergeValidator<Id, Cat: FgCategory>: Send + Sync {
+    /// Does the validator apply to models of the given theory?
+    fn applies_to(&self, theory: &DiscreteDblTheory<Cat>) -> bool;
+
+    /// Finds the semantic conflicts in a merged model.
+    fn conflicts(&self, merged: &DiscreteDblModel<Id, Cat>) -> Vec<MergeConflict<Id>>;
+}
+
+/** A semantic conflict in a merged model, found by a [`MergeValidator`].
+
+Validators for further theories should add variants for their conflicts.
+ */
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum MergeConflict<Id> {
+    /// Entity having several attributes with the same name and type.
+    #[error("Entity `{entity}` has several attributes named `{name}`")]
+    DuplicateAttr {
+        /// The entity having the attributes.
+        entity: Id,
+        /// The shared name of the attributes.
+        name: String,
+    },
+
+    /// Entity having attributes with the same name but different types.
+    #[error("Attributes named `{name}` on entity `{entity}` have different types")]
+    AttrTypeMismatch {
+        /// The entity having the attributes.
+        entity: Id,
+        /// The shared name of the attributes.
+        name: String,
+    },
+}
+
+/// A registry of [merge validators](MergeValidator).
+pub struct MergeValidatorRegistry<Id, Cat: FgCategory> {
+    validators: Vec<Box<dyn MergeValidator<Id, Cat>>>,
+}
+
+impl<Id, Cat: FgCategory> Default for MergeValidatorRegistry<Id, Cat> {
+    fn default() -> Self {
+        Self {
+            validators: Vec::new(),
+        }
+    }
+}
+
+impl<Id, Cat> MergeValidatorRegistry<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Registers a validator.
+    pub fn register(&mut self, validator: Box<dyn MergeValidator<Id, Cat>>) {
+        self.validators.push(validator);
+    }
+
+    /// Finds the conflicts in a merged model using all applicable validators.
+    pub fn conflicts(&self, merged: &DiscreteDblModel<Id, Cat>) -> Vec<MergeConflict<Id>> {
+        let theory = merged.theory();
+        self.validators
+            .iter()
+            .filter(|validator| validator.applies_to(theory))
+            .flat_map(|validator| validator.conflicts(merged))
+            .collect()
+    }
+}
+
+impl<Id, Cat> ModelSpan<Id, Cat>
+where
+    Id: Eq + Clone + Hash + Ord,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /** Computes the pushout of the span and validates the merged model.
+
+    Structural failures of the [pushout](ModelSpan::pushout), including invalid
+    feet, are reported as errors rather than panics. If the pushout exists, its
+    apex is checked by the applicable validators in the registry and any
+    semantic conflicts are reported.
+     */
+    pub fn pushout_checked(
+        self,
+        registry: &MergeValidatorRegistry<Id, Cat>,
+    ) -> Result<ModelCospan<Id, Cat>, MergeError<Id>> {
+        let cospan = self.pushout().map_err(MergeError::Pushout)?;
+        validate::wrap_errors(registry.conflicts(cospan.apex()).into_iter())
+            .map_err(MergeError::Conflicts)?;
+        Ok(cospan)
+    }
+}
+
+/// A failure to merge models.
+#[derive(Debug, Error)]
+pub enum MergeError<Id> {
+    /// Structural failure to compute the pushout.
+    #[error("{0}")]
+    Pushout(PushoutError<Id>),
+
+    /// Semantic conflicts in the merged model.
+    #[error("Merged model has {} conflict(s)", .0.len())]
+    Conflicts(NonEmpty<MergeConflict<Id>>),
+}
+
+/** Merge validator for theories of schemas, such as the
+[theory of schemas](crate::stdlib::theories::th_schema).
+
+Attributes in a schema are identified by their IDs, but they are presented to
+users by name, and two attributes of the same entity should not share a name.
+The attributes are the morphisms whose type is a basic morphism type between
+distinct object types in the theory, like `Attr: Entity -> AttrType`, and the
+validator applies to theories having such types. The validator is given a
+function that names each attribute. Attributes of an entity with the same name
+are reported as duplicates if they also have the same attribute type and as a
+type mismatch otherwise.
+ */
+pub struct SchemaMergeValidator<F>(pub F);
+
+/// Basic morphism types between distinct object types, typing attributes.
+fn attr_mor_types(theory: &DiscreteDblTheory<UstrFinCategory>) -> Vec<FinMor<Ustr, Ustr>> {
+    let cat = theory.category();
+    cat.morphism_generators()
+        .filter(|e| cat.morphism_generator_dom(e) != cat.morphism_generator_cod(e))
+        .map(FinMor::Generator)
+        .collect()
+}
+
+impl<Id, F> MergeValidator<Id, UstrFinCategory> for SchemaMergeValidator<F>
+where
+    Id: Eq + Clone + Hash,
+    F: Fn(&Id) -> String + Send + Sync,
+{
+    fn applies_to(&self, theory: &DiscreteDblTheory<UstrFinCategory>) -> bool {
+        !attr_mor_types(theory).is_empty()
+    }
+
+    fn conflicts(&self, merged: &DiscreteDblModel<Id, UstrFinCategory>) -> Vec<MergeConflict<Id>> {
+        let attrs = attr_mor_types(merged.theory());
+        let mut keys = Vec::new();
+        let mut attr_types: HashMap<(Id, String), Vec<Option<Id>>> = HashMap::new();
+        for f in merged.morphism_generators() {
+            if !merged.lookup_mor_type(&f).is_some_and(|typ| attrs.contains(&typ)) {
+                continue;
+            }
+            let Some(entity) = merged.get_dom(&f) else {
+                continue;
+            };
+            let key = (entity.clone(), (self.0)(&f));
+            let types = attr_types.entry(key.clone()).or_insert_with(|| {
+                keys.push(key);
+                Vec::new()
+            });
+            types.push(merged.get_cod(&f).cloned());
+        }
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let types = &attr_types[&key];
+                let (entity, name) = key;
+                if types.len() < 2 {
+                    None
+                } else if types.iter().all(|typ| *typ == types[0]) {
+                    Some(MergeConflict::DuplicateAttr { entity, name })
+                } else {
+                    Some(MergeConflict::AttrTypeMismatch { entity, name })
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ustr::{ustr, Ustr};
+
+    use super::*;
+    use crate::dbl::model_morphism::DiscreteDblModelMapping;
+    use crate::stdlib::theories::*;
+    use crate::validate::Validate;
+
+    fn name_before_dot(f: &Ustr) -> String {
+        f.as_str().split('.').next().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn schema_merge_conflicts() {
+        let th = Arc::new(th_schema());
+        let (entity, attr_type, attr) = (ustr("Entity"), ustr("AttrType"), ustr("Attr"));
+        let mut registry: MergeValidatorRegistry<Ustr, _> = MergeValidatorRegistry::new();
+        registry.register(Box::new(SchemaMergeValidator(name_before_dot)));
+        assert!(registry.validators[0].applies_to(&th));
+        assert!(!registry.validators[0].applies_to(&th_category()));
+        assert!(!registry.validators[0].applies_to(&th_signed_category()));
+
+        // Attribute types are found from the theory, whatever they are called.
+        let th_tables = crate::theory! {
+            ob_types: [Table, Value],
+            mor_types: [Column: Table -> Value],
+        }
+        .unwrap();
+        assert!(registry.validators[0].applies_to(&th_tables));
+
+        // Both fragments give the entity an attribute named "name".
+        let mut left = DiscreteDblModel::new(th.clone());
+        left.add_ob(ustr("Person"), entity);
+        left.add_ob(ustr("String"), attr_type);
+        left.add_mor(ustr("name.1"), ustr("Person"), ustr("String"), FinMor::Generator(attr));
+        let mut right = DiscreteDblModel::new(th.clone());
+        right.add_ob(ustr("Person"), entity);
+        right.add_ob(ustr("Int"), attr_type);
+        right.add_mor(ustr("name.2"), ustr("Person"), ustr("Int"), FinMor::Generator(attr));
+
+        let mut apex = DiscreteDblModel::new(th.clone());
+        apex.add_ob(ustr("Person"), entity);
+        let mut leg: DiscreteDblModelMapping<_, _> = Default::default();
+        leg.assign_ob(ustr("Person"), ustr("Person"));
+        let span = ModelSpan::new(left.clone(), leg.clone(), apex.clone(), leg.clone(), right);
+
+        // The merge succeeds structurally but the attribute types differ.
+        assert!(span.validate().is_ok());
+        let Err(MergeError::Conflicts(conflicts)) = span.pushout_checked(&registry) else {
+            panic!("Merge should fail with conflicts");
+        };
+        assert_eq!(
+            conflicts,
+            NonEmpty::new(MergeConflict::AttrTypeMismatch {
+                entity: ustr("Person"),
+                name: "name".into(),
+            })
+        );
+
+        // When the attribute types are also merged, the attributes are duplicates.
+        let mut right = DiscreteDblModel::new(th.clone());
+        right.add_ob(ustr("Person"), entity);
+        right.add_ob(ustr("String"), attr_type);
+        right.add_mor(ustr("name.2"), ustr("Person"), ustr("String"), FinMor::Generator(attr));
+        apex.add_ob(ustr("String"), attr_type);
+        leg.assign_ob(ustr("String"), ustr("String"));
+        let span =
+            ModelSpan::new(left.clone(), leg.clone(), apex.clone(), leg.clone(), right.clone());
+        let Err(MergeError::Conflicts(conflicts)) = span.pushout_checked(&registry) else {
+            panic!("Merge should fail with conflicts");
+        };
+        assert_eq!(
+            conflicts,
+            NonEmpty::new(MergeConflict::DuplicateAttr {
+                entity: ustr("Person"),
+                name: "name".into(),
+            })
+        );
+
+        // Without any validators, the same merge succeeds.
+        let span = ModelSpan::new(left.clone(), leg.clone(), apex.clone(), leg.clone(), right);
+        assert!(span.pushout_checked(&MergeValidatorRegistry::new()).is_ok());
+    }
+}
@@ -1,9 +1,28 @@
 //! Double category theory and two-dimensional categorical logic.
 
 pub mod computad;
+pub mod confidence;
+pub mod description;
 pub mod diagram;
 pub mod pasting;
 
+pub mod key;
+pub mod merge;
 pub mod model;
 pub mod model_morphism;
+pub mod namespace;
+pub mod observer;
+pub mod render;
+pub mod selection;
 pub mod theory;
+pub mod theory_morphism;
+
+// Bookkeeping attached to models, whose items are exported by the prelude.
+pub(crate) mod derived;
+pub(crate) mod generator_column;
+pub(crate) mod inverse;
+pub(crate) mod ownership;
+pub(crate) mod provenance;
+
+#[cfg(test)]
+mod empty_tests;
@@ -35,11 +35,16 @@ In addition, a model has the following operations:
   whose type is the composite of the corresponding morphism types.
  */
 
-use std::hash::Hash;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hash};
 use std::iter::Iterator;
 use std::sync::Arc;
 
 use derivative::Derivative;
+use either::Either;
+use nonempty::NonEmpty;
+use thiserror::Error;
 use ustr::Ustr;
 
 #[cfg(feature = "serde")]
@@ -47,11 +52,23 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde-wasm")]
 use tsify_next::Tsify;
 
-use super::theory::{DblTheory, DiscreteDblTheory};
-use crate::one::fin_category::{FpCategory, InvalidFpCategory, UstrFinCategory};
+use super::derived::DerivedCache;
+use super::inverse::InverseColumn;
+use super::model_morphism::{
+    DblModelMapping, DiscreteDblModelMapping, DiscreteDblModelMorphism, InvalidDblModelMorphism,
+};
+use super::observer::{BoxedModelObserver, ObFiberIndex};
+use super::provenance::{EditStamp, ProvenanceColumn};
+use super::theory::{DblTheory, Directionality, DiscreteDblTheory};
+use crate::layout::{self, ClusterTree, ClusteringOptions};
+use crate::one::fin_category::{
+    FinCategory, FinMor, FpCategory, InvalidFpCategory, UstrFinCategory,
+};
+use crate::one::graph_algorithms::{self, InvalidWeight};
+use crate::one::typed_graph::TypedGraph;
 use crate::one::*;
-use crate::validate::{self, Validate};
-use crate::zero::{Column, IndexedHashColumn, Mapping};
+use crate::validate::{self, BatchError, Validate};
+use crate::zero::{Column, FinSet, HashColumn, HashFinSet, IndexedHashColumn, Mapping};
 
 /** A model of a double theory.
 
@@ -149,15 +166,41 @@ finite presentation of a category sliced over the object and morphism types
 comprising the theory. A type theorist would call it a ["displayed
 category"](https://ncatlab.org/nlab/show/displayed+category).
 */
-#[derive(Clone, Derivative, Debug)]
+#[derive(Derivative)]
+#[derivative(Clone(bound = "Id: Clone, Cat::Ob: Clone, Cat::Mor: Clone"))]
+#[derivative(Debug(
+    bound = "Id: std::fmt::Debug, Cat: std::fmt::Debug, Cat::Ob: std::fmt::Debug, Cat::Mor: std::fmt::Debug"
+))]
 #[derivative(PartialEq(bound = "Id: Eq + Hash"))]
 #[derivative(Eq(bound = "Id: Eq + Hash"))]
 pub struct DiscreteDblModel<Id, Cat: FgCategory> {
     #[derivative(PartialEq(compare_with = "Arc::ptr_eq"))]
     theory: Arc<DiscreteDblTheory<Cat>>,
     category: FpCategory<Id, Id, Id>,
-    ob_types: IndexedHashColumn<Id, Cat::Ob>,
+    ob_types: HashColumn<Id, Cat::Ob>,
     mor_types: IndexedHashColumn<Id, Cat::Mor>,
+    #[derivative(
+        Clone(clone_with = "ObFiberIndex::detached"),
+        Debug = "ignore",
+        PartialEq = "ignore"
+    )]
+    ob_fibers: ObFiberIndex<Id, Cat::Ob>,
+    #[derivative(PartialEq = "ignore")]
+    revision: u64,
+    #[derivative(PartialEq = "ignore")]
+    provenance: ProvenanceColumn<Id>,
+    #[derivative(PartialEq = "ignore")]
+    edit_stamp: Option<EditStamp>,
+    #[derivative(
+        Clone(clone_with = "no_observers"),
+        Debug = "ignore",
+        PartialEq = "ignore"
+    )]
+    observers: Vec<BoxedModelObserver<Id, Cat::Ob, Cat::Mor>>,
+}
+
+fn no_observers<T>(_: &[T]) -> Vec<T> {
+    Vec::new()
 }
 
 /// A model of a discrete double theory where both the model and theory have
@@ -181,6 +224,11 @@ where
             category: Default::default(),
             ob_types: Default::default(),
             mor_types: Default::default(),
+            ob_fibers: Default::default(),
+            revision: 0,
+            provenance: Default::default(),
+            edit_stamp: None,
+            observers: Default::default(),
         }
     }
 
@@ -199,77 +247,770 @@ where
         self.category.is_free()
     }
 
+    /** Revision of the model, incremented by every mutation that changes it.
+
+    Data computed from the model, such as a [selection](super::selection),
+    can record the revision to detect when it has gone stale. Clones start at
+    the revision of the original, and the revision is ignored by equality.
+     */
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /** Provenance of the generators of the model.
+
+    Like the revision, provenance is ignored by equality. It is carried over by
+    [submodels](Self::induced_submodel) and merged by
+    [pushouts](ModelSpan::pushout).
+     */
+    pub fn provenance(&self) -> &ProvenanceColumn<Id> {
+        &self.provenance
+    }
+
+    /// Mutable access to the provenance, as when loading it from a document.
+    pub fn provenance_mut(&mut self) -> &mut ProvenanceColumn<Id> {
+        &mut self.provenance
+    }
+
+    /** Sets the author and time to record for subsequent mutations.
+
+    While a stamp is set, each mutation that changes a generator
+    [records](ProvenanceColumn::record) it as created or modified by the
+    stamped author at the stamped time, and removing a generator forgets its
+    provenance. Without a stamp, mutations leave the provenance unchanged,
+    except for removals.
+     */
+    pub fn set_edit_stamp(&mut self, stamp: Option<EditStamp>) {
+        self.edit_stamp = stamp;
+    }
+
+    /// Records an edit to a generator if an edit stamp is set.
+    fn stamp(&mut self, id: &Id) {
+        if let Some(stamp) = self.edit_stamp {
+            self.provenance.record(id.clone(), stamp.author_id, stamp.at);
+        }
+    }
+
+    /** Registers an observer of mutations to the model.
+
+    The observer is notified after every subsequent mutation. Observers are not
+    carried over when the model is cloned.
+     */
+    pub fn add_observer(&mut self, observer: BoxedModelObserver<Id, Cat::Ob, Cat::Mor>) {
+        self.observers.push(observer);
+    }
+
     /// Adds a basic object to the model.
     pub fn add_ob(&mut self, x: Id, typ: Cat::Ob) -> bool {
-        self.ob_types.set(x.clone(), typ);
-        self.category.add_ob_generator(x)
+        let old = self.ob_types.set(x.clone(), typ.clone());
+        let is_new = self.category.add_ob_generator(x.clone());
+        if is_new || old.as_ref() != Some(&typ) {
+            self.revision += 1;
+            self.stamp(&x);
+            if let Some(old) = &old {
+                self.ob_fibers.remove(&x, old);
+            }
+            self.ob_fibers.insert(x.clone(), typ.clone());
+        }
+        for observer in self.observers.iter_mut() {
+            match &old {
+                Some(old) if !is_new => {
+                    if *old != typ {
+                        observer.on_retype_ob(&x, old, &typ);
+                    }
+                }
+                _ => observer.on_add_ob(&x, &typ),
+            }
+        }
+        is_new
     }
 
     /// Adds a basic morphism to the model.
     pub fn add_mor(&mut self, f: Id, dom: Id, cod: Id, typ: Cat::Mor) -> bool {
-        self.mor_types.set(f.clone(), typ);
-        self.category.add_mor_generator(f, dom, cod)
+        let is_new = self.make_mor(f.clone(), typ);
+        self.update_dom(f.clone(), Some(dom));
+        self.update_cod(f, Some(cod));
+        is_new
     }
 
     /// Adds a basic morphism to the model without setting its (co)domain.
     pub fn make_mor(&mut self, f: Id, typ: Cat::Mor) -> bool {
-        self.mor_types.set(f.clone(), typ);
-        self.category.make_mor_generator(f)
+        let old = self.mor_types.set(f.clone(), typ.clone());
+        let is_new = self.category.make_mor_generator(f.clone());
+        if is_new || old.as_ref() != Some(&typ) {
+            self.revision += 1;
+            self.stamp(&f);
+        }
+        for observer in self.observers.iter_mut() {
+            match &old {
+                Some(old) if !is_new => {
+                    if *old != typ {
+                        observer.on_retype_mor(&f, old, &typ);
+                    }
+                }
+                _ => observer.on_add_mor(&f, &typ),
+            }
+        }
+        is_new
     }
 
     /// Updates the domain of a morphism, setting or unsetting it.
     pub fn update_dom(&mut self, f: Id, x: Option<Id>) -> Option<Id> {
-        self.category.update_dom(f, x)
+        let old = self.category.update_dom(f.clone(), x.clone());
+        if old != x {
+            self.revision += 1;
+            self.stamp(&f);
+            for observer in self.observers.iter_mut() {
+                observer.on_update_dom(&f, old.as_ref(), x.as_ref());
+            }
+        }
+        old
     }
 
     /// Updates the codomain of a morphism, setting or unsetting it.
     pub fn update_cod(&mut self, f: Id, x: Option<Id>) -> Option<Id> {
-        self.category.update_cod(f, x)
+        let old = self.category.update_cod(f.clone(), x.clone());
+        if old != x {
+            self.revision += 1;
+            self.stamp(&f);
+            for observer in self.observers.iter_mut() {
+                observer.on_update_cod(&f, old.as_ref(), x.as_ref());
+            }
+        }
+        old
     }
 
-    /// Iterates over failures to be well-defined model.
-    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidDiscreteDblModel<Id>> + '_ {
+    /** Removes a basic object from the model, returning whether it was present.
+
+    Basic morphisms with the object as their domain or codomain are kept, but
+    the (co)domain is unset, so that the model remains well defined only once
+    they are updated or removed.
+     */
+    pub fn remove_ob(&mut self, x: &Id) -> bool {
+        let Some(typ) = self.ob_types.unset(x) else {
+            return false;
+        };
+        let generators = self.category.generators();
+        let (ins, outs): (Vec<_>, Vec<_>) =
+            (generators.in_edges(x).collect(), generators.out_edges(x).collect());
+        for f in outs {
+            self.update_dom(f, None);
+        }
+        for f in ins {
+            self.update_cod(f, None);
+        }
+        self.category.remove_ob_generator(x);
+        self.provenance.remove(x);
+        self.ob_fibers.remove(x, &typ);
+        self.revision += 1;
+        for observer in self.observers.iter_mut() {
+            observer.on_remove_ob(x, &typ);
+        }
+        true
+    }
+
+    /** Removes a basic morphism from the model, returning whether it was present.
+
+    The (co)domain of the morphism is unset before it is removed, and equations
+    mentioning the morphism are removed along with it.
+     */
+    pub fn remove_mor(&mut self, f: &Id) -> bool {
+        let Some(typ) = self.mor_types.unset(f) else {
+            return false;
+        };
+        self.update_dom(f.clone(), None);
+        self.update_cod(f.clone(), None);
+        self.category.remove_mor_generator(f);
+        self.provenance.remove(f);
+        self.revision += 1;
+        for observer in self.observers.iter_mut() {
+            observer.on_remove_mor(f, &typ);
+        }
+        true
+    }
+
+    /** Adds many basic objects to the model at once.
+
+    Each object is checked on its own to have a type in the theory. If any
+    check fails, the errors are returned by position in the batch and the model
+    is left unchanged. Otherwise, the end state is the same as calling
+    [`add_ob`](Self::add_ob) on each object in turn, but capacity is reserved up
+    front and the rest of the model is not validated again.
+     */
+    pub fn add_obs<T>(
+        &mut self,
+        obs: T,
+    ) -> Result<(), NonEmpty<BatchError<InvalidDiscreteDblModel<Id>>>>
+    where
+        T: IntoIterator<Item = (Id, Cat::Ob)>,
+    {
+        let obs: Vec<_> = obs.into_iter().collect();
+        let errors = obs
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, typ))| !self.theory.has_ob_type(typ))
+            .map(|(index, (x, _))| BatchError {
+                index,
+                error: InvalidDiscreteDblModel::ObType(x.clone()),
+            });
+        validate::wrap_errors(errors)?;
+
+        self.category.reserve_ob_generators(obs.len());
+        self.ob_types.reserve(obs.len());
+        for (x, typ) in obs {
+            self.add_ob(x, typ);
+        }
+        Ok(())
+    }
+
+    /** Adds many basic morphisms to the model at once.
+
+    The batch counterpart of [`add_mor`](Self::add_mor), analogous to
+    [`add_obs`](Self::add_obs). Each morphism is checked to have a type in the
+    theory and a domain and codomain that are objects of the model, which must
+    therefore be added first, of the types required by the morphism type.
+    Missing reverses of symmetric morphisms are not reported, since they can
+    involve several batches.
+     */
+    pub fn add_mors<T>(
+        &mut self,
+        mors: T,
+    ) -> Result<(), NonEmpty<BatchError<InvalidDiscreteDblModel<Id>>>>
+    where
+        T: IntoIterator<Item = MorSpec<Id, Cat::Mor>>,
+    {
         type Invalid<Id> = InvalidDiscreteDblModel<Id>;
-        let category_errors = self.category.iter_invalid().map(|err| match err {
-            InvalidFpCategory::Dom(e) => Invalid::Dom(e),
-            InvalidFpCategory::Cod(e) => Invalid::Cod(e),
-            InvalidFpCategory::EqLhs(eq) => Invalid::EqLhs(eq),
-            InvalidFpCategory::EqRhs(eq) => Invalid::EqRhs(eq),
-            InvalidFpCategory::EqSrc(eq) => Invalid::EqSrc(eq),
-            InvalidFpCategory::EqTgt(eq) => Invalid::EqTgt(eq),
-        });
-        let ob_type_errors = self.category.object_generators().filter_map(|x| {
-            if self.theory.has_ob_type(&self.ob_type(&x)) {
-                None
+        let mors: Vec<_> = mors.into_iter().collect();
+        let errors = mors.iter().enumerate().flat_map(|(index, spec)| {
+            let theory = &self.theory;
+            let (dom_type, cod_type) =
+                (self.ob_types.apply(&spec.dom), self.ob_types.apply(&spec.cod));
+            let mut errs = Vec::new();
+            if dom_type.is_none() {
+                errs.push(Invalid::Dom(spec.id.clone()));
+            }
+            if cod_type.is_none() {
+                errs.push(Invalid::Cod(spec.id.clone()));
+            }
+            if !theory.has_mor_type(&spec.mor_type) {
+                errs.push(Invalid::MorType(spec.id.clone()));
             } else {
-                Some(Invalid::ObType(x))
+                if dom_type.is_some_and(|typ| *typ != theory.src(&spec.mor_type)) {
+                    errs.push(Invalid::DomType(spec.id.clone()));
+                }
+                if cod_type.is_some_and(|typ| *typ != theory.tgt(&spec.mor_type)) {
+                    errs.push(Invalid::CodType(spec.id.clone()));
+                }
             }
+            errs.into_iter().map(move |error| BatchError { index, error })
         });
-        let mor_type_errors = self.category.morphism_generators().flat_map(|f| {
-            let mut errs = Vec::new();
-            let e = f.clone();
-            let mor_type = self.mor_type(&f.into());
-            if self.theory.has_mor_type(&mor_type) {
-                if self
-                    .category
-                    .get_dom(&e)
-                    .map_or(false, |x| self.ob_type(x) != self.theory.src(&mor_type))
+        validate::wrap_errors(errors)?;
+
+        self.category.reserve_mor_generators(mors.len());
+        self.mor_types.reserve(mors.len());
+        for spec in mors {
+            self.add_mor(spec.id, spec.dom, spec.cod, spec.mor_type);
+        }
+        Ok(())
+    }
+
+    /// Gets the domain of a basic morphism, if it is set.
+    pub fn get_dom(&self, f: &Id) -> Option<&Id> {
+        self.category.get_dom(f)
+    }
+
+    /// Gets the codomain of a basic morphism, if it is set.
+    pub fn get_cod(&self, f: &Id) -> Option<&Id> {
+        self.category.get_cod(f)
+    }
+
+    /** Computes the connected components of the model.
+
+    Two basic objects belong to the same component if they are connected by a
+    zig-zag of basic morphisms. Morphisms with an undefined domain or codomain
+    are ignored. Computed on the [underlying typed
+    graph](Self::underlying_typed_graph); see
+    [`TypedGraph::connected_components`].
+     */
+    pub fn connected_components(&self) -> Vec<HashFinSet<Id>> {
+        connected_components_in_store(self)
+    }
+
+    /** Finds the basic objects within a given distance of a basic object.
+
+    Computed on the [underlying typed graph](Self::underlying_typed_graph),
+    ignoring the direction of morphisms; see [`TypedGraph::neighborhood`].
+     */
+    pub fn neighborhood(&self, x: &Id, radius: usize) -> HashFinSet<Id> {
+        self.underlying_typed_graph().neighborhood(x, radius)
+    }
+
+    /** Enumerates the loops of basic morphisms up to a given length.
+
+    Computed on the [underlying typed graph](Self::underlying_typed_graph); see
+    [`TypedGraph::loops`].
+     */
+    pub fn loops(&self, max_length: usize) -> Vec<Path<Id, Id>> {
+        self.underlying_typed_graph().loops(max_length)
+    }
+
+    /** Enumerates the loops whose composite has a given morphism type.
+
+    For instance, in a model of the theory of signed categories, these are the
+    positive or negative feedback loops. See [`loops`](Self::loops).
+     */
+    pub fn loops_with_type(&self, mor_type: &Cat::Mor, max_length: usize) -> Vec<Path<Id, Id>> {
+        let mut loops = self.loops(max_length);
+        loops.retain(|path| self.mor_type(path) == *mor_type);
+        loops
+    }
+
+    /** Gets the underlying typed graph of the model from a cache.
+
+    The typed graph is extracted again only if the model has changed since it
+    was cached. The cache should only be used with this model.
+     */
+    pub fn cached_typed_graph<'a>(
+        &self,
+        cache: &'a mut DerivedCache<TypedGraph<Id, Cat::Ob, Cat::Mor>>,
+    ) -> &'a TypedGraph<Id, Cat::Ob, Cat::Mor> {
+        cache.get_or_derive(self.revision, || self.underlying_typed_graph())
+    }
+
+    /** Extracts the underlying typed graph of the model.
+
+    The typed graph has a vertex for each basic object and an edge for each
+    basic morphism, tagged with their types, and is built in time linear in the
+    number of generators. Morphisms with an undefined domain or codomain, or
+    with one not in the model, are omitted. Since the graph does not change with
+    the model, it can be cached along with the [revision](Self::revision) of the
+    model it was built from.
+     */
+    pub fn underlying_typed_graph(&self) -> TypedGraph<Id, Cat::Ob, Cat::Mor> {
+        underlying_typed_graph_in_store(self)
+    }
+
+    /** Clusters the basic objects of the model hierarchically.
+
+    The clustering is computed from the underlying graph of the model, ignoring
+    morphisms with an undefined domain or codomain, and each cluster records its
+    most common object type. See [`layout`] for details.
+     */
+    pub fn hierarchical_clustering(&self, options: &ClusteringOptions) -> ClusterTree<Id, Cat::Ob> {
+        let obs = self.category.object_generators().map(|x| {
+            let typ = self.ob_gen_type(&x);
+            (x, typ)
+        });
+        let edges = self
+            .category
+            .morphism_generators()
+            .filter_map(|f| Some((self.get_dom(&f)?.clone(), self.get_cod(&f)?.clone())));
+        layout::hierarchical_clustering(obs, edges, options)
+    }
+
+    /** Finds the basic objects reachable from a basic object.
+
+    Only the basic morphisms accepted by the `follow` predicate are traversed.
+    When declared inverses are given, an accepted morphism having a declared
+    inverse can also be traversed backwards, whether or not its inverse is
+    accepted, provided that the declaration is still
+    [valid](InverseColumn::valid_inverse) in the model. Morphisms whose domain
+    or codomain has been unset, as by removing an object, are skipped. The
+    object itself is always reachable. See [`reachable_in_store`].
+     */
+    pub fn reachable(
+        &self,
+        x: &Id,
+        follow: impl Fn(&Id) -> bool,
+        inverses: Option<&InverseColumn<Id>>,
+    ) -> HashFinSet<Id> {
+        reachable_in_store(self, x, follow, inverses)
+    }
+
+    /** Extracts the submodel generated by some basic objects and morphisms.
+
+    The submodel contains the given basic objects and morphisms, along with the
+    domain and codomain of each given morphism, so that it is well defined even
+    when some morphisms cross the boundary of the given set. Generators keep
+    their types and are added in the order they were added to this model. Only
+    generators are copied, so the model is assumed to be free. Identifiers not
+    belonging to the model are ignored.
+     */
+    pub fn induced_submodel(&self, gens: impl IntoIterator<Item = Id>) -> Self {
+        let gens: HashSet<_> = gens.into_iter().collect();
+        let mors: Vec<_> =
+            self.category.morphism_generators().filter(|f| gens.contains(f)).collect();
+        let mut obs: HashSet<_> = gens.iter().filter(|x| self.has_ob(x)).cloned().collect();
+        obs.extend(mors.iter().flat_map(|f| [self.get_dom(f), self.get_cod(f)]).flatten().cloned());
+
+        let mut sub = Self::new(self.theory.clone());
+        for x in self.category.object_generators().filter(|x| obs.contains(x)) {
+            sub.add_ob(x.clone(), self.ob_gen_type(&x));
+        }
+        for f in mors {
+            sub.make_mor(f.clone(), self.mor_gen_type(&f));
+            sub.update_dom(f.clone(), self.get_dom(&f).cloned());
+            sub.update_cod(f.clone(), self.get_cod(&f).cloned());
+        }
+        let gens: Vec<_> = sub.object_generators().chain(sub.morphism_generators()).collect();
+        sub.provenance = self.provenance.restrict(&gens);
+        sub
+    }
+
+    /** Finds a path of basic morphisms of least total weight.
+
+    Each basic morphism is weighted by the given function, which must take
+    nonnegative values. The path, if one exists, is returned together with its
+    composite morphism type. See [`shortest_path_in_store`].
+    */
+    #[allow(clippy::type_complexity)]
+    pub fn shortest_path(
+        &self,
+        src: &Id,
+        tgt: &Id,
+        weight: impl Fn(&Id) -> f64,
+    ) -> Result<Option<(Path<Id, Id>, Cat::Mor)>, InvalidWeight<Id>>
+    where
+        Id: Ord,
+    {
+        shortest_path_in_store(self, &self.theory, src, tgt, weight)
+    }
+
+    /** Iterates over failures to be well-defined model.
+
+    Failures of the generators are found as in [`iter_invalid_in_store`],
+    followed by failures of the equations.
+     */
+    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidDiscreteDblModel<Id>> + '_ {
+        type Invalid<Id> = InvalidDiscreteDblModel<Id>;
+        let equation_errors = self.category.iter_invalid().filter_map(|err| match err {
+            InvalidFpCategory::Dom(_) | InvalidFpCategory::Cod(_) => None,
+            InvalidFpCategory::EqLhs(eq) => Some(Invalid::EqLhs(eq)),
+            InvalidFpCategory::EqRhs(eq) => Some(Invalid::EqRhs(eq)),
+            InvalidFpCategory::EqSrc(eq) => Some(Invalid::EqSrc(eq)),
+            InvalidFpCategory::EqTgt(eq) => Some(Invalid::EqTgt(eq)),
+        });
+        iter_invalid_in_store(self, &self.theory).chain(equation_errors)
+    }
+}
+
+/** Read access to the generators of a model of a discrete double theory.
+
+Validation and read-only analyses only look up the generators of a model along
+with their types, domains, and codomains. Abstracting these lookups lets them
+run against a model kept elsewhere, such as in a database, without first
+materializing it in memory. The in-memory [`DiscreteDblModel`] is the default
+implementation. Iterators borrow from the store, so that a store can stream its
+generators page by page instead of collecting them.
+ */
+pub trait ModelStore {
+    /// Type of IDs of generators.
+    type Id: Eq + Clone;
+
+    /// Type of object types.
+    type ObType: Eq + Clone;
+
+    /// Type of morphism types.
+    type MorType: Eq + Clone;
+
+    /// Iterates over the object generators.
+    fn ob_generators(&self) -> impl Iterator<Item = Self::Id> + '_;
+
+    /// Iterates over the morphism generators.
+    fn mor_generators(&self) -> impl Iterator<Item = Self::Id> + '_;
+
+    /// Does the store contain the object generator?
+    fn has_ob_generator(&self, x: &Self::Id) -> bool;
+
+    /// Does the store contain the morphism generator?
+    fn has_mor_generator(&self, f: &Self::Id) -> bool;
+
+    /// Looks up the type of an object generator, if set.
+    fn lookup_ob_type(&self, x: &Self::Id) -> Option<Self::ObType>;
+
+    /// Looks up the type of a morphism generator, if set.
+    fn lookup_mor_type(&self, f: &Self::Id) -> Option<Self::MorType>;
+
+    /// Looks up the domain of a morphism generator, if set.
+    fn lookup_dom(&self, f: &Self::Id) -> Option<Self::Id>;
+
+    /// Looks up the codomain of a morphism generator, if set.
+    fn lookup_cod(&self, f: &Self::Id) -> Option<Self::Id>;
+
+    /// Iterates over the morphism generators with the given domain.
+    fn mor_generators_from(&self, x: &Self::Id) -> impl Iterator<Item = Self::Id> + '_ {
+        let x = x.clone();
+        self.mor_generators().filter(move |f| self.lookup_dom(f).as_ref() == Some(&x))
+    }
+
+    /// Iterates over the morphism generators of the given type.
+    fn mor_generators_with_type<'a>(
+        &'a self,
+        typ: &'a Self::MorType,
+    ) -> impl Iterator<Item = Self::Id> + 'a {
+        self.mor_generators()
+            .filter(move |f| self.lookup_mor_type(f).as_ref() == Some(typ))
+    }
+}
+
+impl<Id, Cat> ModelStore for DiscreteDblModel<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    type Id = Id;
+    type ObType = Cat::Ob;
+    type MorType = Cat::Mor;
+
+    fn ob_generators(&self) -> impl Iterator<Item = Id> + '_ {
+        self.category.object_generators()
+    }
+    fn mor_generators(&self) -> impl Iterator<Item = Id> + '_ {
+        self.category.morphism_generators()
+    }
+    fn has_ob_generator(&self, x: &Id) -> bool {
+        self.category.has_ob(x)
+    }
+    fn has_mor_generator(&self, f: &Id) -> bool {
+        self.category.has_mor(&f.clone().into())
+    }
+    fn lookup_ob_type(&self, x: &Id) -> Option<Cat::Ob> {
+        self.ob_types.apply(x).cloned()
+    }
+    fn lookup_mor_type(&self, f: &Id) -> Option<Cat::Mor> {
+        self.mor_types.apply(f).cloned()
+    }
+    fn lookup_dom(&self, f: &Id) -> Option<Id> {
+        self.category.get_dom(f).cloned()
+    }
+    fn lookup_cod(&self, f: &Id) -> Option<Id> {
+        self.category.get_cod(f).cloned()
+    }
+    fn mor_generators_with_type<'a>(&'a self, typ: &'a Cat::Mor) -> impl Iterator<Item = Id> + 'a {
+        self.mor_types.preimage(typ)
+    }
+}
+
+/** Extracts the underlying typed graph of the generators in a store.
+
+The typed graph has a vertex for each object generator and an edge for each
+morphism generator, tagged with their types, and is built in time linear in the
+number of generators. Generators without a type are omitted, as are morphisms
+with an undefined domain or codomain or with one not in the store. Each
+generator is looked up once, so the store is read in a single pass over its
+objects and one over its morphisms, plus the lookups.
+ */
+pub fn underlying_typed_graph_in_store<S>(store: &S) -> TypedGraph<S::Id, S::ObType, S::MorType>
+where
+    S: ModelStore,
+    S::Id: Hash,
+{
+    let mut typed = TypedGraph::default();
+    for x in store.ob_generators() {
+        if let Some(typ) = store.lookup_ob_type(&x) {
+            typed.add_vertex(x, typ);
+        }
+    }
+    for f in store.mor_generators() {
+        if let (Some(dom), Some(cod), Some(typ)) =
+            (store.lookup_dom(&f), store.lookup_cod(&f), store.lookup_mor_type(&f))
+        {
+            typed.add_edge(f, &dom, &cod, typ);
+        }
+    }
+    typed
+}
+
+/** Computes the connected components of the generators in a store.
+
+Computed on the [underlying typed graph](underlying_typed_graph_in_store); see
+[`TypedGraph::connected_components`].
+ */
+pub fn connected_components_in_store<S>(store: &S) -> Vec<HashFinSet<S::Id>>
+where
+    S: ModelStore,
+    S::Id: Hash,
+{
+    underlying_typed_graph_in_store(store).connected_components()
+}
+
+/** Finds the object generators in a store reachable from an object generator.
+
+Computed on the [underlying typed graph](underlying_typed_graph_in_store); see
+[`DiscreteDblModel::reachable`] for the meaning of the arguments and
+[`TypedGraph::reachable`] for the traversal.
+ */
+pub fn reachable_in_store<S>(
+    store: &S,
+    x: &S::Id,
+    follow: impl Fn(&S::Id) -> bool,
+    inverses: Option<&InverseColumn<S::Id>>,
+) -> HashFinSet<S::Id>
+where
+    S: ModelStore,
+    S::Id: Hash,
+{
+    let backward = |f: &S::Id| {
+        follow(f) && inverses.is_some_and(|inverses| inverses.valid_inverse(store, f).is_some())
+    };
+    underlying_typed_graph_in_store(store).reachable(x, &follow, backward)
+}
+
+/** Finds a path of morphism generators in a store of least total weight.
+
+The search runs on the graph of generators, omitting those that would be
+omitted from the [underlying typed graph](underlying_typed_graph_in_store), and
+the composite morphism type of the path is computed in the theory. See
+[`graph_algorithms::shortest_path`] for details.
+ */
+#[allow(clippy::type_complexity)]
+pub fn shortest_path_in_store<S, Cat>(
+    store: &S,
+    theory: &DiscreteDblTheory<Cat>,
+    src: &S::Id,
+    tgt: &S::Id,
+    weight: impl Fn(&S::Id) -> f64,
+) -> Result<Option<(Path<S::Id, S::Id>, Cat::Mor)>, InvalidWeight<S::Id>>
+where
+    S: ModelStore<ObType = Cat::Ob, MorType = Cat::Mor>,
+    S::Id: Ord + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    let typed = underlying_typed_graph_in_store(store);
+    let mut graph: HashGraph<S::Id, S::Id> = HashGraph::default();
+    graph.add_vertices(typed.vertices().map(|v| typed.vertex_id(v).clone()));
+    for e in typed.edges() {
+        let (src, tgt) = (typed.src(&e), typed.tgt(&e));
+        graph.add_edge(
+            typed.edge_id(e).clone(),
+            typed.vertex_id(src).clone(),
+            typed.vertex_id(tgt).clone(),
+        );
+    }
+    let path = graph_algorithms::shortest_path(&graph, src, tgt, weight)?;
+    Ok(path.map(|path| {
+        let types = path.clone().map(
+            |x| typed.vertex_type(typed.vertex_index(&x).unwrap()).clone(),
+            |f| typed.edge_type(typed.edge_index(&f).unwrap()).clone(),
+        );
+        (path, theory.compose_types(types))
+    }))
+}
+
+/** Iterates over failures of the generators in a store to form a model.
+
+Failures are reported in the following order: morphisms with an invalid
+domain or codomain, the domain of each morphism before its codomain, then
+objects and morphisms with invalid types, and finally morphisms of symmetric
+type without a reverse. Within each group, generators are taken in the order of
+the store.
+ */
+pub fn iter_invalid_in_store<'a, S, Cat>(
+    store: &'a S,
+    theory: &'a DiscreteDblTheory<Cat>,
+) -> impl Iterator<Item = InvalidDiscreteDblModel<S::Id>> + 'a
+where
+    S: ModelStore<ObType = Cat::Ob, MorType = Cat::Mor>,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    type Invalid<Id> = InvalidDiscreteDblModel<Id>;
+    let is_valid_ob = |x: Option<S::Id>| x.is_some_and(|x| store.has_ob_generator(&x));
+    let endpoint_errors = store.mor_generators().flat_map(move |f| {
+        let dom_error = (!is_valid_ob(store.lookup_dom(&f))).then(|| Invalid::Dom(f.clone()));
+        let cod_error = (!is_valid_ob(store.lookup_cod(&f))).then(|| Invalid::Cod(f));
+        dom_error.into_iter().chain(cod_error)
+    });
+    let ob_type_errors = store.ob_generators().filter_map(|x| {
+        if store.lookup_ob_type(&x).is_some_and(|typ| theory.has_ob_type(&typ)) {
+            None
+        } else {
+            Some(Invalid::ObType(x))
+        }
+    });
+    let mor_type_errors = store.mor_generators().flat_map(|e| {
+        let mut errs = Vec::new();
+        match store.lookup_mor_type(&e).filter(|typ| theory.has_mor_type(typ)) {
+            Some(mor_type) => {
+                if store
+                    .lookup_dom(&e)
+                    .and_then(|x| store.lookup_ob_type(&x))
+                    .is_some_and(|x_type| x_type != theory.src(&mor_type))
                 {
                     errs.push(Invalid::DomType(e.clone()));
                 }
-                if self
-                    .category
-                    .get_cod(&e)
-                    .map_or(false, |x| self.ob_type(x) != self.theory.tgt(&mor_type))
+                if store
+                    .lookup_cod(&e)
+                    .and_then(|x| store.lookup_ob_type(&x))
+                    .is_some_and(|x_type| x_type != theory.tgt(&mor_type))
                 {
                     errs.push(Invalid::CodType(e));
                 }
-            } else {
-                errs.push(Invalid::MorType(e));
             }
-            errs.into_iter()
+            None => errs.push(Invalid::MorType(e)),
+        }
+        errs.into_iter()
+    });
+    let reverse_errors = store.mor_generators().filter(|f| {
+        let Some(mor_type) = store.lookup_mor_type(f) else {
+            return false;
+        };
+        if theory.directionality(&mor_type) != Directionality::Symmetric {
+            return false;
+        }
+        let (Some(x), Some(y)) = (store.lookup_dom(f), store.lookup_cod(f)) else {
+            return false;
+        };
+        let has_reverse = store.mor_generators_with_type(&mor_type).any(|g| {
+            store.lookup_dom(&g).as_ref() == Some(&y) && store.lookup_cod(&g).as_ref() == Some(&x)
         });
-        category_errors.chain(ob_type_errors).chain(mor_type_errors)
+        !has_reverse
+    });
+    let reverse_errors = reverse_errors.map(Invalid::MissingReverse);
+    endpoint_errors
+        .chain(ob_type_errors)
+        .chain(mor_type_errors)
+        .chain(reverse_errors)
+}
+
+impl<Id, V, E, S> DiscreteDblModel<Id, FinCategory<V, E, S>>
+where
+    Id: Eq + Clone + Hash,
+    V: Eq + Clone + Hash,
+    E: Eq + Clone + Hash,
+    S: BuildHasher + Default,
+{
+    /** Fragment of the theory actually used by the model.
+
+    This is the subcategory of the theory generated by the types of the basic
+    objects and morphisms in the model. Types not belonging to the theory are
+    ignored.
+    */
+    pub fn minimal_theory(&self) -> DiscreteDblTheory<FinCategory<V, E, S>> {
+        let category = self.theory.category();
+        let mor_gens: Vec<_> = self
+            .mor_types
+            .values()
+            .filter_map(|typ| match typ {
+                FinMor::Generator(e) => Some(e.clone()),
+                FinMor::Id(_) => None,
+            })
+            .collect();
+        let (mut sub, _) = category.generated_subcategory(&mor_gens);
+        let ob_types =
+            self.ob_types
+                .values()
+                .chain(self.mor_types.values().filter_map(|typ| match typ {
+                    FinMor::Id(x) => Some(x),
+                    FinMor::Generator(_) => None,
+                }));
+        sub.add_ob_generators(ob_types.filter(|x| category.has_ob(x)).cloned());
+        DiscreteDblTheory::from(sub)
     }
 }
 
@@ -382,7 +1123,7 @@ where
     }
 
     fn object_generators_with_type(&self, typ: &Self::ObType) -> impl Iterator<Item = Self::ObGen> {
-        self.ob_types.preimage(typ)
+        self.ob_fibers.fiber(typ).into_iter()
     }
 
     fn morphism_generators_with_type(
@@ -407,61 +1148,1108 @@ where
     }
 }
 
+/** Builder for a model of a discrete double theory.
+
+Objects and morphisms are declared by chaining method calls, after which
+[`build`](Self::build) assembles and validates the model. As with
+[`FinCategoryBuilder`](crate::one::fin_category::FinCategoryBuilder), errors
+refer to declarations by their positions in the chain. For the most common
+shapes, the [`model!`](crate::model) macro provides a more concise syntax.
+ */
+pub struct DiscreteDblModelBuilder<Id, Cat: FgCategory> {
+    theory: Arc<DiscreteDblTheory<Cat>>,
+    decls: Vec<DiscreteDblModelDecl<Id, Cat::Ob, Cat::Mor>>,
+}
+
+/// Declaration in a builder for a model of a discrete double theory.
+enum DiscreteDblModelDecl<Id, ObType, MorType> {
+    Ob(Id, ObType),
+    Mor(Id, Id, Id, MorType),
+}
+
+impl<Id, Cat> DiscreteDblModelBuilder<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /// Creates an empty builder for a model of the given theory.
+    pub fn new(theory: Arc<DiscreteDblTheory<Cat>>) -> Self {
+        Self {
+            theory,
+            decls: Vec::new(),
+        }
+    }
+
+    /// Declares a basic object of the given type.
+    pub fn object(mut self, x: impl Into<Id>, typ: impl Into<Cat::Ob>) -> Self {
+        self.decls.push(DiscreteDblModelDecl::Ob(x.into(), typ.into()));
+        self
+    }
+
+    /// Declares a basic morphism of the given type.
+    pub fn morphism(
+        mut self,
+        f: impl Into<Id>,
+        dom: impl Into<Id>,
+        cod: impl Into<Id>,
+        typ: impl Into<Cat::Mor>,
+    ) -> Self {
+        self.decls
+            .push(DiscreteDblModelDecl::Mor(f.into(), dom.into(), cod.into(), typ.into()));
+        self
+    }
+
+    /** Builds the model.
+
+    Besides the errors in the declarations themselves, such as duplicate
+    objects or references to undeclared objects, the errors from
+    [validating](DiscreteDblModel::iter_invalid) the model are reported.
+     */
+    pub fn build(
+        self,
+    ) -> Result<DiscreteDblModel<Id, Cat>, NonEmpty<DiscreteDblModelBuildError<Id>>> {
+        type Error<Id> = DiscreteDblModelBuildError<Id>;
+        let mut model = DiscreteDblModel::new(self.theory);
+        let mut errs = Vec::new();
+        let mut mors = Vec::new();
+        for (i, decl) in self.decls.into_iter().enumerate() {
+            match decl {
+                DiscreteDblModelDecl::Ob(x, typ) => {
+                    if model.has_ob(&x) {
+                        errs.push(Error::DuplicateOb(i, x));
+                    } else {
+                        model.add_ob(x, typ);
+                    }
+                }
+                DiscreteDblModelDecl::Mor(f, dom, cod, typ) => mors.push((i, f, dom, cod, typ)),
+            }
+        }
+        for (i, f, dom, cod, typ) in mors {
+            if model.generating_graph().has_edge(&f) {
+                errs.push(Error::DuplicateMor(i, f));
+                continue;
+            }
+            let unknown: Vec<_> = [&dom, &cod].into_iter().filter(|x| !model.has_ob(x)).collect();
+            if unknown.is_empty() {
+                model.add_mor(f, dom, cod, typ);
+            } else {
+                errs.extend(unknown.into_iter().map(|x| Error::UnknownOb(i, x.clone())));
+            }
+        }
+        if errs.is_empty() {
+            errs.extend(model.iter_invalid().map(Error::Invalid));
+        }
+        match NonEmpty::from_vec(errs) {
+            Some(errs) => Err(errs),
+            None => Ok(model),
+        }
+    }
+}
+
+/// A failure to build a model of a discrete double theory from its declarations.
+#[derive(Debug, Error)]
+pub enum DiscreteDblModelBuildError<Id> {
+    /// Object declared more than once.
+    #[error("Declaration {0}: object `{1}` is already declared")]
+    DuplicateOb(usize, Id),
+
+    /// Morphism declared more than once.
+    #[error("Declaration {0}: morphism `{1}` is already declared")]
+    DuplicateMor(usize, Id),
+
+    /// Reference to an undeclared object.
+    #[error("Declaration {0}: object `{1}` is not declared")]
+    UnknownOb(usize, Id),
+
+    /// Failure of the declared model to be well defined.
+    #[error("Model is not well defined: {0:?}")]
+    Invalid(InvalidDiscreteDblModel<Id>),
+}
+
+/** Builds a model of a discrete double theory from lists of generators.
+
+The macro evaluates to the result of [`DiscreteDblModelBuilder::build`].
+
+```
+# use std::sync::Arc;
+# use ustr::ustr;
+# use catlog::model;
+# use catlog::dbl::model::UstrDiscreteDblModel;
+# use catlog::stdlib::theories::th_schema;
+let model: UstrDiscreteDblModel = model! {
+    theory: Arc::new(th_schema()),
+    obs: ["customers" => "Entity", "name" => "AttrType"],
+    mors: ["customer_name" => ("customers", "name", ustr("Attr"))],
+}
+.unwrap();
+```
+ */
+#[macro_export]
+macro_rules! model {
+    (
+        theory: $theory:expr,
+        obs: [$($ob:expr => $ob_type:expr),* $(,)?]
+        $(, mors: [$($mor:expr => ($dom:expr, $cod:expr, $mor_type:expr)),* $(,)?])?
+        $(,)?
+    ) => {
+        $crate::dbl::model::DiscreteDblModelBuilder::new($theory)
+            $(.object($ob, $ob_type))*
+            $($(.morphism($mor, $dom, $cod, $mor_type))*)?
+            .build()
+    };
+}
+
+/// Specification of a basic morphism to be added to a model in a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MorSpec<Id, MorType> {
+    /// ID of the morphism.
+    pub id: Id,
+
+    /// Domain of the morphism.
+    pub dom: Id,
+
+    /// Codomain of the morphism.
+    pub cod: Id,
+
+    /// Type of the morphism.
+    pub mor_type: MorType,
+}
+
 /** A failure of a model of a discrete double theory to be well defined.
 
 TODO: Missing case that equation has different composite morphism types on left
 and right hand sides.
 */
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
 #[cfg_attr(feature = "serde-wasm", derive(Tsify))]
 #[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
 pub enum InvalidDiscreteDblModel<Id> {
     /// Domain of basic morphism is undefined or invalid.
+    #[error("Domain of morphism `{0}` is undefined or not in the model")]
     Dom(Id),
 
     /// Codomain of basic morphism is missing or invalid.
+    #[error("Codomain of morphism `{0}` is undefined or not in the model")]
     Cod(Id),
 
     /// Basic object has invalid object type.
+    #[error("Object `{0}` has a type not in the theory")]
     ObType(Id),
 
     /// Basic morphism has invalid morphism type.
+    #[error("Morphism `{0}` has a type not in the theory")]
     MorType(Id),
 
     /// Domain of basic morphism has type incompatible with morphism type.
+    #[error("Domain of morphism `{0}` has the wrong object type")]
     DomType(Id),
 
     /// Codomain of basic morphism has type incompatible with morphism type.
+    #[error("Codomain of morphism `{0}` has the wrong object type")]
     CodType(Id),
 
     /// Equation has left hand side that is not a well defined path.
+    #[error("LHS of equation `{0}` is not a path in the model")]
     EqLhs(Id),
 
     /// Equation has right hand side that is not a well defined path.
+    #[error("RHS of equation `{0}` is not a path in the model")]
     EqRhs(Id),
 
     /// Equation has different sources on left and right hand sides.
+    #[error("Equation `{0}` has sources that are not equal")]
     EqSrc(Id),
 
     /// Equation has different sources on left and right hand sides.
+    #[error("Equation `{0}` has targets that are not equal")]
     EqTgt(Id),
-}
 
-#[cfg(test)]
-mod tests {
-    use ustr::ustr;
+    /// Basic morphism of symmetric type has no reverse morphism of that type.
+    #[error("Morphism `{0}` of symmetric type has no reverse morphism")]
+    MissingReverse(Id),
 
-    use super::*;
-    use crate::one::fin_category::FinMor;
-    use crate::stdlib::theories::*;
+    /// Basic morphism has domain and codomain owned by different cells.
+    #[error("Morphism `{0}` connects objects owned by different cells")]
+    CrossCell(Id),
+}
 
-    #[test]
-    fn validate_discrete_dbl_model() {
-        let th = Arc::new(th_schema());
-        let mut model = DiscreteDblModel::new(th.clone());
-        let entity = ustr("entity");
+impl<Id> InvalidDiscreteDblModel<Id> {
+    /// Gets the ID of the basic object, morphism, or equation that is invalid.
+    pub fn id(&self) -> &Id {
+        match self {
+            Self::Dom(id)
+            | Self::Cod(id)
+            | Self::ObType(id)
+            | Self::MorType(id)
+            | Self::DomType(id)
+            | Self::CodType(id)
+            | Self::EqLhs(id)
+            | Self::EqRhs(id)
+            | Self::EqSrc(id)
+            | Self::EqTgt(id)
+            | Self::MissingReverse(id)
+            | Self::CrossCell(id) => id,
+        }
+    }
+
+    /// Maps over the ID of the invalid element, keeping the kind of failure.
+    pub fn map<Id2>(self, f: impl FnOnce(Id) -> Id2) -> InvalidDiscreteDblModel<Id2> {
+        type Invalid<Id> = InvalidDiscreteDblModel<Id>;
+        match self {
+            Self::Dom(id) => Invalid::Dom(f(id)),
+            Self::Cod(id) => Invalid::Cod(f(id)),
+            Self::ObType(id) => Invalid::ObType(f(id)),
+            Self::MorType(id) => Invalid::MorType(f(id)),
+            Self::DomType(id) => Invalid::DomType(f(id)),
+            Self::CodType(id) => Invalid::CodType(f(id)),
+            Self::EqLhs(id) => Invalid::EqLhs(f(id)),
+            Self::EqRhs(id) => Invalid::EqRhs(f(id)),
+            Self::EqSrc(id) => Invalid::EqSrc(f(id)),
+            Self::EqTgt(id) => Invalid::EqTgt(f(id)),
+            Self::MissingReverse(id) => Invalid::MissingReverse(f(id)),
+            Self::CrossCell(id) => Invalid::CrossCell(f(id)),
+        }
+    }
+}
+
+/** A span of models of a discrete double theory.
+
+The span consists of an apex model together with two legs, which are model
+morphisms out of the apex into the two feet.
+ */
+pub struct ModelSpan<Id, Cat: FgCategory> {
+    apex: DiscreteDblModel<Id, Cat>,
+    left: DiscreteDblModelMapping<Id, Id>,
+    right: DiscreteDblModelMapping<Id, Id>,
+    left_foot: DiscreteDblModel<Id, Cat>,
+    right_foot: DiscreteDblModel<Id, Cat>,
+}
+
+/** A cospan of models of a discrete double theory.
+
+The cospan consists of an apex model together with two legs, which are model
+morphisms into the apex out of the two feet. Viewing the feet as interfaces,
+cospans are the structured open models that can be glued together by
+[`compose_cospans`].
+ */
+pub struct ModelCospan<Id, Cat: FgCategory> {
+    apex: DiscreteDblModel<Id, Cat>,
+    left: DiscreteDblModelMapping<Id, Id>,
+    right: DiscreteDblModelMapping<Id, Id>,
+    left_foot: DiscreteDblModel<Id, Cat>,
+    right_foot: DiscreteDblModel<Id, Cat>,
+}
+
+impl<Id, Cat> ModelSpan<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /// Creates a span from its feet, legs, and apex.
+    pub fn new(
+        left_foot: DiscreteDblModel<Id, Cat>,
+        left: DiscreteDblModelMapping<Id, Id>,
+        apex: DiscreteDblModel<Id, Cat>,
+        right: DiscreteDblModelMapping<Id, Id>,
+        right_foot: DiscreteDblModel<Id, Cat>,
+    ) -> Self {
+        Self {
+            apex,
+            left,
+            right,
+            left_foot,
+            right_foot,
+        }
+    }
+
+    /// Apex of the span.
+    pub fn apex(&self) -> &DiscreteDblModel<Id, Cat> {
+        &self.apex
+    }
+
+    /// Left foot of the span, the codomain of the left leg.
+    pub fn left_foot(&self) -> &DiscreteDblModel<Id, Cat> {
+        &self.left_foot
+    }
+
+    /// Right foot of the span, the codomain of the right leg.
+    pub fn right_foot(&self) -> &DiscreteDblModel<Id, Cat> {
+        &self.right_foot
+    }
+
+    /// Left leg of the span.
+    pub fn left_leg(&self) -> DiscreteDblModelMorphism<'_, Id, Id, Cat> {
+        DiscreteDblModelMorphism(&self.left, &self.apex, &self.left_foot)
+    }
+
+    /// Right leg of the span.
+    pub fn right_leg(&self) -> DiscreteDblModelMorphism<'_, Id, Id, Cat> {
+        DiscreteDblModelMorphism(&self.right, &self.apex, &self.right_foot)
+    }
+
+    /// Iterates over failures of the legs to be model morphisms.
+    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidModelLeg<Id>> + '_ {
+        let left = self.left_leg().iter_invalid().map(InvalidModelLeg::Left);
+        let right = self.right_leg().iter_invalid().map(InvalidModelLeg::Right);
+        left.chain(right)
+    }
+}
+
+impl<Id, Cat> ModelSpan<Id, Cat>
+where
+    Id: Eq + Clone + Hash + Ord,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /** Computes the pushout of the span.
+
+    The pushout is returned as a cospan whose feet are the feet of this span.
+    Elements of the feet identified by the legs are merged, while all other
+    elements are kept apart. Each merged element takes the least of its names in
+    the left foot or, failing that, the least of its names in the right foot.
+    It is an error if this gives two distinct elements the same name. The
+    provenance of merged elements is combined as by
+    [`merge`](ProvenanceColumn::merge), with ties favoring the left foot.
+
+    For the pushout to be computable by merging generators, the feet must be
+    free models of the same theory and both legs must send each generator of
+    the apex to a generator of the same kind in their foot, rather than to an
+    identity or a composite. Failures of these conditions are reported as
+    errors. The legs are otherwise assumed to be valid model morphisms.
+     */
+    pub fn pushout(self) -> Result<ModelCospan<Id, Cat>, PushoutError<Id>> {
+        let (apex, ob_names, mor_names) = glue_models(
+            (&self.left_foot, &self.left),
+            &self.apex,
+            (&self.right_foot, &self.right),
+            merged_names,
+        )?;
+
+        let (mut left, mut right): (DiscreteDblModelMapping<_, _>, DiscreteDblModelMapping<_, _>) =
+            Default::default();
+        for (elem, name) in ob_names {
+            match elem {
+                Either::Left(x) => left.assign_ob(x, name),
+                Either::Right(x) => right.assign_ob(x, name),
+            };
+        }
+        for (elem, name) in mor_names {
+            match elem {
+                Either::Left(e) => left.assign_basic_mor(e, Path::single(name)),
+                Either::Right(e) => right.assign_basic_mor(e, Path::single(name)),
+            };
+        }
+
+        Ok(ModelCospan {
+            apex,
+            left,
+            right,
+            left_foot: self.left_foot,
+            right_foot: self.right_foot,
+        })
+    }
+}
+
+impl<Id, Cat> DiscreteDblModel<Id, Cat>
+where
+    Id: Eq + Clone + Hash + Ord + Display + From<String>,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /** Computes the pushout of two model morphisms out of a common model.
+
+    The pushout glues the codomains of the morphisms along the images of their
+    common domain. Elements identified by the morphisms are merged, while all
+    other elements are kept apart, as in [`ModelSpan::pushout`]. Unlike there, a
+    name shared by distinct elements is not an error: the elements after the
+    first are given fresh names with numeric suffixes, such as `x_2`, chosen
+    deterministically from the names in the codomains.
+
+    As for [`ModelSpan::pushout`], both morphisms must send each generator to a
+    generator of their codomain and the codomains must be free models of the
+    same theory.
+     */
+    pub fn pushout(
+        f: &DiscreteDblModelMorphism<'_, Id, Id, Cat>,
+        g: &DiscreteDblModelMorphism<'_, Id, Id, Cat>,
+    ) -> Result<Self, ColimitError<Id>> {
+        let (
+            DiscreteDblModelMorphism(f_map, f_dom, f_cod),
+            DiscreteDblModelMorphism(g_map, g_dom, g_cod),
+        ) = (f, g);
+        if !(std::ptr::eq(*f_dom, *g_dom) || f_dom == g_dom) {
+            return Err(ColimitError::DomMismatch);
+        }
+        let names = |left, right, pairs| Ok(fresh_merged_names(left, right, pairs));
+        let (model, _, _) =
+            glue_models((f_cod, f_map), f_dom, (g_cod, g_map), names).map_err(|err| match err {
+                PushoutError::TheoryMismatch => ColimitError::TheoryMismatch,
+                PushoutError::NotFree => ColimitError::NotFree,
+                PushoutError::NonBasicOb(x) => ColimitError::NonBasicOb(x),
+                PushoutError::NonBasicMor(e) => ColimitError::NonBasicMor(e),
+                PushoutError::InvalidFoot(e) => ColimitError::InvalidFoot(e),
+                PushoutError::Collision(_) => unreachable!("Fresh names should not collide"),
+            })?;
+        Ok(model)
+    }
+}
+
+/// Names of the elements of a disjoint union of two models' generators.
+type GluedNames<Id> = HashMap<Either<Id, Id>, Id>;
+
+/** Glues two models along the images of a common model.
+
+Returns the glued model along with the names given in it to the object and
+morphism generators of the two feet. The names are chosen by `names` from the
+generators of the feet and the pairs of generators identified by the legs.
+
+Fails unless the feet are free models of the same theory, the legs send each
+generator of the common model to a generator of the same kind in their foot,
+and every morphism generator in the feet has a domain and codomain there.
+ */
+#[allow(clippy::type_complexity)]
+fn glue_models<Id, Cat>(
+    (left_foot, left): (&DiscreteDblModel<Id, Cat>, &DiscreteDblModelMapping<Id, Id>),
+    apex_model: &DiscreteDblModel<Id, Cat>,
+    (right_foot, right): (&DiscreteDblModel<Id, Cat>, &DiscreteDblModelMapping<Id, Id>),
+    names: impl Fn(Vec<Id>, Vec<Id>, Vec<(Id, Id)>) -> Result<GluedNames<Id>, PushoutError<Id>>,
+) -> Result<(DiscreteDblModel<Id, Cat>, GluedNames<Id>, GluedNames<Id>), PushoutError<Id>>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    if !Arc::ptr_eq(&left_foot.theory, &right_foot.theory) {
+        return Err(PushoutError::TheoryMismatch);
+    }
+    if !(left_foot.is_free() && right_foot.is_free()) {
+        return Err(PushoutError::NotFree);
+    }
+    for foot in [left_foot, right_foot] {
+        let graph = foot.generating_graph();
+        for e in graph.edges() {
+            let has_ob = |x: Option<&Id>| x.is_some_and(|x| graph.has_vertex(x));
+            if !(has_ob(foot.get_dom(&e)) && has_ob(foot.get_cod(&e))) {
+                return Err(PushoutError::InvalidFoot(e));
+            }
+        }
+    }
+
+    let mut ob_pairs = Vec::new();
+    for x in apex_model.object_generators() {
+        let image = |leg: &DiscreteDblModelMapping<Id, Id>, foot: &DiscreteDblModel<Id, Cat>| {
+            leg.apply_ob(&x).filter(|y| foot.generating_graph().has_vertex(y))
+        };
+        match image(left, left_foot).zip(image(right, right_foot)) {
+            Some(pair) => ob_pairs.push(pair),
+            None => return Err(PushoutError::NonBasicOb(x)),
+        }
+    }
+    let ob_names = names(
+        left_foot.object_generators().collect(),
+        right_foot.object_generators().collect(),
+        ob_pairs,
+    )?;
+
+    let mut mor_pairs = Vec::new();
+    for e in apex_model.morphism_generators() {
+        let image = |leg: &DiscreteDblModelMapping<Id, Id>, foot: &DiscreteDblModel<Id, Cat>| {
+            leg.apply_basic_mor(&e)
+                .and_then(|path| path.only())
+                .filter(|f| foot.generating_graph().has_edge(f))
+        };
+        match image(left, left_foot).zip(image(right, right_foot)) {
+            Some(pair) => mor_pairs.push(pair),
+            None => return Err(PushoutError::NonBasicMor(e)),
+        }
+    }
+    let mor_names = names(
+        left_foot.morphism_generators().collect(),
+        right_foot.morphism_generators().collect(),
+        mor_pairs,
+    )?;
+
+    let mut model = DiscreteDblModel::new(left_foot.theory_arc());
+    for (elem, name) in ob_names.iter() {
+        let (foot, x) = match elem {
+            Either::Left(x) => (left_foot, x),
+            Either::Right(x) => (right_foot, x),
+        };
+        model.add_ob(name.clone(), foot.ob_gen_type(x));
+    }
+    for (elem, name) in mor_names.iter() {
+        let (foot, e) = match elem {
+            Either::Left(e) => (left_foot, e),
+            Either::Right(e) => (right_foot, e),
+        };
+        let side = |x| {
+            if elem.is_left() {
+                Either::Left(x)
+            } else {
+                Either::Right(x)
+            }
+        };
+        // Endpoints were checked to be object generators of the foot above.
+        let name_of = |x: Option<&Id>| x.and_then(|x| ob_names.get(&side(x.clone()))).cloned();
+        model.make_mor(name.clone(), foot.mor_gen_type(e));
+        model.update_dom(name.clone(), name_of(foot.get_dom(e)));
+        model.update_cod(name.clone(), name_of(foot.get_cod(e)));
+    }
+
+    // Merge the provenance of the feet, with ties favoring the left foot.
+    let glued = ob_names.iter().chain(mor_names.iter());
+    let (lefts, rights): (Vec<_>, Vec<_>) = glued.partition(|(elem, _)| elem.is_left());
+    for (elem, name) in lefts.into_iter().chain(rights) {
+        let (foot, x) = match elem {
+            Either::Left(x) => (left_foot, x),
+            Either::Right(x) => (right_foot, x),
+        };
+        if let Some(prov) = foot.provenance.get(x) {
+            model.provenance.update(name.clone(), prov.clone());
+        }
+    }
+
+    Ok((model, ob_names, mor_names))
+}
+
+/** Names the elements of a disjoint union after merging identified elements.
+
+Returns the name assigned to each element of the disjoint union, following the
+convention described in [`ModelSpan::pushout`].
+ */
+fn merged_names<Id>(
+    left: Vec<Id>,
+    right: Vec<Id>,
+    pairs: Vec<(Id, Id)>,
+) -> Result<GluedNames<Id>, PushoutError<Id>>
+where
+    Id: Eq + Clone + Hash + Ord,
+{
+    let classes = merged_classes(left, right, pairs);
+    let mut reps: HashMap<&Id, &Either<Id, Id>> = HashMap::new();
+    for (_, rep) in classes.iter() {
+        let name = rep.as_ref().into_inner();
+        if reps.insert(name, rep).is_some_and(|other| other != rep) {
+            return Err(PushoutError::Collision(name.clone()));
+        }
+    }
+    Ok(classes.into_iter().map(|(elem, rep)| (elem, rep.into_inner())).collect())
+}
+
+/** Names the elements of a disjoint union, choosing fresh names on collision.
+
+Each merged element is named as in [`merged_names`] unless that name is already
+taken by another element, in which case it is given the first name of the form
+`{name}_2`, `{name}_3`, and so on, that is not otherwise in use. Elements are
+named in order of their least representative, so the naming is deterministic.
+ */
+fn fresh_merged_names<Id>(left: Vec<Id>, right: Vec<Id>, pairs: Vec<(Id, Id)>) -> GluedNames<Id>
+where
+    Id: Eq + Clone + Hash + Ord + Display + From<String>,
+{
+    let classes = merged_classes(left, right, pairs);
+    let mut reps: Vec<_> = classes.iter().map(|(_, rep)| rep.clone()).collect();
+    reps.sort();
+    reps.dedup();
+    let mut used: HashSet<Id> = reps.iter().map(|rep| rep.as_ref().into_inner().clone()).collect();
+    let mut taken: HashSet<Id> = HashSet::new();
+    let mut names: HashMap<Either<Id, Id>, Id> = HashMap::new();
+    for rep in reps {
+        let name = rep.as_ref().into_inner();
+        let name = if taken.insert(name.clone()) {
+            name.clone()
+        } else {
+            let fresh = (2..)
+                .map(|i| Id::from(format!("{name}_{i}")))
+                .find(|fresh| !used.contains(fresh))
+                .expect("Some suffix should give a fresh name");
+            used.insert(fresh.clone());
+            taken.insert(fresh.clone());
+            fresh
+        };
+        names.insert(rep, name);
+    }
+    classes
+        .into_iter()
+        .map(|(elem, rep)| {
+            let name = names[&rep].clone();
+            (elem, name)
+        })
+        .collect()
+}
+
+/** Merges the elements of a disjoint union identified by the given pairs.
+
+Returns each element of the disjoint union together with the least element of
+its class, where elements of the left summand precede those of the right one.
+ */
+fn merged_classes<Id>(
+    left: Vec<Id>,
+    right: Vec<Id>,
+    pairs: Vec<(Id, Id)>,
+) -> Vec<(Either<Id, Id>, Either<Id, Id>)>
+where
+    Id: Eq + Clone + Hash + Ord,
+{
+    let elems: Vec<_> = left
+        .into_iter()
+        .map(Either::Left)
+        .chain(right.into_iter().map(Either::Right))
+        .collect();
+    let edges = pairs.into_iter().map(|(x, y)| (Either::Left(x), Either::Right(y)));
+
+    // Since left elements precede right ones, the least element is the one we want.
+    let mut reps: HashMap<Either<Id, Id>, Either<Id, Id>> = HashMap::new();
+    for class in components::weakly_connected(elems.iter().cloned(), edges) {
+        let rep = class.iter().min().expect("Class should be nonempty");
+        reps.extend(class.into_iter().map(|elem| (elem, rep.clone())));
+    }
+    elems
+        .into_iter()
+        .map(|elem| {
+            let rep = reps[&elem].clone();
+            (elem, rep)
+        })
+        .collect()
+}
+
+impl<Id, Cat> ModelCospan<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /// Creates a cospan from its feet, legs, and apex.
+    pub fn new(
+        left_foot: DiscreteDblModel<Id, Cat>,
+        left: DiscreteDblModelMapping<Id, Id>,
+        apex: DiscreteDblModel<Id, Cat>,
+        right: DiscreteDblModelMapping<Id, Id>,
+        right_foot: DiscreteDblModel<Id, Cat>,
+    ) -> Self {
+        Self {
+            apex,
+            left,
+            right,
+            left_foot,
+            right_foot,
+        }
+    }
+
+    /// Apex of the cospan.
+    pub fn apex(&self) -> &DiscreteDblModel<Id, Cat> {
+        &self.apex
+    }
+
+    /// Left foot of the cospan, the domain of the left leg.
+    pub fn left_foot(&self) -> &DiscreteDblModel<Id, Cat> {
+        &self.left_foot
+    }
+
+    /// Right foot of the cospan, the domain of the right leg.
+    pub fn right_foot(&self) -> &DiscreteDblModel<Id, Cat> {
+        &self.right_foot
+    }
+
+    /// Left leg of the cospan.
+    pub fn left_leg(&self) -> DiscreteDblModelMorphism<'_, Id, Id, Cat> {
+        DiscreteDblModelMorphism(&self.left, &self.left_foot, &self.apex)
+    }
+
+    /// Right leg of the cospan.
+    pub fn right_leg(&self) -> DiscreteDblModelMorphism<'_, Id, Id, Cat> {
+        DiscreteDblModelMorphism(&self.right, &self.right_foot, &self.apex)
+    }
+
+    /// Iterates over failures of the legs to be model morphisms.
+    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidModelLeg<Id>> + '_ {
+        let left = self.left_leg().iter_invalid().map(InvalidModelLeg::Left);
+        let right = self.right_leg().iter_invalid().map(InvalidModelLeg::Right);
+        left.chain(right)
+    }
+}
+
+impl<Id, Cat> Validate for ModelSpan<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    type ValidationError = InvalidModelLeg<Id>;
+
+    fn validate(&self) -> Result<(), nonempty::NonEmpty<Self::ValidationError>> {
+        validate::wrap_errors(self.iter_invalid())
+    }
+}
+
+impl<Id, Cat> Validate for ModelCospan<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    type ValidationError = InvalidModelLeg<Id>;
+
+    fn validate(&self) -> Result<(), nonempty::NonEmpty<Self::ValidationError>> {
+        validate::wrap_errors(self.iter_invalid())
+    }
+}
+
+/** Composes two cospans of models by pushout over their shared foot.
+
+The right foot of the first cospan must equal the left foot of the second. The
+apex of the composite is the [pushout](ModelSpan::pushout) of the span formed by
+the inner legs, so the same restrictions apply.
+ */
+pub fn compose_cospans<Id, Cat>(
+    c1: ModelCospan<Id, Cat>,
+    c2: ModelCospan<Id, Cat>,
+) -> Result<ModelCospan<Id, Cat>, ComposeCospansError<Id>>
+where
+    Id: Eq + Clone + Hash + Ord,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    if c1.right_foot != c2.left_foot {
+        return Err(ComposeCospansError::FootMismatch);
+    }
+    let span = ModelSpan::new(c1.apex, c1.right, c1.right_foot, c2.left, c2.apex);
+    let pushout = span.pushout().map_err(ComposeCospansError::Pushout)?;
+    Ok(ModelCospan {
+        apex: pushout.apex,
+        left: c1.left.compose(&pushout.left),
+        right: c2.right.compose(&pushout.right),
+        left_foot: c1.left_foot,
+        right_foot: c2.right_foot,
+    })
+}
+
+/** A rewrite rule for models of a discrete double theory.
+
+The rule is a span `L ← K → R` whose left foot is the *pattern* to be found, whose
+right foot is the *replacement*, and whose apex is the *interface* shared by the
+two. Rules are applied by [double-pushout rewriting](apply_rewrite): elements of
+the pattern outside the interface are deleted, and elements of the replacement
+outside the interface are added.
+
+Both legs of the span are assumed to be valid model morphisms sending distinct
+basic objects and morphisms to distinct basic objects and morphisms.
+ */
+pub struct RewriteRule<Id, Cat: FgCategory> {
+    span: ModelSpan<Id, Cat>,
+}
+
+impl<Id, Cat> RewriteRule<Id, Cat>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /// Creates a rewrite rule from a span with the pattern as its left foot.
+    pub fn new(span: ModelSpan<Id, Cat>) -> Self {
+        Self { span }
+    }
+
+    /// Pattern to be matched, the left-hand side of the rule.
+    pub fn pattern(&self) -> &DiscreteDblModel<Id, Cat> {
+        self.span.left_foot()
+    }
+
+    /// Interface shared by the pattern and the replacement.
+    pub fn interface(&self) -> &DiscreteDblModel<Id, Cat> {
+        self.span.apex()
+    }
+
+    /// Replacement for the pattern, the right-hand side of the rule.
+    pub fn replacement(&self) -> &DiscreteDblModel<Id, Cat> {
+        self.span.right_foot()
+    }
+
+    /** Checks that a match of the pattern can be rewritten.
+
+    The match must be injective and send basic morphisms to basic morphisms, and
+    it must satisfy the *dangling condition*: no morphism outside the match can
+    have a domain or codomain that would be deleted by the rule. Returns the
+    basic objects and morphisms of the model that would be deleted.
+     */
+    fn check_match(
+        &self,
+        model: &DiscreteDblModel<Id, Cat>,
+        m: &DiscreteDblModelMapping<Id, Id>,
+    ) -> Result<(HashSet<Id>, HashSet<Id>), RewriteError<Id>> {
+        let (pattern, left) = (self.pattern(), &self.span.left);
+        let mut ob_image = HashMap::new();
+        for x in pattern.object_generators() {
+            let y = m.apply_ob(&x).ok_or_else(|| RewriteError::InvalidMatch(x.clone()))?;
+            ob_image.insert(y, x);
+        }
+        if let Some((x, y)) = DiscreteDblModelMorphism(m, pattern, model).classify_obs().collision {
+            return Err(RewriteError::NonInjective(x, y));
+        }
+        let mut mor_image = HashMap::new();
+        for e in pattern.morphism_generators() {
+            let f = m
+                .apply_basic_mor(&e)
+                .and_then(|path| path.only())
+                .ok_or_else(|| RewriteError::NonBasicMatch(e.clone()))?;
+            if let Some(prev) = mor_image.insert(f, e.clone()) {
+                return Err(RewriteError::NonInjective(prev, e));
+            }
+        }
+
+        let kept_obs: HashSet<_> =
+            self.interface().object_generators().filter_map(|x| left.apply_ob(&x)).collect();
+        let kept_mors: HashSet<_> = self
+            .interface()
+            .morphism_generators()
+            .filter_map(|e| left.apply_basic_mor(&e).and_then(|path| path.only()))
+            .collect();
+        let deleted_obs: HashSet<_> = ob_image
+            .into_iter()
+            .filter(|(_, x)| !kept_obs.contains(x))
+            .map(|(y, _)| y)
+            .collect();
+        let deleted_mors: HashSet<_> = mor_image
+            .into_iter()
+            .filter(|(_, e)| !kept_mors.contains(e))
+            .map(|(f, _)| f)
+            .collect();
+
+        for f in model.morphism_generators() {
+            let dangling = [model.get_dom(&f), model.get_cod(&f)]
+                .into_iter()
+                .any(|x| x.is_some_and(|x| deleted_obs.contains(x)));
+            if dangling && !deleted_mors.contains(&f) {
+                return Err(RewriteError::Dangling(f));
+            }
+        }
+        Ok((deleted_obs, deleted_mors))
+    }
+}
+
+/** Finds the matches of a rewrite rule's pattern to which it can be applied.
+
+These are the model morphisms from the pattern into the model that satisfy the
+conditions checked by [`apply_rewrite`].
+ */
+pub fn find_matches<Id, Cat>(
+    model: &DiscreteDblModel<Id, Cat>,
+    rule: &RewriteRule<Id, Cat>,
+) -> Vec<DiscreteDblModelMapping<Id, Id>>
+where
+    Id: Eq + Clone + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    let mut matches = DiscreteDblModelMapping::morphisms(rule.pattern(), model).find_all();
+    matches.retain(|m| rule.check_match(model, m).is_ok());
+    matches
+}
+
+/** Applies a rewrite rule to a model at a match of its pattern.
+
+This is double-pushout rewriting. First, the elements of the match that are not
+in the image of the interface are deleted from the model, which is only possible
+when the match satisfies the dangling condition. Then the replacement is glued
+onto the remaining model along the interface by [pushout](ModelSpan::pushout).
+Elements of the model keep their names, while new elements take their names
+from the replacement, so these must not clash with names in the model.
+
+The model and the models making up the rule must be free, which is checked
+before anything else, and the match must be a valid model morphism. The
+rewritten model is validated before it is returned.
+ */
+pub fn apply_rewrite<Id, Cat>(
+    model: &DiscreteDblModel<Id, Cat>,
+    rule: &RewriteRule<Id, Cat>,
+    m: &DiscreteDblModelMapping<Id, Id>,
+) -> Result<DiscreteDblModel<Id, Cat>, RewriteError<Id>>
+where
+    Id: Eq + Clone + Hash + Ord,
+    Cat: FgCategory + Clone,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    if !model.is_free() {
+        return Err(RewriteError::NotFree);
+    }
+    if ![rule.pattern(), rule.interface(), rule.replacement()]
+        .iter()
+        .all(|m| m.is_free())
+    {
+        return Err(RewriteError::RuleNotFree);
+    }
+    let (deleted_obs, deleted_mors) = rule.check_match(model, m)?;
+
+    let mut context = DiscreteDblModel::new(model.theory_arc());
+    for x in model.object_generators().filter(|x| !deleted_obs.contains(x)) {
+        context.add_ob(x.clone(), model.ob_gen_type(&x));
+    }
+    for f in model.morphism_generators().filter(|f| !deleted_mors.contains(f)) {
+        context.make_mor(f.clone(), model.mor_gen_type(&f));
+        context.update_dom(f.clone(), model.get_dom(&f).cloned());
+        context.update_cod(f.clone(), model.get_cod(&f).cloned());
+    }
+
+    let span = ModelSpan::new(
+        context,
+        rule.span.left.compose(m),
+        rule.interface().clone(),
+        rule.span.right.clone(),
+        rule.replacement().clone(),
+    );
+    let result = span.pushout().map_err(RewriteError::Pushout)?.apex;
+    result.validate().map_err(RewriteError::Invalid)?;
+    Ok(result)
+}
+
+/// A failure of a leg of a span or cospan to be a model morphism.
+#[derive(Debug, Error)]
+pub enum InvalidModelLeg<Id> {
+    /// Failure of the left leg.
+    #[error("Left leg: {0}")]
+    Left(InvalidDblModelMorphism<Id>),
+
+    /// Failure of the right leg.
+    #[error("Right leg: {0}")]
+    Right(InvalidDblModelMorphism<Id>),
+}
+
+/// A failure to compute the pushout of a span of models.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PushoutError<Id> {
+    /// Feet of the span are not models of the same theory.
+    #[error("Feet of the span are not models of the same theory")]
+    TheoryMismatch,
+
+    /// A foot of the span is not freely generated.
+    #[error("Feet of the span should be free")]
+    NotFree,
+
+    /// Object of the apex not sent to an object generator by both legs.
+    #[error("Object `{0}` is not sent to a basic object by both legs")]
+    NonBasicOb(Id),
+
+    /// Basic morphism not sent to a basic morphism by both legs.
+    #[error("Morphism `{0}` is not sent to a basic morphism by both legs")]
+    NonBasicMor(Id),
+
+    /// Morphism in a foot without a domain or codomain in that foot.
+    #[error("Morphism `{0}` in a foot of the span has no domain or codomain")]
+    InvalidFoot(Id),
+
+    /// Name shared by two distinct elements of the pushout.
+    #[error("Distinct elements of the pushout would both be named `{0}`")]
+    Collision(Id),
+}
+
+/// A failure to compute a colimit of models.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ColimitError<Id> {
+    /// Model morphisms do not have a common domain.
+    #[error("Model morphisms do not have a common domain")]
+    DomMismatch,
+
+    /// Models are not models of the same theory.
+    #[error("Models are not models of the same theory")]
+    TheoryMismatch,
+
+    /// Model is not freely generated.
+    #[error("Models should be free")]
+    NotFree,
+
+    /// Object not sent to an object generator by both model morphisms.
+    #[error("Object `{0}` is not sent to a basic object by both model morphisms")]
+    NonBasicOb(Id),
+
+    /// Basic morphism not sent to a basic morphism by both model morphisms.
+    #[error("Morphism `{0}` is not sent to a basic morphism by both model morphisms")]
+    NonBasicMor(Id),
+
+    /// Morphism in a codomain without a domain or codomain there.
+    #[error("Morphism `{0}` in a codomain has no domain or codomain")]
+    InvalidFoot(Id),
+}
+
+/// A failure to compose two cospans of models.
+#[derive(Debug, Error)]
+pub enum ComposeCospansError<Id> {
+    /// Right foot of the first cospan differs from left foot of the second.
+    #[error("Cospans do not share a common foot")]
+    FootMismatch,
+
+    /// Failure to compute the pushout over the common foot.
+    #[error("{0}")]
+    Pushout(PushoutError<Id>),
+}
+
+/// A failure to apply a rewrite rule to a model.
+#[derive(Debug, Error)]
+pub enum RewriteError<Id> {
+    /// The model is not free.
+    #[error("Model to be rewritten is not free")]
+    NotFree,
+
+    /// A model in the rule is not free.
+    #[error("Pattern, interface, and replacement of the rule should be free")]
+    RuleNotFree,
+
+    /// Object in the pattern is not mapped by the match.
+    #[error("Object `{0}` in the pattern is not matched")]
+    InvalidMatch(Id),
+
+    /// Basic morphism in the pattern not matched to a basic morphism.
+    #[error("Morphism `{0}` in the pattern is not matched to a basic morphism")]
+    NonBasicMatch(Id),
+
+    /// Element of the pattern matched to the same element as another.
+    #[error("Elements `{0}` and `{1}` of the pattern have the same match")]
+    NonInjective(Id, Id),
+
+    /// Morphism in the model that would be left without a domain or codomain.
+    #[error("Morphism `{0}` would be left dangling by the rewrite")]
+    Dangling(Id),
+
+    /// Failure to glue the replacement onto the model.
+    #[error("{0}")]
+    Pushout(PushoutError<Id>),
+
+    /// The rewritten model is not well defined.
+    #[error("Rewritten model is not well defined")]
+    Invalid(NonEmpty<InvalidDiscreteDblModel<Id>>),
+}
+
+#[cfg(test)]
+mod tests {
+    use ustr::ustr;
+
+    use super::*;
+    use crate::dbl::observer::ModelObserver;
+    use crate::dbl::provenance::AuthorId;
+    use crate::one::PathEq;
+    use crate::stdlib::theories::*;
+    use crate::zero::{FinSet, Set};
+
+    #[test]
+    fn validate_discrete_dbl_model() {
+        let th = Arc::new(th_schema());
+        let mut model = DiscreteDblModel::new(th.clone());
+        let entity = ustr("entity");
         model.add_ob(entity, ustr("NotObType"));
         assert_eq!(model.validate().unwrap_err().len(), 1);
 
@@ -478,4 +2266,749 @@ mod tests {
         model.add_mor(ustr("b"), entity, ustr("type"), FinMor::Id(ustr("Entity")));
         assert_eq!(model.validate().unwrap_err().len(), 1);
     }
+
+    /// Row of a morphism in the mock store: ID, domain, codomain, and type.
+    type MorRow = (Ustr, Option<Ustr>, Option<Ustr>, FinMor<Ustr, Ustr>);
+
+    /// Mock store that reads generators in pages, as from a database.
+    struct PagedStore {
+        obs: Vec<(Ustr, Ustr)>,
+        mors: Vec<MorRow>,
+        pages_read: std::cell::Cell<usize>,
+    }
+
+    impl PagedStore {
+        const PAGE_SIZE: usize = 2;
+
+        fn copy_of(model: &UstrDiscreteDblModel) -> Self {
+            let obs = model.ob_generators().map(|x| (x, model.ob_gen_type(&x))).collect();
+            let mors = model
+                .mor_generators()
+                .map(|f| {
+                    (
+                        f,
+                        model.get_dom(&f).copied(),
+                        model.get_cod(&f).copied(),
+                        model.mor_gen_type(&f),
+                    )
+                })
+                .collect();
+            Self {
+                obs,
+                mors,
+                pages_read: Default::default(),
+            }
+        }
+
+        fn pages<'a, T>(&'a self, rows: &'a [T]) -> impl Iterator<Item = &'a T> + 'a {
+            rows.chunks(Self::PAGE_SIZE).flat_map(|page| {
+                self.pages_read.set(self.pages_read.get() + 1);
+                page.iter()
+            })
+        }
+
+        fn find_mor(&self, f: &Ustr) -> Option<&MorRow> {
+            self.pages(&self.mors).find(|row| row.0 == *f)
+        }
+    }
+
+    impl ModelStore for PagedStore {
+        type Id = Ustr;
+        type ObType = Ustr;
+        type MorType = FinMor<Ustr, Ustr>;
+
+        fn ob_generators(&self) -> impl Iterator<Item = Ustr> + '_ {
+            self.pages(&self.obs).map(|row| row.0)
+        }
+        fn mor_generators(&self) -> impl Iterator<Item = Ustr> + '_ {
+            self.pages(&self.mors).map(|row| row.0)
+        }
+        fn has_ob_generator(&self, x: &Ustr) -> bool {
+            self.pages(&self.obs).any(|row| row.0 == *x)
+        }
+        fn has_mor_generator(&self, f: &Ustr) -> bool {
+            self.find_mor(f).is_some()
+        }
+        fn lookup_ob_type(&self, x: &Ustr) -> Option<Ustr> {
+            self.pages(&self.obs).find(|row| row.0 == *x).map(|row| row.1)
+        }
+        fn lookup_mor_type(&self, f: &Ustr) -> Option<FinMor<Ustr, Ustr>> {
+            self.find_mor(f).map(|row| row.3.clone())
+        }
+        fn lookup_dom(&self, f: &Ustr) -> Option<Ustr> {
+            self.find_mor(f).and_then(|row| row.1)
+        }
+        fn lookup_cod(&self, f: &Ustr) -> Option<Ustr> {
+            self.find_mor(f).and_then(|row| row.2)
+        }
+    }
+
+    #[test]
+    fn validate_in_store() {
+        let th = Arc::new(th_symmetric_links());
+        let link = FinMor::Generator(ustr("Link"));
+        let mut model = DiscreteDblModel::new(th.clone());
+        for x in ["x", "y", "z"] {
+            model.add_ob(ustr(x), ustr("Object"));
+        }
+        model.add_ob(ustr("w"), ustr("Thing"));
+        model.add_mor(ustr("f"), ustr("x"), ustr("y"), link.clone());
+        model.add_mor(ustr("g"), ustr("y"), ustr("x"), link.clone());
+        model.add_mor(ustr("h"), ustr("y"), ustr("z"), link.clone());
+        model.add_mor(ustr("k"), ustr("z"), ustr("w"), FinMor::Generator(ustr("Path")));
+        model.make_mor(ustr("dangling"), link);
+        model.update_dom(ustr("dangling"), Some(ustr("missing")));
+
+        let store = PagedStore::copy_of(&model);
+        let expected: Vec<_> = model.iter_invalid().collect();
+        assert_eq!(expected.len(), 5);
+        assert_eq!(iter_invalid_in_store(&store, &th).collect::<Vec<_>>(), expected);
+
+        // Four objects and five morphisms take two and three pages, and a
+        // lookup reads pages only until it finds the generator.
+        let pages_read = |f: &dyn Fn()| {
+            store.pages_read.set(0);
+            f();
+            store.pages_read.get()
+        };
+        assert_eq!(pages_read(&|| assert_eq!(store.ob_generators().count(), 4)), 2);
+        assert_eq!(pages_read(&|| assert_eq!(store.mor_generators().count(), 5)), 3);
+        assert_eq!(pages_read(&|| assert_eq!(store.lookup_dom(&ustr("f")), Some(ustr("x")))), 1);
+        assert_eq!(pages_read(&|| assert_eq!(store.lookup_cod(&ustr("dangling")), None)), 3);
+        assert_eq!(pages_read(&|| assert!(!store.has_ob_generator(&ustr("missing")))), 2);
+
+        // Extracting the typed graph scans the objects, looks up their types
+        // (1 + 1 + 2 + 2 pages), scans the morphisms, and looks up the domain,
+        // codomain, and type of each (3 × (1 + 1 + 2 + 2 + 3) pages).
+        assert_eq!(pages_read(&|| drop(underlying_typed_graph_in_store(&store))), 2 + 6 + 3 + 27);
+    }
+
+    #[test]
+    fn queries_in_store() {
+        let th = Arc::new(th_signed_category());
+        let (object, negative) = (ustr("Object"), FinMor::Generator(ustr("Negative")));
+        let mut model = DiscreteDblModel::new(th.clone());
+        for x in ["x", "y", "z", "w"] {
+            model.add_ob(ustr(x), object);
+        }
+        model.add_mor(ustr("f"), ustr("x"), ustr("y"), negative.clone());
+        model.add_mor(ustr("g"), ustr("y"), ustr("z"), negative);
+        model.add_mor(ustr("h"), ustr("x"), ustr("z"), FinMor::Id(object));
+        model.make_mor(ustr("dangling"), FinMor::Id(object));
+        model.update_dom(ustr("dangling"), Some(ustr("w")));
+
+        let store = PagedStore::copy_of(&model);
+        let sorted = |components: Vec<HashFinSet<Ustr>>| {
+            let mut components: Vec<Vec<_>> = components
+                .into_iter()
+                .map(|c| {
+                    let mut c: Vec<_> = c.into_iter().collect();
+                    c.sort();
+                    c
+                })
+                .collect();
+            components.sort();
+            components
+        };
+        assert_eq!(
+            sorted(connected_components_in_store(&store)),
+            sorted(model.connected_components())
+        );
+        assert_eq!(sorted(model.connected_components()).len(), 2);
+
+        let follow = |f: &Ustr| *f != ustr("h");
+        let reached = reachable_in_store(&store, &ustr("x"), follow, None);
+        assert_eq!(reached, model.reachable(&ustr("x"), follow, None));
+        assert_eq!(reached.len(), 3);
+
+        let weight = |f: &Ustr| if *f == ustr("h") { 3.0 } else { 1.0 };
+        let path = shortest_path_in_store(&store, &th, &ustr("x"), &ustr("z"), weight).unwrap();
+        assert_eq!(path, model.shortest_path(&ustr("x"), &ustr("z"), weight).unwrap());
+        let (path, mor_type) = path.unwrap();
+        assert_eq!(path, Path::pair(ustr("f"), ustr("g")));
+        assert_eq!(mor_type, FinMor::Id(object));
+    }
+
+    #[test]
+    fn bulk_insertion() {
+        let th = Arc::new(th_schema());
+        let (entity, attr_type, attr) = (ustr("Entity"), ustr("AttrType"), ustr("Attr"));
+        let n = 100;
+        let obs: Vec<_> = (0..n)
+            .map(|i| (ustr(&format!("ob{i}")), if i % 2 == 0 { entity } else { attr_type }))
+            .collect();
+        let mors: Vec<_> = (0..n / 2)
+            .map(|i| MorSpec {
+                id: ustr(&format!("mor{i}")),
+                dom: ustr(&format!("ob{}", 2 * i)),
+                cod: ustr(&format!("ob{}", 2 * i + 1)),
+                mor_type: FinMor::Generator(attr),
+            })
+            .collect();
+
+        let mut bulk = DiscreteDblModel::new(th.clone());
+        assert!(bulk.add_obs(obs.clone()).is_ok());
+        assert!(bulk.add_mors(mors.clone()).is_ok());
+
+        let mut incremental = DiscreteDblModel::new(th.clone());
+        for (x, typ) in obs {
+            incremental.add_ob(x, typ);
+        }
+        for spec in mors {
+            incremental.add_mor(spec.id, spec.dom, spec.cod, spec.mor_type);
+        }
+        assert_eq!(bulk, incremental);
+        assert_eq!(
+            bulk.object_generators().collect::<Vec<_>>(),
+            incremental.object_generators().collect::<Vec<_>>()
+        );
+
+        // Errors in a batch are reported by position, including dangling
+        // references that would otherwise go unchecked until validation.
+        let errs = bulk
+            .add_mors([
+                MorSpec {
+                    id: ustr("ok"),
+                    dom: ustr("ob0"),
+                    cod: ustr("ob1"),
+                    mor_type: FinMor::Generator(attr),
+                },
+                MorSpec {
+                    id: ustr("dangling"),
+                    dom: ustr("ob0"),
+                    cod: ustr("missing"),
+                    mor_type: FinMor::Generator(attr),
+                },
+                MorSpec {
+                    id: ustr("ill_typed"),
+                    dom: ustr("ob1"),
+                    cod: ustr("ob0"),
+                    mor_type: FinMor::Generator(attr),
+                },
+            ])
+            .unwrap_err();
+        let indices: Vec<_> = errs.iter().map(|err| err.index).collect();
+        assert_eq!(indices, vec![1, 2, 2]);
+        assert!(
+            matches!(errs[0].error, InvalidDiscreteDblModel::Cod(id) if id == ustr("dangling"))
+        );
+
+        // A batch with errors leaves the model unchanged.
+        assert_eq!(bulk, incremental);
+        assert!(!bulk.has_mor(&Path::single(ustr("ok"))));
+        let revision = bulk.revision();
+        let errs = bulk.add_obs([(ustr("new"), entity), (ustr("bad"), ustr("Bad"))]).unwrap_err();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs.head.index, 1);
+        assert!(!bulk.has_ob(&ustr("new")));
+        assert_eq!(bulk.revision(), revision);
+    }
+
+    #[test]
+    fn validate_symmetric_morphisms() {
+        let th = Arc::new(th_symmetric_links());
+        let link = FinMor::Generator(ustr("Link"));
+        let mut model = DiscreteDblModel::new(th);
+        let (x, y) = (ustr("x"), ustr("y"));
+        model.add_ob(x, ustr("Object"));
+        model.add_ob(y, ustr("Object"));
+        model.add_mor(ustr("loop"), x, x, link.clone());
+        model.add_mor(ustr("f"), x, y, link.clone());
+        let errs: Vec<_> = model.iter_invalid().collect();
+        assert!(matches!(errs[..], [InvalidDiscreteDblModel::MissingReverse(f)] if f == ustr("f")));
+        model.add_mor(ustr("g"), y, x, link);
+        assert!(model.validate().is_ok());
+    }
+
+    #[test]
+    fn build_discrete_dbl_model() {
+        let th = Arc::new(th_schema());
+        let model: UstrDiscreteDblModel = model! {
+            theory: th.clone(),
+            obs: ["customers" => "Entity", "name" => "AttrType"],
+            mors: ["customer_name" => ("customers", "name", ustr("Attr"))],
+        }
+        .unwrap();
+        assert_eq!(model.object_generators().count(), 2);
+        assert_eq!(model.mor_gen_type(&ustr("customer_name")), FinMor::Generator(ustr("Attr")));
+
+        let errs = DiscreteDblModelBuilder::<Ustr, _>::new(th.clone())
+            .object("x", "Entity")
+            .object("x", "Entity")
+            .morphism("f", "x", "y", FinMor::Id(ustr("Entity")))
+            .build()
+            .unwrap_err();
+        assert!(matches!(errs.head, DiscreteDblModelBuildError::DuplicateOb(1, _)));
+        assert!(matches!(errs.tail[..], [DiscreteDblModelBuildError::UnknownOb(2, _)]));
+        assert_eq!(errs.tail[0].to_string(), "Declaration 2: object `y` is not declared");
+
+        let errs = DiscreteDblModelBuilder::<Ustr, _>::new(th)
+            .object("x", "Entity")
+            .morphism("f", "x", "x", ustr("Attr"))
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            errs.head,
+            DiscreteDblModelBuildError::Invalid(InvalidDiscreteDblModel::CodType(_))
+        ));
+    }
+
+    #[test]
+    fn provenance() {
+        let th = Arc::new(th_category());
+        let (ob, id) = (ustr("Object"), FinMor::Id(ustr("Object")));
+        let (alice, bob) = (AuthorId::from("alice"), AuthorId::from("bob"));
+        let stamp = |author_id, at| Some(EditStamp { author_id, at });
+        let prov = |model: &UstrDiscreteDblModel, x: &str| {
+            model
+                .provenance()
+                .get(&ustr(x))
+                .map(|p| (p.author_id, p.created_at, p.modified_at))
+        };
+
+        let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(th.clone());
+        model.set_edit_stamp(stamp(alice, 10));
+        model.add_ob(ustr("x"), ob);
+        model.add_ob(ustr("y"), ob);
+        model.add_mor(ustr("f"), ustr("x"), ustr("y"), id.clone());
+        model.set_edit_stamp(stamp(bob, 20));
+        model.add_ob(ustr("x"), ob);
+        model.update_cod(ustr("f"), Some(ustr("x")));
+        assert_eq!(prov(&model, "x"), Some((alice, 10, 10)));
+        assert_eq!(prov(&model, "f"), Some((alice, 10, 20)));
+        model.set_edit_stamp(None);
+        model.add_ob(ustr("z"), ob);
+        assert_eq!(prov(&model, "z"), None);
+
+        let sub = model.induced_submodel([ustr("y")]);
+        assert_eq!(sub.provenance().len(), 1);
+        assert_eq!(prov(&sub, "y"), Some((alice, 10, 10)));
+        assert!(model.remove_mor(&ustr("f")));
+        assert_eq!(prov(&model, "f"), None);
+
+        // Merged elements keep the earliest creation and latest modification.
+        let vertex: UstrDiscreteDblModel =
+            model! { theory: th.clone(), obs: ["v" => "Object"] }.unwrap();
+        let mut other: UstrDiscreteDblModel = DiscreteDblModel::new(th.clone());
+        other.set_edit_stamp(stamp(bob, 5));
+        other.add_ob(ustr("w"), ob);
+        other.provenance_mut().record_modified(&ustr("w"), 30);
+        let (mut f, mut g): (DiscreteDblModelMapping<_, _>, DiscreteDblModelMapping<_, _>) =
+            Default::default();
+        f.assign_ob(ustr("v"), ustr("y"));
+        g.assign_ob(ustr("v"), ustr("w"));
+        let glued = DiscreteDblModel::pushout(
+            &DiscreteDblModelMorphism(&f, &vertex, &model),
+            &DiscreteDblModelMorphism(&g, &vertex, &other),
+        )
+        .unwrap();
+        assert_eq!(prov(&glued, "x"), Some((alice, 10, 10)));
+        assert_eq!(prov(&glued, "y"), Some((bob, 5, 30)));
+        assert_eq!(prov(&glued, "w"), None);
+    }
+
+    #[test]
+    fn pushout_along_shared_vertex() {
+        let th = Arc::new(th_category());
+        let ob = ustr("Object");
+        let edge = |x: &str, y: &str| -> UstrDiscreteDblModel {
+            model! {
+                theory: th.clone(),
+                obs: [x => "Object", y => "Object"],
+                mors: ["f" => (x, y, FinMor::Id(ob))],
+            }
+            .unwrap()
+        };
+        let vertex: UstrDiscreteDblModel =
+            model! { theory: th.clone(), obs: ["v" => "Object"] }.unwrap();
+        let (left, right) = (edge("x", "y"), edge("y", "x"));
+        let (mut f, mut g): (DiscreteDblModelMapping<_, _>, DiscreteDblModelMapping<_, _>) =
+            Default::default();
+        f.assign_ob(ustr("v"), ustr("y"));
+        g.assign_ob(ustr("v"), ustr("y"));
+
+        // The unmerged elements of the right model that share names with those
+        // of the left model are renamed.
+        let glued = DiscreteDblModel::pushout(
+            &DiscreteDblModelMorphism(&f, &vertex, &left),
+            &DiscreteDblModelMorphism(&g, &vertex, &right),
+        )
+        .unwrap();
+        assert!(glued.validate().is_ok());
+        let mut obs: Vec<_> = glued.object_generators().collect();
+        obs.sort();
+        assert_eq!(obs, vec![ustr("x"), ustr("x_2"), ustr("y")]);
+        let mut mors: Vec<_> = glued.morphism_generators().collect();
+        mors.sort();
+        assert_eq!(mors, vec![ustr("f"), ustr("f_2")]);
+        assert_eq!(glued.get_dom(&ustr("f_2")), Some(&ustr("y")));
+        assert_eq!(glued.get_cod(&ustr("f_2")), Some(&ustr("x_2")));
+
+        // The model morphisms must have a common domain.
+        let mut other_vertex = vertex.clone();
+        other_vertex.add_ob(ustr("w"), ob);
+        assert_eq!(
+            DiscreteDblModel::pushout(
+                &DiscreteDblModelMorphism(&f, &vertex, &left),
+                &DiscreteDblModelMorphism(&g, &other_vertex, &right),
+            ),
+            Err(ColimitError::DomMismatch)
+        );
+    }
+
+    #[test]
+    fn compose_schema_cospans() {
+        let th = Arc::new(th_schema());
+        let entity = |x: &str| -> UstrDiscreteDblModel {
+            model! { theory: th.clone(), obs: [x => "Entity"] }.unwrap()
+        };
+        let leg = |x: &str, y: &str| {
+            let mut f: DiscreteDblModelMapping<_, _> = Default::default();
+            f.assign_ob(ustr(x), ustr(y));
+            f
+        };
+
+        let people: UstrDiscreteDblModel = model! {
+            theory: th.clone(),
+            obs: ["person" => "Entity", "name" => "AttrType"],
+            mors: ["person_name" => ("person", "name", ustr("Attr"))],
+        }
+        .unwrap();
+        let c1 = ModelCospan::new(
+            entity("a"),
+            leg("a", "person"),
+            people,
+            leg("i", "person"),
+            entity("i"),
+        );
+        assert!(c1.validate().is_ok());
+
+        let employees: UstrDiscreteDblModel = model! {
+            theory: th.clone(),
+            obs: ["employee" => "Entity", "salary" => "AttrType"],
+            mors: ["employee_salary" => ("employee", "salary", ustr("Attr"))],
+        }
+        .unwrap();
+        let c2 = ModelCospan::new(
+            entity("i"),
+            leg("i", "employee"),
+            employees.clone(),
+            leg("b", "employee"),
+            entity("b"),
+        );
+        assert!(c2.validate().is_ok());
+
+        let c = compose_cospans(c1, c2).unwrap();
+        assert!(c.validate().is_ok());
+        assert!(c.apex().validate().is_ok());
+        assert_eq!(c.apex().object_generators().count(), 3);
+        assert_eq!(c.apex().morphism_generators().count(), 2);
+        assert!(c.apex().has_ob(&ustr("person")));
+        assert!(!c.apex().has_ob(&ustr("employee")));
+        assert_eq!(c.apex().dom(&ustr("employee_salary").into()), ustr("person"));
+        assert_eq!(c.right_leg().0.apply_ob(&ustr("b")), Some(ustr("person")));
+
+        // Legs must preserve types.
+        let bad = ModelCospan::new(
+            entity("i"),
+            leg("i", "salary"),
+            employees.clone(),
+            leg("b", "employee"),
+            entity("b"),
+        );
+        assert!(matches!(
+            bad.validate().unwrap_err().head,
+            InvalidModelLeg::Left(InvalidDblModelMorphism::ObType(_))
+        ));
+
+        // Names of unmerged elements must not collide.
+        let c1 = ModelCospan::new(
+            entity("a"),
+            leg("a", "employee"),
+            employees.clone(),
+            leg("i", "employee"),
+            entity("i"),
+        );
+        let c2 = ModelCospan::new(
+            entity("i"),
+            leg("i", "employee"),
+            employees,
+            leg("b", "employee"),
+            entity("b"),
+        );
+        assert!(matches!(
+            compose_cospans(c1, c2),
+            Err(ComposeCospansError::Pushout(PushoutError::Collision(_)))
+        ));
+    }
+
+    #[test]
+    fn pushout_invalid_spans() {
+        let th = Arc::new(th_schema());
+        let entity = |x: &str| -> UstrDiscreteDblModel {
+            model! { theory: th.clone(), obs: [x => "Entity"] }.unwrap()
+        };
+        let leg = |x: &str, y: &str| {
+            let mut f: DiscreteDblModelMapping<_, _> = Default::default();
+            f.assign_ob(ustr(x), ustr(y));
+            f
+        };
+        let span = |left, right_foot| {
+            ModelSpan::new(entity("x"), left, entity("i"), leg("i", "y"), right_foot)
+        };
+
+        // Legs undefined on the apex or leaving their foot.
+        let undefined = span(Default::default(), entity("y")).pushout();
+        assert_eq!(undefined.err(), Some(PushoutError::NonBasicOb(ustr("i"))));
+        let outside = span(leg("i", "z"), entity("y")).pushout();
+        assert_eq!(outside.err(), Some(PushoutError::NonBasicOb(ustr("i"))));
+
+        // Feet of different theories.
+        let other: UstrDiscreteDblModel =
+            model! { theory: Arc::new(th_schema()), obs: ["y" => "Entity"] }.unwrap();
+        let mismatch = span(leg("i", "x"), other).pushout();
+        assert_eq!(mismatch.err(), Some(PushoutError::TheoryMismatch));
+
+        // Feet with dangling morphisms.
+        let mut dangling = entity("y");
+        dangling.make_mor(ustr("f"), FinMor::Id(ustr("Entity")));
+        let invalid = span(leg("i", "x"), dangling).pushout();
+        assert_eq!(invalid.err(), Some(PushoutError::InvalidFoot(ustr("f"))));
+    }
+
+    #[test]
+    fn observe_mutations() {
+        use crate::dbl::observer::ObFiberIndex;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct Counter {
+            adds: Arc<AtomicUsize>,
+            retypes: Arc<AtomicUsize>,
+            updates: Arc<AtomicUsize>,
+        }
+        impl ModelObserver<Ustr, Ustr, FinMor<Ustr, Ustr>> for Counter {
+            fn on_add_ob(&mut self, _: &Ustr, _: &Ustr) {
+                self.adds.fetch_add(1, Ordering::Relaxed);
+            }
+            fn on_add_mor(&mut self, _: &Ustr, _: &FinMor<Ustr, Ustr>) {
+                self.adds.fetch_add(1, Ordering::Relaxed);
+            }
+            fn on_retype_ob(&mut self, _: &Ustr, _: &Ustr, _: &Ustr) {
+                self.retypes.fetch_add(1, Ordering::Relaxed);
+            }
+            fn on_update_dom(&mut self, _: &Ustr, _: Option<&Ustr>, _: Option<&Ustr>) {
+                self.updates.fetch_add(1, Ordering::Relaxed);
+            }
+            fn on_update_cod(&mut self, _: &Ustr, _: Option<&Ustr>, _: Option<&Ustr>) {
+                self.updates.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut model = DiscreteDblModel::new(Arc::new(th_schema()));
+        let counter = Counter::default();
+        let (adds, retypes, updates) =
+            (counter.adds.clone(), counter.retypes.clone(), counter.updates.clone());
+        let index = ObFiberIndex::default();
+        model.add_observer(Box::new(counter));
+        model.add_observer(Box::new(index.clone()));
+
+        let (entity, attr_type) = (ustr("Entity"), ustr("AttrType"));
+        let (x, y, a) = (ustr("x"), ustr("y"), ustr("a"));
+        model.add_ob(x, entity);
+        model.add_ob(y, entity);
+        model.add_ob(y, entity);
+        model.add_mor(a, x, y, FinMor::Generator(ustr("Attr")));
+        model.add_ob(y, attr_type);
+        model.update_cod(a, Some(y));
+        model.update_cod(a, None);
+        assert_eq!(adds.load(Ordering::Relaxed), 3);
+        assert_eq!(retypes.load(Ordering::Relaxed), 1);
+        assert_eq!(updates.load(Ordering::Relaxed), 3);
+
+        assert_eq!(index.fiber(&entity), vec![x]);
+        assert_eq!(index.fiber(&attr_type), vec![y]);
+        let fiber: Vec<_> = model.object_generators_with_type(&attr_type).collect();
+        assert_eq!(index.fiber(&attr_type), fiber);
+
+        // Clones of the model do not notify the original observers.
+        let mut clone = model.clone();
+        clone.add_ob(ustr("z"), entity);
+        assert_eq!(adds.load(Ordering::Relaxed), 3);
+
+        // The model's own index of objects by type is not shared with clones.
+        let fiber = |model: &UstrDiscreteDblModel| -> Vec<_> {
+            model.object_generators_with_type(&entity).collect()
+        };
+        assert_eq!(fiber(&model), vec![x]);
+        assert_eq!(fiber(&clone), vec![x, ustr("z")]);
+        clone.remove_ob(&x);
+        assert_eq!((fiber(&model), fiber(&clone)), (vec![x], vec![ustr("z")]));
+    }
+
+    #[test]
+    fn shortest_path() {
+        let th = Arc::new(th_signed_category());
+        let mut model = DiscreteDblModel::new(th);
+        let (x, y, z) = (ustr("x"), ustr("y"), ustr("z"));
+        let (object, negative) = (ustr("Object"), FinMor::Generator(ustr("Negative")));
+        model.add_ob(x, object);
+        model.add_ob(y, object);
+        model.add_ob(z, object);
+        model.add_mor(ustr("f"), x, y, negative.clone());
+        model.add_mor(ustr("g"), y, z, negative.clone());
+        model.add_mor(ustr("h"), x, z, negative.clone());
+
+        let result = model.shortest_path(&x, &z, |_| 1.0);
+        assert_eq!(result, Ok(Some((Path::single(ustr("h")), negative.clone()))));
+        let result = model.shortest_path(&x, &z, |f| if *f == ustr("h") { 3.0 } else { 1.0 });
+        assert_eq!(result, Ok(Some((Path::pair(ustr("f"), ustr("g")), FinMor::Id(object)))));
+        assert_eq!(model.shortest_path(&z, &x, |_| 1.0), Ok(None));
+        assert!(model.shortest_path(&x, &z, |_| -1.0).is_err());
+
+        assert_eq!(model.connected_components().len(), 1);
+        model.add_ob(ustr("w"), object);
+        assert_eq!(model.connected_components().len(), 2);
+
+        let graph = model.underlying_typed_graph();
+        assert_eq!(graph.connected_components(), model.connected_components());
+        let f = graph.edge_index(&ustr("f")).unwrap();
+        assert_eq!(graph.vertex_id(graph.src(&f)), &x);
+        assert_eq!(graph.edge_type(f), &FinMor::Generator(ustr("Negative")));
+        assert_eq!(model.neighborhood(&x, 1).len(), 3);
+        assert_eq!(model.neighborhood(&ustr("w"), 1).len(), 1);
+
+        // Closing the paths from `x` to `z` gives a negative and a positive loop.
+        model.add_mor(ustr("k"), z, x, negative.clone());
+        assert_eq!(model.loops(3).len(), 2);
+        let fgk = Path::from_vec(vec![ustr("f"), ustr("g"), ustr("k")]).unwrap();
+        assert_eq!(model.loops_with_type(&negative, 3), vec![fgk]);
+        let positive = model.loops_with_type(&FinMor::Id(object), 3);
+        assert_eq!(positive, vec![Path::pair(ustr("h"), ustr("k"))]);
+
+        // The typed graph is derived again only after the model changes.
+        let mut cache = DerivedCache::default();
+        assert_eq!(model.cached_typed_graph(&mut cache).edge_count(), 4);
+        assert!(cache.get(model.revision()).is_some());
+        model.add_mor(ustr("l"), z, z, negative);
+        assert!(cache.get(model.revision()).is_none());
+        assert_eq!(model.cached_typed_graph(&mut cache).edge_count(), 5);
+    }
+
+    #[test]
+    fn traverse_after_removal() {
+        let th = Arc::new(th_category());
+        let mut model = DiscreteDblModel::new(th);
+        let (x, y) = (ustr("x"), ustr("y"));
+        model.add_ob(x, ustr("Object"));
+        model.add_ob(y, ustr("Object"));
+        model.add_mor(ustr("f"), x, y, FinMor::Id(ustr("Object")));
+        assert_eq!(model.reachable(&x, |_| true, None).len(), 2);
+
+        // The morphism `f` is left with an unset codomain, which is skipped.
+        model.remove_ob(&y);
+        let reached = model.reachable(&x, |_| true, None);
+        assert!(reached.contains(&x) && reached.len() == 1);
+    }
+
+    #[test]
+    fn minimal_theory() {
+        let th = Arc::new(th_schema());
+        let mut model = DiscreteDblModel::new(th.clone());
+        let (x, y) = (ustr("x"), ustr("y"));
+        model.add_ob(x, ustr("Entity"));
+        model.add_ob(y, ustr("Entity"));
+        model.add_mor(ustr("f"), x, y, FinMor::Id(ustr("Entity")));
+        let sub = model.minimal_theory();
+        assert!(sub.has_ob_type(&ustr("Entity")));
+        assert!(!sub.has_ob_type(&ustr("AttrType")));
+        assert!(!sub.has_mor_type(&FinMor::Generator(ustr("Attr"))));
+
+        model.add_ob(ustr("type"), ustr("AttrType"));
+        model.add_mor(ustr("a"), x, ustr("type"), FinMor::Generator(ustr("Attr")));
+        let sub = model.minimal_theory();
+        assert!(sub.has_ob_type(&ustr("AttrType")));
+        assert!(sub.has_mor_type(&FinMor::Generator(ustr("Attr"))));
+    }
+
+    #[test]
+    fn inline_pass_through_object() {
+        let th = Arc::new(th_category());
+        let hom = FinMor::Id(ustr("Object"));
+        let pattern: UstrDiscreteDblModel = model! {
+            theory: th.clone(),
+            obs: ["x" => "Object", "y" => "Object", "z" => "Object"],
+            mors: ["f" => ("x", "y", hom.clone()), "g" => ("y", "z", hom.clone())],
+        }
+        .unwrap();
+        let interface: UstrDiscreteDblModel = model! {
+            theory: th.clone(),
+            obs: ["x" => "Object", "z" => "Object"],
+        }
+        .unwrap();
+        let replacement: UstrDiscreteDblModel = model! {
+            theory: th.clone(),
+            obs: ["x" => "Object", "z" => "Object"],
+            mors: ["h" => ("x", "z", hom.clone())],
+        }
+        .unwrap();
+        let mut inclusion: DiscreteDblModelMapping<_, _> = Default::default();
+        inclusion.assign_ob(ustr("x"), ustr("x"));
+        inclusion.assign_ob(ustr("z"), ustr("z"));
+        let rule = RewriteRule::new(ModelSpan::new(
+            pattern,
+            inclusion.clone(),
+            interface,
+            inclusion,
+            replacement,
+        ));
+
+        let mut model: UstrDiscreteDblModel = model! {
+            theory: th.clone(),
+            obs: ["a" => "Object", "b" => "Object", "c" => "Object", "d" => "Object"],
+            mors: [
+                "p" => ("a", "b", hom.clone()),
+                "q" => ("b", "c", hom.clone()),
+                "r" => ("a", "d", hom.clone()),
+            ],
+        }
+        .unwrap();
+        let matches = find_matches(&model, &rule);
+        assert_eq!(matches.len(), 1);
+        let m = &matches[0];
+        assert_eq!(m.apply_ob(&ustr("y")), Some(ustr("b")));
+
+        let result = apply_rewrite(&model, &rule, m).unwrap();
+        assert_eq!(model.object_generators().count(), 4);
+        assert_eq!(model.morphism_generators().count(), 3);
+        assert_eq!(result.object_generators().count(), 3);
+        assert_eq!(result.morphism_generators().count(), 2);
+        assert!(!result.has_ob(&ustr("b")));
+        let h = Path::single(ustr("h"));
+        assert_eq!((result.dom(&h), result.cod(&h)), (ustr("a"), ustr("c")));
+
+        // Deleting the pass-through object would leave a morphism dangling.
+        model.add_mor(ustr("s"), ustr("b"), ustr("d"), hom.clone());
+        assert!(find_matches(&model, &rule).is_empty());
+        assert!(matches!(
+            apply_rewrite(&model, &rule, m),
+            Err(RewriteError::Dangling(s)) if s == ustr("s")
+        ));
+
+        // A replacement with equations is rejected before gluing.
+        let (pattern, left, interface, right, mut replacement) = (
+            rule.pattern().clone(),
+            rule.span.left.clone(),
+            rule.interface().clone(),
+            rule.span.right.clone(),
+            rule.replacement().clone(),
+        );
+        replacement.add_mor(ustr("k"), ustr("x"), ustr("z"), hom);
+        replacement.category.add_equation(
+            ustr("eq"),
+            PathEq::new(Path::single(ustr("h")), Path::single(ustr("k"))),
+        );
+        let rule = RewriteRule::new(ModelSpan::new(pattern, left, interface, right, replacement));
+        assert!(matches!(apply_rewrite(&model, &rule, m), Err(RewriteError::RuleNotFree)));
+    }
 }
@@ -19,13 +19,18 @@ oplax.
  */
 
 use std::hash::Hash;
+use std::ops::ControlFlow;
 use std::sync::Arc;
 
 use derivative::Derivative;
+use nonempty::NonEmpty;
+use thiserror::Error;
 
 use crate::one::graph_algorithms::{simple_paths, spec_order};
 use crate::one::*;
-use crate::zero::{Column, HashColumn, Mapping};
+use crate::progress::{Cancelled, NoProgress, Progress};
+use crate::validate::{self, Validate};
+use crate::zero::{classify, Column, HashColumn, MapClassification, Mapping, OrderedHashFinSet};
 
 use super::model::{DblModel, DiscreteDblModel, FgDblModel};
 
@@ -155,6 +160,31 @@ where
         im
     }
 
+    /** Composes with another mapping, in diagrammatic order.
+
+    The composite is defined exactly where both mappings are defined.
+     */
+    pub fn compose<Id>(
+        &self,
+        other: &DiscreteDblModelMapping<CodId, Id>,
+    ) -> DiscreteDblModelMapping<DomId, Id>
+    where
+        Id: Clone + Eq + Hash,
+    {
+        let mut composite: DiscreteDblModelMapping<_, _> = Default::default();
+        for (x, y) in self.ob_map.iter() {
+            if let Some(z) = other.apply_ob(y) {
+                composite.assign_ob(x, z);
+            }
+        }
+        for (e, path) in self.mor_map.iter() {
+            if let Some(path) = other.apply_mor(path) {
+                composite.assign_basic_mor(e, path);
+            }
+        }
+        composite
+    }
+
     /// Finder of morphisms between two models of a discrete double theory.
     pub fn morphisms<'a, Cat>(
         dom: &'a DiscreteDblModel<DomId, Cat>,
@@ -201,6 +231,145 @@ where
     }
 }
 
+/** A morphism between models of a discrete double theory.
+
+Like a [graph morphism](crate::one::GraphMorphism), this struct borrows its data
+to perform validation. The domain and codomain are assumed to be valid models.
+ */
+pub struct DiscreteDblModelMorphism<'a, DomId, CodId, Cat: FgCategory>(
+    pub &'a DiscreteDblModelMapping<DomId, CodId>,
+    pub &'a DiscreteDblModel<DomId, Cat>,
+    pub &'a DiscreteDblModel<CodId, Cat>,
+);
+
+impl<'a, DomId, CodId, Cat> DiscreteDblModelMorphism<'a, DomId, CodId, Cat>
+where
+    DomId: Clone + Eq + Hash,
+    CodId: Clone + Eq + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    /// Iterates over failures of the mapping to be a model morphism.
+    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidDblModelMorphism<DomId>> + 'a {
+        let DiscreteDblModelMorphism(mapping, dom, cod) = *self;
+        let ob_errors = dom.object_generators().filter_map(|x| match mapping.apply_ob(&x) {
+            Some(y) if cod.has_ob(&y) => {
+                if cod.ob_type(&y) == dom.ob_type(&x) {
+                    None
+                } else {
+                    Some(InvalidDblModelMorphism::ObType(x))
+                }
+            }
+            _ => Some(InvalidDblModelMorphism::Ob(x)),
+        });
+
+        let mor_errors = dom.morphism_generators().flat_map(|e| {
+            let path = Path::single(e.clone());
+            if let Some(image) = mapping.apply_basic_mor(&e) {
+                if cod.has_mor(&image) {
+                    let mut errs = Vec::new();
+                    if mapping.apply_ob(&dom.dom(&path)) != Some(cod.dom(&image)) {
+                        errs.push(InvalidDblModelMorphism::Dom(e.clone()));
+                    }
+                    if mapping.apply_ob(&dom.cod(&path)) != Some(cod.cod(&image)) {
+                        errs.push(InvalidDblModelMorphism::Cod(e.clone()));
+                    }
+                    if cod.mor_type(&image) != dom.mor_type(&path) {
+                        errs.push(InvalidDblModelMorphism::MorType(e));
+                    }
+                    return errs;
+                }
+            }
+            vec![InvalidDblModelMorphism::Mor(e)]
+        });
+
+        ob_errors.chain(mor_errors)
+    }
+
+    /** Classifies the mapping on basic objects as injective and/or surjective.
+
+    Any witnesses are chosen deterministically, following the order in which
+    the basic objects were added to the domain and codomain models.
+     */
+    pub fn classify_obs(&self) -> MapClassification<DomId, CodId> {
+        let DiscreteDblModelMorphism(mapping, dom, cod) = *self;
+        let mut dom_obs: OrderedHashFinSet<_> = Default::default();
+        dom_obs.extend(dom.object_generators());
+        let mut cod_obs: OrderedHashFinSet<_> = Default::default();
+        cod_obs.extend(cod.object_generators());
+        classify(&mapping.ob_map, &dom_obs, &cod_obs)
+    }
+
+    /** Iterates over failures of the mapping to be a model morphism that is
+    injective on objects.
+
+    The failures to be a model morphism are followed by a pair of basic objects
+    mapped to the same object, if any, as witnessed by
+    [`classify_obs`](Self::classify_obs).
+     */
+    pub fn iter_invalid_injective(
+        &self,
+    ) -> impl Iterator<Item = InvalidDblModelMorphism<DomId>> + 'a {
+        let collision = self.classify_obs().collision;
+        let collision = collision.map(|(x, y)| InvalidDblModelMorphism::NonInjectiveOb(x, y));
+        self.iter_invalid().chain(collision)
+    }
+
+    /// Validates that the mapping is a model morphism injective on objects.
+    pub fn validate_injective(&self) -> Result<(), NonEmpty<InvalidDblModelMorphism<DomId>>> {
+        validate::wrap_errors(self.iter_invalid_injective())
+    }
+}
+
+impl<DomId, CodId, Cat> Validate for DiscreteDblModelMorphism<'_, DomId, CodId, Cat>
+where
+    DomId: Clone + Eq + Hash,
+    CodId: Clone + Eq + Hash,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    type ValidationError = InvalidDblModelMorphism<DomId>;
+
+    fn validate(&self) -> Result<(), NonEmpty<Self::ValidationError>> {
+        validate::wrap_errors(self.iter_invalid())
+    }
+}
+
+/// A failure of a [mapping](DblModelMapping) between models to define a model
+/// morphism.
+#[derive(Debug, Error)]
+pub enum InvalidDblModelMorphism<Id> {
+    /// A basic object not mapped to an object in the codomain.
+    #[error("Object `{0}` is not mapped to an object in the codomain")]
+    Ob(Id),
+
+    /// A basic morphism not mapped to a morphism in the codomain.
+    #[error("Morphism `{0}` is not mapped to a morphism in the codomain")]
+    Mor(Id),
+
+    /// A basic object whose type is not preserved.
+    #[error("Mapping of object `{0}` does not preserve its type")]
+    ObType(Id),
+
+    /// A basic morphism whose type is not preserved.
+    #[error("Mapping of morphism `{0}` does not preserve its type")]
+    MorType(Id),
+
+    /// A basic morphism whose domain is not preserved.
+    #[error("Mapping of morphism `{0}` does not preserve its domain")]
+    Dom(Id),
+
+    /// A basic morphism whose codomain is not preserved.
+    #[error("Mapping of morphism `{0}` does not preserve its codomain")]
+    Cod(Id),
+
+    /// Two basic objects mapped to the same object by an injective morphism.
+    #[error("Objects `{0}` and `{1}` are mapped to the same object")]
+    NonInjectiveOb(Id, Id),
+}
+
 /** Finds morphisms between two models of a discrete double theory.
 
 Morphisms are found using backtracking search. In general, there can be
@@ -262,21 +431,48 @@ where
 
     /// Finds all morphisms.
     pub fn find_all(&mut self) -> Vec<DiscreteDblModelMapping<DomId, CodId>> {
-        self.search(0);
-        std::mem::take(&mut self.results)
+        self.find_all_with_progress(&mut NoProgress)
+            .expect("Search without progress receiver should not be cancelled")
+    }
+
+    /** Finds all morphisms, reporting progress as the search proceeds.
+
+    Progress is reported in the phase `"search"` after each partial assignment
+    is explored, with the number of partial assignments explored so far. If
+    the search is cancelled, the morphisms found so far are discarded.
+     */
+    pub fn find_all_with_progress(
+        &mut self,
+        progress: &mut impl Progress,
+    ) -> Result<Vec<DiscreteDblModelMapping<DomId, CodId>>, Cancelled> {
+        let mut explored = 0;
+        let flow = self.search(0, &mut explored, progress);
+        self.map = Default::default();
+        let results = std::mem::take(&mut self.results);
+        match flow {
+            ControlFlow::Continue(()) => Ok(results),
+            ControlFlow::Break(()) => Err(Cancelled),
+        }
     }
 
-    fn search(&mut self, depth: usize) {
+    fn search(
+        &mut self,
+        depth: usize,
+        explored: &mut usize,
+        progress: &mut impl Progress,
+    ) -> ControlFlow<()> {
+        *explored += 1;
+        progress.report("search", *explored, None)?;
         if depth >= self.var_order.len() {
             self.results.push(self.map.clone());
-            return;
+            return ControlFlow::Continue(());
         }
         let var = &self.var_order[depth];
         match var.clone() {
             GraphElem::Vertex(x) => {
                 for y in self.cod.object_generators_with_type(&self.dom.ob_type(&x)) {
                     self.map.assign_ob(x.clone(), y);
-                    self.search(depth + 1);
+                    self.search(depth + 1, explored, progress)?;
                 }
             }
             GraphElem::Edge(m) => {
@@ -296,11 +492,12 @@ where
                 for path in simple_paths(cod_graph, &w, &z) {
                     if self.cod.mor_type(&path) == mor_type && !(self.monic && path.is_empty()) {
                         self.map.assign_basic_mor(m.clone(), path);
-                        self.search(depth + 1);
+                        self.search(depth + 1, explored, progress)?;
                     }
                 }
             }
         }
+        ControlFlow::Continue(())
     }
 }
 
@@ -309,6 +506,7 @@ mod tests {
     use ustr::ustr;
 
     use super::*;
+    use crate::one::fin_category::FinMor;
     use crate::stdlib::*;
     use crate::validate::Validate;
 
@@ -363,4 +561,84 @@ mod tests {
         assert!(im.has_mor(&Path::single(ustr("positive"))));
         assert!(im.has_mor(&Path::single(ustr("negative"))));
     }
+
+    #[test]
+    fn cancel_search() {
+        // Positive loops in a complete graph of positive morphisms, a search
+        // with many partial assignments.
+        let th = Arc::new(th_signed_category());
+        let positive_loop = positive_loop(th.clone());
+        let mut complete = DiscreteDblModel::new(th);
+        let obs: Vec<_> = (0..3).map(|i| ustr(&format!("x{i}"))).collect();
+        for x in &obs {
+            complete.add_ob(*x, ustr("Object"));
+        }
+        for x in &obs {
+            for y in obs.iter().filter(|y| *y != x) {
+                let f = ustr(&format!("{x}_{y}"));
+                complete.add_mor(f, *x, *y, FinMor::Id(ustr("Object")));
+            }
+        }
+        let pos = positive_loop.morphism_generators().next().unwrap().into();
+        let images = |maps: Vec<DiscreteDblModelMapping<_, _>>| -> Vec<_> {
+            maps.into_iter().map(|map| map.apply_mor(&pos)).collect()
+        };
+        let expected =
+            images(DiscreteDblModelMapping::morphisms(&positive_loop, &complete).find_all());
+
+        let mut finder = DiscreteDblModelMapping::morphisms(&positive_loop, &complete);
+        let mut reports = 0;
+        let mut cancel_early = |phase: &str, done: usize, total: Option<usize>| {
+            assert_eq!((phase, total), ("search", None));
+            reports += 1;
+            if done < 10 {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        };
+        assert!(matches!(finder.find_all_with_progress(&mut cancel_early), Err(Cancelled)));
+        assert_eq!(reports, 10);
+
+        // The finder unwinds cleanly and can be run again to completion.
+        let mut reports = 0;
+        let mut count = |_: &str, _: usize, _: Option<usize>| {
+            reports += 1;
+            ControlFlow::Continue(())
+        };
+        let maps = finder.find_all_with_progress(&mut count).unwrap();
+        assert!(reports > 10);
+        assert_eq!(images(maps), expected);
+    }
+
+    #[test]
+    fn classify_obs() {
+        let th = Arc::new(th_signed_category());
+        let (negative_loop, negative_feedback) = (negative_loop(th.clone()), negative_feedback(th));
+        let base_pt = negative_loop.object_generators().next().unwrap();
+
+        let mut f: DiscreteDblModelMapping<_, _> = Default::default();
+        f.assign_ob(ustr("x"), base_pt);
+        f.assign_ob(ustr("y"), base_pt);
+        let classified =
+            DiscreteDblModelMorphism(&f, &negative_feedback, &negative_loop).classify_obs();
+        assert_eq!(classified.collision, Some((ustr("x"), ustr("y"))));
+        assert!(classified.is_surjective());
+        let morphism = DiscreteDblModelMorphism(&f, &negative_feedback, &negative_loop);
+        let errs: Vec<_> = morphism.iter_invalid_injective().collect();
+        assert_eq!(errs.len(), morphism.iter_invalid().count() + 1);
+        assert!(matches!(
+            errs.last(),
+            Some(InvalidDblModelMorphism::NonInjectiveOb(x, y)) if (*x, *y) == (ustr("x"), ustr("y"))
+        ));
+        let err = morphism.validate_injective().unwrap_err().last().to_string();
+        assert_eq!(err, "Objects `x` and `y` are mapped to the same object");
+
+        let mut g: DiscreteDblModelMapping<_, _> = Default::default();
+        g.assign_ob(base_pt, ustr("x"));
+        let classified =
+            DiscreteDblModelMorphism(&g, &negative_loop, &negative_feedback).classify_obs();
+        assert!(classified.is_injective());
+        assert_eq!(classified.missed, Some(ustr("y")));
+    }
 }
@@ -0,0 +1,219 @@
+/*! Namespaces for organizing the generators of models.
+
+Large models are easier to navigate when their generators are grouped into
+nested folders, such as `billing/invoices`. Folders are purely organizational:
+the folder of each generator is recorded in a [`NamespaceTree`]. Generators not
+assigned to any folder belong to the root folder.
+
+Only generators of the model can be moved into folders. The tree does not
+observe the model, so after generators are removed from the model, the tree
+should be [pruned](NamespaceTree::prune) to forget their folders.
+ */
+
+use std::hash::Hash;
+
+use thiserror::Error;
+use ustr::Ustr;
+
+use super::generator_column::GeneratorColumn;
+use super::model::{DiscreteDblModel, ModelStore};
+use crate::one::FgCategory;
+
+/// Path to a folder, as a sequence of folder names starting from the root.
+pub type FolderPath = Vec<Ustr>;
+
+/// Folders of the generators of a model.
+pub type NamespaceTree<Id> = GeneratorColumn<Id, FolderPath>;
+
+/// A failure to move generators into a folder.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum NamespaceError<Id> {
+    /// Generator is not a basic object or morphism in the model.
+    #[error("Generator `{0}` is not in the model")]
+    NotInModel(Id),
+}
+
+impl<Id> NamespaceTree<Id>
+where
+    Id: Eq + Clone + Hash,
+{
+    /// Gets the folder containing a generator, empty for the root.
+    pub fn folder_of(&self, id: &Id) -> &[Ustr] {
+        self.get(id).map(|path| path.as_slice()).unwrap_or_default()
+    }
+
+    /** Moves generators of a model into a folder.
+
+    Moving generators into the root folder, given by the empty path, forgets
+    their recorded folders. If any generator is not in the model, no generator
+    is moved. The model can be any [store](ModelStore) of generators.
+     */
+    pub fn move_generators<S>(
+        &mut self,
+        store: &S,
+        ids: impl IntoIterator<Item = Id>,
+        path: &[Ustr],
+    ) -> Result<(), NamespaceError<Id>>
+    where
+        S: ModelStore<Id = Id>,
+    {
+        let ids: Vec<_> = ids.into_iter().collect();
+        if let Some(id) = ids.iter().find(|id| !has_generator(store, id)) {
+            return Err(NamespaceError::NotInModel(id.clone()));
+        }
+        for id in ids {
+            if path.is_empty() {
+                self.remove(&id);
+            } else {
+                self.insert(id, path.to_vec());
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets the folders of generators no longer in the model.
+    pub fn prune<S>(&mut self, store: &S)
+    where
+        S: ModelStore<Id = Id>,
+    {
+        self.retain(|id, _| has_generator(store, id))
+    }
+
+    /** Is the generator in the folder?
+
+    When `recursive` is true, generators in subfolders of the folder count as
+    being in the folder.
+     */
+    pub fn is_in(&self, id: &Id, path: &[Ustr], recursive: bool) -> bool {
+        let folder = self.folder_of(id);
+        if recursive {
+            folder.starts_with(path)
+        } else {
+            folder == path
+        }
+    }
+
+    /** Lists the generators of a model in a folder.
+
+    The basic objects are listed first and then the basic morphisms, each in the
+    order in which they were added to the model.
+     */
+    pub fn generators_in<Cat: FgCategory>(
+        &self,
+        model: &DiscreteDblModel<Id, Cat>,
+        path: &[Ustr],
+        recursive: bool,
+    ) -> Vec<Id>
+    where
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        model
+            .object_generators()
+            .chain(model.morphism_generators())
+            .filter(|id| self.is_in(id, path, recursive))
+            .collect()
+    }
+
+    /** Extracts the contents of a folder as a standalone model.
+
+    This is the [submodel induced](DiscreteDblModel::induced_submodel) by the
+    generators in the folder. Morphisms in the folder whose domain or codomain
+    lies outside it bring their endpoints into the extracted model.
+     */
+    pub fn extract<Cat: FgCategory>(
+        &self,
+        model: &DiscreteDblModel<Id, Cat>,
+        path: &[Ustr],
+        recursive: bool,
+    ) -> DiscreteDblModel<Id, Cat>
+    where
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        model.induced_submodel(self.generators_in(model, path, recursive))
+    }
+}
+
+fn has_generator<S: ModelStore>(store: &S, id: &S::Id) -> bool {
+    store.has_ob_generator(id) || store.has_mor_generator(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ustr::ustr;
+
+    use super::*;
+    use crate::dbl::model::{FgDblModel, UstrDiscreteDblModel};
+    use crate::one::fin_category::FinMor;
+    use crate::stdlib::theories::th_schema;
+    use crate::validate::Validate;
+
+    #[test]
+    fn nested_folders() {
+        let (entity, attr_type, attr) = (ustr("Entity"), ustr("AttrType"), ustr("Attr"));
+        let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(Arc::new(th_schema()));
+        model.add_ob(ustr("Customer"), entity);
+        model.add_ob(ustr("Invoice"), entity);
+        model.add_ob(ustr("Amount"), attr_type);
+        model.add_ob(ustr("Item"), entity);
+        model.add_mor(ustr("total"), ustr("Invoice"), ustr("Amount"), FinMor::Generator(attr));
+        model.add_mor(ustr("billed"), ustr("Invoice"), ustr("Customer"), FinMor::Id(entity));
+        model.add_mor(ustr("price"), ustr("Item"), ustr("Amount"), FinMor::Generator(attr));
+
+        let (billing, invoices) = (vec![ustr("billing")], vec![ustr("billing"), ustr("invoices")]);
+        let mut tree: NamespaceTree<_> = Default::default();
+        assert!(tree.move_generators(&model, [ustr("Customer")], &billing).is_ok());
+        let ids = [ustr("Invoice"), ustr("total"), ustr("billed")];
+        assert!(tree.move_generators(&model, ids, &invoices).is_ok());
+        assert_eq!(tree.folder_of(&ustr("Invoice")), invoices.as_slice());
+        assert!(tree.folder_of(&ustr("Item")).is_empty());
+
+        assert_eq!(tree.generators_in(&model, &billing, false), vec![ustr("Customer")]);
+        assert_eq!(
+            tree.generators_in(&model, &billing, true),
+            vec![ustr("Customer"), ustr("Invoice"), ustr("total"), ustr("billed")]
+        );
+        assert_eq!(
+            tree.generators_in(&model, &[], false),
+            vec![ustr("Amount"), ustr("Item"), ustr("price")]
+        );
+        assert_eq!(tree.generators_in(&model, &[], true).len(), 7);
+
+        // The morphisms `total` and `billed` cross the boundary of the folder,
+        // so their codomains are brought along, but the unrelated item is not.
+        let sub = tree.extract(&model, &invoices, false);
+        assert!(sub.validate().is_ok());
+        assert_eq!(
+            sub.object_generators().collect::<Vec<_>>(),
+            vec![ustr("Customer"), ustr("Invoice"), ustr("Amount")]
+        );
+        assert_eq!(
+            sub.morphism_generators().collect::<Vec<_>>(),
+            vec![ustr("total"), ustr("billed")]
+        );
+        assert_eq!(sub.ob_gen_type(&ustr("Amount")), attr_type);
+
+        // Moving to the root forgets the folder, without touching the model.
+        let before = model.clone();
+        assert!(tree.move_generators(&model, [ustr("Customer")], &[]).is_ok());
+        assert!(tree.generators_in(&model, &billing, false).is_empty());
+        assert_eq!(model, before);
+
+        // Generators not in the model cannot be moved, and none are moved then.
+        assert_eq!(
+            tree.move_generators(&model, [ustr("Item"), ustr("Vendor")], &billing),
+            Err(NamespaceError::NotInModel(ustr("Vendor")))
+        );
+        assert!(!tree.contains(&ustr("Item")));
+
+        // Removing generators from the model leaves their folders to be pruned.
+        model.remove_mor(&ustr("total"));
+        assert!(tree.contains(&ustr("total")));
+        tree.prune(&model);
+        assert!(!tree.contains(&ustr("total")));
+        assert_eq!(tree.len(), 2);
+    }
+}
@@ -0,0 +1,120 @@
+/*! Observers of mutations to models.
+
+Downstream data structures like search indexes and caches often need to stay in
+sync with a model as it is edited. Rather than building each of these into the
+model itself, they can be implemented as [observers](ModelObserver) and
+registered with a [discrete model](super::model::DiscreteDblModel::add_observer).
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, RwLock};
+
+/** An observer of mutations to a model of a double theory.
+
+Each method is invoked synchronously after the corresponding mutation has been
+applied, with the affected generator and its old and new values. All methods
+do nothing by default, so observers need only implement those they care about.
+
+Because observers are notified only after the model has been updated, an
+observer that panics cannot leave the model in an inconsistent state, though
+observers registered after it will miss the notification.
+ */
+pub trait ModelObserver<Id, ObType, MorType>: Send + Sync {
+    /// Called after a new basic object is added.
+    fn on_add_ob(&mut self, _x: &Id, _typ: &ObType) {}
+
+    /// Called after a new basic morphism is added.
+    fn on_add_mor(&mut self, _f: &Id, _typ: &MorType) {}
+
+    /// Called after a basic object is removed, with its former type.
+    fn on_remove_ob(&mut self, _x: &Id, _typ: &ObType) {}
+
+    /// Called after a basic morphism is removed, with its former type.
+    fn on_remove_mor(&mut self, _f: &Id, _typ: &MorType) {}
+
+    /// Called after the type of an existing basic object is changed.
+    fn on_retype_ob(&mut self, _x: &Id, _old: &ObType, _new: &ObType) {}
+
+    /// Called after the type of an existing basic morphism is changed.
+    fn on_retype_mor(&mut self, _f: &Id, _old: &MorType, _new: &MorType) {}
+
+    /// Called after the domain of a basic morphism is updated.
+    fn on_update_dom(&mut self, _f: &Id, _old: Option<&Id>, _new: Option<&Id>) {}
+
+    /// Called after the codomain of a basic morphism is updated.
+    fn on_update_cod(&mut self, _f: &Id, _old: Option<&Id>, _new: Option<&Id>) {}
+}
+
+/// A boxed observer, as stored by a model.
+pub type BoxedModelObserver<Id, ObType, MorType> = Box<dyn ModelObserver<Id, ObType, MorType>>;
+
+/** An index of the basic objects in a model by their types.
+
+This is a reference implementation of a [`ModelObserver`]. The index is shared
+between clones, so one clone can be registered with a model while another is
+kept to query the index. A [detached](Self::detached) copy is not shared.
+Discrete models keep such an index themselves to find their
+[objects of a given type](super::model::FgDblModel::object_generators_with_type).
+ */
+#[derive(Clone, Debug)]
+pub struct ObFiberIndex<Id, ObType>(Arc<RwLock<HashMap<ObType, Vec<Id>>>>);
+
+impl<Id, ObType> Default for ObFiberIndex<Id, ObType> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<Id: Clone, ObType: Clone> ObFiberIndex<Id, ObType> {
+    /// Copies the index into a new one that is not shared with this one.
+    pub fn detached(&self) -> Self {
+        let fibers = self.0.read().expect("Index lock should not be poisoned");
+        Self(Arc::new(RwLock::new(fibers.clone())))
+    }
+}
+
+impl<Id, ObType> ObFiberIndex<Id, ObType>
+where
+    Id: Eq + Clone,
+    ObType: Eq + Hash,
+{
+    /// Gets the basic objects of the given type, in order of addition.
+    pub fn fiber(&self, typ: &ObType) -> Vec<Id> {
+        let fibers = self.0.read().expect("Index lock should not be poisoned");
+        fibers.get(typ).cloned().unwrap_or_default()
+    }
+
+    /// Adds a basic object to the fiber over its type.
+    pub fn insert(&self, x: Id, typ: ObType) {
+        let mut fibers = self.0.write().expect("Index lock should not be poisoned");
+        fibers.entry(typ).or_default().push(x);
+    }
+
+    /// Removes a basic object from the fiber over its type.
+    pub fn remove(&self, x: &Id, typ: &ObType) {
+        let mut fibers = self.0.write().expect("Index lock should not be poisoned");
+        if let Some(fiber) = fibers.get_mut(typ) {
+            fiber.retain(|y| y != x);
+        }
+    }
+}
+
+impl<Id, ObType, MorType> ModelObserver<Id, ObType, MorType> for ObFiberIndex<Id, ObType>
+where
+    Id: Eq + Clone + Send + Sync,
+    ObType: Eq + Hash + Clone + Send + Sync,
+{
+    fn on_add_ob(&mut self, x: &Id, typ: &ObType) {
+        self.insert(x.clone(), typ.clone());
+    }
+
+    fn on_remove_ob(&mut self, x: &Id, typ: &ObType) {
+        self.remove(x, typ);
+    }
+
+    fn on_retype_ob(&mut self, x: &Id, old: &ObType, new: &ObType) {
+        self.remove(x, old);
+        self.insert(x.clone(), new.clone());
+    }
+}
@@ -0,0 +1,297 @@
+/*! Ownership of generators by notebook cells.
+
+In a notebook, each cell introduces some generators of the model, and moving
+or deleting the cell should move or delete exactly those generators. The
+[`CellOwnership`] column records the cell owning each generator.
+
+Deleting a cell can leave morphisms introduced by other cells with a domain or
+codomain that no longer exists. Such [dangling references](DanglingRef) are
+reported, so that the other cells can be flagged for the user to repair. If the
+theory [asks for it](super::theory::DiscreteDblTheory::cross_cell_warnings),
+validation also warns about morphisms between objects of different cells.
+ */
+
+use std::hash::Hash;
+
+use nonempty::NonEmpty;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+use super::generator_column::GeneratorColumn;
+use super::model::{DblModel, DiscreteDblModel, InvalidDiscreteDblModel};
+use crate::one::{FgCategory, FinGraph, Graph};
+use crate::validate;
+
+/// Cells owning the generators of a model.
+pub type CellOwnership<Id> = GeneratorColumn<Id, CellId<Id>>;
+
+/** ID of a notebook cell, as distinct from the ID of a generator.
+
+Cells and generators are typically identified by the same kind of ID, such as a
+UUID, so wrapping cell IDs keeps the two apart in a [`CellOwnership`] column.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct CellId<Id>(pub Id);
+
+/** A reference to an object removed along with the cell that owned it.
+
+The reference is from a morphism not owned by the deleted cell, whose domain or
+codomain was the removed object and has now been unset.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct DanglingRef<Id> {
+    /// Morphism with the dangling reference.
+    pub mor: Id,
+
+    /// Cell owning the morphism, if any.
+    pub cell: Option<Id>,
+
+    /// Object that was removed.
+    pub ob: Id,
+
+    /// Whether the object was the domain, as opposed to the codomain.
+    pub is_dom: bool,
+}
+
+impl<Id> CellOwnership<Id>
+where
+    Id: Eq + Clone + Hash,
+{
+    /// Gets the cell owning a generator, if recorded.
+    pub fn owner(&self, id: &Id) -> Option<&Id> {
+        self.get(id).map(|cell| &cell.0)
+    }
+
+    /// Records that a cell owns a generator, returning the previous owner.
+    pub fn assign(&mut self, id: Id, cell: Id) -> Option<Id> {
+        self.insert(id, CellId(cell)).map(|cell| cell.0)
+    }
+
+    /// Moves generators to another cell, as when they are cut and pasted.
+    pub fn reassign(&mut self, ids: impl IntoIterator<Item = Id>, cell: Id) {
+        for id in ids {
+            self.insert(id, CellId(cell.clone()));
+        }
+    }
+
+    /// Iterates over the generators owned by a cell, in no particular order.
+    pub fn generators_owned_by<'a>(&'a self, cell: &'a Id) -> impl Iterator<Item = &'a Id> {
+        self.iter().filter(move |(_, owner)| owner.0 == *cell).map(|(id, _)| id)
+    }
+
+    /** Deletes a cell, removing the generators it owns from the model.
+
+    Morphisms owned by the cell are removed first, so that only references from
+    morphisms owned by other cells, or by no cell, are reported as dangling.
+    The references are returned in no particular order.
+     */
+    pub fn delete_cell<Cat>(
+        &mut self,
+        cell: &Id,
+        model: &mut DiscreteDblModel<Id, Cat>,
+    ) -> Vec<DanglingRef<Id>>
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        let owned: Vec<_> = self.generators_owned_by(cell).cloned().collect();
+        for id in owned.iter() {
+            self.remove(id);
+        }
+        let (mors, obs): (Vec<_>, Vec<_>) =
+            owned.into_iter().partition(|id| model.generating_graph().has_edge(id));
+        for f in mors.iter() {
+            model.remove_mor(f);
+        }
+
+        let mut dangling = Vec::new();
+        for x in obs {
+            let graph = model.generating_graph();
+            let outs = graph.out_edges(&x).map(|f| (f, true));
+            let ins = graph.in_edges(&x).map(|f| (f, false));
+            for (mor, is_dom) in outs.chain(ins) {
+                dangling.push(DanglingRef {
+                    cell: self.owner(&mor).cloned(),
+                    mor,
+                    ob: x.clone(),
+                    is_dom,
+                });
+            }
+            model.remove_ob(&x);
+        }
+        dangling
+    }
+
+    /** Iterates over morphisms whose domain and codomain are owned by different cells.
+
+    Such morphisms are well defined but may be unwanted, for instance when each
+    cell is meant to present a self-contained part of the model, so callers can
+    surface them as warnings. Morphisms with an endpoint owned by no cell are
+    not reported.
+     */
+    pub fn cross_cell_morphisms<'a, Cat>(
+        &'a self,
+        model: &'a DiscreteDblModel<Id, Cat>,
+    ) -> impl Iterator<Item = Id> + 'a
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        model.generating_graph().edges().filter(move |f| {
+            let owner = |x: Option<&Id>| x.and_then(|x| self.owner(x));
+            match (owner(model.get_dom(f)), owner(model.get_cod(f))) {
+                (Some(c), Some(d)) => c != d,
+                _ => false,
+            }
+        })
+    }
+
+    /// Validates a model along with the ownership of its generators by cells.
+    pub fn validate_in<Cat>(
+        &self,
+        model: &DiscreteDblModel<Id, Cat>,
+    ) -> Result<(), NonEmpty<InvalidDiscreteDblModel<Id>>>
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        validate::wrap_errors(self.iter_invalid_in(model))
+    }
+
+    /** Iterates over failures of a model, including those due to cell ownership.
+
+    The failures of the model itself come first. They are followed by a
+    [warning](InvalidDiscreteDblModel::CrossCell) for each [cross-cell
+    morphism](Self::cross_cell_morphisms), but only if the theory of the model
+    [asks for them](super::theory::DiscreteDblTheory::cross_cell_warnings).
+     */
+    pub fn iter_invalid_in<'a, Cat>(
+        &'a self,
+        model: &'a DiscreteDblModel<Id, Cat>,
+    ) -> impl Iterator<Item = InvalidDiscreteDblModel<Id>> + 'a
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        let warn = model.theory().cross_cell_warnings();
+        let cross_cell_warnings = warn
+            .then(|| self.cross_cell_morphisms(model))
+            .into_iter()
+            .flatten()
+            .map(InvalidDiscreteDblModel::CrossCell);
+        model.iter_invalid().chain(cross_cell_warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ustr::{ustr, Ustr};
+
+    use super::*;
+    use crate::dbl::model::UstrDiscreteDblModel;
+    use crate::dbl::theory::UstrDiscreteDblTheory;
+    use crate::one::fin_category::FinMor;
+    use crate::stdlib::theories::{th_category, th_schema};
+    use crate::validate::Validate;
+
+    #[test]
+    fn delete_cell() {
+        let (entity, attr_type) = (ustr("Entity"), ustr("AttrType"));
+        let attr = FinMor::Generator(ustr("Attr"));
+        let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(Arc::new(th_schema()));
+        model.add_ob(ustr("person"), entity);
+        model.add_ob(ustr("pet"), entity);
+        model.add_ob(ustr("name"), attr_type);
+        model.add_mor(ustr("owner"), ustr("pet"), ustr("person"), FinMor::Id(entity));
+        model.add_mor(ustr("person_name"), ustr("person"), ustr("name"), attr.clone());
+        model.add_mor(ustr("pet_name"), ustr("pet"), ustr("name"), attr);
+
+        let (c1, c2, c3) = (ustr("cell1"), ustr("cell2"), ustr("cell3"));
+        let mut cells: CellOwnership<Ustr> = Default::default();
+        cells.reassign([ustr("person"), ustr("name"), ustr("person_name")], c1);
+        cells.reassign([ustr("pet"), ustr("owner")], c2);
+        cells.assign(ustr("pet_name"), c3);
+        let mut cross: Vec<_> = cells.cross_cell_morphisms(&model).collect();
+        cross.sort();
+        assert_eq!(cross, vec![ustr("owner"), ustr("pet_name")]);
+
+        let mut dangling = cells.delete_cell(&c1, &mut model);
+        dangling.sort_by_key(|r| r.mor);
+        assert_eq!(
+            dangling,
+            vec![
+                DanglingRef {
+                    mor: ustr("owner"),
+                    cell: Some(c2),
+                    ob: ustr("person"),
+                    is_dom: false,
+                },
+                DanglingRef {
+                    mor: ustr("pet_name"),
+                    cell: Some(c3),
+                    ob: ustr("name"),
+                    is_dom: false,
+                },
+            ]
+        );
+        assert!(!model.generating_graph().has_vertex(&ustr("person")));
+        assert!(!model.generating_graph().has_edge(&ustr("person_name")));
+        assert_eq!(model.get_cod(&ustr("owner")), None);
+        assert_eq!(cells.generators_owned_by(&c1).count(), 0);
+        assert_eq!(cells.owner(&ustr("pet")), Some(&c2));
+
+        // Deleting the remaining cells leaves an empty, valid model.
+        assert!(model.validate().is_err());
+        assert_eq!(
+            cells.delete_cell(&c2, &mut model),
+            vec![DanglingRef {
+                mor: ustr("pet_name"),
+                cell: Some(c3),
+                ob: ustr("pet"),
+                is_dom: true,
+            }]
+        );
+        assert!(cells.delete_cell(&c3, &mut model).is_empty());
+        assert!(model.validate().is_ok());
+        assert_eq!(model.generating_graph().vertex_count(), 0);
+    }
+
+    #[test]
+    fn cross_cell_warnings() {
+        let build = |th: UstrDiscreteDblTheory| {
+            let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(Arc::new(th));
+            model.add_ob(ustr("x"), ustr("Object"));
+            model.add_ob(ustr("y"), ustr("Object"));
+            model.add_mor(ustr("f"), ustr("x"), ustr("y"), FinMor::Id(ustr("Object")));
+            model
+        };
+        let mut cells: CellOwnership<Ustr> = Default::default();
+        cells.reassign([ustr("x"), ustr("f")], ustr("cell1"));
+        cells.assign(ustr("y"), ustr("cell2"));
+
+        // Off by default, so the model is valid.
+        assert!(cells.validate_in(&build(th_category())).is_ok());
+
+        let mut th = th_category();
+        th.set_cross_cell_warnings(true);
+        let model = build(th);
+        let errs = cells.validate_in(&model).unwrap_err();
+        assert_eq!(errs, NonEmpty::new(InvalidDiscreteDblModel::CrossCell(ustr("f"))));
+        assert_eq!(errs.head.severity(), crate::dbl::render::Severity::Warning);
+        assert!(model.validate().is_ok());
+    }
+}
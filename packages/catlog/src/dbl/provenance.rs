@@ -0,0 +1,195 @@
+/*! Provenance of generators in models.
+
+Collaborative editing needs to know who added each element of a model and when
+it was last changed, as recorded in a [`ProvenanceColumn`]. A [model] keeps the
+provenance of its generators and, once given an [`EditStamp`] naming the
+current author and time, updates it on every mutation.
+
+[model]: super::model::DiscreteDblModel::provenance
+ */
+
+use std::hash::Hash;
+
+use ustr::Ustr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+use super::generator_column::GeneratorColumn;
+
+/// Identifier of an author of edits to a model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+pub struct AuthorId(pub Ustr);
+
+impl From<&str> for AuthorId {
+    fn from(value: &str) -> Self {
+        AuthorId(Ustr::from(value))
+    }
+}
+
+/** Authorship and timestamps of a generator.
+
+Timestamps are in milliseconds since the Unix epoch, as in JavaScript.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct Provenance {
+    /// Identifier of the author who created the generator.
+    pub author_id: AuthorId,
+
+    /// Time at which the generator was created.
+    pub created_at: u64,
+
+    /// Time at which the generator was last modified.
+    pub modified_at: u64,
+}
+
+/** Author and time to record as the provenance of edits to a model.
+
+Timestamps are in milliseconds since the Unix epoch, as for [`Provenance`].
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct EditStamp {
+    /// Identifier of the author making the edits.
+    pub author_id: AuthorId,
+
+    /// Time at which the edits are made.
+    pub at: u64,
+}
+
+/// Provenance of the generators of a model.
+pub type ProvenanceColumn<Id> = GeneratorColumn<Id, Provenance>;
+
+impl<Id> ProvenanceColumn<Id>
+where
+    Id: Eq + Clone + Hash,
+{
+    /** Records an edit to a generator.
+
+    If the generator has no recorded provenance, it is recorded as created by
+    the given author at the given time. Otherwise, only the modification time is
+    updated. Returns whether the generator was newly created.
+     */
+    pub fn record(&mut self, id: Id, author_id: AuthorId, at: u64) -> bool {
+        let created = !self.contains(&id);
+        let prov = Provenance {
+            author_id,
+            created_at: at,
+            modified_at: at,
+        };
+        self.update(id, prov);
+        created
+    }
+
+    /** Updates the provenance of a generator.
+
+    When the generator already has recorded provenance, the earlier creation
+    takes precedence, along with its author, and the later modification is
+    kept. Ties in creation time are resolved in favor of the existing record.
+     */
+    pub fn update(&mut self, id: Id, prov: Provenance) {
+        match self.get_mut(&id) {
+            Some(ours) => {
+                let modified_at = ours.modified_at.max(prov.modified_at);
+                if prov.created_at < ours.created_at {
+                    *ours = prov;
+                }
+                ours.modified_at = modified_at;
+            }
+            None => {
+                self.insert(id, prov);
+            }
+        }
+    }
+
+    /** Records a modification to a generator.
+
+    The modification time never moves backwards. Returns whether the generator
+    has recorded provenance.
+     */
+    pub fn record_modified(&mut self, id: &Id, at: u64) -> bool {
+        if let Some(prov) = self.get_mut(id) {
+            prov.modified_at = prov.modified_at.max(at);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Iterates over generators created by the given author.
+    pub fn generators_by_author<'a>(
+        &'a self,
+        author_id: &'a AuthorId,
+    ) -> impl Iterator<Item = &'a Id> {
+        self.iter()
+            .filter(move |(_, prov)| prov.author_id == *author_id)
+            .map(|(id, _)| id)
+    }
+
+    /** Merges the provenance recorded in another column into this one.
+
+    Provenance for generators in both columns is combined as in
+    [`update`](Self::update), so that ties favor this column.
+     */
+    pub fn merge(&mut self, other: Self) {
+        for (id, prov) in other {
+            self.update(id, prov);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_provenance() {
+        let (alice, bob) = (AuthorId::from("alice"), AuthorId::from("bob"));
+        let mut col: ProvenanceColumn<char> = Default::default();
+        assert!(col.record('x', alice, 10));
+        assert!(!col.record('x', bob, 20));
+        assert!(col.record('y', bob, 15));
+        assert!(!col.record_modified(&'z', 30));
+        assert_eq!(
+            col.get(&'x'),
+            Some(&Provenance {
+                author_id: alice,
+                created_at: 10,
+                modified_at: 20
+            })
+        );
+        assert_eq!(col.generators_by_author(&bob).collect::<Vec<_>>(), vec![&'y']);
+    }
+
+    #[test]
+    fn merge_provenance() {
+        let (alice, bob) = (AuthorId::from("alice"), AuthorId::from("bob"));
+        let mut ours: ProvenanceColumn<char> = Default::default();
+        ours.record('x', alice, 10);
+        ours.record('y', alice, 10);
+        let mut theirs: ProvenanceColumn<char> = Default::default();
+        theirs.record('x', bob, 5);
+        theirs.record('y', bob, 10);
+        theirs.record_modified(&'y', 40);
+        theirs.record('z', bob, 20);
+
+        ours.merge(theirs);
+        let x = ours.get(&'x').unwrap();
+        assert_eq!((x.author_id, x.created_at, x.modified_at), (bob, 5, 10));
+        let y = ours.get(&'y').unwrap();
+        assert_eq!((y.author_id, y.created_at, y.modified_at), (alice, 10, 40));
+        assert_eq!(ours.get(&'z').map(|z| z.author_id), Some(bob));
+    }
+}
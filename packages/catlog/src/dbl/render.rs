@@ -0,0 +1,174 @@
+/*! Text rendering of validation results for models.
+
+Validation reports [failures](InvalidDiscreteDblModel) by the IDs of the
+offending generators, which are meaningless to people reading logs or command
+line output. The functions here render the failures with human-readable names,
+supplied by the caller, in a stable order: by [severity](Severity), then by ID.
+[`render_errors`] produces one line per failure and [`summarize_errors`] a
+single line suitable for logs.
+ */
+
+use std::fmt::Display;
+
+use super::model::InvalidDiscreteDblModel;
+
+/// Severity of a failure of a model to be well defined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The model is malformed and cannot be used.
+    Error,
+
+    /// The model is well formed but not as the theory intends.
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+impl<Id> InvalidDiscreteDblModel<Id> {
+    /** Severity of the failure.
+
+    Most failures are errors. A missing reverse of a morphism of symmetric type,
+    or a morphism crossing notebook cells, is only a warning, since the model is
+    otherwise well formed.
+     */
+    pub fn severity(&self) -> Severity {
+        match self {
+            InvalidDiscreteDblModel::MissingReverse(_) | InvalidDiscreteDblModel::CrossCell(_) => {
+                Severity::Warning
+            }
+            _ => Severity::Error,
+        }
+    }
+}
+
+/** Renders failures of a model to be well defined, one per line.
+
+Each generator is shown by the name given by `names` or, if it has no name, by
+the first eight characters of its ID. Lines are prefixed by the severity of the
+failure and sorted by severity, then by ID, keeping failures with the same
+severity and ID in their original order.
+ */
+pub fn render_errors<Id>(
+    errors: impl IntoIterator<Item = InvalidDiscreteDblModel<Id>>,
+    names: impl Fn(&Id) -> Option<String>,
+) -> String
+where
+    Id: Ord + Display,
+{
+    rendered_lines(errors, names).into_iter().map(|(_, line)| line + "\n").collect()
+}
+
+/** Summarizes failures of a model to be well defined on a single line.
+
+The failures are counted by severity and then listed as in [`render_errors`],
+separated by semicolons.
+ */
+pub fn summarize_errors<Id>(
+    errors: impl IntoIterator<Item = InvalidDiscreteDblModel<Id>>,
+    names: impl Fn(&Id) -> Option<String>,
+) -> String
+where
+    Id: Ord + Display,
+{
+    let lines = rendered_lines(errors, names);
+    if lines.is_empty() {
+        return "no errors".into();
+    }
+    let count = |severity| lines.iter().filter(|(s, _)| *s == severity).count();
+    let (n_errors, n_warnings) = (count(Severity::Error), count(Severity::Warning));
+    let lines: Vec<_> = lines.into_iter().map(|(_, line)| line).collect();
+    format!("{n_errors} error(s), {n_warnings} warning(s): {}", lines.join("; "))
+}
+
+fn rendered_lines<Id>(
+    errors: impl IntoIterator<Item = InvalidDiscreteDblModel<Id>>,
+    names: impl Fn(&Id) -> Option<String>,
+) -> Vec<(Severity, String)>
+where
+    Id: Ord + Display,
+{
+    let mut errors: Vec<_> = errors.into_iter().collect();
+    errors.sort_by(|a, b| (a.severity(), a.id()).cmp(&(b.severity(), b.id())));
+    errors
+        .into_iter()
+        .map(|err| {
+            let severity = err.severity();
+            let named = err.map(|id| names(&id).unwrap_or_else(|| short_id(&id)));
+            (severity, format!("{severity}: {named}"))
+        })
+        .collect()
+}
+
+fn short_id(id: &impl Display) -> String {
+    id.to_string().chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ustr::{ustr, Ustr};
+
+    use super::*;
+    use crate::dbl::model::{DiscreteDblModel, UstrDiscreteDblModel};
+    use crate::one::fin_category::FinMor;
+    use crate::stdlib::theories::th_symmetric_links;
+
+    fn invalid_model() -> UstrDiscreteDblModel {
+        let (ob, link) = (ustr("Object"), FinMor::Generator(ustr("Link")));
+        let mut model = DiscreteDblModel::new(Arc::new(th_symmetric_links()));
+        model.add_ob(ustr("x"), ob);
+        model.add_ob(ustr("y"), ob);
+        model.add_ob(ustr("0b3c5f2e-9a41"), ustr("Thing"));
+        model.add_mor(ustr("link"), ustr("x"), ustr("y"), link.clone());
+        model.make_mor(ustr("no-dom"), link.clone());
+        model.update_cod(ustr("no-dom"), Some(ustr("x")));
+        model.make_mor(ustr("no-cod"), link);
+        model.update_dom(ustr("no-cod"), Some(ustr("y")));
+        model.add_mor(ustr("shortcut"), ustr("x"), ustr("y"), FinMor::Generator(ustr("Path")));
+        model
+    }
+
+    fn names(id: &Ustr) -> Option<String> {
+        match id.as_str() {
+            "link" => Some("friendship".into()),
+            "shortcut" => Some("x to y".into()),
+            _ => (id.len() < 8).then(|| id.to_string()),
+        }
+    }
+
+    #[test]
+    fn render_invalid_model() {
+        let model = invalid_model();
+        assert_eq!(
+            render_errors(model.iter_invalid(), names),
+            "\
+error: Object `0b3c5f2e` has a type not in the theory
+error: Codomain of morphism `no-cod` is undefined or not in the model
+error: Domain of morphism `no-dom` is undefined or not in the model
+error: Morphism `x to y` has a type not in the theory
+warning: Morphism `friendship` of symmetric type has no reverse morphism
+"
+        );
+        assert_eq!(
+            summarize_errors(model.iter_invalid(), names),
+            "4 error(s), 1 warning(s): \
+error: Object `0b3c5f2e` has a type not in the theory; \
+error: Codomain of morphism `no-cod` is undefined or not in the model; \
+error: Domain of morphism `no-dom` is undefined or not in the model; \
+error: Morphism `x to y` has a type not in the theory; \
+warning: Morphism `friendship` of symmetric type has no reverse morphism"
+        );
+        assert_eq!(
+            summarize_errors(Vec::<InvalidDiscreteDblModel<Ustr>>::new(), names),
+            "no errors"
+        );
+    }
+}
@@ -0,0 +1,350 @@
+/*! Selections of basic objects in models.
+
+A [`Selection`] is a set of basic objects in a model, such as the objects
+highlighted by a user or picked out by an analysis. The selections of a model
+form a lattice, with [meets](Selection::meet) and [joins](Selection::join)
+given by intersection and union, and a [complement](Selection::complement)
+relative to the objects currently in the model.
+
+Because the model can change after a selection is made, each selection records
+the [revision](DiscreteDblModel::revision) of the model it was made from. A
+selection made from an older revision is *stale*: it may refer to objects that
+have since been removed. Operations that consult the model refuse to work with
+stale selections, and selections are serialized together with their revision so
+that a stale selection can be [detected](Selection::check) when it is loaded.
+ */
+
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+use derivative::Derivative;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+use super::model::DiscreteDblModel;
+use crate::one::{Category, FgCategory, FinGraph};
+use crate::zero::{FinSet, HashFinSet, Set};
+
+/// A set of basic objects in a model, made at some revision of the model.
+#[derive(Clone, Debug, Derivative)]
+#[derivative(PartialEq(bound = "Id: Eq + Hash"))]
+#[derivative(Eq(bound = "Id: Eq + Hash"))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "SelectionData<Id>",
+        into = "SelectionData<Id>",
+        bound(
+            serialize = "Id: Serialize + Eq + Clone + Hash",
+            deserialize = "Id: Deserialize<'de> + Eq + Hash"
+        )
+    )
+)]
+pub struct Selection<Id> {
+    objects: HashFinSet<Id>,
+    revision: u64,
+}
+
+/// Serialized form of a selection.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SelectionData<Id: Eq + Hash> {
+    objects: HashSet<Id>,
+    revision: u64,
+}
+
+#[cfg(feature = "serde")]
+impl<Id: Eq + Hash> From<SelectionData<Id>> for Selection<Id> {
+    fn from(data: SelectionData<Id>) -> Self {
+        Self {
+            objects: data.objects.into(),
+            revision: data.revision,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Id: Eq + Hash> From<Selection<Id>> for SelectionData<Id> {
+    fn from(selection: Selection<Id>) -> Self {
+        Self {
+            objects: selection.objects.into(),
+            revision: selection.revision,
+        }
+    }
+}
+
+/// Direction in which to close a selection under basic morphisms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum ClosureDirection {
+    /// Add the codomains of morphisms out of selected objects.
+    Forward,
+
+    /// Add the domains of morphisms into selected objects.
+    Backward,
+
+    /// Add objects connected to selected objects in either direction.
+    Both,
+}
+
+/// A selection made from an earlier revision of a model.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+#[error("Selection was made at revision {selection} but the model is at revision {model}")]
+pub struct StaleSelection {
+    /// Revision of the model when the selection was made.
+    pub selection: u64,
+
+    /// Current revision of the model.
+    pub model: u64,
+}
+
+impl<Id> Selection<Id>
+where
+    Id: Eq + Clone + Hash,
+{
+    /** Selects basic objects in a model at its current revision.
+
+    Identifiers not belonging to basic objects of the model are ignored.
+     */
+    pub fn new<Cat>(model: &DiscreteDblModel<Id, Cat>, ids: impl IntoIterator<Item = Id>) -> Self
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        let objects: HashSet<_> = ids.into_iter().filter(|x| model.has_ob(x)).collect();
+        Self {
+            objects: objects.into(),
+            revision: model.revision(),
+        }
+    }
+
+    /// Selects all the basic objects in a model.
+    pub fn all<Cat>(model: &DiscreteDblModel<Id, Cat>) -> Self
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        Self::new(model, model.object_generators())
+    }
+
+    /// Revision of the model when the selection was made.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Is the object selected?
+    pub fn contains(&self, x: &Id) -> bool {
+        self.objects.contains(x)
+    }
+
+    /// Number of selected objects.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Is the selection empty?
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Iterates over the selected objects, in no particular order.
+    pub fn objects(&self) -> impl Iterator<Item = Id> + '_ {
+        self.objects.iter()
+    }
+
+    /// Is the selection stale, having been made from an earlier revision?
+    pub fn is_stale<Cat>(&self, model: &DiscreteDblModel<Id, Cat>) -> bool
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        self.revision != model.revision()
+    }
+
+    /** Checks that the selection is not stale.
+
+    Selections loaded from storage should be checked against the model before
+    they are used, since the model may have changed in the meantime.
+     */
+    pub fn check<Cat>(&self, model: &DiscreteDblModel<Id, Cat>) -> Result<(), StaleSelection>
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        if self.is_stale(model) {
+            Err(StaleSelection {
+                selection: self.revision,
+                model: model.revision(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /** Brings the selection up to date with the current revision of a model.
+
+    Selected objects that are no longer in the model are dropped.
+     */
+    pub fn refresh<Cat>(&self, model: &DiscreteDblModel<Id, Cat>) -> Self
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        Self::new(model, self.objects())
+    }
+
+    /** Meet of two selections, the objects selected by both.
+
+    When the selections were made at different revisions, the result has the
+    earlier revision, so that it is stale whenever either selection is.
+     */
+    pub fn meet(&self, other: &Self) -> Self {
+        let objects: HashSet<_> = self.objects().filter(|x| other.contains(x)).collect();
+        Self {
+            objects: objects.into(),
+            revision: self.revision.min(other.revision),
+        }
+    }
+
+    /** Join of two selections, the objects selected by either.
+
+    Revisions are combined as in [`meet`](Self::meet).
+     */
+    pub fn join(&self, other: &Self) -> Self {
+        let objects: HashSet<_> = self.objects().chain(other.objects()).collect();
+        Self {
+            objects: objects.into(),
+            revision: self.revision.min(other.revision),
+        }
+    }
+
+    /// Complement of the selection, relative to the objects in the model.
+    pub fn complement<Cat>(&self, model: &DiscreteDblModel<Id, Cat>) -> Result<Self, StaleSelection>
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        self.check(model)?;
+        Ok(Self::new(model, model.object_generators().filter(|x| !self.contains(x))))
+    }
+
+    /** Closes the selection under the basic morphisms of a model.
+
+    The closure is the smallest selection containing this one such that, for
+    every basic morphism with a selected domain, its codomain is also selected
+    when going [forward](ClosureDirection::Forward), and dually when going
+    [backward](ClosureDirection::Backward). Morphisms with an undefined domain
+    or codomain are ignored.
+     */
+    pub fn closure_under_morphisms<Cat>(
+        &self,
+        model: &DiscreteDblModel<Id, Cat>,
+        direction: ClosureDirection,
+    ) -> Result<Self, StaleSelection>
+    where
+        Cat: FgCategory,
+        Cat::Ob: Eq + Clone + Hash,
+        Cat::Mor: Eq + Clone + Hash,
+    {
+        self.check(model)?;
+        let (forward, backward) = match direction {
+            ClosureDirection::Forward => (true, false),
+            ClosureDirection::Backward => (false, true),
+            ClosureDirection::Both => (true, true),
+        };
+        let graph = model.generating_graph();
+        let mut reached: HashSet<Id> = self.objects().collect();
+        let mut queue: VecDeque<Id> = reached.iter().cloned().collect();
+        while let Some(x) = queue.pop_front() {
+            let outs = graph.out_edges(&x).filter(|_| forward);
+            let ins = graph.in_edges(&x).filter(|_| backward);
+            let next: Vec<_> = outs
+                .filter_map(|f| model.get_cod(&f).cloned())
+                .chain(ins.filter_map(|f| model.get_dom(&f).cloned()))
+                .collect();
+            for y in next {
+                if reached.insert(y.clone()) {
+                    queue.push_back(y);
+                }
+            }
+        }
+        Ok(Self {
+            objects: reached.into(),
+            revision: self.revision,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ustr::{ustr, Ustr};
+
+    use super::*;
+    use crate::dbl::model::UstrDiscreteDblModel;
+    use crate::one::fin_category::FinMor;
+    use crate::stdlib::theories::th_category;
+
+    fn sorted(selection: &Selection<Ustr>) -> Vec<Ustr> {
+        let mut objects: Vec<_> = selection.objects().collect();
+        objects.sort();
+        objects
+    }
+
+    #[test]
+    fn closure_and_staleness() {
+        let ob = ustr("Object");
+        let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(Arc::new(th_category()));
+        for x in ["w", "x", "y", "z"] {
+            model.add_ob(ustr(x), ob);
+        }
+        model.add_mor(ustr("f"), ustr("x"), ustr("y"), FinMor::Id(ob));
+        model.add_mor(ustr("g"), ustr("y"), ustr("z"), FinMor::Id(ob));
+
+        let sel = Selection::new(&model, [ustr("y"), ustr("nope")]);
+        assert_eq!(sorted(&sel), vec![ustr("y")]);
+        let fwd = sel.closure_under_morphisms(&model, ClosureDirection::Forward).unwrap();
+        assert_eq!(sorted(&fwd), vec![ustr("y"), ustr("z")]);
+        let bwd = sel.closure_under_morphisms(&model, ClosureDirection::Backward).unwrap();
+        assert_eq!(sorted(&bwd), vec![ustr("x"), ustr("y")]);
+        let both = sel.closure_under_morphisms(&model, ClosureDirection::Both).unwrap();
+        assert_eq!(sorted(&both), vec![ustr("x"), ustr("y"), ustr("z")]);
+
+        assert_eq!(sorted(&fwd.meet(&bwd)), vec![ustr("y")]);
+        assert_eq!(fwd.join(&bwd), both);
+        assert_eq!(sorted(&both.complement(&model).unwrap()), vec![ustr("w")]);
+
+        // Deleting an object makes the selections stale.
+        assert!(model.remove_ob(&ustr("z")));
+        assert!(both.is_stale(&model));
+        assert_eq!(
+            both.complement(&model),
+            Err(StaleSelection {
+                selection: both.revision(),
+                model: model.revision()
+            })
+        );
+        let fresh = both.refresh(&model);
+        assert!(!fresh.is_stale(&model));
+        assert_eq!(sorted(&fresh), vec![ustr("x"), ustr("y")]);
+        let fwd = fresh.closure_under_morphisms(&model, ClosureDirection::Forward).unwrap();
+        assert_eq!(fwd, fresh);
+
+        // Combining with a stale selection gives a stale selection.
+        assert!(fresh.join(&sel).is_stale(&model));
+    }
+}
@@ -67,21 +67,46 @@ composed:
   Section 10: Finite-product double theories
 */
 
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::hash::{BuildHasher, BuildHasherDefault, Hash, RandomState};
 
 use derivative::Derivative;
 use derive_more::From;
 use nonempty::nonempty;
 use ref_cast::RefCast;
+use thiserror::Error;
 use ustr::{IdentityHasher, Ustr};
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
 use super::pasting::DblPasting;
 use crate::one::category::*;
-use crate::one::fin_category::UstrFinCategory;
-use crate::one::path::Path;
+use crate::one::fin_category::{FinCategory, FinMor, UstrFinCategory};
+use crate::one::path::{CompositionNotation, Path};
 use crate::validate::Validate;
 use crate::zero::*;
 
+/** Kind of double theory, or "double doctrine".
+
+Code holding a theory of unknown provenance, such as the frontend choosing an
+editor for a model, can branch on this instead of inspecting the theory itself.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum TheoryKind {
+    /// A discrete double theory, see [`DiscreteDblTheory`].
+    Discrete,
+
+    /// A discrete tabulator theory, see [`DiscreteTabTheory`].
+    DiscreteTab,
+}
+
 /** A double theory.
 
 The terminology used here is explained at greater length in the
@@ -112,6 +137,9 @@ pub trait DblTheory {
     */
     type MorOp: Eq;
 
+    /// Kind of double theory.
+    fn kind(&self) -> TheoryKind;
+
     /// Does the object type belong to the theory?
     fn has_ob_type(&self, x: &Self::ObType) -> bool;
 
@@ -124,6 +152,23 @@ pub trait DblTheory {
     /// Target of morphism type.
     fn tgt(&self, m: &Self::MorType) -> Self::ObType;
 
+    /// Iterates over basic morphism types with the given source.
+    fn mor_types_from(&self, x: &Self::ObType) -> impl Iterator<Item = Self::MorType>;
+
+    /// Iterates over basic morphism types with the given target.
+    fn mor_types_into(&self, y: &Self::ObType) -> impl Iterator<Item = Self::MorType>;
+
+    /** Does the object type admit a basic morphism type to itself?
+
+    Such object types are self-referential, since an object of the type can be
+    related to others of the same type by a morphism that is not the identity.
+    Hom types do not count, but basic morphism types whose composites include a
+    hom type, like the negative sign, do.
+     */
+    fn has_endomorphism(&self, x: &Self::ObType) -> bool {
+        self.mor_types_from(x).any(|m| self.tgt(&m) == *x)
+    }
+
     /// Domain of operation on objects.
     fn dom(&self, f: &Self::ObOp) -> Self::ObType;
 
@@ -200,13 +245,256 @@ indeed **discrete**, which can equivalently be defined as
 - a discrete object in the 2-category of double categories
 - a double category whose underlying categories are both discrete categories
 */
-#[derive(From, RefCast, Debug)]
-#[repr(transparent)]
-pub struct DiscreteDblTheory<Cat: FgCategory>(Cat);
+#[derive(Debug)]
+pub struct DiscreteDblTheory<Cat: FgCategory> {
+    category: Cat,
+    directionality: HashColumn<Cat::Mor, Directionality>,
+    cross_cell_warnings: bool,
+}
+
+/** Directionality of a basic morphism type in a discrete double theory.
+
+Some morphism types are conceptually undirected, representing symmetric
+relations between objects. In a model, a morphism of a [symmetric](Self::Symmetric)
+type must be accompanied by a morphism of the same type in the reverse direction.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum Directionality {
+    /// Morphisms of this type are directed, the default.
+    #[default]
+    Directed,
+
+    /// Morphisms of this type are undirected.
+    Symmetric,
+}
+
+/// A failure to set the directionality of a morphism type.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum DirectionalityError<Mor> {
+    /// Morphism type is not in the theory.
+    #[error("Morphism type `{0}` is not in the theory")]
+    NotInTheory(Mor),
+
+    /// Morphism type set to be symmetric has different source and target.
+    #[error("Symmetric morphism type `{0}` should have equal source and target")]
+    NotEndomorphism(Mor),
+}
+
+/// Converts from the underlying category, with all morphism types directed.
+impl<Cat: FgCategory> From<Cat> for DiscreteDblTheory<Cat> {
+    fn from(category: Cat) -> Self {
+        Self {
+            category,
+            directionality: Default::default(),
+            cross_cell_warnings: false,
+        }
+    }
+}
 
 /// A discrete double theory with keys of type `Ustr`.
 pub type UstrDiscreteDblTheory = DiscreteDblTheory<UstrFinCategory>;
 
+impl<C: FgCategory> DiscreteDblTheory<C> {
+    /** Underlying category of the discrete double theory.
+
+    The object and morphism types of the theory are the objects and morphisms of
+    this category, so it can be used to run graph algorithms on the types.
+     */
+    pub fn category(&self) -> &C {
+        &self.category
+    }
+
+    /** Whether models should be warned about morphisms crossing notebook cells.
+
+    When set, [validating](super::ownership::CellOwnership::validate_in) the
+    cell ownership of a model warns about each morphism whose domain and
+    codomain are owned by different cells. Off by default.
+     */
+    pub fn cross_cell_warnings(&self) -> bool {
+        self.cross_cell_warnings
+    }
+
+    /// Sets whether models should be warned about morphisms crossing cells.
+    pub fn set_cross_cell_warnings(&mut self, warn: bool) {
+        self.cross_cell_warnings = warn;
+    }
+}
+
+impl<C: FgCategory> DiscreteDblTheory<C>
+where
+    C::Mor: Eq + Clone + Hash,
+{
+    /// Directionality of a morphism type, which is directed unless set otherwise.
+    pub fn directionality(&self, m: &C::Mor) -> Directionality {
+        self.directionality.apply(m).copied().unwrap_or_default()
+    }
+
+    /** Sets the directionality of a morphism type.
+
+    The morphism type must be in the theory, and a symmetric morphism type must
+    have the same source and target. Otherwise, the directionality is left
+    unchanged.
+     */
+    pub fn set_directionality(
+        &mut self,
+        m: C::Mor,
+        dir: Directionality,
+    ) -> Result<(), DirectionalityError<C::Mor>> {
+        if !self.category.has_mor(&m) {
+            return Err(DirectionalityError::NotInTheory(m));
+        }
+        if dir == Directionality::Symmetric && self.category.dom(&m) != self.category.cod(&m) {
+            return Err(DirectionalityError::NotEndomorphism(m));
+        }
+        self.directionality.set(m, dir);
+        Ok(())
+    }
+
+    /// Iterates over the morphism types that are set to be symmetric.
+    pub fn symmetric_mor_types(&self) -> impl Iterator<Item = C::Mor> + '_ {
+        self.directionality.preimage(&Directionality::Symmetric)
+    }
+}
+
+/** Serialized form of a discrete double theory.
+
+Only the morphism types set to be symmetric are recorded, since all others are
+directed. Cross-cell warnings are recorded only when turned on.
+ */
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct DiscreteDblTheoryData<Cat, Mor> {
+    category: Cat,
+    symmetric: Vec<Mor>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    cross_cell_warnings: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<V, E, S> Serialize for DiscreteDblTheory<FinCategory<V, E, S>>
+where
+    V: Eq + Clone + Hash + Ord + Serialize,
+    E: Eq + Clone + Hash + Ord + Serialize,
+    S: BuildHasher,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut symmetric: Vec<_> = self.symmetric_mor_types().collect();
+        symmetric.sort();
+        DiscreteDblTheoryData {
+            category: &self.category,
+            symmetric,
+            cross_cell_warnings: self.cross_cell_warnings,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes a discrete double theory, failing unless it is well defined.
+#[cfg(feature = "serde")]
+impl<'de, V, E, S> Deserialize<'de> for DiscreteDblTheory<FinCategory<V, E, S>>
+where
+    V: Eq + Clone + Hash + Deserialize<'de>,
+    E: Eq + Clone + Hash + Deserialize<'de>,
+    S: BuildHasher + Default,
+    crate::one::fin_category::InvalidFinCategory<V, E>: std::fmt::Display,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data =
+            DiscreteDblTheoryData::<FinCategory<V, E, S>, FinMor<V, E>>::deserialize(deserializer)?;
+        let mut theory = DiscreteDblTheory::from(data.category);
+        theory.set_cross_cell_warnings(data.cross_cell_warnings);
+        for m in data.symmetric {
+            if theory.set_directionality(m, Directionality::Symmetric).is_err() {
+                return Err(serde::de::Error::custom(
+                    "Symmetric morphism type should be an endomorphism in the theory",
+                ));
+            }
+        }
+        Ok(theory)
+    }
+}
+
+impl<V, E, S> DiscreteDblTheory<FinCategory<V, E, S>>
+where
+    V: Eq + Clone + Hash,
+    E: Eq + Clone + Hash,
+    S: BuildHasher,
+{
+    /** Groups the morphism types of the theory by their source and target.
+
+    Since the underlying category is finite, every morphism type is either an
+    identity or a generator, and composites are among these. Each hom-set lists
+    the identity first, if the source and target are equal, followed by the
+    generators in their order in the category.
+     */
+    pub fn hom_sets(&self) -> HashMap<(V, V), Vec<FinMor<V, E>>> {
+        let mut homs: HashMap<_, Vec<_>> = HashMap::new();
+        for x in self.category.object_generators() {
+            homs.entry((x.clone(), x.clone())).or_default().push(FinMor::Id(x));
+        }
+        for f in self.category.morphism_generators() {
+            let x = self.category.morphism_generator_dom(&f);
+            let y = self.category.morphism_generator_cod(&f);
+            homs.entry((x, y)).or_default().push(FinMor::Generator(f));
+        }
+        homs
+    }
+
+    /** Iterates over the composites of morphism types mentioning a given one.
+
+    Useful for finding the usages of a morphism type in the composition law of
+    the theory. See [`FinCategory::composites_involving`].
+     */
+    pub fn composites_involving<'a>(
+        &'a self,
+        m: &E,
+    ) -> impl Iterator<Item = ((E, E), FinMor<V, E>)> + 'a {
+        self.category.composites_involving(m)
+    }
+
+    /** Human-readable label for a morphism type, for display in diagrams.
+
+    The label is the [canonical name](FinCategory::canonical_name) of the
+    morphism type in the given notation: a basic morphism type is labeled by its
+    generator and the identity on `x` by `id(x)`.
+     */
+    pub fn mor_type_label(&self, m: &FinMor<V, E>, notation: CompositionNotation) -> String
+    where
+        V: Display,
+        E: Display,
+    {
+        self.category.canonical_name(m).display(notation).to_string()
+    }
+
+    /** Human-readable label for a composite of morphism types.
+
+    Unlike [`mor_type_label`](Self::mor_type_label), the composite is labeled as
+    written, such as `t ∘ i` in classical notation, rather than by the morphism
+    type it computes to. Identities in the path are omitted unless the composite
+    is itself an identity. Fails if the morphism types are not composable.
+     */
+    pub fn composite_label(
+        &self,
+        path: Path<V, FinMor<V, E>>,
+        notation: CompositionNotation,
+    ) -> Result<String, ComposeError<V>>
+    where
+        V: Display,
+        E: Display,
+    {
+        if let Path::Seq(ms) = &path {
+            ms.iter()
+                .skip(1)
+                .try_fold(ms.head.clone(), |f, g| self.category.try_compose2(f, g.clone()))?;
+        }
+        let path = path.map(|x| x, |m| self.category.canonical_name(&m).0).flatten();
+        Ok(path.display(notation).to_string())
+    }
+}
+
 impl<C: FgCategory> DblTheory for DiscreteDblTheory<C>
 where
     C::Ob: Clone,
@@ -217,18 +505,37 @@ where
     type MorType = C::Mor;
     type MorOp = C::Mor;
 
+    fn kind(&self) -> TheoryKind {
+        TheoryKind::Discrete
+    }
+
     fn has_ob_type(&self, x: &Self::ObType) -> bool {
-        self.0.has_ob(x)
+        self.category.has_ob(x)
     }
     fn has_mor_type(&self, m: &Self::MorType) -> bool {
-        self.0.has_mor(m)
+        self.category.has_mor(m)
     }
 
     fn src(&self, m: &Self::MorType) -> Self::ObType {
-        self.0.dom(m)
+        self.category.dom(m)
     }
     fn tgt(&self, m: &Self::MorType) -> Self::ObType {
-        self.0.cod(m)
+        self.category.cod(m)
+    }
+
+    fn mor_types_from(&self, x: &Self::ObType) -> impl Iterator<Item = Self::MorType> {
+        let x = x.clone();
+        self.category
+            .morphism_generators()
+            .filter(move |f| self.category.morphism_generator_dom(f) == x)
+            .map(|f| f.into())
+    }
+    fn mor_types_into(&self, y: &Self::ObType) -> impl Iterator<Item = Self::MorType> {
+        let y = y.clone();
+        self.category
+            .morphism_generators()
+            .filter(move |f| self.category.morphism_generator_cod(f) == y)
+            .map(|f| f.into())
     }
     fn dom(&self, x: &Self::ObOp) -> Self::ObType {
         x.clone()
@@ -238,10 +545,10 @@ where
     }
 
     fn op_src(&self, m: &Self::MorOp) -> Self::ObOp {
-        self.0.dom(m)
+        self.category.dom(m)
     }
     fn op_tgt(&self, m: &Self::MorOp) -> Self::ObOp {
-        self.0.cod(m)
+        self.category.cod(m)
     }
     fn op_dom(&self, m: &Self::MorOp) -> Self::MorType {
         m.clone()
@@ -251,18 +558,18 @@ where
     }
 
     fn compose_types(&self, path: Path<C::Ob, C::Mor>) -> C::Mor {
-        self.0.compose(path)
+        self.category.compose(path)
     }
 
     fn compose_ob_ops(&self, path: Path<C::Ob, C::Ob>) -> C::Ob {
-        let disc = DiscreteCategory::ref_cast(ObSet::ref_cast(&self.0));
+        let disc = DiscreteCategory::ref_cast(ObSet::ref_cast(&self.category));
         disc.compose(path)
     }
 
     fn compose_mor_ops(&self, pasting: DblPasting<C::Ob, C::Ob, C::Mor, C::Mor>) -> C::Mor {
         match pasting {
-            DblPasting::ObId(x) => self.0.id(x),
-            DblPasting::ArrId(fs) => self.0.id(self.compose_ob_ops(Path::Seq(fs))),
+            DblPasting::ObId(x) => self.category.id(x),
+            DblPasting::ArrId(fs) => self.category.id(self.compose_ob_ops(Path::Seq(fs))),
             DblPasting::ProId(ms) => self.compose_types(Path::Seq(ms)),
             DblPasting::Diagram(_) => panic!("General pasting not implemented"),
         }
@@ -273,7 +580,7 @@ impl<C: FgCategory + Validate> Validate for DiscreteDblTheory<C> {
     type ValidationError = C::ValidationError;
 
     fn validate(&self) -> Result<(), nonempty::NonEmpty<Self::ValidationError>> {
-        self.0.validate()
+        self.category.validate()
     }
 }
 
@@ -297,6 +604,24 @@ pub enum TabMorType<V, E> {
     Hom(Box<TabObType<V, E>>),
 }
 
+impl<V: Display, E: Display> Display for TabObType<V, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TabObType::Basic(v) => write!(f, "{v}"),
+            TabObType::Tabulator(m) => write!(f, "Tab({m})"),
+        }
+    }
+}
+
+impl<V: Display, E: Display> Display for TabMorType<V, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TabMorType::Basic(e) => write!(f, "{e}"),
+            TabMorType::Hom(x) => write!(f, "Hom({x})"),
+        }
+    }
+}
+
 /// Object operation in a discrete tabulator theory.
 #[derive(Clone, PartialEq, Eq)]
 pub enum TabObOp<V, E> {
@@ -382,6 +707,44 @@ where
         self.mor_types.insert(e)
     }
 
+    /// Iterates over the basic object types of the theory.
+    pub fn basic_ob_types(&self) -> impl Iterator<Item = V> + '_ {
+        self.ob_types.iter()
+    }
+
+    /// Iterates over the basic morphism types of the theory.
+    pub fn basic_mor_types(&self) -> impl Iterator<Item = E> + '_ {
+        self.mor_types.iter()
+    }
+
+    /// Iterates over the composites of basic morphism types set in the theory.
+    pub fn composites(&self) -> impl Iterator<Item = ((E, E), TabMorType<V, E>)> + '_ {
+        self.compose_map.iter().map(|(pair, m)| (pair, m.clone()))
+    }
+
+    /** Composes a pair of morphism types, if possible.
+
+    Unlike [`compose_types`](DblTheory::compose_types), this method does not
+    panic when the morphism types are not composable or when their composite is
+    not defined, but returns `None`.
+     */
+    pub fn try_compose2_types(
+        &self,
+        m: TabMorType<V, E>,
+        n: TabMorType<V, E>,
+    ) -> Option<TabMorType<V, E>> {
+        if self.tgt(&m) != self.src(&n) {
+            return None;
+        }
+        match (m, n) {
+            (TabMorType::Hom(_), n) => Some(n),
+            (m, TabMorType::Hom(_)) => Some(m),
+            (TabMorType::Basic(d), TabMorType::Basic(e)) => {
+                self.compose_map.apply(&(d, e)).cloned()
+            }
+        }
+    }
+
     fn compose2_types(&self, m: TabMorType<V, E>, n: TabMorType<V, E>) -> TabMorType<V, E> {
         match (m, n) {
             (TabMorType::Hom(_), n) => n,
@@ -412,6 +775,10 @@ where
     type ObOp = TabObOp<V, E>;
     type MorOp = TabMorOp<V, E>;
 
+    fn kind(&self) -> TheoryKind {
+        TheoryKind::DiscreteTab
+    }
+
     fn has_ob_type(&self, ob_type: &Self::ObType) -> bool {
         match ob_type {
             TabObType::Basic(x) => self.ob_types.contains(x),
@@ -444,6 +811,22 @@ where
         }
     }
 
+    fn mor_types_from(&self, x: &Self::ObType) -> impl Iterator<Item = Self::MorType> {
+        let x = x.clone();
+        self.mor_types
+            .iter()
+            .filter(move |e| self.src.apply(e) == Some(&x))
+            .map(TabMorType::Basic)
+    }
+
+    fn mor_types_into(&self, y: &Self::ObType) -> impl Iterator<Item = Self::MorType> {
+        let y = y.clone();
+        self.mor_types
+            .iter()
+            .filter(move |e| self.tgt.apply(e) == Some(&y))
+            .map(TabMorType::Basic)
+    }
+
     fn dom(&self, ob_op: &Self::ObOp) -> Self::ObType {
         match ob_op {
             TabObOp::Id(x) => x.clone(),
@@ -519,10 +902,69 @@ where
     }
 }
 
+/** Defines a discrete double theory from lists of types and equations.
+
+The object types and morphism types are given as identifiers, the morphism types
+together with their source and target types. Equations between composites of
+morphism types are written in diagrammatic order, as `f; g = h` or, when the
+composite is an identity, as `f; g = id(x)`. The macro evaluates to a result
+with the theory, whose underlying category is built and validated as by
+[`FinCategoryBuilder::build`](crate::one::fin_category::FinCategoryBuilder::build).
+
+```
+# use catlog::theory;
+# use catlog::dbl::theory::UstrDiscreteDblTheory;
+let th_sgn: UstrDiscreteDblTheory = theory! {
+    ob_types: [Object],
+    mor_types: [Negative: Object -> Object],
+    equations: [Negative; Negative = id(Object)],
+}
+.unwrap();
+```
+
+Names are resolved at compile time, so referring to an undeclared type is an
+error:
+
+```compile_fail,E0425
+# use catlog::theory;
+let th = theory! {
+    ob_types: [Entity],
+    mor_types: [Attr: Entity -> AttrType],
+};
+```
+ */
+#[macro_export]
+macro_rules! theory {
+    (
+        ob_types: [$($ob:ident),* $(,)?],
+        mor_types: [$($mor:ident : $src:ident -> $tgt:ident),* $(,)?]
+        $(, equations: [$($equations:tt)*])?
+        $(,)?
+    ) => {{
+        $(#[allow(non_upper_case_globals)] const $ob: &str = stringify!($ob);)*
+        $(#[allow(non_upper_case_globals)] const $mor: &str = stringify!($mor);)*
+        let builder = $crate::one::fin_category::FinCategoryBuilder::new()
+            $(.ob($ob))*
+            $(.hom($mor, $src, $tgt))*;
+        let result: ::std::result::Result<$crate::one::fin_category::UstrFinCategory, _> =
+            $crate::theory!(@equations builder; $($($equations)*)?).build();
+        result.map($crate::dbl::theory::DiscreteDblTheory::from)
+    }};
+    (@equations $builder:expr;) => { $builder };
+    (@equations $builder:expr; $d:ident ; $e:ident = id($x:ident) $(, $($rest:tt)*)?) => {
+        $crate::theory!(@equations $builder.composite_id($d, $e, $x); $($($rest)*)?)
+    };
+    (@equations $builder:expr; $d:ident ; $e:ident = $f:ident $(, $($rest:tt)*)?) => {
+        $crate::theory!(@equations $builder.composite($d, $e, $f); $($($rest)*)?)
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::one::fin_category::*;
+    use crate::stdlib::theories::*;
+    use ustr::ustr;
 
     #[test]
     fn discrete_double_theory() {
@@ -540,6 +982,88 @@ mod tests {
         assert_eq!(th.compose_types(path), Mor::Id('*'));
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let th = th_symmetric_links();
+        let json = serde_json::to_string(&th).unwrap();
+        let de: UstrDiscreteDblTheory = serde_json::from_str(&json).unwrap();
+        assert_eq!(de.category(), th.category());
+        let link = FinMor::Generator(ustr("Link"));
+        assert_eq!(de.directionality(&link), Directionality::Symmetric);
+
+        let th = th_schema();
+        let json = serde_json::to_string(&th).unwrap();
+        let de: DiscreteDblTheory<FinCategory<String, String>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(de.symmetric_mor_types().count(), 0);
+        assert_eq!(serde_json::to_string(&de).unwrap(), json);
+
+        // Symmetric morphism types must be endomorphisms in the theory.
+        let json = json
+            .replace(r#""symmetric":[]"#, r#""symmetric":[{"tag":"Generator","content":"Attr"}]"#);
+        assert!(serde_json::from_str::<UstrDiscreteDblTheory>(&json).is_err());
+    }
+
+    #[test]
+    fn endomorphisms() {
+        let th = th_signed_category();
+        assert!(th.has_endomorphism(&ustr("Object")));
+        let th = th_schema();
+        assert!(!th.has_endomorphism(&ustr("AttrType")));
+        assert!(!th.has_endomorphism(&ustr("Entity")));
+        assert!(!th_category().has_endomorphism(&ustr("Object")));
+        let th = th_category_links();
+        assert!(!th.has_endomorphism(&TabObType::Basic(ustr("Object"))));
+    }
+
+    #[test]
+    fn directionality() {
+        let mut th = th_schema();
+        let (attr, unknown) = (FinMor::Generator(ustr("Attr")), FinMor::Generator(ustr("Link")));
+        assert_eq!(
+            th.set_directionality(unknown.clone(), Directionality::Directed),
+            Err(DirectionalityError::NotInTheory(unknown))
+        );
+        assert_eq!(
+            th.set_directionality(attr.clone(), Directionality::Symmetric),
+            Err(DirectionalityError::NotEndomorphism(attr.clone()))
+        );
+        assert_eq!(th.directionality(&attr), Directionality::Directed);
+
+        let id = FinMor::Id(ustr("Entity"));
+        assert!(th.set_directionality(id.clone(), Directionality::Symmetric).is_ok());
+        assert_eq!(th.symmetric_mor_types().collect::<Vec<_>>(), vec![id]);
+    }
+
+    #[test]
+    fn mor_type_labels() {
+        let th = th_signed_category();
+        let neg = FinMor::Generator(ustr("Negative"));
+        let notation = CompositionNotation::Classical;
+        assert_eq!(th.mor_type_label(&neg, notation), "Negative");
+        assert_eq!(th.mor_type_label(&FinMor::Id(ustr("Object")), notation), "id(Object)");
+        let composite = th.category().compose2(neg.clone(), neg.clone());
+        assert_eq!(th.mor_type_label(&composite, notation), "id(Object)");
+
+        let th: DiscreteDblTheory<FinCategory<char, char>> = crate::category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V'), 'i' => ('E', 'E')],
+            composites: [('i', 'i') => id('E'), ('i', 's') => 't', ('i', 't') => 's'],
+        }
+        .unwrap()
+        .into();
+        let (i, t) = (FinMor::Generator('i'), FinMor::Generator('t'));
+        let path = Path::pair(i.clone(), t.clone());
+        assert_eq!(th.composite_label(path.clone(), notation), Ok("t ∘ i".into()));
+        assert_eq!(th.composite_label(path, CompositionNotation::Diagrammatic), Ok("i; t".into()));
+        let path = Path::Seq(nonempty![FinMor::Id('E'), i.clone(), i.clone()]);
+        assert_eq!(th.composite_label(path, notation), Ok("i ∘ i".into()));
+        let path = Path::pair(FinMor::Id('E'), FinMor::Id('E'));
+        assert_eq!(th.composite_label(path, notation), Ok("id(E)".into()));
+        assert!(th.composite_label(Path::pair(t, i), notation).is_err());
+    }
+
     #[test]
     fn discrete_tabulator_theory() {
         let mut th = DiscreteTabTheory::<char, char>::new();
@@ -550,4 +1074,36 @@ mod tests {
         assert!(th.has_ob_type(&tab));
         assert!(th.has_mor_type(&th.hom_type(tab)));
     }
+
+    #[test]
+    fn mor_types_from_and_into() {
+        let th = th_schema();
+        let (entity, attr_type) = (ustr("Entity"), ustr("AttrType"));
+        let attr = FinMor::Generator(ustr("Attr"));
+        assert_eq!(th.mor_types_from(&entity).collect::<Vec<_>>(), vec![attr.clone()]);
+        assert_eq!(th.mor_types_from(&attr_type).count(), 0);
+        assert_eq!(th.mor_types_into(&attr_type).collect::<Vec<_>>(), vec![attr]);
+        assert_eq!(th.mor_types_into(&entity).count(), 0);
+
+        let th = th_category_links();
+        let x = TabObType::Basic(ustr("Object"));
+        assert_eq!(th.mor_types_from(&x).count(), 1);
+        assert_eq!(th.mor_types_into(&x).count(), 0);
+    }
+
+    #[test]
+    fn hom_sets() {
+        let th = th_signed_category();
+        let ob = ustr("Object");
+        let homs = th.hom_sets();
+        assert_eq!(homs.len(), 1);
+        assert_eq!(homs[&(ob, ob)], vec![FinMor::Id(ob), FinMor::Generator(ustr("Negative"))]);
+
+        let homs = th_schema().hom_sets();
+        assert_eq!(homs.len(), 3);
+        assert_eq!(
+            homs[&(ustr("Entity"), ustr("AttrType"))],
+            vec![FinMor::Generator(ustr("Attr"))]
+        );
+    }
 }
@@ -0,0 +1,271 @@
+/*! Morphisms between double theories.
+
+A morphism between [double theories](super::theory) is a double functor between
+them, regarded as double categories. For *discrete* double theories, which are
+just categories, a theory morphism is simply a functor between the underlying
+categories. Theory morphisms are the basis for migrating models from one theory
+to another, such as forgetting structure.
+ */
+
+use std::hash::{BuildHasher, Hash};
+
+use derivative::Derivative;
+use nonempty::NonEmpty;
+use thiserror::Error;
+use ustr::{ustr, Ustr};
+
+use super::theory::DiscreteDblTheory;
+use crate::one::fin_category::{FinCategory, FinMor};
+use crate::one::*;
+use crate::validate::{self, Validate};
+use crate::zero::{HashColumn, Mapping};
+
+/** A mapping between discrete double theories.
+
+The mapping is defined on the object and morphism generators of the domain
+theory. It is extended to all morphisms of a finite category by sending
+identities to identities.
+ */
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct DiscreteDblTheoryMapping<DomOb, DomMor, CodOb, CodMor> {
+    ob_map: HashColumn<DomOb, CodOb>,
+    mor_map: HashColumn<DomMor, CodMor>,
+}
+
+impl<DomOb, DomMor, CodOb, CodMor> DiscreteDblTheoryMapping<DomOb, DomMor, CodOb, CodMor>
+where
+    DomOb: Eq + Clone + Hash,
+    DomMor: Eq + Clone + Hash,
+    CodOb: Eq + Clone,
+    CodMor: Eq + Clone,
+{
+    /// Applies the mapping at an object type in the domain theory.
+    pub fn apply_ob_type(&self, x: &DomOb) -> Option<CodOb> {
+        self.ob_map.apply(x).cloned()
+    }
+
+    /// Applies the mapping at a basic morphism type in the domain theory.
+    pub fn apply_basic_mor_type(&self, e: &DomMor) -> Option<CodMor> {
+        self.mor_map.apply(e).cloned()
+    }
+
+    /// Assigns the mapping at an object type, returning the previous assignment.
+    pub fn assign_ob_type(&mut self, x: DomOb, y: CodOb) -> Option<CodOb> {
+        self.ob_map.set(x, y)
+    }
+
+    /// Assigns the mapping at a basic morphism type, returning the previous
+    /// assignment.
+    pub fn assign_basic_mor_type(&mut self, e: DomMor, m: CodMor) -> Option<CodMor> {
+        self.mor_map.set(e, m)
+    }
+
+    /** Applies the mapping at a morphism type in a finite category.
+
+    Identities are sent to identities in the given codomain category.
+     */
+    pub fn apply_mor_type<Cat>(&self, m: &FinMor<DomOb, DomMor>, cod: &Cat) -> Option<CodMor>
+    where
+        Cat: Category<Ob = CodOb, Mor = CodMor>,
+    {
+        match m {
+            FinMor::Id(x) => self.apply_ob_type(x).map(|y| cod.id(y)),
+            FinMor::Generator(e) => self.apply_basic_mor_type(e),
+        }
+    }
+}
+
+/** A morphism between discrete double theories.
+
+Like a [model morphism](super::model_morphism::DiscreteDblModelMorphism), this
+struct borrows its data to perform validation. The domain and codomain are
+assumed to be valid theories. The domain theory must be presented by a finite
+category, so that preservation of composition can be checked.
+ */
+pub struct DiscreteDblTheoryMorphism<'a, V, E, S, Cat>(
+    pub &'a DiscreteDblTheoryMapping<V, E, Cat::Ob, Cat::Mor>,
+    pub &'a DiscreteDblTheory<FinCategory<V, E, S>>,
+    pub &'a DiscreteDblTheory<Cat>,
+)
+where
+    V: Eq + Clone + Hash,
+    E: Eq + Clone + Hash,
+    S: BuildHasher,
+    Cat: FgCategory;
+
+impl<'a, V, E, S, Cat> DiscreteDblTheoryMorphism<'a, V, E, S, Cat>
+where
+    V: Eq + Clone + Hash,
+    E: Eq + Clone + Hash,
+    S: BuildHasher,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone,
+    Cat::Mor: Eq + Clone,
+{
+    /// Iterates over failures of the mapping to be a theory morphism.
+    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidDblTheoryMorphism<V, E>> + 'a {
+        let DiscreteDblTheoryMorphism(mapping, dom, cod) = *self;
+        let (dom, cod) = (dom.category(), cod.category());
+        let ob_errors = dom.object_generators().filter_map(|x| match mapping.apply_ob_type(&x) {
+            Some(y) if cod.has_ob(&y) => None,
+            _ => Some(InvalidDblTheoryMorphism::ObType(x)),
+        });
+
+        let mor_errors = dom.morphism_generators().flat_map(|e| {
+            if let Some(m) = mapping.apply_basic_mor_type(&e) {
+                if cod.has_mor(&m) {
+                    let mut errs = Vec::new();
+                    if mapping.apply_ob_type(&dom.morphism_generator_dom(&e)) != Some(cod.dom(&m)) {
+                        errs.push(InvalidDblTheoryMorphism::Dom(e.clone()));
+                    }
+                    if mapping.apply_ob_type(&dom.morphism_generator_cod(&e)) != Some(cod.cod(&m)) {
+                        errs.push(InvalidDblTheoryMorphism::Cod(e));
+                    }
+                    return errs;
+                }
+            }
+            vec![InvalidDblTheoryMorphism::MorType(e)]
+        });
+
+        // Only check composites of generators whose images are well defined.
+        let is_well_defined = |e: &E| {
+            mapping.apply_basic_mor_type(e).is_some_and(|m| {
+                cod.has_mor(&m)
+                    && mapping.apply_ob_type(&dom.morphism_generator_dom(e)) == Some(cod.dom(&m))
+                    && mapping.apply_ob_type(&dom.morphism_generator_cod(e)) == Some(cod.cod(&m))
+            })
+        };
+        let composite_errors = dom.morphism_generators().flat_map(move |d| {
+            let mut errs = Vec::new();
+            if is_well_defined(&d) {
+                let y = dom.morphism_generator_cod(&d);
+                for e in dom.morphism_generators() {
+                    if !(dom.morphism_generator_dom(&e) == y && is_well_defined(&e)) {
+                        continue;
+                    }
+                    let (f, g) = (FinMor::Generator(d.clone()), FinMor::Generator(e.clone()));
+                    let lhs = dom
+                        .try_compose2(f, g)
+                        .ok()
+                        .and_then(|composite| mapping.apply_mor_type(&composite, cod));
+                    let rhs = cod.compose2(
+                        mapping.apply_basic_mor_type(&d).unwrap(),
+                        mapping.apply_basic_mor_type(&e).unwrap(),
+                    );
+                    if lhs != Some(rhs) {
+                        errs.push(InvalidDblTheoryMorphism::Composite(d.clone(), e));
+                    }
+                }
+            }
+            errs
+        });
+
+        ob_errors.chain(mor_errors).chain(composite_errors)
+    }
+}
+
+impl<V, E, S, Cat> Validate for DiscreteDblTheoryMorphism<'_, V, E, S, Cat>
+where
+    V: Eq + Clone + Hash,
+    E: Eq + Clone + Hash,
+    S: BuildHasher,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone,
+    Cat::Mor: Eq + Clone,
+{
+    type ValidationError = InvalidDblTheoryMorphism<V, E>;
+
+    fn validate(&self) -> Result<(), NonEmpty<Self::ValidationError>> {
+        validate::wrap_errors(self.iter_invalid())
+    }
+}
+
+/// A failure of a [mapping](DiscreteDblTheoryMapping) between theories to
+/// define a theory morphism.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InvalidDblTheoryMorphism<V, E> {
+    /// An object type not mapped to an object type in the codomain.
+    #[error("Object type `{0}` is not mapped to an object type in the codomain")]
+    ObType(V),
+
+    /// A basic morphism type not mapped to a morphism type in the codomain.
+    #[error("Morphism type `{0}` is not mapped to a morphism type in the codomain")]
+    MorType(E),
+
+    /// A basic morphism type whose source is not preserved.
+    #[error("Mapping of morphism type `{0}` does not preserve its source")]
+    Dom(E),
+
+    /// A basic morphism type whose target is not preserved.
+    #[error("Mapping of morphism type `{0}` does not preserve its target")]
+    Cod(E),
+
+    /// A pair of basic morphism types whose composite is not preserved.
+    #[error("Mapping does not preserve the composite of `{0}` and `{1}`")]
+    Composite(E, E),
+}
+
+impl<V, E, S> DiscreteDblTheory<FinCategory<V, E, S>>
+where
+    V: Eq + Clone + Hash,
+    E: Eq + Clone + Hash,
+    S: BuildHasher,
+{
+    /** The unique morphism from this theory into the terminal theory.
+
+    The terminal discrete double theory is the
+    [theory of categories](crate::stdlib::theories::th_category), having a
+    single object type `Object` and only its identity morphism type. The unique
+    theory morphism into it sends every object type to `Object` and every
+    morphism type to the identity on `Object`. Migrating a model along this
+    morphism forgets all of its typing.
+     */
+    pub fn unique_morphism_to_terminal(
+        &self,
+    ) -> DiscreteDblTheoryMapping<V, E, Ustr, FinMor<Ustr, Ustr>> {
+        let object = ustr("Object");
+        let mut mapping: DiscreteDblTheoryMapping<_, _, _, _> = Default::default();
+        for x in self.category().object_generators() {
+            mapping.assign_ob_type(x, object);
+        }
+        for e in self.category().morphism_generators() {
+            mapping.assign_basic_mor_type(e, FinMor::Id(object));
+        }
+        mapping
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::theories::*;
+
+    #[test]
+    fn terminal_theory_morphism() {
+        let (th, terminal) = (th_schema(), th_category());
+        let mapping = th.unique_morphism_to_terminal();
+        assert_eq!(mapping.apply_ob_type(&ustr("Entity")), Some(ustr("Object")));
+        assert!(DiscreteDblTheoryMorphism(&mapping, &th, &terminal).validate().is_ok());
+
+        let th = th_signed_category();
+        let mapping = th.unique_morphism_to_terminal();
+        assert!(DiscreteDblTheoryMorphism(&mapping, &th, &terminal).validate().is_ok());
+
+        // The inclusion of signed categories into nullable signed categories is
+        // a theory morphism, but sending negative signs to zero is not.
+        let nullable = th_nullable_signed_category();
+        let (object, negative, zero) = (ustr("Object"), ustr("Negative"), ustr("Zero"));
+        let mut mapping: DiscreteDblTheoryMapping<_, _, _, _> = Default::default();
+        mapping.assign_ob_type(object, object);
+        mapping.assign_basic_mor_type(negative, FinMor::Generator(negative));
+        assert!(DiscreteDblTheoryMorphism(&mapping, &th, &nullable).validate().is_ok());
+        mapping.assign_basic_mor_type(negative, FinMor::Generator(zero));
+        assert_eq!(
+            DiscreteDblTheoryMorphism(&mapping, &th, &nullable)
+                .validate()
+                .map_err(|errs| errs.head),
+            Err(InvalidDblTheoryMorphism::Composite(negative, negative))
+        );
+    }
+}
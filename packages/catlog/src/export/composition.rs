@@ -0,0 +1,150 @@
+/*! Rendering of composition tables for documentation.
+
+The [composition table](crate::one::fin_category::FinCategory::composition_table)
+of a finite category is rendered as a Markdown or HTML table, with a row and a
+column for each morphism. In [diagrammatic](CompositionNotation::Diagrammatic)
+notation, the cell in row `f` and column `g` shows the composite `f · g`, first
+`f` and then `g`. In [classical](CompositionNotation::Classical) notation, the
+table is transposed, so that the cell in row `g` and column `f` shows `g ∘ f`.
+A cell is `undefined` when the morphisms are composable but the composite is not
+defined, and left empty when they are not composable. Morphisms are shown by
+labels supplied by the caller.
+ */
+
+use super::RenderOptions;
+use crate::prelude::{CompositionCell, CompositionMatrix, CompositionNotation};
+
+/// Renders a composition table as a Markdown table.
+pub fn render_markdown<Mor>(
+    matrix: &CompositionMatrix<Mor>,
+    label: impl Fn(&Mor) -> String,
+    options: &RenderOptions,
+) -> String {
+    let (corner, rows) = oriented_rows(matrix, options);
+    let escape = |s: String| s.replace('|', "\\|");
+    let row = |first: String, rest: Vec<String>| {
+        let cells: Vec<_> = std::iter::once(first).chain(rest).map(escape).collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+    let mut out = row(corner.into(), matrix.labels.iter().map(&label).collect());
+    out.push_str(&row("---".into(), matrix.labels.iter().map(|_| "---".into()).collect()));
+    for (f, cells) in matrix.labels.iter().zip(rows) {
+        out.push_str(&row(
+            label(f),
+            cells.into_iter().map(|cell| render_cell(cell, &label)).collect(),
+        ));
+    }
+    out
+}
+
+/// Renders a composition table as an HTML table.
+pub fn render_html<Mor>(
+    matrix: &CompositionMatrix<Mor>,
+    label: impl Fn(&Mor) -> String,
+    options: &RenderOptions,
+) -> String {
+    let (corner, rows) = oriented_rows(matrix, options);
+    let escape = |s: String| s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let mut out = format!("<table>\n<tr><th>{corner}</th>");
+    for f in &matrix.labels {
+        out.push_str(&format!("<th>{}</th>", escape(label(f))));
+    }
+    out.push_str("</tr>\n");
+    for (f, cells) in matrix.labels.iter().zip(rows) {
+        out.push_str(&format!("<tr><th>{}</th>", escape(label(f))));
+        for cell in cells {
+            out.push_str(&format!("<td>{}</td>", escape(render_cell(cell, &label))));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+/// Corner symbol and rows of the table in the given notation.
+fn oriented_rows<'a, Mor>(
+    matrix: &'a CompositionMatrix<Mor>,
+    options: &RenderOptions,
+) -> (&'static str, Vec<Vec<&'a CompositionCell<Mor>>>) {
+    let n = matrix.labels.len();
+    match options.notation {
+        CompositionNotation::Diagrammatic => {
+            ("·", matrix.cells.iter().map(|row| row.iter().collect()).collect())
+        }
+        CompositionNotation::Classical => {
+            ("∘", (0..n).map(|j| (0..n).map(|i| &matrix.cells[i][j]).collect()).collect())
+        }
+    }
+}
+
+fn render_cell<Mor>(cell: &CompositionCell<Mor>, label: impl Fn(&Mor) -> String) -> String {
+    match cell {
+        CompositionCell::Composite(h) => label(h),
+        CompositionCell::Undefined => "undefined".into(),
+        CompositionCell::NotComposable => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category;
+    use crate::one::fin_category::{FinCategory, FinMor};
+
+    fn label(f: &FinMor<char, char>) -> String {
+        match f {
+            FinMor::Id(x) => format!("id({x})"),
+            FinMor::Generator(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn symmetric_graph_schema() {
+        let sch_sgraph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V'), 'i' => ('E', 'E')],
+            composites: [('i', 'i') => id('E'), ('i', 's') => 't', ('i', 't') => 's'],
+        }
+        .unwrap();
+        let matrix = sch_sgraph.composition_table();
+        assert_eq!(
+            render_markdown(&matrix, label, &Default::default()),
+            "\
+| · | id(V) | id(E) | s | t | i |
+| --- | --- | --- | --- | --- | --- |
+| id(V) | id(V) |  |  |  |  |
+| id(E) |  | id(E) | s | t | i |
+| s | s |  |  |  |  |
+| t | t |  |  |  |  |
+| i |  | i | t | s | id(E) |
+"
+        );
+
+        let matrix = sch_sgraph.composition_table_on(['s']);
+        assert_eq!(
+            render_html(&matrix, label, &Default::default()),
+            "\
+<table>
+<tr><th>·</th><th>id(V)</th><th>id(E)</th><th>s</th></tr>
+<tr><th>id(V)</th><td>id(V)</td><td></td><td></td></tr>
+<tr><th>id(E)</th><td></td><td>id(E)</td><td>s</td></tr>
+<tr><th>s</th><td>s</td><td></td><td></td></tr>
+</table>
+"
+        );
+
+        let classical = RenderOptions {
+            notation: CompositionNotation::Classical,
+        };
+        assert_eq!(
+            render_markdown(&matrix, label, &classical),
+            "\
+| ∘ | id(V) | id(E) | s |
+| --- | --- | --- | --- |
+| id(V) | id(V) |  | s |
+| id(E) |  | id(E) |  |
+| s |  | s |  |
+"
+        );
+    }
+}
@@ -0,0 +1,16 @@
+//! Import and export of categorical structures in external formats.
+
+use crate::prelude::CompositionNotation;
+
+pub mod composition;
+pub mod reference;
+
+#[cfg(feature = "quiver")]
+pub mod quiver;
+
+/// Options shared by the renderers of categorical structures as text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Notation for composites of morphisms.
+    pub notation: CompositionNotation,
+}
@@ -0,0 +1,374 @@
+/*! Import and export of diagrams drawn in [quiver](https://q.uiver.app).
+
+Quiver serializes a diagram as a JSON array of the form
+
+```text
+[version, vertex_count, ...vertices, ...edges]
+```
+
+where each vertex is an array `[x, y, label?, color?]` and each edge is an array
+`[source, target, label?, alignment?, options?, color?]`. The source and target
+of an edge are indices into the list of all cells, vertices followed by edges,
+so that edges between edges are allowed. The URLs produced by quiver's export
+menu contain this array encoded in base64; it is the decoded JSON that is read
+and written here.
+
+Only the part of a diagram expressible as a [graph](crate::one::graph) is
+supported. Higher cells, adjunctions, and pullback corners are skipped on import
+and reported as [warnings](QuiverWarning). Colors, alignment, and most styling
+options are ignored.
+ */
+
+use std::hash::Hash;
+
+use derivative::Derivative;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::prelude::{
+    Checked, Column, DiscreteDblModel, FgCategory, FinGraph, HashColumn, HashGraph, Mapping,
+};
+
+/// The version of quiver's format that is supported.
+pub const QUIVER_VERSION: u64 = 0;
+
+/** Layout of a graph drawn in quiver.
+
+Positions are cells in quiver's integer grid, with the `y` coordinate increasing
+downwards. The curvature of an edge is quiver's `curve` option, where zero means
+a straight edge.
+ */
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct Layout<V, E> {
+    /// Grid positions of vertices.
+    pub positions: HashColumn<V, (i64, i64)>,
+
+    /// Labels of vertices, usually in LaTeX.
+    pub vertex_labels: HashColumn<V, String>,
+
+    /// Labels of edges, usually in LaTeX.
+    pub edge_labels: HashColumn<E, String>,
+
+    /// Curvatures of edges.
+    pub edge_curves: HashColumn<E, i64>,
+}
+
+/** Error importing a quiver diagram.
+
+Cells are referred to by their index in the list of all cells, vertices first.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ImportError {
+    /// The input is not valid JSON.
+    #[error("Invalid JSON: {0}")]
+    Json(String),
+
+    /// The input is not an array starting with a version and vertex count.
+    #[error("Expected an array starting with a version and a vertex count")]
+    NotADiagram,
+
+    /// The diagram has a version of quiver's format that is not supported.
+    #[error("Unsupported version of quiver format: {0}")]
+    UnsupportedVersion(u64),
+
+    /// The vertex count is greater than the number of cells.
+    #[error("Vertex count {0} exceeds the number of cells")]
+    VertexCount(usize),
+
+    /// A cell that should be a vertex is malformed.
+    #[error("Cell {0} is not a valid vertex")]
+    InvalidVertex(usize),
+
+    /// A cell that should be an edge is malformed.
+    #[error("Cell {0} is not a valid edge")]
+    InvalidEdge(usize),
+
+    /// An endpoint of an edge does not refer to an earlier cell.
+    #[error("Edge {edge} refers to cell {cell}, which does not precede it")]
+    InvalidEndpoint {
+        /// Index of the edge.
+        edge: usize,
+        /// Index of the cell referred to.
+        cell: usize,
+    },
+}
+
+/// Feature of a quiver diagram that is skipped on import.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum QuiverWarning {
+    /// An edge having an edge as its source or target.
+    #[error("Edge {0} is a higher cell and was skipped")]
+    HigherCell(usize),
+
+    /// An adjunction symbol between two edges.
+    #[error("Edge {0} is an adjunction and was skipped")]
+    Adjunction(usize),
+
+    /// A pullback or pushout corner.
+    #[error("Edge {0} is a pullback or pushout corner and was skipped")]
+    Corner(usize),
+}
+
+/// Graph imported from quiver, with vertices and edges indexed by cell.
+pub type QuiverGraph = HashGraph<usize, usize>;
+
+/// Layout of a graph imported from quiver.
+pub type QuiverLayout = Layout<usize, usize>;
+
+/** Imports a diagram from quiver's JSON format.
+
+The vertices and edges of the resulting graph are identified by their index in
+the list of cells. On success, returns the graph, its layout, and warnings about
+any features of the diagram that were skipped. Otherwise, returns all the errors
+encountered.
+ */
+pub fn import_quiver(
+    json: &str,
+) -> Result<(QuiverGraph, QuiverLayout, Vec<QuiverWarning>), Vec<ImportError>> {
+    let value: Value =
+        serde_json::from_str(json).map_err(|e| vec![ImportError::Json(e.to_string())])?;
+    let cells = match value.as_array().map(Vec::as_slice) {
+        Some([version, count, cells @ ..]) => {
+            let (Some(version), Some(count)) = (version.as_u64(), count.as_u64()) else {
+                return Err(vec![ImportError::NotADiagram]);
+            };
+            if version != QUIVER_VERSION {
+                return Err(vec![ImportError::UnsupportedVersion(version)]);
+            }
+            let count = count as usize;
+            if count > cells.len() {
+                return Err(vec![ImportError::VertexCount(count)]);
+            }
+            cells.split_at(count)
+        }
+        _ => return Err(vec![ImportError::NotADiagram]),
+    };
+    let (vertices, edges) = cells;
+    let nv = vertices.len();
+
+    let mut graph: QuiverGraph = Default::default();
+    let mut layout: QuiverLayout = Default::default();
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+
+    for (v, cell) in vertices.iter().enumerate() {
+        let Some(fields) = cell.as_array() else {
+            errors.push(ImportError::InvalidVertex(v));
+            continue;
+        };
+        let (x, y) = match (fields.first(), fields.get(1)) {
+            (Some(x), Some(y)) => (x.as_i64(), y.as_i64()),
+            _ => (None, None),
+        };
+        let label = label_field(fields.get(2));
+        let (Some(x), Some(y), Some(label)) = (x, y, label) else {
+            errors.push(ImportError::InvalidVertex(v));
+            continue;
+        };
+        graph.add_vertex(v);
+        layout.positions.set(v, (x, y));
+        if !label.is_empty() {
+            layout.vertex_labels.set(v, label);
+        }
+    }
+
+    for (i, cell) in edges.iter().enumerate() {
+        let e = nv + i;
+        let Some(fields) = cell.as_array() else {
+            errors.push(ImportError::InvalidEdge(e));
+            continue;
+        };
+        let (src, tgt) = match (fields.first(), fields.get(1)) {
+            (Some(src), Some(tgt)) => (src.as_u64(), tgt.as_u64()),
+            _ => (None, None),
+        };
+        let label = label_field(fields.get(2));
+        let options = match fields.get(4) {
+            None => Some(Default::default()),
+            Some(options) => options.as_object().cloned(),
+        };
+        let (Some(src), Some(tgt), Some(label), Some(options)) = (src, tgt, label, options) else {
+            errors.push(ImportError::InvalidEdge(e));
+            continue;
+        };
+        let (src, tgt) = (src as usize, tgt as usize);
+        let invalid: Vec<_> = [src, tgt]
+            .into_iter()
+            .filter(|cell| *cell >= e)
+            .map(|cell| ImportError::InvalidEndpoint { edge: e, cell })
+            .collect();
+        if !invalid.is_empty() {
+            errors.extend(invalid);
+            continue;
+        }
+
+        let style =
+            options.get("style").and_then(|style| style.get("name")).and_then(Value::as_str);
+        match style {
+            Some("adjunction") => warnings.push(QuiverWarning::Adjunction(e)),
+            Some("corner" | "corner-inverse") => warnings.push(QuiverWarning::Corner(e)),
+            _ if src >= nv || tgt >= nv => warnings.push(QuiverWarning::HigherCell(e)),
+            _ => {
+                graph.add_edge(e, src, tgt);
+                if !label.is_empty() {
+                    layout.edge_labels.set(e, label);
+                }
+                if let Some(curve) = options.get("curve").and_then(Value::as_i64) {
+                    layout.edge_curves.set(e, curve);
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok((graph, layout, warnings))
+    } else {
+        Err(errors)
+    }
+}
+
+/// Reads an optional label, which quiver omits when empty.
+fn label_field(value: Option<&Value>) -> Option<String> {
+    match value {
+        None => Some(String::new()),
+        Some(value) => value.as_str().map(String::from),
+    }
+}
+
+/** Exports a graph to quiver's JSON format.
+
+Vertices and edges are written in sorted order so that the output is
+deterministic. Vertices without a position in the layout are placed in a row
+below all the positioned vertices. The source and target of every edge must be
+vertices of the graph. Models are exported by [`export_model_quiver`].
+ */
+pub fn export_quiver<G>(graph: &G, layout: &Layout<G::V, G::E>) -> String
+where
+    G: FinGraph,
+    G::V: Ord + Hash + Clone,
+    G::E: Ord + Hash + Clone,
+{
+    let mut vertices: Vec<_> = graph.vertices().collect();
+    vertices.sort();
+    let mut edges: Vec<_> = graph.edges().collect();
+    edges.sort();
+
+    let row = layout.positions.values().map(|(_, y)| y + 1).max().unwrap_or(0);
+    let mut column = 0;
+    let mut cells = vec![json!(QUIVER_VERSION), json!(vertices.len())];
+    for v in vertices.iter() {
+        let (x, y) = layout.positions.apply(v).copied().unwrap_or_else(|| {
+            column += 1;
+            (column - 1, row)
+        });
+        let mut cell = vec![json!(x), json!(y)];
+        if let Some(label) = layout.vertex_labels.apply(v) {
+            cell.push(json!(label));
+        }
+        cells.push(Value::Array(cell));
+    }
+    for e in edges.iter() {
+        let index = |v: G::V| vertices.binary_search(&v).expect("Endpoint should be a vertex");
+        let mut cell = vec![json!(index(graph.src(e))), json!(index(graph.tgt(e)))];
+        let label = layout.edge_labels.apply(e);
+        match layout.edge_curves.apply(e).filter(|curve| **curve != 0) {
+            Some(curve) => {
+                let label = label.map_or("", |label| label.as_str());
+                cell.extend([json!(label), json!(0), json!({"curve": curve})]);
+            }
+            None => cell.extend(label.map(|label| json!(label))),
+        }
+        cells.push(Value::Array(cell));
+    }
+    Value::Array(cells).to_string()
+}
+
+/** Exports the generating graph of a model to quiver's JSON format.
+
+The model must be [checked](Checked) to be valid, so that every basic morphism
+has a domain and codomain among the basic objects. See [`export_quiver`].
+ */
+pub fn export_model_quiver<Id, Cat>(
+    model: &Checked<DiscreteDblModel<Id, Cat>>,
+    layout: &Layout<Id, Id>,
+) -> String
+where
+    Id: Eq + Clone + Hash + Ord,
+    Cat: FgCategory,
+    Cat::Ob: Eq + Clone + Hash,
+    Cat::Mor: Eq + Clone + Hash,
+{
+    export_quiver(model.generating_graph(), layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::one::graph::Graph;
+    use crate::stdlib::models::positive_loop;
+    use crate::stdlib::theories::th_signed_category;
+    use std::sync::Arc;
+    use ustr::ustr;
+
+    /// Commutative square exported from quiver.
+    const SQUARE: &str =
+        r#"[0,4,[0,0,"A"],[1,0,"B"],[0,1,"C"],[1,1,"D"],[0,1,"f"],[0,2,"g"],[1,3,"h"],[2,3,"k"]]"#;
+
+    /// Adjunction with its unit exported from quiver.
+    const ADJUNCTION: &str = r#"[0,2,[0,0,"\\mathcal{C}"],[2,0,"\\mathcal{D}"],[0,1,"F",0,{"curve":-2}],[1,0,"G",0,{"curve":-2}],[2,3,"",0,{"level":1,"style":{"name":"adjunction"}}]]"#;
+
+    #[test]
+    fn import_square() {
+        let (graph, layout, warnings) = import_quiver(SQUARE).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(graph.vertex_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!((graph.src(&6), graph.tgt(&6)), (1, 3));
+        assert_eq!(layout.positions.apply(&3), Some(&(1, 1)));
+        assert_eq!(layout.vertex_labels.apply(&2).map(String::as_str), Some("C"));
+        assert_eq!(layout.edge_labels.apply(&7).map(String::as_str), Some("k"));
+        assert!(layout.edge_curves.is_empty());
+
+        assert_eq!(export_quiver(&graph, &layout), SQUARE);
+    }
+
+    #[test]
+    fn import_unsupported() {
+        let (graph, layout, warnings) = import_quiver(ADJUNCTION).unwrap();
+        assert_eq!(warnings, vec![QuiverWarning::Adjunction(4)]);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(layout.edge_curves.apply(&3), Some(&-2));
+
+        let two_cell = r#"[0,2,[0,0],[1,0],[0,1],[0,1],[2,3,"\\alpha"]]"#;
+        let (graph, _, warnings) = import_quiver(two_cell).unwrap();
+        assert_eq!(warnings, vec![QuiverWarning::HigherCell(4)]);
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn import_errors() {
+        assert!(
+            matches!(import_quiver("[0,"), Err(errs) if matches!(errs[..], [ImportError::Json(_)]))
+        );
+        assert_eq!(import_quiver("{}").err(), Some(vec![ImportError::NotADiagram]));
+        assert_eq!(import_quiver("[1,0]").err(), Some(vec![ImportError::UnsupportedVersion(1)]));
+        assert_eq!(
+            import_quiver(r#"[0,2,[0,0],"B",[0,5]]"#).err(),
+            Some(vec![
+                ImportError::InvalidVertex(1),
+                ImportError::InvalidEndpoint { edge: 2, cell: 5 }
+            ])
+        );
+    }
+
+    #[test]
+    fn export_model() {
+        let th = Arc::new(th_signed_category());
+        let model = positive_loop(th);
+        let mut layout: Layout<_, _> = Default::default();
+        layout.vertex_labels.set(ustr("x"), "x".into());
+        let json = export_model_quiver(&Checked::new(model).unwrap(), &layout);
+        assert_eq!(json, r#"[0,1,[0,0,"x"],[0,0]]"#);
+    }
+}
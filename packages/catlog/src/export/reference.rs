@@ -0,0 +1,124 @@
+/*! Rendering of reference pages for double theories.
+
+A [description](TheoryDescription) of a theory is rendered as a Markdown page
+with sections for the object types, the morphism types with their sources and
+targets, and the composites of morphism types, written as equations in the
+notation given by the [options](RenderOptions).
+ */
+
+use super::RenderOptions;
+use crate::prelude::{Path, TheoryDescription, TheoryKind};
+
+/// Renders a description of a theory as a Markdown reference page.
+pub fn render_markdown(desc: &TheoryDescription, options: &RenderOptions) -> String {
+    let code = |s: &str| format!("`{}`", s.replace('`', "\\`"));
+    let kind = match desc.kind {
+        TheoryKind::Discrete => "discrete double theory",
+        TheoryKind::DiscreteTab => "discrete tabulator theory",
+    };
+
+    let mut out = format!("# {}\n\n", desc.name.as_deref().unwrap_or("Theory"));
+    if let Some(docs) = &desc.docs {
+        out.push_str(&format!("{}\n\n", docs.trim_end()));
+    }
+    out.push_str(&format!("Kind: {kind}\n\n## Object types\n\n"));
+    for x in &desc.ob_types {
+        out.push_str(&format!("- {}\n", code(x)));
+    }
+
+    out.push_str("\n## Morphism types\n\n");
+    if desc.mor_types.is_empty() {
+        out.push_str("None.\n");
+    } else {
+        out.push_str("| Type | Source | Target |\n| --- | --- | --- |\n");
+        for m in &desc.mor_types {
+            let symmetric = if m.symmetric { " (symmetric)" } else { "" };
+            let (name, src, tgt) = (code(&m.name), code(&m.src), code(&m.tgt));
+            out.push_str(&format!("| {name}{symmetric} | {src} | {tgt} |\n"));
+        }
+    }
+
+    out.push_str("\n## Composites\n\n");
+    if desc.composites.is_empty() {
+        out.push_str("None.\n");
+    }
+    for c in &desc.composites {
+        let path: Path<&str, &str> = Path::pair(c.first.as_str(), c.second.as_str());
+        let lhs = path.display(options.notation).to_string();
+        out.push_str(&format!("- {}\n", code(&format!("{lhs} = {}", c.composite))));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::one::path::CompositionNotation;
+    use crate::stdlib::theories::*;
+
+    #[test]
+    fn signed_category_page() {
+        let desc = th_signed_category()
+            .describe()
+            .with_docs("Signed categories", "Categories sliced over the group of signs.");
+        assert_eq!(
+            render_markdown(&desc, &Default::default()),
+            "\
+# Signed categories
+
+Categories sliced over the group of signs.
+
+Kind: discrete double theory
+
+## Object types
+
+- `Object`
+
+## Morphism types
+
+| Type | Source | Target |
+| --- | --- | --- |
+| `Negative` | `Object` | `Object` |
+
+## Composites
+
+- `Negative; Negative = id(Object)`
+"
+        );
+    }
+
+    #[test]
+    fn category_links_page() {
+        let desc = th_category_links().describe();
+        let classical = RenderOptions {
+            notation: CompositionNotation::Classical,
+        };
+        assert_eq!(
+            render_markdown(&desc, &classical),
+            "\
+# Theory
+
+Kind: discrete tabulator theory
+
+## Object types
+
+- `Object`
+
+## Morphism types
+
+| Type | Source | Target |
+| --- | --- | --- |
+| `Link` | `Object` | `Tab(Hom(Object))` |
+
+## Composites
+
+None.
+"
+        );
+
+        let desc = th_symmetric_links().describe();
+        let page = render_markdown(&desc, &classical);
+        assert!(page.contains("| `Link` (symmetric) | `Object` | `Object` |"));
+        assert!(page.contains("- `Link ∘ Link = Link`"));
+    }
+}
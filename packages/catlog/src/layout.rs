@@ -0,0 +1,415 @@
+/*! Layout of large graphs and models.
+
+Large graphs, and models having large underlying graphs, cannot be drawn all at
+once in a readable way. Instead, they can be clustered into a hierarchy, which
+is computed once and then [cut](ClusterTree::cut_at) at a level of detail to
+obtain a coarse layout.
+
+The clustering is computed by greedy agglomeration on
+[modularity](https://en.wikipedia.org/wiki/Modularity_(networks)), following
+Clauset, Newman, and Moore. Starting from singleton clusters, the pair of
+adjacent clusters whose merger most increases the modularity is merged, until
+each connected component is a single cluster. Ties between equally good
+mergers are broken pseudorandomly by the [seed](ClusteringOptions::seed), so
+the result is deterministic given the seed.
+
+The functions in this module operate on raw lists of vertices and edges rather
+than on any particular graph data structure. Models are clustered by
+[`DiscreteDblModel::hierarchical_clustering`](crate::dbl::model::DiscreteDblModel::hierarchical_clustering).
+ */
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+/// Options for [hierarchical clustering](hierarchical_clustering).
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ClusteringOptions {
+    /** Resolution of the modularity, one by default.
+
+    Larger resolutions favor merging small clusters before large ones.
+     */
+    pub resolution: f64,
+
+    /** Seed for breaking ties between equally good mergers, zero by default.
+
+    Clustering the same graph with the same seed always gives the same tree.
+     */
+    pub seed: u64,
+}
+
+impl Default for ClusteringOptions {
+    fn default() -> Self {
+        Self {
+            resolution: 1.0,
+            seed: 0,
+        }
+    }
+}
+
+/// A node in a [cluster tree](ClusterTree).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+pub struct ClusterNode<V, T> {
+    /// Vertex at a leaf, or nothing at an internal node.
+    pub leaf: Option<V>,
+
+    /// Indices of the child nodes, empty at a leaf.
+    pub children: Vec<usize>,
+
+    /// Number of leaves below the node.
+    pub size: usize,
+
+    /** Most common label of the leaves below the node.
+
+    Ties are broken in favor of the label occurring first.
+     */
+    pub dominant: T,
+}
+
+/** A hierarchical clustering of the vertices of a graph.
+
+The leaves of the tree are the vertices, in their original order, and are
+followed by the internal nodes, each coming after its children. The last node
+is the root.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct ClusterTree<V, T> {
+    nodes: Vec<ClusterNode<V, T>>,
+}
+
+impl<V, T> ClusterTree<V, T> {
+    /// Gets the nodes of the tree, ending with the root.
+    pub fn nodes(&self) -> &[ClusterNode<V, T>] {
+        &self.nodes
+    }
+
+    /// Gets the index of the root node, if the tree is nonempty.
+    pub fn root(&self) -> Option<usize> {
+        self.nodes.len().checked_sub(1)
+    }
+
+    /// Iterates over the vertices below a node, in depth-first order.
+    pub fn leaves(&self, node: usize) -> impl Iterator<Item = &V> + '_ {
+        let mut stack = vec![node];
+        std::iter::from_fn(move || {
+            while let Some(i) = stack.pop() {
+                let node = &self.nodes[i];
+                stack.extend(node.children.iter().rev());
+                if let Some(v) = &node.leaf {
+                    return Some(v);
+                }
+            }
+            None
+        })
+    }
+
+    /** Cuts the tree at a level, partitioning the vertices into clusters.
+
+    The clusters are the nodes at the given depth below the root, together
+    with any leaves at smaller depth. Level zero gives the single cluster of all
+    vertices. Clusters are listed in depth-first order.
+     */
+    pub fn cut_at(&self, level: usize) -> Vec<Vec<V>>
+    where
+        V: Clone,
+    {
+        let mut frontier: Vec<usize> = self.root().into_iter().collect();
+        for _ in 0..level {
+            frontier = frontier
+                .into_iter()
+                .flat_map(|i| {
+                    let children = &self.nodes[i].children;
+                    if children.is_empty() {
+                        vec![i]
+                    } else {
+                        children.clone()
+                    }
+                })
+                .collect();
+        }
+        frontier.into_iter().map(|i| self.leaves(i).cloned().collect()).collect()
+    }
+
+    /// Maps over the vertices and labels of the tree.
+    pub fn map<W, U>(
+        self,
+        mut fv: impl FnMut(V) -> W,
+        mut ft: impl FnMut(T) -> U,
+    ) -> ClusterTree<W, U> {
+        let nodes = self
+            .nodes
+            .into_iter()
+            .map(|node| ClusterNode {
+                leaf: node.leaf.map(&mut fv),
+                children: node.children,
+                size: node.size,
+                dominant: ft(node.dominant),
+            })
+            .collect();
+        ClusterTree { nodes }
+    }
+}
+
+/** Clusters the vertices of a graph hierarchically.
+
+The graph is given by an iterator over its labeled vertices and an iterator
+over the endpoints of its edges, whose orientations are ignored. Edges between
+a vertex and itself, or with endpoints not among the vertices, are ignored. The
+labels, such as object types, are summarized at each node of the tree.
+
+See the [module-level docs](self) for the algorithm.
+ */
+pub fn hierarchical_clustering<V, T>(
+    vertices: impl IntoIterator<Item = (V, T)>,
+    edges: impl IntoIterator<Item = (V, V)>,
+    options: &ClusteringOptions,
+) -> ClusterTree<V, T>
+where
+    V: Eq + Hash + Clone,
+    T: Eq + Clone,
+{
+    let mut nodes: Vec<ClusterNode<V, T>> = Vec::new();
+    let mut index: HashMap<V, usize> = HashMap::new();
+    for (v, label) in vertices {
+        if index.contains_key(&v) {
+            continue;
+        }
+        index.insert(v.clone(), nodes.len());
+        nodes.push(ClusterNode {
+            leaf: Some(v),
+            children: Vec::new(),
+            size: 1,
+            dominant: label,
+        });
+    }
+    let n = nodes.len();
+
+    // Fractions of edge ends between and at each cluster.
+    let mut between: Vec<HashMap<usize, f64>> = vec![HashMap::new(); n];
+    let mut ends = vec![0.0; n];
+    let pairs: Vec<_> = edges
+        .into_iter()
+        .filter_map(|(x, y)| Some((*index.get(&x)?, *index.get(&y)?)))
+        .filter(|(i, j)| i != j)
+        .collect();
+    let total = 2.0 * pairs.len() as f64;
+    for (i, j) in pairs {
+        *between[i].entry(j).or_default() += 1.0 / total;
+        *between[j].entry(i).or_default() += 1.0 / total;
+        ends[i] += 1.0 / total;
+        ends[j] += 1.0 / total;
+    }
+
+    // State of each cluster, indexed by its first vertex.
+    let mut node_of: Vec<usize> = (0..n).collect();
+    let mut labels: Vec<Vec<(T, usize)>> =
+        nodes.iter().map(|node| vec![(node.dominant.clone(), 1)]).collect();
+    let mut alive = vec![true; n];
+    let mut version = vec![0; n];
+
+    let gain = |between: &[HashMap<usize, f64>], ends: &[f64], i: usize, j: usize| {
+        2.0 * (between[i][&j] - options.resolution * ends[i] * ends[j])
+    };
+    let tiebreak = |(i, j): (usize, usize)| mix(options.seed, ((i as u64) << 32) ^ j as u64);
+    let mut heap = BinaryHeap::new();
+    for i in 0..n {
+        for &j in between[i].keys().filter(|j| i < **j) {
+            heap.push(Merge {
+                gain: gain(&between, &ends, i, j),
+                tiebreak: tiebreak((i, j)),
+                pair: (i, j),
+                versions: (0, 0),
+            });
+        }
+    }
+
+    while let Some(Merge {
+        pair: (i, j),
+        versions,
+        ..
+    }) = heap.pop()
+    {
+        if !(alive[i] && alive[j]) || versions != (version[i], version[j]) {
+            continue;
+        }
+        // Merge cluster `j` into cluster `i`.
+        let neighbors = std::mem::take(&mut between[j]);
+        for (k, weight) in neighbors {
+            between[k].remove(&j);
+            if k != i {
+                *between[i].entry(k).or_default() += weight;
+                *between[k].entry(i).or_default() += weight;
+            }
+        }
+        between[i].remove(&j);
+        ends[i] += ends[j];
+        alive[j] = false;
+        version[i] += 1;
+
+        let merged = std::mem::take(&mut labels[j]);
+        for (label, count) in merged {
+            match labels[i].iter_mut().find(|(l, _)| *l == label) {
+                Some((_, c)) => *c += count,
+                None => labels[i].push((label, count)),
+            }
+        }
+        let children = vec![node_of[i], node_of[j]];
+        nodes.push(ClusterNode {
+            leaf: None,
+            size: children.iter().map(|c| nodes[*c].size).sum(),
+            children,
+            dominant: dominant(&labels[i]),
+        });
+        node_of[i] = nodes.len() - 1;
+
+        for &k in between[i].keys() {
+            let (a, b) = (i.min(k), i.max(k));
+            heap.push(Merge {
+                gain: gain(&between, &ends, a, b),
+                tiebreak: tiebreak((a, b)),
+                pair: (a, b),
+                versions: (version[a], version[b]),
+            });
+        }
+    }
+
+    // Join the clusters of the connected components under a single root.
+    let roots: Vec<_> = (0..n).filter(|i| alive[*i]).collect();
+    if roots.len() > 1 {
+        let mut all: Vec<(T, usize)> = Vec::new();
+        for i in &roots {
+            for (label, count) in &labels[*i] {
+                match all.iter_mut().find(|(l, _)| l == label) {
+                    Some((_, c)) => *c += count,
+                    None => all.push((label.clone(), *count)),
+                }
+            }
+        }
+        let children: Vec<_> = roots.iter().map(|i| node_of[*i]).collect();
+        nodes.push(ClusterNode {
+            leaf: None,
+            children,
+            size: n,
+            dominant: dominant(&all),
+        });
+    }
+    ClusterTree { nodes }
+}
+
+fn dominant<T: Clone>(labels: &[(T, usize)]) -> T {
+    let max = labels
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .expect("Cluster should be nonempty");
+    labels.iter().find(|(_, count)| *count == max).unwrap().0.clone()
+}
+
+/// Mixes a seed with a value, as in the SplitMix64 generator.
+fn mix(seed: u64, value: u64) -> u64 {
+    let mut z = seed.wrapping_add(value.wrapping_mul(0x9e3779b97f4a7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Candidate merger of two clusters, ordered by gain in modularity.
+struct Merge {
+    gain: f64,
+    tiebreak: u64,
+    pair: (usize, usize),
+    versions: (usize, usize),
+}
+
+impl PartialEq for Merge {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Merge {}
+
+impl PartialOrd for Merge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Merge {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Prefer larger gains, then smaller tiebreakers.
+        self.gain
+            .total_cmp(&other.gain)
+            .then_with(|| other.tiebreak.cmp(&self.tiebreak))
+            .then_with(|| other.pair.cmp(&self.pair))
+            .then_with(|| other.versions.cmp(&self.versions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_blobs() {
+        // Two complete graphs on four vertices, joined by a single edge.
+        let blob = |offset: usize| {
+            (0..4).flat_map(move |i| ((i + 1)..4).map(move |j| (offset + i, offset + j)))
+        };
+        let edges: Vec<_> = blob(0).chain(blob(4)).chain([(3, 4)]).collect();
+        let vertices = (0..8).map(|i| (i, if i < 4 || i == 7 { 'a' } else { 'b' }));
+        let tree = hierarchical_clustering(vertices, edges.clone(), &Default::default());
+
+        let root = tree.root().unwrap();
+        assert_eq!(tree.nodes()[root].size, 8);
+        assert_eq!(tree.nodes()[root].dominant, 'a');
+        assert_eq!(tree.nodes().len(), 15);
+        assert_eq!(tree.cut_at(0).len(), 1);
+
+        let mut cut = tree.cut_at(1);
+        cut.iter_mut().for_each(|cluster| cluster.sort());
+        cut.sort();
+        assert_eq!(cut, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+        let mut leaves: Vec<_> = tree.cut_at(10).into_iter().flatten().collect();
+        leaves.sort();
+        assert_eq!(leaves, (0..8).collect::<Vec<_>>());
+
+        // The seed breaks ties within the blobs but not the top-level cut, and
+        // the same seed always gives the same tree.
+        for seed in 1..5 {
+            let options = ClusteringOptions {
+                seed,
+                ..Default::default()
+            };
+            let vertices = (0..8).map(|i| (i, ()));
+            let tree = hierarchical_clustering(vertices.clone(), edges.clone(), &options);
+            assert_eq!(hierarchical_clustering(vertices, edges.clone(), &options), tree);
+            let mut cut = tree.cut_at(1);
+            cut.iter_mut().for_each(|cluster| cluster.sort());
+            cut.sort();
+            assert_eq!(cut, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7]]);
+        }
+
+        // An isolated vertex is joined to the rest only at the root.
+        let vertices = (0..9).map(|i| (i, ()));
+        let edges: Vec<_> = blob(0).chain(blob(4)).chain([(3, 4)]).collect();
+        let tree = hierarchical_clustering(vertices, edges, &Default::default());
+        let root = &tree.nodes()[tree.root().unwrap()];
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(tree.cut_at(1).iter().map(|c| c.len()).collect::<Vec<_>>(), vec![8, 1]);
+    }
+}
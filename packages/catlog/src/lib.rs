@@ -14,6 +14,9 @@ modules according to dimensionality:
 1. [`one`]: Ordinary, or one-dimensional, category theory.
 2. [`dbl`]: Double category theory.
 
+Interchange with other tools, such as diagram editors, is in [`export`], and
+support for drawing large models is in [`layout`].
+
 The foundational modules make no pretence to completeness, but if they become
 sufficiently useful in their own right, they may be spun off into their own
 packages.
@@ -27,9 +30,13 @@ packages.
 #[cfg(doc)]
 pub mod refs;
 
+pub mod prelude;
+pub mod progress;
 pub mod validate;
 
 pub mod dbl;
+pub mod export;
+pub mod layout;
 pub mod one;
 pub mod stdlib;
 pub mod zero;
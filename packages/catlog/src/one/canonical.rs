@@ -0,0 +1,366 @@
+/*! Canonical forms of finite categories.
+
+Two finite categories are isomorphic exactly when they have the same
+[canonical form](CanonicalCategory). Canonical forms can be compared, hashed, and
+sorted, so they are useful for deduplicating large collections of categories,
+such as cached theories, up to isomorphism.
+
+The canonical form is computed by the individualization-refinement method used
+by graph canonization tools like [nauty](https://pallini.di.uniroma1.it/). The
+objects and morphism generators are first partitioned by invariants, such as
+their (co)domains and the composites they participate in, and the partition is
+refined until it is stable. When a cell of the stable partition has several
+elements, each element is tried in turn as the first element of the cell and
+the search continues. The least encoding of the category among the resulting
+labelings is its canonical form. Refinement usually leaves few choices, but the
+worst case is exponential.
+ */
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+use super::fin_category::{FinCategory, FinMor, InvalidFinCategory};
+use super::graph::{FinGraph, Graph};
+
+/** Canonical form of a finite category.
+
+Objects and morphism generators are numbered consecutively from zero. Equal
+canonical forms present isomorphic categories and vice versa.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CanonicalCategory {
+    /// Number of objects.
+    pub ob_count: usize,
+
+    /// Domain and codomain of each morphism generator.
+    pub homs: Vec<(usize, usize)>,
+
+    /// Composites of pairs of morphism generators, in sorted order.
+    pub composites: Vec<(usize, usize, CanonicalMor)>,
+}
+
+/// Morphism in the canonical form of a finite category.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CanonicalMor {
+    /// Identity morphism on an object.
+    Id(usize),
+
+    /// Morphism generator.
+    Generator(usize),
+}
+
+impl<V, E, S> FinCategory<V, E, S>
+where
+    V: Eq + Clone + Hash,
+    E: Eq + Clone + Hash,
+    S: BuildHasher,
+{
+    /** Computes the canonical form of the category.
+
+    See the [module-level docs](self) for the algorithm. Fails if a morphism
+    generator or composite refers to an object or generator not in the
+    category, which [validation](crate::validate::Validate) also reports.
+     */
+    pub fn canonical_form(&self) -> Result<CanonicalCategory, InvalidFinCategory<V, E>> {
+        Ok(Labeling::new(self)?.canonical_form())
+    }
+
+    /** Is the category isomorphic to another, by comparing canonical forms?
+
+    A category that fails to have a [canonical form](Self::canonical_form) is
+    not isomorphic to any category.
+     */
+    pub fn is_isomorphic_to<V2, E2, S2>(&self, other: &FinCategory<V2, E2, S2>) -> bool
+    where
+        V2: Eq + Clone + Hash,
+        E2: Eq + Clone + Hash,
+        S2: BuildHasher,
+    {
+        let (Ok(this), Ok(that)) = (Labeling::new(self), Labeling::new(other)) else {
+            return false;
+        };
+        this.ob_count() == that.ob_count()
+            && this.dom.len() == that.dom.len()
+            && this.composites.len() == that.composites.len()
+            && this.canonical_form() == that.canonical_form()
+    }
+}
+
+/// A finite category with its elements numbered, the input to canonization.
+struct Labeling {
+    dom: Vec<usize>,
+    cod: Vec<usize>,
+    out_homs: Vec<Vec<usize>>,
+    in_homs: Vec<Vec<usize>>,
+    composites: Vec<(usize, usize, CanonicalMor)>,
+}
+
+/// Colors of the objects and morphism generators, ordering the cells.
+#[derive(Clone)]
+struct Coloring {
+    obs: Vec<usize>,
+    homs: Vec<usize>,
+}
+
+impl Labeling {
+    fn new<V, E, S>(cat: &FinCategory<V, E, S>) -> Result<Self, InvalidFinCategory<V, E>>
+    where
+        V: Eq + Clone + Hash,
+        E: Eq + Clone + Hash,
+        S: BuildHasher,
+    {
+        let graph = cat.generators();
+        let obs: HashMap<V, usize> = graph.vertices().enumerate().map(|(i, x)| (x, i)).collect();
+        let edges: Vec<E> = graph.edges().collect();
+        let homs: HashMap<&E, usize> = edges.iter().enumerate().map(|(i, f)| (f, i)).collect();
+        let dom = edges
+            .iter()
+            .map(|f| {
+                obs.get(&graph.src(f))
+                    .copied()
+                    .ok_or_else(|| InvalidFinCategory::Dom(f.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let cod = edges
+            .iter()
+            .map(|f| {
+                obs.get(&graph.tgt(f))
+                    .copied()
+                    .ok_or_else(|| InvalidFinCategory::Cod(f.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (mut out_homs, mut in_homs) =
+            (vec![Vec::new(); obs.len()], vec![Vec::new(); obs.len()]);
+        for (i, (x, y)) in dom.iter().zip(&cod).enumerate() {
+            out_homs[*x].push(i);
+            in_homs[*y].push(i);
+        }
+        let composites = cat
+            .composites()
+            .map(|((d, e), f)| {
+                let f = match f {
+                    FinMor::Id(x) => obs.get(&x).copied().map(CanonicalMor::Id),
+                    FinMor::Generator(g) => homs.get(&g).copied().map(CanonicalMor::Generator),
+                };
+                match (homs.get(&d), homs.get(&e), f) {
+                    (Some(i), Some(j), Some(f)) => Ok((*i, *j, f)),
+                    _ => Err(InvalidFinCategory::Composite(d, e)),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            dom,
+            cod,
+            out_homs,
+            in_homs,
+            composites,
+        })
+    }
+
+    fn ob_count(&self) -> usize {
+        self.out_homs.len()
+    }
+
+    fn canonical_form(&self) -> CanonicalCategory {
+        let coloring = Coloring {
+            obs: vec![0; self.ob_count()],
+            homs: vec![0; self.dom.len()],
+        };
+        self.search(self.refine(coloring))
+    }
+
+    /// Searches over the ways to individualize the coloring.
+    fn search(&self, coloring: Coloring) -> CanonicalCategory {
+        let split_ob = first_nontrivial_cell(&coloring.obs);
+        let (cell, is_ob) = match split_ob {
+            Some(cell) => (cell, true),
+            None => match first_nontrivial_cell(&coloring.homs) {
+                Some(cell) => (cell, false),
+                None => return self.encode(&coloring),
+            },
+        };
+        let colors = if is_ob { &coloring.obs } else { &coloring.homs };
+        let members: Vec<_> = (0..colors.len()).filter(|i| colors[*i] == cell).collect();
+        members
+            .into_iter()
+            .map(|i| {
+                let mut coloring = coloring.clone();
+                let colors = if is_ob {
+                    &mut coloring.obs
+                } else {
+                    &mut coloring.homs
+                };
+                individualize(colors, i);
+                self.search(self.refine(coloring))
+            })
+            .min()
+            .expect("Nontrivial cell should have elements")
+    }
+
+    /// Refines the coloring until it is stable.
+    fn refine(&self, mut coloring: Coloring) -> Coloring {
+        let mut count = cell_count(&coloring.obs) + cell_count(&coloring.homs);
+        loop {
+            let color = |f: &CanonicalMor| match f {
+                CanonicalMor::Id(x) => (0, coloring.obs[*x]),
+                CanonicalMor::Generator(g) => (1, coloring.homs[*g]),
+            };
+            let mut after = vec![Vec::new(); self.dom.len()];
+            let mut before = vec![Vec::new(); self.dom.len()];
+            for (d, e, f) in &self.composites {
+                after[*d].push((coloring.homs[*e], color(f)));
+                before[*e].push((coloring.homs[*d], color(f)));
+            }
+            let ob_sigs: Vec<_> = (0..self.ob_count())
+                .map(|x| {
+                    let out = sorted(self.out_homs[x].iter().map(|f| coloring.homs[*f]));
+                    let inc = sorted(self.in_homs[x].iter().map(|f| coloring.homs[*f]));
+                    (coloring.obs[x], out, inc)
+                })
+                .collect();
+            let hom_sigs: Vec<_> = (0..self.dom.len())
+                .map(|f| {
+                    let (x, y) = (coloring.obs[self.dom[f]], coloring.obs[self.cod[f]]);
+                    let after = sorted(after[f].iter().copied());
+                    let before = sorted(before[f].iter().copied());
+                    (coloring.homs[f], x, y, after, before)
+                })
+                .collect();
+            coloring = Coloring {
+                obs: ranks(&ob_sigs),
+                homs: ranks(&hom_sigs),
+            };
+            let new_count = cell_count(&coloring.obs) + cell_count(&coloring.homs);
+            if new_count == count {
+                return coloring;
+            }
+            count = new_count;
+        }
+    }
+
+    /// Encodes the category relabeled by a discrete coloring.
+    fn encode(&self, coloring: &Coloring) -> CanonicalCategory {
+        let (obs, homs) = (&coloring.obs, &coloring.homs);
+        let mut hom_list = vec![(0, 0); self.dom.len()];
+        for f in 0..self.dom.len() {
+            hom_list[homs[f]] = (obs[self.dom[f]], obs[self.cod[f]]);
+        }
+        let mut composites: Vec<_> = self
+            .composites
+            .iter()
+            .map(|(d, e, f)| {
+                let f = match f {
+                    CanonicalMor::Id(x) => CanonicalMor::Id(obs[*x]),
+                    CanonicalMor::Generator(g) => CanonicalMor::Generator(homs[*g]),
+                };
+                (homs[*d], homs[*e], f)
+            })
+            .collect();
+        composites.sort();
+        CanonicalCategory {
+            ob_count: self.ob_count(),
+            homs: hom_list,
+            composites,
+        }
+    }
+}
+
+/// Ranks the signatures, giving equal signatures equal ranks.
+fn ranks<T: Ord>(sigs: &[T]) -> Vec<usize> {
+    let distinct: BTreeSet<&T> = sigs.iter().collect();
+    let rank: BTreeMap<&T, usize> =
+        distinct.into_iter().enumerate().map(|(i, sig)| (sig, i)).collect();
+    sigs.iter().map(|sig| rank[sig]).collect()
+}
+
+/// Splits an element off from the front of its cell.
+fn individualize(colors: &mut [usize], i: usize) {
+    let cell = colors[i];
+    for (j, color) in colors.iter_mut().enumerate() {
+        if *color > cell || (*color == cell && j != i) {
+            *color += 1;
+        }
+    }
+}
+
+/// Finds the least color shared by more than one element.
+fn first_nontrivial_cell(colors: &[usize]) -> Option<usize> {
+    let mut sizes = vec![0; colors.len()];
+    for color in colors {
+        sizes[*color] += 1;
+    }
+    sizes.iter().position(|size| *size > 1)
+}
+
+fn cell_count(colors: &[usize]) -> usize {
+    colors.iter().max().map_or(0, |max| max + 1)
+}
+
+fn sorted<T: Ord>(iter: impl Iterator<Item = T>) -> Vec<T> {
+    let mut vec: Vec<_> = iter.collect();
+    vec.sort();
+    vec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category;
+
+    #[test]
+    fn symmetric_graph_schema() {
+        let sch_sgraph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V'), 'i' => ('E', 'E')],
+            composites: [('i', 'i') => id('E'), ('i', 's') => 't', ('i', 't') => 's'],
+        }
+        .unwrap();
+        let relabeled: FinCategory<&str, &str> = category! {
+            obs: ["edge", "vertex"],
+            homs: [
+                "rev" => ("edge", "edge"),
+                "tgt" => ("edge", "vertex"),
+                "src" => ("edge", "vertex"),
+            ],
+            composites: [
+                ("rev", "tgt") => "src",
+                ("rev", "src") => "tgt",
+                ("rev", "rev") => id("edge"),
+            ],
+        }
+        .unwrap();
+        assert_eq!(sch_sgraph.canonical_form().unwrap(), relabeled.canonical_form().unwrap());
+        assert!(sch_sgraph.is_isomorphic_to(&relabeled));
+
+        // Making the involution idempotent gives a non-isomorphic category with
+        // the same generators.
+        let idempotent: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V'), 'i' => ('E', 'E')],
+            composites: [('i', 'i') => 'i', ('i', 's') => 's', ('i', 't') => 't'],
+        }
+        .unwrap();
+        assert_ne!(sch_sgraph.canonical_form().unwrap(), idempotent.canonical_form().unwrap());
+        assert!(!sch_sgraph.is_isomorphic_to(&idempotent));
+
+        let canonical = sch_sgraph.canonical_form().unwrap();
+        assert_eq!(canonical.ob_count, 2);
+        assert_eq!(canonical.homs.len(), 3);
+        assert_eq!(canonical.composites.len(), 3);
+    }
+
+    #[test]
+    fn invalid_category() {
+        let mut cat: FinCategory<char, char> = Default::default();
+        cat.add_ob_generator('x');
+        cat.add_mor_generator('f', 'x', 'x');
+        cat.set_composite('f', 'g', FinMor::Generator('f'));
+        assert!(matches!(cat.canonical_form(), Err(InvalidFinCategory::Composite('f', 'g'))));
+        assert!(!cat.is_isomorphic_to(&cat));
+
+        let mut cat: FinCategory<char, char> = Default::default();
+        cat.add_mor_generator('f', 'x', 'y');
+        assert!(matches!(cat.canonical_form(), Err(InvalidFinCategory::Dom('f'))));
+    }
+}
@@ -1,12 +1,15 @@
 /*! Categories: interfaces and basic constructions.
  */
 
+use std::hash::Hash;
+
 use derive_more::From;
 use ref_cast::RefCast;
+use thiserror::Error;
 
 use super::graph::{FinGraph, Graph};
 use super::path::Path;
-use crate::zero::{FinSet, Set};
+use crate::zero::{FinSet, HashColumn, Mapping, Set};
 
 /** A category.
 
@@ -47,12 +50,67 @@ pub trait Category {
         self.compose(Path::pair(f, g))
     }
 
+    /** Composes a pair of morphisms, checking that they are compatible.
+
+    The codomain of the first morphism is compared with the domain of the second
+    by value, not by how the morphisms were constructed, so that any identities
+    needed to match up the endpoints are inserted implicitly. Unlike
+    [`compose2`](Category::compose2), which may panic, morphisms not in the
+    category or incompatible morphisms result in an error. Categories in which
+    composites can be undefined should override this method to report that
+    as an error too.
+     */
+    fn compose2_lenient(
+        &self,
+        f: Self::Mor,
+        g: Self::Mor,
+    ) -> Result<Self::Mor, ComposeError<Self::Ob>> {
+        if !(self.has_mor(&f) && self.has_mor(&g)) {
+            return Err(ComposeError::NotInCategory);
+        }
+        let (y, z) = (self.cod(&f), self.dom(&g));
+        if y != z {
+            return Err(ComposeError::Incompatible(y, z));
+        }
+        Ok(self.compose2(f, g))
+    }
+
+    /** Composes a sequence of morphisms starting at an object.
+
+    The morphisms are composed one at a time, starting from the identity at the
+    given object, so they need not be collected into a [`Path`] first. The
+    composite of an empty sequence is the identity. Compatibility of the
+    morphisms is checked as in [`compose2_lenient`](Category::compose2_lenient).
+     */
+    fn compose_iter<I>(&self, x: Self::Ob, iter: I) -> Result<Self::Mor, ComposeError<Self::Ob>>
+    where
+        I: IntoIterator<Item = Self::Mor>,
+    {
+        iter.into_iter().try_fold(self.id(x), |f, g| self.compose2_lenient(f, g))
+    }
+
     /// Constructs the identity morphism at an object.
     fn id(&self, x: Self::Ob) -> Self::Mor {
         self.compose(Path::empty(x))
     }
 }
 
+/// A failure to compose morphisms in a category.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ComposeError<Ob> {
+    /// Codomain of a morphism is not equal to the domain of the next one.
+    #[error("Codomain `{0}` of morphism is not equal to domain `{1}` of next morphism")]
+    Incompatible(Ob, Ob),
+
+    /// Composite of the morphisms is not defined in the category.
+    #[error("Composite of morphisms is not defined")]
+    Undefined,
+
+    /// One of the morphisms is not in the category.
+    #[error("Morphism is not in the category")]
+    NotInCategory,
+}
+
 /// The set of objects of a category.
 #[derive(From, RefCast)]
 #[repr(transparent)]
@@ -195,6 +253,30 @@ pub trait FgCategory: Category {
 
     /// The codomain of a morphism generator
     fn morphism_generator_cod(&self, f: &Self::MorGen) -> Self::Ob;
+
+    /** An iterator over object generators with no outgoing morphism generators.
+
+    These are the sinks of the graph of generators, the "leaves" of a schema.
+    They need not be terminal objects, which have exactly one morphism, possibly
+    a composite, from every object.
+     */
+    fn sink_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        let doms: Vec<_> =
+            self.morphism_generators().map(|f| self.morphism_generator_dom(&f)).collect();
+        self.object_generators().map(Into::into).filter(move |x| !doms.contains(x))
+    }
+
+    /** An iterator over object generators with no incoming morphism generators.
+
+    These are the sources of the graph of generators, the "roots" of a schema.
+    They need not be initial objects, which have exactly one morphism, possibly
+    a composite, to every object.
+     */
+    fn source_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        let cods: Vec<_> =
+            self.morphism_generators().map(|f| self.morphism_generator_cod(&f)).collect();
+        self.object_generators().map(Into::into).filter(move |x| !cods.contains(x))
+    }
 }
 
 impl<S: FinSet> Graph for DiscreteCategory<S>
@@ -276,6 +358,15 @@ where
     fn morphism_generator_cod(&self, f: &Self::MorGen) -> Self::Ob {
         f.clone()
     }
+
+    // The morphism generators of a discrete category are its identities.
+    fn sink_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.0.iter()
+    }
+
+    fn source_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.0.iter()
+    }
 }
 
 impl<G: FinGraph> FgCategory for FreeCategory<G>
@@ -302,12 +393,300 @@ where
     }
 }
 
+/** A category whose objects and morphisms can be enumerated.
+
+Such a category has finitely many morphisms, not just finitely many generators,
+so that properties quantifying over all morphisms can be checked exhaustively.
+ */
+pub trait EnumerableCategory: Category {
+    /// An iterator over all objects in the category.
+    fn objects(&self) -> impl Iterator<Item = Self::Ob>;
+
+    /// An iterator over all morphisms in the category.
+    fn morphisms(&self) -> impl Iterator<Item = Self::Mor>;
+}
+
+impl<S: FinSet> EnumerableCategory for DiscreteCategory<S>
+where
+    S::Elem: Clone,
+{
+    fn objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.0.iter()
+    }
+
+    fn morphisms(&self) -> impl Iterator<Item = Self::Mor> {
+        self.0.iter()
+    }
+}
+
+/** A functor between enumerable categories.
+
+The functor is given by its action on objects and morphisms, assigned
+explicitly. Identities that are not assigned are sent to identities. The
+functor is assumed to preserve domains, codomains, and composition.
+ */
+pub struct FinFunctor<'a, C: Category, D: Category>
+where
+    C::Ob: Hash,
+    C::Mor: Hash,
+{
+    /// Domain of the functor.
+    pub dom: &'a C,
+
+    /// Codomain of the functor.
+    pub cod: &'a D,
+
+    ob_map: HashColumn<C::Ob, D::Ob>,
+    mor_map: HashColumn<C::Mor, D::Mor>,
+}
+
+impl<'a, C, D> FinFunctor<'a, C, D>
+where
+    C: Category,
+    D: Category,
+    C::Ob: Hash + Clone,
+    C::Mor: Hash + Clone,
+    D::Ob: Clone,
+    D::Mor: Clone,
+{
+    /// Creates a functor between two categories, initially unassigned.
+    pub fn new(dom: &'a C, cod: &'a D) -> Self {
+        Self {
+            dom,
+            cod,
+            ob_map: Default::default(),
+            mor_map: Default::default(),
+        }
+    }
+
+    /// Assigns the functor at an object, returning the previous assignment.
+    pub fn assign_ob(&mut self, x: C::Ob, y: D::Ob) -> Option<D::Ob> {
+        self.ob_map.set(x, y)
+    }
+
+    /// Assigns the functor at a morphism, returning the previous assignment.
+    pub fn assign_mor(&mut self, f: C::Mor, g: D::Mor) -> Option<D::Mor> {
+        self.mor_map.set(f, g)
+    }
+
+    /// Applies the functor at an object.
+    pub fn apply_ob(&self, x: &C::Ob) -> Option<D::Ob> {
+        self.ob_map.apply(x).cloned()
+    }
+
+    /// Applies the functor at a morphism.
+    pub fn apply_mor(&self, f: &C::Mor) -> Option<D::Mor> {
+        if let Some(g) = self.mor_map.apply(f) {
+            return Some(g.clone());
+        }
+        let x = self.dom.dom(f);
+        if *f == self.dom.id(x.clone()) {
+            self.apply_ob(&x).map(|y| self.cod.id(y))
+        } else {
+            None
+        }
+    }
+}
+
+/// Maximum number of morphisms in either category for which adjunctions are
+/// checked by [`is_adjunction`].
+pub const ADJUNCTION_MAX_MORPHISMS: usize = 1000;
+
+/** Unit and counit of an adjunction between enumerable categories.
+
+For an adjunction F ⊣ G with F: C → D, the unit has a component
+`x → G(F(x))` at each object `x` of C and the counit has a component
+`F(G(y)) → y` at each object `y` of D.
+ */
+#[derive(Clone, Debug)]
+pub struct AdjunctionWitness<COb, CMor, DOb, DMor>
+where
+    COb: Eq + Hash,
+    DOb: Eq + Hash,
+{
+    /// Components of the unit, indexed by objects of the domain of F.
+    pub unit: HashColumn<COb, CMor>,
+
+    /// Components of the counit, indexed by objects of the domain of G.
+    pub counit: HashColumn<DOb, DMor>,
+}
+
+/// A failure of two functors to form an adjunction.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum NotAdjoint<COb, DOb> {
+    /// One of the functors is not defined at some object or morphism.
+    #[error("Functor is not defined everywhere")]
+    Undefined,
+
+    /// One of the categories has too many morphisms to check exhaustively.
+    #[error("Category has more than {0} morphisms")]
+    TooLarge(usize),
+
+    /// No bijection `D(F(x), y) ≅ C(x, G(y))` induced by a unit at `x`.
+    #[error("No bijection between hom-sets at objects `{0}` and `{1}`")]
+    Bijection(COb, DOb),
+
+    /// Unit cannot be natural with respect to some morphism `x → x'`.
+    #[error("Unit is not natural at objects `{0}` and `{1}`")]
+    Naturality(COb, COb),
+}
+
+/** Checks whether two functors form an adjunction F ⊣ G.
+
+The check is exhaustive. For each object `x` in C, the candidate units are the
+morphisms `η: x → G(F(x))` such that `f ↦ η · G(f)` is a bijection from
+`D(F(x), y)` to `C(x, G(y))` for every object `y` in D. A choice of candidates
+forms the unit of an adjunction if it is natural with respect to the given
+action of F on morphisms, and the counit is then determined. The search over
+choices backtracks, so the cost can be exponential, but the categories are
+limited to [`ADJUNCTION_MAX_MORPHISMS`] morphisms each.
+
+On failure, the objects named are those at which the bijection of hom-sets or
+the naturality of the unit first failed.
+ */
+#[allow(clippy::type_complexity)]
+pub fn is_adjunction<C, D>(
+    f: &FinFunctor<C, D>,
+    g: &FinFunctor<D, C>,
+) -> Result<AdjunctionWitness<C::Ob, C::Mor, D::Ob, D::Mor>, NotAdjoint<C::Ob, D::Ob>>
+where
+    C: EnumerableCategory,
+    D: EnumerableCategory,
+    C::Ob: Hash + Clone,
+    C::Mor: Hash + Clone,
+    D::Ob: Hash + Clone,
+    D::Mor: Hash + Clone,
+{
+    let (c, d) = (f.dom, f.cod);
+    let (c_mors, d_mors): (Vec<_>, Vec<_>) = (c.morphisms().collect(), d.morphisms().collect());
+    if c_mors.len().max(d_mors.len()) > ADJUNCTION_MAX_MORPHISMS {
+        return Err(NotAdjoint::TooLarge(ADJUNCTION_MAX_MORPHISMS));
+    }
+    let d_hom = |x: &D::Ob, y: &D::Ob| -> Vec<D::Mor> {
+        d_mors.iter().filter(|h| d.dom(h) == *x && d.cod(h) == *y).cloned().collect()
+    };
+    let c_hom = |x: &C::Ob, y: &C::Ob| -> Vec<C::Mor> {
+        c_mors.iter().filter(|h| c.dom(h) == *x && c.cod(h) == *y).cloned().collect()
+    };
+    let (c_obs, d_obs): (Vec<_>, Vec<_>) = (c.objects().collect(), d.objects().collect());
+    let fo = |x: &C::Ob| f.apply_ob(x).ok_or(NotAdjoint::Undefined);
+    let go = |y: &D::Ob| g.apply_ob(y).ok_or(NotAdjoint::Undefined);
+    let gm = |h: &D::Mor| g.apply_mor(h).ok_or(NotAdjoint::Undefined);
+
+    // Find the candidate units at each object.
+    let mut candidates = Vec::new();
+    for x in &c_obs {
+        let fx = fo(x)?;
+        let mut first_failure = None;
+        let mut universal = Vec::new();
+        for eta in c_hom(x, &go(&fx)?) {
+            let mut failure = None;
+            for y in &d_obs {
+                // The map is a bijection if it is injective and the hom-sets
+                // have the same size.
+                let homs = d_hom(&fx, y);
+                let mut images = Vec::new();
+                for h in homs.iter() {
+                    let image = c.compose2(eta.clone(), gm(h)?);
+                    if !images.contains(&image) {
+                        images.push(image);
+                    }
+                }
+                if images.len() != homs.len() || homs.len() != c_hom(x, &go(y)?).len() {
+                    failure = Some(y.clone());
+                    break;
+                }
+            }
+            match failure {
+                Some(y) => {
+                    first_failure.get_or_insert(y);
+                }
+                None => universal.push(eta),
+            }
+        }
+        if universal.is_empty() {
+            let y = first_failure.unwrap_or(fx);
+            return Err(NotAdjoint::Bijection(x.clone(), y));
+        }
+        candidates.push(universal);
+    }
+
+    // Choose candidates that are natural, backtracking as needed.
+    let mut naturality = Vec::new();
+    for h in &c_mors {
+        let (i, j) = (c.dom(h), c.cod(h));
+        let i = c_obs.iter().position(|x| *x == i).ok_or(NotAdjoint::Undefined)?;
+        let j = c_obs.iter().position(|x| *x == j).ok_or(NotAdjoint::Undefined)?;
+        let gfh = f.apply_mor(h).and_then(|k| g.apply_mor(&k)).ok_or(NotAdjoint::Undefined)?;
+        naturality.push((i.max(j), i, j, h.clone(), gfh));
+    }
+    let is_natural = |choice: &[usize], k: usize| {
+        naturality.iter().filter(|(n, ..)| *n == k).find(|(_, i, j, h, gfh)| {
+            let (eta_i, eta_j) = (&candidates[*i][choice[*i]], &candidates[*j][choice[*j]]);
+            c.compose2(eta_i.clone(), gfh.clone()) != c.compose2(h.clone(), eta_j.clone())
+        })
+    };
+    let mut choice = vec![0];
+    let mut conflict = None;
+    while choice.len() <= c_obs.len() {
+        let k = choice.len() - 1;
+        if k == c_obs.len() {
+            break;
+        }
+        match is_natural(&choice, k) {
+            None => choice.push(0),
+            Some((_, i, j, ..)) => {
+                conflict.get_or_insert((c_obs[*i].clone(), c_obs[*j].clone()));
+                // Advance to the next untried choice, backtracking if needed.
+                loop {
+                    let Some(last) = choice.last_mut() else {
+                        let (x, y) = conflict.unwrap();
+                        return Err(NotAdjoint::Naturality(x, y));
+                    };
+                    *last += 1;
+                    if *last < candidates[choice.len() - 1].len() {
+                        break;
+                    }
+                    choice.pop();
+                }
+            }
+        }
+    }
+    choice.truncate(c_obs.len());
+
+    let unit: HashColumn<_, _> = c_obs
+        .iter()
+        .enumerate()
+        .map(|(k, x)| (x.clone(), candidates[k][choice[k]].clone()))
+        .collect();
+
+    // The counit at y is the unique morphism ε: F(G(y)) → y with η · G(ε) = 1.
+    let mut counit: HashColumn<_, _> = Default::default();
+    for y in &d_obs {
+        let gy = go(y)?;
+        let eta = unit.apply(&gy).ok_or(NotAdjoint::Undefined)?.clone();
+        let id = c.id(gy.clone());
+        let mut eps = None;
+        for h in d_hom(&fo(&gy)?, y) {
+            if c.compose2(eta.clone(), gm(&h)?) == id {
+                eps = Some(h);
+                break;
+            }
+        }
+        let eps = eps.ok_or_else(|| NotAdjoint::Bijection(gy, y.clone()))?;
+        counit.set(y.clone(), eps);
+    }
+    Ok(AdjunctionWitness { unit, counit })
+}
+
 #[cfg(test)]
 mod tests {
     use nonempty::nonempty;
 
+    use super::super::fin_category::{FinCategory, FinMor};
     use super::super::graph::SkelGraph;
     use super::*;
+    use crate::category;
     use crate::zero::SkelFinSet;
 
     #[test]
@@ -339,6 +718,16 @@ mod tests {
         assert_eq!(cat.dom(&path), 0);
         assert_eq!(cat.cod(&path), 2);
 
+        assert_eq!(cat.compose2_lenient(Path::single(0), Path::single(1)), Ok(path));
+        assert_eq!(
+            cat.compose2_lenient(Path::single(0), Path::single(2)),
+            Err(ComposeError::Incompatible(1, 0))
+        );
+        assert_eq!(
+            cat.compose2_lenient(Path::single(0), Path::single(3)),
+            Err(ComposeError::NotInCategory)
+        );
+
         let cat = FreeCategory::from(SkelGraph::path(5));
         let path = Path::Seq(nonempty![
             Path::Id(0),
@@ -349,4 +738,65 @@ mod tests {
         ]);
         assert_eq!(cat.compose(path), Path::Seq(nonempty![0, 1, 2, 3]));
     }
+
+    #[test]
+    fn adjunction() {
+        // The walking arrow `0 → 1` and the terminal category.
+        let arrow: FinCategory<char, char> =
+            category! { obs: ['0', '1'], homs: ['a' => ('0', '1')] }.unwrap();
+        let point = DiscreteCategory::from(SkelFinSet::from(1));
+        let collapse = |arrow, homs: &[char]| {
+            let mut bang = FinFunctor::new(arrow, &point);
+            bang.assign_ob('0', 0);
+            bang.assign_ob('1', 0);
+            for &e in homs {
+                bang.assign_mor(FinMor::Generator(e), 0);
+            }
+            bang
+        };
+        let pick = |x| {
+            let mut pick = FinFunctor::new(&point, &arrow);
+            pick.assign_ob(0, x);
+            pick
+        };
+
+        // An initial object is left adjoint to the functor to the point, like a
+        // free construction left adjoint to a forgetful functor.
+        let witness = is_adjunction(&pick('0'), &collapse(&arrow, &['a'])).unwrap();
+        assert_eq!(witness.unit.apply(&0), Some(&0));
+        assert_eq!(witness.counit.apply(&'0'), Some(&FinMor::Id('0')));
+        assert_eq!(witness.counit.apply(&'1'), Some(&FinMor::Generator('a')));
+
+        // A terminal object is right adjoint to it.
+        let witness = is_adjunction(&collapse(&arrow, &['a']), &pick('1')).unwrap();
+        assert_eq!(witness.unit.apply(&'0'), Some(&FinMor::Generator('a')));
+        assert_eq!(witness.counit.apply(&0), Some(&0));
+
+        // But a terminal object is not left adjoint, nor an initial one right.
+        assert_eq!(
+            is_adjunction(&pick('1'), &collapse(&arrow, &['a'])).err(),
+            Some(NotAdjoint::Bijection(0, '0'))
+        );
+        assert_eq!(
+            is_adjunction(&collapse(&arrow, &['a']), &pick('0')).err(),
+            Some(NotAdjoint::Bijection('1', 0))
+        );
+
+        // With two parallel arrows, `0` is not initial, even though every
+        // morphism out of the point is hit.
+        let parallel: FinCategory<char, char> = category! {
+            obs: ['0', '1'],
+            homs: ['a' => ('0', '1'), 'b' => ('0', '1')],
+        }
+        .unwrap();
+        let pick = |x| {
+            let mut pick = FinFunctor::new(&point, &parallel);
+            pick.assign_ob(0, x);
+            pick
+        };
+        assert_eq!(
+            is_adjunction(&pick('0'), &collapse(&parallel, &['a', 'b'])).err(),
+            Some(NotAdjoint::Bijection(0, '1'))
+        );
+    }
 }
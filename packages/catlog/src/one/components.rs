@@ -0,0 +1,95 @@
+/*! Connected components.
+
+The functions in this module operate on raw lists of vertices and edges rather
+than on any particular graph data structure, so that they can be shared between
+graphs, models, and other structures having an underlying graph.
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::zero::HashFinSet;
+
+/** Computes the weakly connected components of a graph.
+
+The graph is given by an iterator over its vertices and an iterator over the
+endpoints of its edges. Endpoints not among the given vertices are added to the
+graph. Edge orientations are ignored, so the result consists of the *weakly*
+connected components.
+
+The components are returned in order of their first vertex, as it appears in
+the list of vertices followed by the list of edges. The computation uses a
+union-find data structure and so is nearly linear in the size of the graph.
+ */
+pub fn weakly_connected<V>(
+    vertices: impl IntoIterator<Item = V>,
+    edges: impl IntoIterator<Item = (V, V)>,
+) -> Vec<HashFinSet<V>>
+where
+    V: Eq + Hash + Clone,
+{
+    let mut elems: Vec<V> = Vec::new();
+    let mut index: HashMap<V, usize> = HashMap::new();
+    let mut index_of = |v: V, elems: &mut Vec<V>| {
+        *index.entry(v.clone()).or_insert_with(|| {
+            elems.push(v);
+            elems.len() - 1
+        })
+    };
+    for v in vertices {
+        index_of(v, &mut elems);
+    }
+    let pairs: Vec<_> = edges
+        .into_iter()
+        .map(|(x, y)| (index_of(x, &mut elems), index_of(y, &mut elems)))
+        .collect();
+
+    let mut parent: Vec<_> = (0..elems.len()).collect();
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for (i, j) in pairs {
+        let (i, j) = (find(&mut parent, i), find(&mut parent, j));
+        parent[i.max(j)] = i.min(j);
+    }
+
+    // Roots are always the least index in their class, so each component is
+    // created when its first vertex is reached.
+    let mut component_of: HashMap<usize, usize> = HashMap::new();
+    let mut components: Vec<HashFinSet<V>> = Vec::new();
+    for (i, v) in elems.into_iter().enumerate() {
+        let root = find(&mut parent, i);
+        let c = *component_of.entry(root).or_insert_with(|| {
+            components.push(Default::default());
+            components.len() - 1
+        });
+        components[c].insert(v);
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zero::{FinSet, Set};
+
+    #[test]
+    fn weakly_connected_lists() {
+        let vertices = ['a', 'b', 'c', 'd', 'e'];
+        let edges = [('b', 'a'), ('c', 'b'), ('d', 'e')];
+        let components = weakly_connected(vertices, edges);
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].len(), 3);
+        assert!(['a', 'b', 'c'].iter().all(|v| components[0].contains(v)));
+        assert_eq!(components[1].len(), 2);
+        assert!(['d', 'e'].iter().all(|v| components[1].contains(v)));
+
+        let components = weakly_connected([], [(1, 2), (3, 3)]);
+        assert_eq!(components.len(), 2);
+        assert!(components[1].contains(&3));
+    }
+}
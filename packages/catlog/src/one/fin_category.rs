@@ -1,20 +1,30 @@
 //! Data structures for finite and finitely presented categories.
 
-use std::hash::{BuildHasher, BuildHasherDefault, Hash, RandomState};
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher, RandomState};
 
 use derivative::Derivative;
+use either::Either;
 use nonempty::NonEmpty;
 use thiserror::Error;
 use ustr::{IdentityHasher, Ustr};
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
 use super::category::*;
 use super::graph::*;
 use super::path::*;
 use crate::validate::{self, Validate};
-use crate::zero::{Column, HashColumn, Mapping};
+use crate::zero::{Column, HashColumn, HashFinSet, Mapping, Set, VecColumn};
 
 /// Morphism in a finite category.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
 pub enum FinMor<V, E> {
     /// Identity morphism on an object.
     Id(V),
@@ -29,6 +39,56 @@ impl<V, E> From<E> for FinMor<V, E> {
     }
 }
 
+impl<V, E> Path<V, FinMor<V, E>> {
+    /** Removes the identities from a path of morphisms in a finite category.
+
+    Identities do not change a composite, so they can be dropped before
+    composing. If only identities remain, the path collapses to the empty path
+    at their common object, preserving the source and target of the path.
+     */
+    pub fn strip_identities(self) -> Self {
+        let Path::Seq(mors) = self else {
+            return self;
+        };
+        let NonEmpty { head, tail } = mors;
+        let (mut ob, mut gens) = (None, Vec::new());
+        for f in std::iter::once(head).chain(tail) {
+            match f {
+                FinMor::Id(x) => {
+                    ob.get_or_insert(x);
+                }
+                f => gens.push(f),
+            }
+        }
+        Path::from_vec(gens).unwrap_or_else(|| Path::Id(ob.expect("Path should be nonempty")))
+    }
+}
+
+/** Canonical identifier of a morphism in a finite category.
+
+The identifier is the normal form of the morphism as a path of generators: the
+empty path for an identity and a path of length one for a generator. Since it
+depends only on the generators, the identifier is stable across runs and can be
+used to refer to morphisms obtained by composition, which are otherwise
+anonymous. See [`FinCategory::canonical_name`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DerivedMorId<V, E>(pub Path<V, E>);
+
+impl<V, E> DerivedMorId<V, E> {
+    /// Displays the identifier as a composite written in the given notation.
+    pub fn display(&self, notation: CompositionNotation) -> PathDisplay<'_, V, E> {
+        self.0.display(notation)
+    }
+}
+
+/// Displays the identifier in diagrammatic order.
+impl<V: std::fmt::Display, E: std::fmt::Display> std::fmt::Display for DerivedMorId<V, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /** A finite category with explicitly defined composition law.
 
 Such a category is not just finitely presented, but actually finite. The
@@ -39,16 +99,408 @@ graphs, and symmetric reflexive graphs are all finite.
  */
 #[derive(Clone, Derivative, Debug)]
 #[derivative(Default(bound = "S: Default"))]
-#[derivative(PartialEq(bound = "V: Eq + Hash, E: Eq + Hash, S: BuildHasher"))]
-#[derivative(Eq(bound = "V: Eq + Hash, E: Eq + Hash, S: BuildHasher"))]
+#[derivative(PartialEq(bound = "V: Eq + Hash + Clone, E: Eq + Hash + Clone, S: BuildHasher"))]
+#[derivative(Eq(bound = "V: Eq + Hash + Clone, E: Eq + Hash + Clone, S: BuildHasher"))]
 pub struct FinCategory<V, E, S = RandomState> {
     generators: HashGraph<V, E, S>,
-    compose_map: HashColumn<(E, E), FinMor<V, E>>,
+    compose_map: IndexedCompositionTable<V, E>,
 }
 
 /// A finite category with objects and morphisms of type `Ustr`.
 pub type UstrFinCategory = FinCategory<Ustr, Ustr, BuildHasherDefault<IdentityHasher>>;
 
+/** Representation of the composition table of a [`FinCategory`].
+
+See [`FinCategory::optimize`].
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompositionRepr {
+    /// Hash map on pairs of morphism generators.
+    Sparse,
+
+    /// Vector indexed by pairs of morphism generators, stored in row-major
+    /// order.
+    Dense,
+}
+
+/** Composition table of a finite category laid out as a matrix.
+
+Rows and columns are both indexed by the morphisms in `labels`, identities
+first. The cell in row `f` and column `g` is the composite `f · g`, meaning `f`
+followed by `g`. Produced by [`FinCategory::composition_table`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct CompositionMatrix<Mor> {
+    /// Morphisms indexing the rows and columns.
+    pub labels: Vec<Mor>,
+
+    /// Cells of the matrix in row-major order.
+    pub cells: Vec<Vec<CompositionCell<Mor>>>,
+}
+
+/// Cell of a [`CompositionMatrix`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CompositionCell<Mor> {
+    /// Composite of the two morphisms.
+    Composite(Mor),
+
+    /// Morphisms are composable but their composite is not defined.
+    Undefined,
+
+    /// Codomain of the first morphism is not the domain of the second.
+    NotComposable,
+}
+
+impl<Mor> CompositionMatrix<Mor> {
+    /// Maps over the morphisms in the labels and cells.
+    pub fn map<T>(self, mut f: impl FnMut(Mor) -> T) -> CompositionMatrix<T> {
+        let labels = self.labels.into_iter().map(&mut f).collect();
+        let cells = self
+            .cells
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| match cell {
+                        CompositionCell::Composite(h) => CompositionCell::Composite(f(h)),
+                        CompositionCell::Undefined => CompositionCell::Undefined,
+                        CompositionCell::NotComposable => CompositionCell::NotComposable,
+                    })
+                    .collect()
+            })
+            .collect();
+        CompositionMatrix { labels, cells }
+    }
+}
+
+/// Maximum number of morphism generators for which a dense composition table
+/// is used.
+const DENSE_COMPOSITION_MAX_GENERATORS: usize = 64;
+
+/** Composition table of a finite category.
+
+The table is a column on pairs of morphism generators. The dense representation
+indexes the generators, which must be known in advance, so setting a composite
+involving any other generator falls back to the sparse representation.
+ */
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+enum CompositionTable<V, E> {
+    #[derivative(Default)]
+    Sparse(HashColumn<(E, E), FinMor<V, E>>),
+    Dense {
+        generators: Vec<E>,
+        index: HashMap<E, usize>,
+        table: VecColumn<FinMor<V, E>>,
+    },
+}
+
+/** Pair of morphism generators, owned or borrowed.
+
+Keys of the sparse composition table are owned pairs, which can be borrowed as
+trait objects so that a pair of references can look up a composite without
+cloning the generators.
+ */
+trait GeneratorPair<E> {
+    fn pair(&self) -> (&E, &E);
+}
+
+impl<E> GeneratorPair<E> for (E, E) {
+    fn pair(&self) -> (&E, &E) {
+        (&self.0, &self.1)
+    }
+}
+
+impl<E> GeneratorPair<E> for (&E, &E) {
+    fn pair(&self) -> (&E, &E) {
+        (self.0, self.1)
+    }
+}
+
+impl<'a, E: 'a> Borrow<dyn GeneratorPair<E> + 'a> for (E, E) {
+    fn borrow(&self) -> &(dyn GeneratorPair<E> + 'a) {
+        self
+    }
+}
+
+impl<E: Hash> Hash for dyn GeneratorPair<E> + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pair().hash(state)
+    }
+}
+
+impl<E: Eq> PartialEq for dyn GeneratorPair<E> + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.pair() == other.pair()
+    }
+}
+
+impl<E: Eq> Eq for dyn GeneratorPair<E> + '_ {}
+
+impl<V, E> CompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    /// Gets the composite of a pair of generators, without cloning them.
+    fn get(&self, d: &E, e: &E) -> Option<&FinMor<V, E>> {
+        match self {
+            CompositionTable::Sparse(column) => column.get(&(d, e) as &dyn GeneratorPair<E>),
+            CompositionTable::Dense {
+                generators,
+                index,
+                table,
+            } => {
+                let (i, j) = (index.get(d)?, index.get(e)?);
+                table.apply(&(i * generators.len() + j))
+            }
+        }
+    }
+
+    /// Creates a dense table on the given generators, if possible.
+    fn dense(
+        generators: Vec<E>,
+        entries: impl Iterator<Item = ((E, E), FinMor<V, E>)>,
+    ) -> Option<Self> {
+        let index: HashMap<_, _> =
+            generators.iter().cloned().enumerate().map(|(i, e)| (e, i)).collect();
+        let n = generators.len();
+        let mut table = VecColumn::default();
+        for ((d, e), f) in entries {
+            table.set(index.get(&d)? * n + index.get(&e)?, f);
+        }
+        Some(CompositionTable::Dense {
+            generators,
+            index,
+            table,
+        })
+    }
+
+    fn repr(&self) -> CompositionRepr {
+        match self {
+            CompositionTable::Sparse(_) => CompositionRepr::Sparse,
+            CompositionTable::Dense { .. } => CompositionRepr::Dense,
+        }
+    }
+
+    fn into_sparse(self) -> HashColumn<(E, E), FinMor<V, E>> {
+        match self {
+            CompositionTable::Sparse(column) => column,
+            dense => {
+                let mut column = HashColumn::default();
+                for (pair, f) in dense.iter() {
+                    column.set(pair, f.clone());
+                }
+                column
+            }
+        }
+    }
+}
+
+impl<V, E> Mapping for CompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    type Dom = (E, E);
+    type Cod = FinMor<V, E>;
+
+    fn apply(&self, pair: &(E, E)) -> Option<&FinMor<V, E>> {
+        match self {
+            CompositionTable::Sparse(column) => column.apply(pair),
+            CompositionTable::Dense {
+                generators,
+                index,
+                table,
+            } => {
+                let (i, j) = (index.get(&pair.0)?, index.get(&pair.1)?);
+                table.apply(&(i * generators.len() + j))
+            }
+        }
+    }
+
+    fn set(&mut self, pair: (E, E), f: FinMor<V, E>) -> Option<FinMor<V, E>> {
+        match self {
+            CompositionTable::Sparse(column) => column.set(pair, f),
+            CompositionTable::Dense {
+                generators,
+                index,
+                table,
+            } if index.contains_key(&pair.0) && index.contains_key(&pair.1) => {
+                table.set(index[&pair.0] * generators.len() + index[&pair.1], f)
+            }
+            CompositionTable::Dense { .. } => {
+                let mut column = std::mem::take(self).into_sparse();
+                let old = column.set(pair, f);
+                *self = CompositionTable::Sparse(column);
+                old
+            }
+        }
+    }
+
+    fn unset(&mut self, pair: &(E, E)) -> Option<FinMor<V, E>> {
+        match self {
+            CompositionTable::Sparse(column) => column.unset(pair),
+            CompositionTable::Dense {
+                generators,
+                index,
+                table,
+            } => {
+                let (i, j) = (index.get(&pair.0)?, index.get(&pair.1)?);
+                table.unset(&(i * generators.len() + j))
+            }
+        }
+    }
+}
+
+impl<V, E> Column for CompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    fn iter(&self) -> impl Iterator<Item = ((E, E), &FinMor<V, E>)> {
+        match self {
+            CompositionTable::Sparse(column) => Either::Left(column.iter()),
+            CompositionTable::Dense {
+                generators, table, ..
+            } => {
+                let n = generators.len();
+                Either::Right(
+                    table.iter().map(move |(k, f)| {
+                        ((generators[k / n].clone(), generators[k % n].clone()), f)
+                    }),
+                )
+            }
+        }
+    }
+}
+
+/** Composition table indexed by the generators mentioned in each entry.
+
+The index maps each morphism generator to the pairs of generators whose entry
+in the table mentions it, either in the pair itself or as the composite. It is
+kept up to date as composites are set and unset.
+ */
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+struct IndexedCompositionTable<V, E> {
+    table: CompositionTable<V, E>,
+    usages: HashMap<E, HashSet<(E, E)>>,
+}
+
+impl<V, E> IndexedCompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    /// Generators mentioned by an entry of the table.
+    fn mentions((d, e): &(E, E), f: Option<&FinMor<V, E>>) -> impl Iterator<Item = E> {
+        let g = match f {
+            Some(FinMor::Generator(g)) => Some(g.clone()),
+            _ => None,
+        };
+        [Some(d.clone()), Some(e.clone()), g].into_iter().flatten()
+    }
+
+    /// Pairs whose entry in the table mentions the generator.
+    fn usages(&self, e: &E) -> impl Iterator<Item = &(E, E)> {
+        self.usages.get(e).into_iter().flatten()
+    }
+
+    /// Gets the composite of a pair of generators, without cloning them.
+    fn get(&self, d: &E, e: &E) -> Option<&FinMor<V, E>> {
+        self.table.get(d, e)
+    }
+}
+
+impl<V, E> Mapping for IndexedCompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    type Dom = (E, E);
+    type Cod = FinMor<V, E>;
+
+    fn apply(&self, pair: &(E, E)) -> Option<&FinMor<V, E>> {
+        self.table.apply(pair)
+    }
+
+    fn set(&mut self, pair: (E, E), f: FinMor<V, E>) -> Option<FinMor<V, E>> {
+        for g in Self::mentions(&pair, Some(&f)) {
+            self.usages.entry(g).or_default().insert(pair.clone());
+        }
+        let old = self.table.set(pair.clone(), f.clone());
+        if let Some(FinMor::Generator(g)) = &old {
+            let still_mentioned = Self::mentions(&pair, Some(&f)).any(|h| h == *g);
+            if let Some(pairs) = self.usages.get_mut(g).filter(|_| !still_mentioned) {
+                pairs.remove(&pair);
+            }
+        }
+        old
+    }
+
+    fn unset(&mut self, pair: &(E, E)) -> Option<FinMor<V, E>> {
+        let old = self.table.unset(pair)?;
+        for g in Self::mentions(pair, Some(&old)) {
+            if let Some(pairs) = self.usages.get_mut(&g) {
+                pairs.remove(pair);
+            }
+        }
+        Some(old)
+    }
+}
+
+impl<V, E> Column for IndexedCompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    fn iter(&self) -> impl Iterator<Item = ((E, E), &FinMor<V, E>)> {
+        self.table.iter()
+    }
+}
+
+impl<V, E> PartialEq for IndexedCompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.table == other.table
+    }
+}
+
+impl<V, E> Eq for IndexedCompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+}
+
+impl<V, E> PartialEq for CompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().count() == other.iter().count()
+            && self.iter().all(|(pair, f)| other.apply(&pair) == Some(f))
+    }
+}
+
+impl<V, E> Eq for CompositionTable<V, E>
+where
+    V: Eq + Clone,
+    E: Eq + Hash + Clone,
+{
+}
+
 impl<V, E, S> FinCategory<V, E, S>
 where
     V: Eq + Hash + Clone,
@@ -78,13 +530,465 @@ where
         self.generators.add_edge(e, dom, cod)
     }
 
+    /** Chooses the representation of the composition table.
+
+    Small categories are best served by a dense table, a vector indexed by pairs
+    of morphism generators, while large categories, whose composition tables
+    tend to be sparse, are best served by a hash map. This method chooses
+    between them based on the number of morphism generators. The semantics of
+    the category are unchanged.
+     */
+    pub fn optimize(&mut self) {
+        let generators: Vec<_> = self.generators.edges().collect();
+        let table = std::mem::take(&mut self.compose_map.table);
+        self.compose_map.table = if generators.len() <= DENSE_COMPOSITION_MAX_GENERATORS {
+            let entries: Vec<_> = table.iter().map(|(pair, f)| (pair, f.clone())).collect();
+            CompositionTable::dense(generators, entries.into_iter())
+                .unwrap_or_else(|| CompositionTable::Sparse(table.into_sparse()))
+        } else {
+            CompositionTable::Sparse(table.into_sparse())
+        };
+    }
+
+    /// Gets the representation of the composition table.
+    pub fn repr(&self) -> CompositionRepr {
+        self.compose_map.table.repr()
+    }
+
     /// Sets the value of a binary composite.
     pub fn set_composite(&mut self, d: E, e: E, f: FinMor<V, E>) {
         self.compose_map.set((d, e), f);
     }
 
+    /// Unsets the value of a binary composite, returning it if it was set.
+    pub fn unset_composite(&mut self, d: &E, e: &E) -> Option<FinMor<V, E>> {
+        self.compose_map.unset(&(d.clone(), e.clone()))
+    }
+
+    /** Iterates over the composites mentioning a morphism generator.
+
+    A composite of generators `d` and `e` mentions a generator when it is `d`,
+    `e`, or the composite itself. The composites are found through an index
+    kept up to date as composites are set, so the cost does not depend on the
+    size of the composition table. They are returned in no particular order.
+     */
+    pub fn composites_involving<'a>(
+        &'a self,
+        e: &E,
+    ) -> impl Iterator<Item = ((E, E), FinMor<V, E>)> + 'a {
+        self.compose_map.usages(e).map(|pair| {
+            let f = self.compose_map.apply(pair).expect("Indexed composite should be set");
+            (pair.clone(), f.clone())
+        })
+    }
+
+    /** Gets the domain of a morphism without cloning it.
+
+    Borrowing variant of [`dom`](Category::dom).
+     */
+    pub fn dom_ref<'a>(&'a self, f: &'a FinMor<V, E>) -> &'a V {
+        match f {
+            FinMor::Id(v) => v,
+            FinMor::Generator(e) => self.generators.get_src(e).expect("Domain should be defined"),
+        }
+    }
+
+    /** Gets the codomain of a morphism without cloning it.
+
+    Borrowing variant of [`cod`](Category::cod).
+     */
+    pub fn cod_ref<'a>(&'a self, f: &'a FinMor<V, E>) -> &'a V {
+        match f {
+            FinMor::Id(v) => v,
+            FinMor::Generator(e) => self.generators.get_tgt(e).expect("Codomain should be defined"),
+        }
+    }
+
+    /// Gets the domain of a morphism, if it is in the category.
+    fn get_dom_ref<'a>(&'a self, f: &'a FinMor<V, E>) -> Option<&'a V> {
+        match f {
+            FinMor::Id(v) => self.generators.has_vertex(v).then_some(v),
+            FinMor::Generator(e) => self.generators.get_src(e),
+        }
+    }
+
+    /// Gets the codomain of a morphism, if it is in the category.
+    fn get_cod_ref<'a>(&'a self, f: &'a FinMor<V, E>) -> Option<&'a V> {
+        match f {
+            FinMor::Id(v) => self.generators.has_vertex(v).then_some(v),
+            FinMor::Generator(e) => self.generators.get_tgt(e),
+        }
+    }
+
+    /** Composes a pair of morphisms without cloning them.
+
+    Borrowing variant of [`compose2`](Category::compose2). In a finite
+    category, the composite of two morphisms is always either one of them, when
+    the other is an identity, or is stored in the composition table, so the
+    result can be borrowed from the arguments or from the category itself.
+     */
+    pub fn compose2_ref<'a>(
+        &'a self,
+        f: &'a FinMor<V, E>,
+        g: &'a FinMor<V, E>,
+    ) -> &'a FinMor<V, E> {
+        match (f, g) {
+            (FinMor::Id(_), g) => g,
+            (f, FinMor::Id(_)) => f,
+            (FinMor::Generator(d), FinMor::Generator(e)) => {
+                assert!(
+                    self.generators.get_tgt(d) == self.generators.get_src(e),
+                    "(Co)domains should be equal"
+                );
+                self.compose_map.get(d, e).expect("Composition should be defined")
+            }
+        }
+    }
+
+    /// Iterates over all composites of generators defined in the category.
+    pub fn composites(&self) -> impl Iterator<Item = ((E, E), FinMor<V, E>)> + '_ {
+        self.compose_map.iter().map(|(pair, f)| (pair, f.clone()))
+    }
+
+    /** Composition table of the category as a matrix.
+
+    The rows and columns are indexed by the identities followed by the morphism
+    generators, each in the order in which they were added.
+     */
+    pub fn composition_table(&self) -> CompositionMatrix<FinMor<V, E>> {
+        self.composition_matrix(self.generators.vertices(), self.generators.edges())
+    }
+
+    /** Composition table restricted to a subset of morphism generators.
+
+    The rows and columns are indexed by the identities at the domains and
+    codomains of the given generators, followed by the generators themselves.
+    Generators not belonging to the category are ignored. For a large category,
+    this gives a table small enough to read.
+     */
+    pub fn composition_table_on(
+        &self,
+        gens: impl IntoIterator<Item = E>,
+    ) -> CompositionMatrix<FinMor<V, E>> {
+        let gens: Vec<_> = gens.into_iter().filter(|e| self.generators.has_edge(e)).collect();
+        let obs = self.generators.vertices().filter(|x| {
+            gens.iter()
+                .any(|e| self.generators.src(e) == *x || self.generators.tgt(e) == *x)
+        });
+        self.composition_matrix(obs, gens.iter().cloned())
+    }
+
+    fn composition_matrix(
+        &self,
+        obs: impl Iterator<Item = V>,
+        gens: impl Iterator<Item = E>,
+    ) -> CompositionMatrix<FinMor<V, E>> {
+        let labels: Vec<_> = obs.map(FinMor::Id).chain(gens.map(FinMor::Generator)).collect();
+        let cells = labels
+            .iter()
+            .map(|f| {
+                labels
+                    .iter()
+                    .map(|g| match self.try_compose2(f.clone(), g.clone()) {
+                        Ok(h) => CompositionCell::Composite(h),
+                        Err(ComposeError::Undefined) => CompositionCell::Undefined,
+                        Err(ComposeError::Incompatible(..) | ComposeError::NotInCategory) => {
+                            CompositionCell::NotComposable
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        CompositionMatrix { labels, cells }
+    }
+
+    /** Audits the table of composites defined in the category.
+
+    Reports entries of the composition table that cannot be part of a
+    well-defined category because they refer to generators that do not exist or
+    have endpoints that do not match. Unlike [`iter_invalid`](Self::iter_invalid),
+    which checks that composites are defined for all composable pairs, this
+    method inspects every entry of the table, including entries that are never
+    consulted during composition.
+     */
+    pub fn audit_composites(&self) -> Vec<CompositeAuditIssue<E>> {
+        let mut issues = Vec::new();
+        for ((d, e), f) in self.compose_map.iter() {
+            let (Some(x), Some(y)) = (self.generators.get_src(&d), self.generators.get_tgt(&e))
+            else {
+                issues.push(CompositeAuditIssue::DanglingKey(d, e));
+                continue;
+            };
+            if !self.has_mor(f) {
+                issues.push(CompositeAuditIssue::DanglingValue(d, e));
+            } else if self.generators.get_tgt(&d) != self.generators.get_src(&e)
+                || self.dom_ref(f) != x
+                || self.cod_ref(f) != y
+            {
+                issues.push(CompositeAuditIssue::EndpointMismatch(d, e));
+            }
+        }
+        issues
+    }
+
+    /** Composes a pair of morphisms, if possible.
+
+    Unlike [`compose2`](Category::compose2), this method does not panic when
+    either morphism is not in the category, when the morphisms are not
+    composable, or when their composite is not defined.
+    */
+    pub fn try_compose2(
+        &self,
+        f: FinMor<V, E>,
+        g: FinMor<V, E>,
+    ) -> Result<FinMor<V, E>, ComposeError<V>> {
+        let (Some(y), Some(y2)) = (self.get_cod_ref(&f), self.get_dom_ref(&g)) else {
+            return Err(ComposeError::NotInCategory);
+        };
+        if y != y2 {
+            return Err(ComposeError::Incompatible(y.clone(), y2.clone()));
+        }
+        match (f, g) {
+            (FinMor::Id(_), g) => Ok(g),
+            (f, FinMor::Id(_)) => Ok(f),
+            (FinMor::Generator(d), FinMor::Generator(e)) => {
+                self.compose_map.apply(&(d, e)).cloned().ok_or(ComposeError::Undefined)
+            }
+        }
+    }
+
+    /// Canonical identifier of a morphism in the category.
+    pub fn canonical_name(&self, f: &FinMor<V, E>) -> DerivedMorId<V, E> {
+        DerivedMorId(match f {
+            FinMor::Id(x) => Path::Id(x.clone()),
+            FinMor::Generator(e) => Path::single(e.clone()),
+        })
+    }
+
+    /** Canonical identifier of the composite of a path of generators.
+
+    Equal composites have equal identifiers, regardless of the paths used to
+    compute them.
+     */
+    pub fn canonical_name_of_path(
+        &self,
+        path: Path<V, E>,
+    ) -> Result<DerivedMorId<V, E>, ComposeError<V>> {
+        let f = match path {
+            Path::Id(x) => FinMor::Id(x),
+            Path::Seq(edges) => edges
+                .into_iter()
+                .map(FinMor::Generator)
+                .try_fold(None, |acc, g| match acc {
+                    None => Ok(Some(g)),
+                    Some(f) => self.try_compose2(f, g).map(Some),
+                })?
+                .expect("Path should be nonempty"),
+        };
+        Ok(self.canonical_name(&f))
+    }
+
+    /** Looks up the morphism with the given canonical identifier.
+
+    Returns `None` if the identifier is not in normal form or does not refer to
+    a morphism in the category.
+     */
+    pub fn morphism_named(&self, id: &DerivedMorId<V, E>) -> Option<FinMor<V, E>> {
+        let f = match &id.0 {
+            Path::Id(x) => FinMor::Id(x.clone()),
+            Path::Seq(edges) if edges.tail.is_empty() => FinMor::Generator(edges.head.clone()),
+            Path::Seq(_) => return None,
+        };
+        self.has_mor(&f).then_some(f)
+    }
+
+    /** Iterates over all morphisms in the category with their canonical names.
+
+    The identities come first, followed by the generators, each in the order in
+    which they were added to the category.
+     */
+    pub fn named_morphisms(&self) -> impl Iterator<Item = (DerivedMorId<V, E>, FinMor<V, E>)> + '_ {
+        let ids = self.generators.vertices().map(FinMor::Id);
+        let gens = self.generators.edges().map(FinMor::Generator);
+        ids.chain(gens).map(|f| (self.canonical_name(&f), f))
+    }
+
+    /** Composes an endomorphism with itself `n` times.
+
+    The zeroth power is the identity on the (co)domain of the morphism. Fails
+    if the morphism is not in the category.
+    */
+    pub fn power(&self, f: &FinMor<V, E>, n: usize) -> Result<FinMor<V, E>, ComposeError<V>> {
+        let (Some(x), Some(y)) = (self.get_dom_ref(f), self.get_cod_ref(f)) else {
+            return Err(ComposeError::NotInCategory);
+        };
+        if x != y {
+            return Err(ComposeError::Incompatible(y.clone(), x.clone()));
+        }
+        let mut result = FinMor::Id(x.clone());
+        for _ in 0..n {
+            result = self.try_compose2(result, f.clone())?;
+        }
+        Ok(result)
+    }
+
+    /** Subcategory generated by a collection of morphism generators.
+
+    The subcategory is the smallest one containing the given morphism
+    generators. Besides these generators, it contains their domains and
+    codomains and any further generators needed to close under composition.
+    Generators not belonging to the category are ignored.
+
+    Returns the subcategory together with the inclusion functor into this
+    category, given by its action on generators.
+    */
+    #[allow(clippy::type_complexity)]
+    pub fn generated_subcategory(
+        &self,
+        homs: &[E],
+    ) -> (Self, ColumnarGraphMapping<HashColumn<V, V, S>, HashColumn<E, E, S>>)
+    where
+        S: Default,
+    {
+        let mut sub: Self = Default::default();
+        let mut new: Vec<_> =
+            homs.iter().filter(|e| self.generators.has_edge(e)).cloned().collect();
+        while !new.is_empty() {
+            for e in new {
+                let (x, y) = (self.generators.src(&e), self.generators.tgt(&e));
+                sub.generators.add_vertices([x.clone(), y.clone()]);
+                sub.generators.add_edge(e, x, y);
+            }
+            new = Vec::new();
+            for d in sub.generators.edges() {
+                for e in sub.generators.out_edges(&sub.generators.tgt(&d)) {
+                    if let Some(FinMor::Generator(f)) = self.compose_map.apply(&(d.clone(), e)) {
+                        if !sub.generators.has_edge(f) && !new.contains(f) {
+                            new.push(f.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let ob_map: HashColumn<V, V, S> =
+            sub.generators.vertices().map(|x| (x.clone(), x)).collect();
+        let mut mor_map: HashColumn<E, E, S> = Default::default();
+        for d in sub.generators.edges() {
+            for e in sub.generators.out_edges(&sub.generators.tgt(&d)) {
+                let pair = (d.clone(), e);
+                if let Some(f) = self.compose_map.apply(&pair) {
+                    sub.compose_map.set(pair, f.clone());
+                }
+            }
+            mor_map.set(d.clone(), d);
+        }
+        (sub, ColumnarGraphMapping::new(ob_map, mor_map))
+    }
+
+    /** Full subcategory on a set of objects.
+
+    The subcategory contains exactly the given objects that belong to this
+    category, in the order they were added, together with every morphism
+    generator whose domain and codomain are both among them. Composites of the
+    retained generators are kept, since their domain and codomain also belong to
+    the set.
+     */
+    pub fn full_subcategory(&self, objects: &HashFinSet<V>) -> Self
+    where
+        S: Default,
+    {
+        let mut sub: Self = Default::default();
+        sub.generators
+            .add_vertices(self.generators.vertices().filter(|x| objects.contains(x)));
+        for e in self.generators.edges() {
+            let (x, y) = (self.generators.src(&e), self.generators.tgt(&e));
+            if objects.contains(&x) && objects.contains(&y) {
+                sub.generators.add_edge(e, x, y);
+            }
+        }
+        for d in sub.generators.edges() {
+            for e in sub.generators.out_edges(&sub.generators.tgt(&d)) {
+                let pair = (d.clone(), e);
+                if let Some(f) = self.compose_map.apply(&pair) {
+                    sub.compose_map.set(pair, f.clone());
+                }
+            }
+        }
+        sub
+    }
+
+    /** Is the category free on its generating graph?
+
+    In a finite category, every composite of generators is again a generator or
+    an identity, so any composable pair of generators imposes a relation
+    collapsing a path of length two. Thus the category is free exactly when no
+    two generators are composable, which is to say the composition map is empty.
+     */
+    pub fn is_free(&self) -> bool {
+        self.generators
+            .edges()
+            .all(|e| self.generators.out_degree(&self.generators.tgt(&e)) == 0)
+    }
+
+    /** Matrix of the sizes of the hom-sets between objects.
+
+    Returns the objects in the order of the rows and columns, followed by the
+    matrix, whose entry in row `x` and column `y` is the number of morphisms
+    from `x` to `y`, identities included. Since isomorphic categories have the
+    same matrix up to simultaneously permuting rows and columns, comparing, say,
+    the sorted entries of two matrices is a cheap test for non-isomorphism.
+     */
+    pub fn hom_size_matrix(&self) -> (Vec<V>, Vec<Vec<usize>>) {
+        let obs: Vec<_> = self.generators.vertices().collect();
+        let index: HashMap<_, _> = obs.iter().cloned().enumerate().map(|(i, x)| (x, i)).collect();
+        let mut matrix = vec![vec![0; obs.len()]; obs.len()];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[i] = 1;
+        }
+        for e in self.generators.edges() {
+            let i = index[&self.generators.src(&e)];
+            let j = index[&self.generators.tgt(&e)];
+            matrix[i][j] += 1;
+        }
+        (obs, matrix)
+    }
+
+    /** Checks that the composites set by hand define a congruence.
+
+    Setting the composite of generators `d` and `e` to be `f` identifies the
+    path `d;e` with `f`. For the quotient category to be well defined, the
+    identifications must be closed under pre- and post-composition with
+    generators: `g;(d;e)` must be identified with `g;f` and `(d;e);g` with
+    `f;g`. Composites that are not defined are skipped, as those failures are
+    reported by [`validate`](Validate::validate).
+     */
+    pub fn check_congruence(&self) -> Result<(), NonEmpty<CongruenceError<E>>> {
+        validate::wrap_errors(self.iter_noncongruent())
+    }
+
+    /** Iterates over failures of the composites to define a congruence.
+
+    The identifications fail to be closed under composition with a generator
+    exactly when composition is not associative on the resulting triple of
+    generators, so each failure is derived from the
+    [associativity](InvalidFinCategory::Associativity) check performed by
+    validation. A nonassociative triple `(f, g, h)` fails to respect both the
+    composite of `g` and `h` under precomposition with `f` and the composite of
+    `f` and `g` under postcomposition with `h`.
+     */
+    pub fn iter_noncongruent(&self) -> impl Iterator<Item = CongruenceError<E>> + '_ {
+        self.iter_nonassociative().flat_map(|err| match err {
+            InvalidFinCategory::Associativity { f, g, h, .. } => vec![
+                CongruenceError::Pre(f.clone(), g.clone(), h.clone()),
+                CongruenceError::Post(h, f, g),
+            ],
+            _ => Vec::new(),
+        })
+    }
+
     /// Iterates over failures to be a well-defined finite category.
-    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidFinCategory<E>> + '_ {
+    pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidFinCategory<V, E>> + '_ {
         let generator_errors = self.generators.iter_invalid().map(|err| match err {
             InvalidGraphData::Src(e) => InvalidFinCategory::Dom(e),
             InvalidGraphData::Tgt(e) => InvalidFinCategory::Cod(e),
@@ -109,7 +1013,42 @@ where
                 errs
             })
         });
-        generator_errors.chain(compose_errors)
+        generator_errors.chain(compose_errors).chain(self.iter_nonassociative())
+    }
+
+    /** Iterates over composable triples of generators violating associativity.
+
+    Triples for which any of the relevant composites fails to be defined are
+    skipped, as those failures are reported separately.
+     */
+    fn iter_nonassociative(&self) -> impl Iterator<Item = InvalidFinCategory<V, E>> + '_ {
+        let graph = &self.generators;
+        let composable = move |f: &E| graph.out_edges(&graph.tgt(f)).collect::<Vec<_>>();
+        graph.edges().flat_map(move |f| {
+            let mut errs = Vec::new();
+            for g in composable(&f) {
+                for h in composable(&g) {
+                    let fg = self.try_compose2(f.clone().into(), g.clone().into());
+                    let gh = self.try_compose2(g.clone().into(), h.clone().into());
+                    let (Ok(fg), Ok(gh)) = (fg, gh) else { continue };
+                    let left = self.try_compose2(fg, h.clone().into());
+                    let right = self.try_compose2(f.clone().into(), gh);
+                    let (Ok(left), Ok(right)) = (left, right) else {
+                        continue;
+                    };
+                    if left != right {
+                        errs.push(InvalidFinCategory::Associativity {
+                            f: f.clone(),
+                            g: g.clone(),
+                            h,
+                            left,
+                            right,
+                        });
+                    }
+                }
+            }
+            errs
+        })
     }
 }
 
@@ -119,13 +1058,109 @@ where
     E: Eq + Hash + Clone,
     S: BuildHasher,
 {
-    type ValidationError = InvalidFinCategory<E>;
+    type ValidationError = InvalidFinCategory<V, E>;
 
     fn validate(&self) -> Result<(), NonEmpty<Self::ValidationError>> {
         validate::wrap_errors(self.iter_invalid())
     }
 }
 
+/** Serialized form of a finite category.
+
+The generators and composites are sorted, so that the serialized form does not
+depend on the order of insertion or on the hasher of the category.
+ */
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct FinCategoryData<V, E> {
+    obs: Vec<V>,
+    homs: Vec<(E, V, V)>,
+    composites: Vec<(E, E, FinMor<V, E>)>,
+}
+
+#[cfg(feature = "serde")]
+impl<V, E, S> Serialize for FinCategory<V, E, S>
+where
+    V: Eq + Hash + Clone + Ord + Serialize,
+    E: Eq + Hash + Clone + Ord + Serialize,
+    S: BuildHasher,
+{
+    fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut obs: Vec<_> = self.generators.vertices().collect();
+        obs.sort();
+        let mut homs: Vec<_> = self
+            .generators
+            .edges()
+            .map(|e| {
+                let (dom, cod) = (self.generators.src(&e), self.generators.tgt(&e));
+                (e, dom, cod)
+            })
+            .collect();
+        homs.sort();
+        let mut composites: Vec<_> = self.composites().map(|((d, e), f)| (d, e, f)).collect();
+        composites.sort();
+        FinCategoryData {
+            obs,
+            homs,
+            composites,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserializes a finite category, failing unless it is well defined.
+#[cfg(feature = "serde")]
+impl<'de, V, E, S> Deserialize<'de> for FinCategory<V, E, S>
+where
+    V: Eq + Hash + Clone + Deserialize<'de>,
+    E: Eq + Hash + Clone + Deserialize<'de>,
+    S: BuildHasher + Default,
+    InvalidFinCategory<V, E>: std::fmt::Display,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = FinCategoryData::<V, E>::deserialize(deserializer)?;
+        let mut cat = FinCategory::default();
+        cat.add_ob_generators(data.obs);
+        for (e, dom, cod) in data.homs {
+            cat.add_mor_generator(e, dom, cod);
+        }
+        for (d, e, f) in data.composites {
+            let graph = &cat.generators;
+            let composable = graph.has_edge(&d)
+                && graph.has_edge(&e)
+                && graph.get_tgt(&d).is_some_and(|y| graph.get_src(&e) == Some(y));
+            if !(composable && cat.has_mor(&f)) {
+                return Err(serde::de::Error::custom(
+                    "Composition table refers to morphisms not in the category",
+                ));
+            }
+            cat.set_composite(d, e, f);
+        }
+        cat.validate().map_err(|errs| serde::de::Error::custom(errs.head))?;
+        Ok(cat)
+    }
+}
+
+impl<V, E, S> FinCategory<V, E, S>
+where
+    V: Eq + Hash + Copy,
+    E: Eq + Hash + Copy,
+    S: BuildHasher,
+{
+    /** Composes a pair of morphisms whose objects and generators are `Copy`.
+
+    Same as [`compose2`](Category::compose2), but the composite is copied out of
+    the composition table rather than cloned, which is cheaper in tight loops
+    over categories with small keys such as `char` or `usize`.
+     */
+    pub fn compose2_copy(&self, f: FinMor<V, E>, g: FinMor<V, E>) -> FinMor<V, E> {
+        match self.compose2_ref(&f, &g) {
+            FinMor::Id(x) => FinMor::Id(*x),
+            FinMor::Generator(e) => FinMor::Generator(*e),
+        }
+    }
+}
+
 impl<V, E, S> Category for FinCategory<V, E, S>
 where
     V: Eq + Hash + Clone,
@@ -147,21 +1182,15 @@ where
     }
 
     fn dom(&self, f: &FinMor<V, E>) -> V {
-        match f {
-            FinMor::Id(v) => v.clone(),
-            FinMor::Generator(e) => self.generators.src(e),
-        }
+        self.dom_ref(f).clone()
     }
 
     fn cod(&self, f: &FinMor<V, E>) -> V {
-        match f {
-            FinMor::Id(v) => v.clone(),
-            FinMor::Generator(e) => self.generators.tgt(e),
-        }
+        self.cod_ref(f).clone()
     }
 
     fn compose(&self, path: Path<V, FinMor<V, E>>) -> FinMor<V, E> {
-        path.reduce(|x| self.id(x), |f, g| self.compose2(f, g))
+        path.strip_identities().reduce(|x| self.id(x), |f, g| self.compose2(f, g))
     }
 
     fn compose2(&self, f: FinMor<V, E>, g: FinMor<V, E>) -> FinMor<V, E> {
@@ -170,7 +1199,7 @@ where
             (f, FinMor::Id(_)) => f,
             (FinMor::Generator(d), FinMor::Generator(e)) => {
                 assert!(
-                    self.generators.tgt(&d) == self.generators.src(&e),
+                    self.generators.get_tgt(&d) == self.generators.get_src(&e),
                     "(Co)domains should be equal"
                 );
                 self.compose_map.apply(&(d, e)).expect("Composition should be defined").clone()
@@ -178,6 +1207,14 @@ where
         }
     }
 
+    fn compose2_lenient(
+        &self,
+        f: FinMor<V, E>,
+        g: FinMor<V, E>,
+    ) -> Result<FinMor<V, E>, ComposeError<V>> {
+        self.try_compose2(f, g)
+    }
+
     fn id(&self, x: V) -> FinMor<V, E> {
         FinMor::Id(x)
     }
@@ -207,11 +1244,35 @@ where
     fn morphism_generator_cod(&self, f: &Self::MorGen) -> Self::Ob {
         self.generators.tgt(f)
     }
+
+    fn sink_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.generators.vertices().filter(|x| self.generators.out_degree(x) == 0)
+    }
+
+    fn source_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.generators.vertices().filter(|x| self.generators.in_degree(x) == 0)
+    }
+}
+
+impl<V, E, S> EnumerableCategory for FinCategory<V, E, S>
+where
+    V: Eq + Hash + Clone,
+    E: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    fn objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.generators.vertices()
+    }
+
+    fn morphisms(&self) -> impl Iterator<Item = Self::Mor> {
+        let ids = self.generators.vertices().map(FinMor::Id);
+        ids.chain(self.generators.edges().map(FinMor::Generator))
+    }
 }
 
 /// A failure of a finite category to be well defined.
 #[derive(Debug, Error)]
-pub enum InvalidFinCategory<E> {
+pub enum InvalidFinCategory<V, E> {
     /// Morphism assigned a domain not contained in the category.
     #[error("Domain of morphism `{0}` is not in the category")]
     Dom(E),
@@ -231,6 +1292,258 @@ pub enum InvalidFinCategory<E> {
     /// Composite of a pair of morphisms has incompatible codomain.
     #[error("Composite of morphisms `{0}` and `{1}` has incompatible codomain")]
     CompositeCod(E, E),
+
+    /// Composition of a triple of morphisms is not associative.
+    #[error("Composition of morphisms `{f}`, `{g}`, and `{h}` is not associative: {left:?} != {right:?}")]
+    Associativity {
+        /// First morphism in the triple.
+        f: E,
+        /// Second morphism in the triple.
+        g: E,
+        /// Third morphism in the triple.
+        h: E,
+        /// The composite `(f;g);h`.
+        left: FinMor<V, E>,
+        /// The composite `f;(g;h)`.
+        right: FinMor<V, E>,
+    },
+}
+
+/** Builder for a finite category.
+
+Objects, morphisms, and composites are declared by chaining method calls,
+after which [`build`](Self::build) assembles and validates the category. Each
+declaration is numbered by its position in the chain, starting from zero, and
+errors refer to declarations by these positions. For the most common shapes,
+the [`category!`](crate::category) macro provides a more concise syntax.
+ */
+#[derive(Clone, Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct FinCategoryBuilder<V, E, S = RandomState> {
+    decls: Vec<FinCategoryDecl<V, E>>,
+    hasher: std::marker::PhantomData<S>,
+}
+
+/// Declaration in a builder for a finite category.
+#[derive(Clone, Debug)]
+enum FinCategoryDecl<V, E> {
+    Ob(V),
+    Mor(E, V, V),
+    Composite(E, E, FinMor<V, E>),
+}
+
+impl<V, E, S> FinCategoryBuilder<V, E, S>
+where
+    V: Eq + Hash + Clone,
+    E: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Declares an object generator.
+    pub fn ob(mut self, v: impl Into<V>) -> Self {
+        self.decls.push(FinCategoryDecl::Ob(v.into()));
+        self
+    }
+
+    /// Declares a morphism generator with given domain and codomain.
+    pub fn hom(mut self, e: impl Into<E>, dom: impl Into<V>, cod: impl Into<V>) -> Self {
+        self.decls.push(FinCategoryDecl::Mor(e.into(), dom.into(), cod.into()));
+        self
+    }
+
+    /// Declares that the composite of two morphism generators is a generator.
+    pub fn composite(mut self, d: impl Into<E>, e: impl Into<E>, f: impl Into<E>) -> Self {
+        let f = FinMor::Generator(f.into());
+        self.decls.push(FinCategoryDecl::Composite(d.into(), e.into(), f));
+        self
+    }
+
+    /// Declares that the composite of two morphism generators is an identity.
+    pub fn composite_id(mut self, d: impl Into<E>, e: impl Into<E>, x: impl Into<V>) -> Self {
+        let f = FinMor::Id(x.into());
+        self.decls.push(FinCategoryDecl::Composite(d.into(), e.into(), f));
+        self
+    }
+
+    /** Builds the finite category.
+
+    Besides the errors in the declarations themselves, such as duplicate
+    generators or references to undeclared generators, the errors from
+    [validating](FinCategory::iter_invalid) the category are reported.
+     */
+    pub fn build(self) -> Result<FinCategory<V, E, S>, NonEmpty<FinCategoryBuildError<V, E>>> {
+        type Error<V, E> = FinCategoryBuildError<V, E>;
+        let mut cat: FinCategory<V, E, S> = Default::default();
+        let mut errs = Vec::new();
+        for (i, decl) in self.decls.iter().enumerate() {
+            if let FinCategoryDecl::Ob(v) = decl {
+                if !cat.add_ob_generator(v.clone()) {
+                    errs.push(Error::DuplicateOb(i, v.clone()));
+                }
+            }
+        }
+        for (i, decl) in self.decls.iter().enumerate() {
+            if let FinCategoryDecl::Mor(e, dom, cod) = decl {
+                if cat.generators.has_edge(e) {
+                    errs.push(Error::DuplicateHom(i, e.clone()));
+                    continue;
+                }
+                let unknown: Vec<_> = [dom, cod].into_iter().filter(|v| !cat.has_ob(v)).collect();
+                if unknown.is_empty() {
+                    cat.add_mor_generator(e.clone(), dom.clone(), cod.clone());
+                } else {
+                    errs.extend(unknown.into_iter().map(|v| Error::UnknownOb(i, v.clone())));
+                }
+            }
+        }
+        for (i, decl) in self.decls.into_iter().enumerate() {
+            if let FinCategoryDecl::Composite(d, e, f) = decl {
+                let mut unknown: Vec<_> = [&d, &e]
+                    .into_iter()
+                    .filter(|e| !cat.generators.has_edge(e))
+                    .map(|e| Error::UnknownHom(i, e.clone()))
+                    .collect();
+                match &f {
+                    FinMor::Generator(f) if !cat.generators.has_edge(f) => {
+                        unknown.push(Error::UnknownHom(i, f.clone()))
+                    }
+                    FinMor::Id(x) if !cat.has_ob(x) => unknown.push(Error::UnknownOb(i, x.clone())),
+                    _ => {}
+                }
+                let pair = (d, e);
+                if !unknown.is_empty() {
+                    errs.append(&mut unknown);
+                } else if cat.compose_map.is_set(&pair) {
+                    errs.push(Error::DuplicateComposite(i, pair.0, pair.1));
+                } else {
+                    cat.compose_map.set(pair, f);
+                }
+            }
+        }
+        if errs.is_empty() {
+            errs.extend(cat.audit_composites().into_iter().map(Error::Audit));
+        }
+        if errs.is_empty() {
+            errs.extend(cat.iter_invalid().map(Error::Invalid));
+        }
+        match NonEmpty::from_vec(errs) {
+            Some(errs) => Err(errs),
+            None => Ok(cat),
+        }
+    }
+}
+
+/** An issue with an entry in the composition table of a finite category.
+
+See [`FinCategory::audit_composites`].
+ */
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum CompositeAuditIssue<E> {
+    /// Composite keyed by a morphism that is not a generator of the category.
+    #[error("Composite of morphisms `{0}` and `{1}` refers to an unknown morphism")]
+    DanglingKey(E, E),
+
+    /// Composite whose value is not a morphism in the category.
+    #[error("Composite of morphisms `{0}` and `{1}` is not a morphism in the category")]
+    DanglingValue(E, E),
+
+    /// Composite of morphisms that are not composable or whose value has the
+    /// wrong domain or codomain.
+    #[error("Composite of morphisms `{0}` and `{1}` has mismatched endpoints")]
+    EndpointMismatch(E, E),
+}
+
+/** A failure of the composites in a finite category to define a congruence.
+
+See [`FinCategory::check_congruence`]. Each variant names a generator `g`
+followed by the pair of generators `d` and `e` whose declared composite is not
+preserved by composing with `g`.
+ */
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum CongruenceError<E> {
+    /// Precomposing with the generator does not respect the composite.
+    #[error("Precomposing with `{0}` does not respect the composite of `{1}` and `{2}`")]
+    Pre(E, E, E),
+
+    /// Postcomposing with the generator does not respect the composite.
+    #[error("Postcomposing with `{0}` does not respect the composite of `{1}` and `{2}`")]
+    Post(E, E, E),
+}
+
+/// A failure to build a finite category from its declarations.
+#[derive(Debug, Error)]
+pub enum FinCategoryBuildError<V, E> {
+    /// Object generator declared more than once.
+    #[error("Declaration {0}: object `{1}` is already declared")]
+    DuplicateOb(usize, V),
+
+    /// Morphism generator declared more than once.
+    #[error("Declaration {0}: morphism `{1}` is already declared")]
+    DuplicateHom(usize, E),
+
+    /// Composite of a pair of morphisms declared more than once.
+    #[error("Declaration {0}: composite of morphisms `{1}` and `{2}` is already declared")]
+    DuplicateComposite(usize, E, E),
+
+    /// Reference to an undeclared object generator.
+    #[error("Declaration {0}: object `{1}` is not declared")]
+    UnknownOb(usize, V),
+
+    /// Reference to an undeclared morphism generator.
+    #[error("Declaration {0}: morphism `{1}` is not declared")]
+    UnknownHom(usize, E),
+
+    /// Issue with a declared composite, found by auditing the composition table.
+    #[error("{0}")]
+    Audit(CompositeAuditIssue<E>),
+
+    /// Failure of the declared category to be well defined.
+    #[error("{0}")]
+    Invalid(InvalidFinCategory<V, E>),
+}
+
+/** Builds a finite category from lists of generators and composites.
+
+The objects and morphisms are given by expressions convertible into the object
+and morphism types of the category. A composite can be either a morphism
+generator or, using the syntax `id(x)`, an identity. The macro evaluates to the
+result of [`FinCategoryBuilder::build`].
+
+```
+# use catlog::category;
+# use catlog::one::fin_category::FinCategory;
+let sch_sgraph: FinCategory<char, char> = category! {
+    obs: ['V', 'E'],
+    homs: ['s' => ('E', 'V'), 't' => ('E', 'V'), 'i' => ('E', 'E')],
+    composites: [('i', 'i') => id('E'), ('i', 's') => 't', ('i', 't') => 's'],
+}
+.unwrap();
+```
+ */
+#[macro_export]
+macro_rules! category {
+    (
+        obs: [$($ob:expr),* $(,)?],
+        homs: [$($hom:expr => ($dom:expr, $cod:expr)),* $(,)?]
+        $(, composites: [$($composites:tt)*])?
+        $(,)?
+    ) => {{
+        let builder = $crate::one::fin_category::FinCategoryBuilder::new()
+            $(.ob($ob))*
+            $(.hom($hom, $dom, $cod))*;
+        $crate::category!(@composites builder; $($($composites)*)?).build()
+    }};
+    (@composites $builder:expr;) => { $builder };
+    (@composites $builder:expr; ($d:expr, $e:expr) => id($x:expr) $(, $($rest:tt)*)?) => {
+        $crate::category!(@composites $builder.composite_id($d, $e, $x); $($($rest)*)?)
+    };
+    (@composites $builder:expr; ($d:expr, $e:expr) => $f:expr $(, $($rest:tt)*)?) => {
+        $crate::category!(@composites $builder.composite($d, $e, $f); $($($rest)*)?)
+    };
 }
 
 /** A finitely presented category.
@@ -301,6 +1614,43 @@ where
         self.generators.make_edge(e)
     }
 
+    /** Removes an object generator, returning whether it was present.
+
+    Morphism generators with the object as their domain or codomain are left
+    alone and should be updated by the caller.
+     */
+    pub fn remove_ob_generator(&mut self, v: &V) -> bool {
+        self.generators.remove_vertex(v)
+    }
+
+    /** Removes a morphism generator, returning whether it was present.
+
+    Equations mentioning the generator on either side are removed too, since
+    they would no longer be well defined.
+     */
+    pub fn remove_mor_generator(&mut self, e: &E) -> bool {
+        let mentioning: Vec<_> = self
+            .equations
+            .iter()
+            .filter(|(_, eq)| eq.lhs.edges().chain(eq.rhs.edges()).any(|f| f == e))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in mentioning {
+            self.equations.unset(&key);
+        }
+        self.generators.remove_edge(e)
+    }
+
+    /// Reserves capacity for at least `additional` more object generators.
+    pub fn reserve_ob_generators(&mut self, additional: usize) {
+        self.generators.reserve_vertices(additional)
+    }
+
+    /// Reserves capacity for at least `additional` more morphism generators.
+    pub fn reserve_mor_generators(&mut self, additional: usize) {
+        self.generators.reserve_edges(additional)
+    }
+
     /// Gets the domain of a morphism generator.
     pub fn get_dom(&self, e: &E) -> Option<&V> {
         self.generators.get_src(e)
@@ -426,6 +1776,14 @@ where
     fn morphism_generator_cod(&self, f: &Self::MorGen) -> Self::Ob {
         self.generators.tgt(f)
     }
+
+    fn sink_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.generators.vertices().filter(|x| self.generators.out_degree(x) == 0)
+    }
+
+    fn source_objects(&self) -> impl Iterator<Item = Self::Ob> {
+        self.generators.vertices().filter(|x| self.generators.in_degree(x) == 0)
+    }
 }
 
 /// A failure of a finite presentation of a category to be well defined.
@@ -459,26 +1817,34 @@ pub enum InvalidFpCategory<E, EqKey> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stdlib::theories::th_signed_category;
     use nonempty::nonempty;
+    use ustr::ustr;
 
     #[test]
     fn fin_category() {
         type Mor<V, E> = FinMor<V, E>;
 
-        let mut sch_sgraph: FinCategory<char, char> = Default::default();
-        sch_sgraph.add_ob_generators(['V', 'E']);
-        sch_sgraph.add_mor_generator('s', 'E', 'V');
-        sch_sgraph.add_mor_generator('t', 'E', 'V');
-        sch_sgraph.add_mor_generator('i', 'E', 'E');
+        let builder = FinCategoryBuilder::new()
+            .ob('V')
+            .ob('E')
+            .hom('s', 'E', 'V')
+            .hom('t', 'E', 'V')
+            .hom('i', 'E', 'E');
+        let errs = builder.clone().build().unwrap_err();
+        assert_eq!(errs.len(), 3);
+        assert!(errs.iter().all(|err| matches!(err, FinCategoryBuildError::Invalid(_))));
+
+        let sch_sgraph: FinCategory<char, char> = builder
+            .composite_id('i', 'i', 'E')
+            .composite('i', 's', 't')
+            .composite('i', 't', 's')
+            .build()
+            .unwrap();
         assert_eq!(sch_sgraph.object_generators().count(), 2);
         assert_eq!(sch_sgraph.morphism_generators().count(), 3);
         assert_eq!(sch_sgraph.dom(&Mor::Generator('t')), 'E');
         assert_eq!(sch_sgraph.cod(&Mor::Generator('t')), 'V');
-        assert_eq!(sch_sgraph.validate().unwrap_err().len(), 3);
-
-        sch_sgraph.set_composite('i', 'i', Mor::Id('E'));
-        sch_sgraph.set_composite('i', 's', Mor::Generator('t'));
-        sch_sgraph.set_composite('i', 't', Mor::Generator('s'));
         assert!(sch_sgraph.validate().is_ok());
         assert_eq!(sch_sgraph.compose2(Mor::Generator('i'), Mor::Generator('i')), Mor::Id('E'));
         let path = Path::Seq(nonempty![
@@ -488,7 +1854,468 @@ mod tests {
             Mor::Generator('i'),
             Mor::Generator('s'),
         ]);
+        let stripped = path.clone().strip_identities();
+        assert_eq!(
+            stripped,
+            Path::Seq(nonempty![
+                Mor::Generator('i'),
+                Mor::Generator('i'),
+                Mor::Generator('i'),
+                Mor::Generator('s'),
+            ])
+        );
+        assert_eq!(sch_sgraph.compose(stripped), Mor::Generator('t'));
         assert_eq!(sch_sgraph.compose(path), Mor::Generator('t'));
+        let ids: Path<char, Mor<char, char>> = Path::Seq(nonempty![Mor::Id('E'), Mor::Id('E')]);
+        assert_eq!(ids.strip_identities(), Path::Id('E'));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let sch_sgraph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V'), 'i' => ('E', 'E')],
+            composites: [('i', 'i') => id('E'), ('i', 's') => 't', ('i', 't') => 's'],
+        }
+        .unwrap();
+        let json = serde_json::to_string(&sch_sgraph).unwrap();
+        let de: FinCategory<char, char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, sch_sgraph);
+
+        // The serialized form does not depend on the hasher.
+        let sch_ustr: UstrFinCategory = FinCategoryBuilder::new()
+            .ob("V")
+            .ob("E")
+            .hom("s", "E", "V")
+            .hom("t", "E", "V")
+            .hom("i", "E", "E")
+            .composite_id("i", "i", "E")
+            .composite("i", "s", "t")
+            .composite("i", "t", "s")
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&sch_ustr).unwrap();
+        let de: UstrFinCategory = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, sch_ustr);
+        let de: FinCategory<String, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&de).unwrap(), json);
+
+        // Composites must refer to composable generators in the category.
+        let json = r#"{"obs": ["V"], "homs": [["f", "V", "V"]],
+            "composites": [["f", "g", {"tag": "Generator", "content": "f"}]]}"#;
+        assert!(serde_json::from_str::<FinCategory<String, String>>(json).is_err());
+        let json = r#"{"obs": ["V"], "homs": [["f", "V", "V"]], "composites": []}"#;
+        assert!(serde_json::from_str::<FinCategory<String, String>>(json).is_err());
+    }
+
+    #[test]
+    fn composition_repr() {
+        let sch_sgraph: FinCategory<char, char> = FinCategoryBuilder::new()
+            .ob('V')
+            .ob('E')
+            .hom('s', 'E', 'V')
+            .hom('t', 'E', 'V')
+            .hom('i', 'E', 'E')
+            .composite_id('i', 'i', 'E')
+            .composite('i', 's', 't')
+            .composite('i', 't', 's')
+            .build()
+            .unwrap();
+        assert_eq!(sch_sgraph.repr(), CompositionRepr::Sparse);
+
+        let mut optimized = sch_sgraph.clone();
+        optimized.optimize();
+        assert_eq!(optimized.repr(), CompositionRepr::Dense);
+        assert_eq!(optimized, sch_sgraph);
+        let mors: Vec<_> = sch_sgraph.named_morphisms().map(|(_, f)| f).collect();
+        for f in &mors {
+            for g in mors.iter().filter(|g| sch_sgraph.cod(f) == sch_sgraph.dom(g)) {
+                assert_eq!(
+                    optimized.compose2(f.clone(), g.clone()),
+                    sch_sgraph.compose2(f.clone(), g.clone())
+                );
+            }
+        }
+        for f in &mors {
+            for g in mors.iter().filter(|g| sch_sgraph.cod(f) == sch_sgraph.dom(g)) {
+                assert_eq!(
+                    sch_sgraph.compose2_copy(f.clone(), g.clone()),
+                    sch_sgraph.compose2(f.clone(), g.clone())
+                );
+            }
+        }
+        let mut composites: Vec<_> = optimized.composites().collect();
+        composites.sort_by_key(|(pair, _)| *pair);
+        assert_eq!(composites.len(), 3);
+        assert_eq!(composites[0], (('i', 'i'), FinMor::Id('E')));
+        assert!(optimized.validate().is_ok());
+
+        // Adding a generator after optimizing falls back to a sparse table.
+        optimized.add_mor_generator('r', 'V', 'E');
+        optimized.set_composite('r', 'i', 'r'.into());
+        assert_eq!(optimized.repr(), CompositionRepr::Sparse);
+        assert_eq!(optimized.compose2('i'.into(), 's'.into()), 't'.into());
+
+        // Usages of generators are indexed through changes of representation.
+        let usages = |cat: &FinCategory<char, char>, e| {
+            let mut pairs: Vec<_> = cat.composites_involving(&e).map(|(pair, _)| pair).collect();
+            pairs.sort();
+            pairs
+        };
+        assert_eq!(usages(&optimized, 't'), vec![('i', 's'), ('i', 't')]);
+        assert_eq!(usages(&optimized, 'r'), vec![('r', 'i')]);
+        assert_eq!(optimized.unset_composite(&'r', &'i'), Some('r'.into()));
+        assert_eq!(usages(&optimized, 'r'), vec![]);
+        optimized.set_composite('i', 's', 's'.into());
+        assert_eq!(usages(&optimized, 't'), vec![('i', 't')]);
+    }
+
+    #[test]
+    fn lenient_composition() {
+        type Mor = FinMor<char, char>;
+
+        let sch_sgraph: FinCategory<char, char> = FinCategoryBuilder::new()
+            .ob('V')
+            .ob('E')
+            .hom('s', 'E', 'V')
+            .hom('t', 'E', 'V')
+            .hom('i', 'E', 'E')
+            .composite_id('i', 'i', 'E')
+            .composite('i', 's', 't')
+            .composite('i', 't', 's')
+            .build()
+            .unwrap();
+
+        // Compose across an explicit identity in the middle of a path.
+        let path = [Mor::Generator('i'), Mor::Id('E'), Mor::Generator('s')];
+        let composite = path
+            .into_iter()
+            .try_fold(Mor::Id('E'), |f, g| sch_sgraph.compose2_lenient(f, g));
+        assert_eq!(composite, Ok(Mor::Generator('t')));
+
+        assert_eq!(
+            sch_sgraph.compose2_lenient(Mor::Generator('s'), Mor::Id('E')),
+            Err(ComposeError::Incompatible('V', 'E'))
+        );
+        assert_eq!(
+            sch_sgraph.compose2_lenient(Mor::Generator('s'), Mor::Generator('i')),
+            Err(ComposeError::Incompatible('V', 'E'))
+        );
+
+        // A generator not in the category is an error, not a panic.
+        assert_eq!(
+            sch_sgraph.compose2_lenient(Mor::Generator('r'), Mor::Generator('s')),
+            Err(ComposeError::NotInCategory)
+        );
+        assert_eq!(
+            sch_sgraph.compose2_lenient(Mor::Generator('i'), Mor::Generator('r')),
+            Err(ComposeError::NotInCategory)
+        );
+    }
+
+    #[test]
+    fn compose_iter() {
+        use crate::stdlib::theories::{th_schema, th_signed_category};
+        use ustr::ustr;
+
+        let th = th_signed_category();
+        let sgn = th.category();
+        let (x, neg) = (ustr("Object"), FinMor::Generator(ustr("Negative")));
+        let signs = |n| std::iter::repeat_with(|| neg.clone()).take(n);
+        assert_eq!(sgn.compose_iter(x, signs(0)), Ok(FinMor::Id(x)));
+        assert_eq!(sgn.compose_iter(x, signs(3)), Ok(neg.clone()));
+        assert_eq!(sgn.compose_iter(x, signs(4)), Ok(FinMor::Id(x)));
+
+        // Identities in the sequence are absorbed.
+        let mixed = (0..5).map(|i| {
+            if i % 2 == 0 {
+                FinMor::Id(x)
+            } else {
+                neg.clone()
+            }
+        });
+        assert_eq!(sgn.compose_iter(x, mixed), Ok(FinMor::Id(x)));
+
+        let th = th_schema();
+        let attr = FinMor::Generator(ustr("Attr"));
+        assert_eq!(
+            th.category().compose_iter(ustr("Entity"), [attr.clone(), attr]),
+            Err(ComposeError::Incompatible(ustr("AttrType"), ustr("Entity")))
+        );
+    }
+
+    #[test]
+    fn borrowing_accessors() {
+        type Mor = FinMor<String, String>;
+
+        let cat: FinCategory<String, String> = FinCategoryBuilder::new()
+            .ob("E")
+            .ob("V")
+            .hom("i", "E", "E")
+            .hom("s", "E", "V")
+            .composite_id("i", "i", "E")
+            .composite("i", "s", "s")
+            .build()
+            .unwrap();
+        let mors = [
+            Mor::Id("E".into()),
+            Mor::Id("V".into()),
+            Mor::Generator("i".into()),
+            Mor::Generator("s".into()),
+        ];
+        let mut optimized = cat.clone();
+        optimized.optimize();
+        assert_eq!(cat.repr(), CompositionRepr::Sparse);
+        assert_eq!(optimized.repr(), CompositionRepr::Dense);
+        for f in &mors {
+            assert_eq!(cat.dom_ref(f), &cat.dom(f));
+            assert_eq!(cat.cod_ref(f), &cat.cod(f));
+            for g in mors.iter().filter(|g| cat.cod_ref(f) == cat.dom_ref(g)) {
+                assert_eq!(cat.compose2_ref(f, g), &cat.compose2(f.clone(), g.clone()));
+                assert_eq!(optimized.compose2_ref(f, g), cat.compose2_ref(f, g));
+            }
+        }
+    }
+
+    #[test]
+    fn audit_composites() {
+        let mut cat: FinCategory<char, char> = FinCategoryBuilder::new()
+            .ob('x')
+            .ob('y')
+            .hom('f', 'x', 'y')
+            .hom('g', 'y', 'y')
+            .composite('f', 'g', 'f')
+            .composite('g', 'g', 'g')
+            .build()
+            .unwrap();
+        assert_eq!(cat.composites().count(), 2);
+        assert!(cat.audit_composites().is_empty());
+
+        // Corrupt the composition table directly.
+        cat.compose_map.set(('h', 'g'), 'f'.into());
+        cat.compose_map.set(('f', 'g'), 'k'.into());
+        cat.compose_map.set(('g', 'f'), 'f'.into());
+        let mut issues = cat.audit_composites();
+        issues.sort_by_key(|issue| format!("{issue:?}"));
+        assert_eq!(
+            issues,
+            vec![
+                CompositeAuditIssue::DanglingKey('h', 'g'),
+                CompositeAuditIssue::DanglingValue('f', 'g'),
+                CompositeAuditIssue::EndpointMismatch('g', 'f'),
+            ]
+        );
+
+        let errs = FinCategoryBuilder::<char, char>::new()
+            .ob('x')
+            .ob('y')
+            .hom('f', 'x', 'y')
+            .composite('f', 'f', 'f')
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            errs.head,
+            FinCategoryBuildError::Audit(CompositeAuditIssue::EndpointMismatch('f', 'f'))
+        ));
+    }
+
+    #[test]
+    fn canonical_names() {
+        let sch_sgraph: FinCategory<char, char> = FinCategoryBuilder::new()
+            .ob('V')
+            .ob('E')
+            .hom('s', 'E', 'V')
+            .hom('t', 'E', 'V')
+            .hom('i', 'E', 'E')
+            .composite_id('i', 'i', 'E')
+            .composite('i', 's', 't')
+            .composite('i', 't', 's')
+            .build()
+            .unwrap();
+        let t = sch_sgraph.canonical_name(&FinMor::Generator('t'));
+        let path = Path::Seq(nonempty!['i', 'i', 'i', 's']);
+        assert_eq!(sch_sgraph.canonical_name_of_path(path), Ok(t.clone()));
+        assert_eq!(sch_sgraph.morphism_named(&t), Some(FinMor::Generator('t')));
+        let path = Path::Seq(nonempty!['i', 'i']);
+        let id = sch_sgraph.canonical_name_of_path(path.clone()).unwrap();
+        assert_eq!(id, DerivedMorId(Path::Id('E')));
+        assert_eq!(sch_sgraph.morphism_named(&DerivedMorId(path)), None);
+        assert!(sch_sgraph.canonical_name_of_path(Path::pair('s', 'i')).is_err());
+
+        let names: Vec<_> = sch_sgraph.named_morphisms().collect();
+        assert_eq!(names.len(), 5);
+        assert!(names.iter().all(|(id, f)| sch_sgraph.morphism_named(id).as_ref() == Some(f)));
+    }
+
+    #[test]
+    fn hom_size_matrix() {
+        let sch_graph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V')],
+            composites: [],
+        }
+        .unwrap();
+        let (obs, matrix) = sch_graph.hom_size_matrix();
+        let at = |x, y| {
+            let i = obs.iter().position(|v| *v == x).unwrap();
+            let j = obs.iter().position(|v| *v == y).unwrap();
+            matrix[i][j]
+        };
+        assert_eq!((at('E', 'V'), at('V', 'E'), at('E', 'E'), at('V', 'V')), (2, 0, 1, 1));
+
+        // The reflexive graph schema, with `a = s;r` and `b = t;r`.
+        let sch_rgraph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: [
+                's' => ('E', 'V'), 't' => ('E', 'V'), 'r' => ('V', 'E'),
+                'a' => ('E', 'E'), 'b' => ('E', 'E'),
+            ],
+            composites: [
+                ('r', 's') => id('V'), ('r', 't') => id('V'),
+                ('s', 'r') => 'a', ('t', 'r') => 'b',
+                ('a', 'a') => 'a', ('a', 'b') => 'a', ('b', 'a') => 'b', ('b', 'b') => 'b',
+                ('a', 's') => 's', ('a', 't') => 's', ('b', 's') => 't', ('b', 't') => 't',
+                ('r', 'a') => 'r', ('r', 'b') => 'r',
+            ],
+        }
+        .unwrap();
+        let sorted_entries = |cat: &FinCategory<char, char>| {
+            let mut entries: Vec<_> = cat.hom_size_matrix().1.concat();
+            entries.sort();
+            entries
+        };
+        assert_eq!(sorted_entries(&sch_graph), vec![0, 1, 1, 2]);
+        assert_eq!(sorted_entries(&sch_rgraph), vec![1, 1, 2, 3]);
+        assert!(!sch_graph.is_isomorphic_to(&sch_rgraph));
+    }
+
+    #[test]
+    fn is_free() {
+        let sch_graph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V')],
+        }
+        .unwrap();
+        assert!(sch_graph.is_free());
+
+        // The reflexive graph schema, with `a = s;r` and `b = t;r`.
+        let sch_rgraph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: [
+                's' => ('E', 'V'), 't' => ('E', 'V'), 'r' => ('V', 'E'),
+                'a' => ('E', 'E'), 'b' => ('E', 'E'),
+            ],
+            composites: [
+                ('r', 's') => id('V'), ('r', 't') => id('V'), ('r', 'a') => 'r', ('r', 'b') => 'r',
+                ('s', 'r') => 'a', ('t', 'r') => 'b',
+                ('a', 's') => 's', ('a', 't') => 's', ('b', 's') => 't', ('b', 't') => 't',
+                ('a', 'a') => 'a', ('a', 'b') => 'a', ('b', 'a') => 'b', ('b', 'b') => 'b',
+            ],
+        }
+        .unwrap();
+        assert!(!sch_rgraph.is_free());
+    }
+
+    #[test]
+    fn nonassociative_composition() {
+        let mut cat: FinCategory<char, char> = Default::default();
+        cat.add_ob_generator('x');
+        cat.add_mor_generator('a', 'x', 'x');
+        cat.add_mor_generator('b', 'x', 'x');
+        cat.set_composite('a', 'a', 'a'.into());
+        cat.set_composite('a', 'b', 'b'.into());
+        cat.set_composite('b', 'a', 'a'.into());
+        cat.set_composite('b', 'b', 'a'.into());
+        let errs: Vec<_> = cat.iter_invalid().collect();
+        assert!(errs.iter().any(|err| matches!(err,
+            InvalidFinCategory::Associativity { f: 'b', g: 'b', h: 'b', left, right }
+            if *left == 'b'.into() && *right == 'a'.into()
+        )));
+        assert!(errs.iter().all(|err| matches!(err, InvalidFinCategory::Associativity { .. })));
+
+        let errs = cat.check_congruence().unwrap_err();
+        assert!(errs.contains(&CongruenceError::Pre('b', 'b', 'b')));
+        assert!(errs.contains(&CongruenceError::Post('b', 'b', 'b')));
+        cat.set_composite('b', 'b', 'b'.into());
+        cat.set_composite('b', 'a', 'b'.into());
+        assert!(cat.check_congruence().is_ok());
+        assert!(cat.validate().is_ok());
+    }
+
+    #[test]
+    fn fin_category_builder_errors() {
+        let errs = FinCategoryBuilder::<char, char>::new()
+            .ob('x')
+            .ob('x')
+            .hom('f', 'x', 'y')
+            .hom('f', 'x', 'x')
+            .composite('f', 'g', 'f')
+            .build()
+            .unwrap_err();
+        let msgs: Vec<_> = errs.iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            msgs,
+            vec![
+                "Declaration 1: object `x` is already declared",
+                "Declaration 2: object `y` is not declared",
+                "Declaration 4: morphism `g` is not declared",
+            ]
+        );
+    }
+
+    #[test]
+    fn power() {
+        // The builder rejects incomplete composition, so build this one by hand.
+        let mut sgn: FinCategory<char, char> = Default::default();
+        sgn.add_ob_generator('*');
+        sgn.add_mor_generator('n', '*', '*');
+        let n = FinMor::Generator('n');
+        assert_eq!(sgn.power(&n, 0), Ok(FinMor::Id('*')));
+        assert_eq!(sgn.power(&n, 1), Ok(n.clone()));
+        assert_eq!(sgn.power(&n, 2), Err(ComposeError::Undefined));
+
+        let th = th_signed_category();
+        let (sgn, negative) = (th.category(), FinMor::Generator(ustr("Negative")));
+        assert_eq!(sgn.power(&negative, 2), Ok(FinMor::Id(ustr("Object"))));
+        assert_eq!(sgn.power(&negative, 3), Ok(negative));
+
+        let cat: FinCategory<char, char> = category! {
+            obs: ['x', 'y'],
+            homs: ['f' => ('x', 'y')],
+        }
+        .unwrap();
+        assert_eq!(cat.power(&'f'.into(), 2), Err(ComposeError::Incompatible('y', 'x')));
+
+        // Morphisms not in the category are errors, not panics.
+        let (g, z) = (FinMor::Generator('g'), FinMor::Id('z'));
+        assert_eq!(cat.power(&g, 2), Err(ComposeError::NotInCategory));
+        assert_eq!(cat.power(&z, 0), Err(ComposeError::NotInCategory));
+        assert_eq!(cat.try_compose2('f'.into(), g.clone()), Err(ComposeError::NotInCategory));
+        assert_eq!(cat.try_compose2(g, FinMor::Id('x')), Err(ComposeError::NotInCategory));
+    }
+
+    #[test]
+    fn generated_subcategory() {
+        let sch_sgraph: FinCategory<char, char> = category! {
+            obs: ['V', 'E'],
+            homs: ['s' => ('E', 'V'), 't' => ('E', 'V'), 'i' => ('E', 'E')],
+            composites: [('i', 'i') => id('E'), ('i', 's') => 't', ('i', 't') => 's'],
+        }
+        .unwrap();
+
+        let (sub, _) = sch_sgraph.generated_subcategory(&['i']);
+        assert_eq!(sub.object_generators().collect::<Vec<_>>(), vec!['E']);
+        assert_eq!(sub.morphism_generators().collect::<Vec<_>>(), vec!['i']);
+        assert!(sub.validate().is_ok());
+
+        let (sub, incl) = sch_sgraph.generated_subcategory(&['i', 's']);
+        assert_eq!(sub.object_generators().count(), 2);
+        assert_eq!(sub.morphism_generators().count(), 3);
+        assert!(sub.validate().is_ok());
+        assert_eq!(sub.compose2(FinMor::Generator('i'), FinMor::Generator('t')), 's'.into());
+        let morphism = GraphMorphism(&incl, sub.generators(), sch_sgraph.generators());
+        assert!(morphism.validate().is_ok());
     }
 
     #[test]
@@ -512,6 +2339,12 @@ mod tests {
         assert_eq!(sch_sgraph.equations().count(), 3);
         assert!(sch_sgraph.validate().is_ok());
 
+        // Removing a generator removes the equations mentioning it.
+        assert!(sch_sgraph.remove_mor_generator(&'t'));
+        assert_eq!(sch_sgraph.equations().count(), 1);
+        assert!(sch_sgraph.get_equation(&"inv").is_some());
+        assert!(sch_sgraph.validate().is_ok());
+
         let mut sch_bad: FpCategory<_, _, _> = Default::default();
         sch_bad.add_ob_generators(['x', 'y']);
         sch_bad.make_mor_generator('f');
@@ -6,6 +6,7 @@ flexible set of traits and structs for graphs as they are used in category
 theory.
  */
 
+use std::collections::HashMap;
 use std::hash::{BuildHasher, BuildHasherDefault, Hash, RandomState};
 
 use derivative::Derivative;
@@ -14,7 +15,8 @@ use ref_cast::RefCast;
 use thiserror::Error;
 use ustr::{IdentityHasher, Ustr};
 
-use crate::validate::{self, Validate};
+use super::graph_algorithms::{self, Cycle};
+use crate::validate::{self, BatchError, Validate};
 use crate::zero::*;
 
 /** A graph.
@@ -358,15 +360,16 @@ impl Validate for SkelGraph {
 /** A finite graph with indexed source and target maps, based on hash maps.
 
 Unlike in a skeletal finite graph, the vertices and edges can have arbitrary
-hashable types.
+hashable types. The vertices and edges are iterated in the order in which they
+were added, so that iteration is deterministic despite the use of hash maps.
 */
 #[derive(Clone, Derivative, Debug)]
 #[derivative(Default(bound = "S: Default"))]
 #[derivative(PartialEq(bound = "V: Eq + Hash, E: Eq + Hash, S: BuildHasher"))]
 #[derivative(Eq(bound = "V: Eq + Hash, E: Eq + Hash, S: BuildHasher"))]
 pub struct HashGraph<V, E, S = RandomState> {
-    vertex_set: HashFinSet<V, S>,
-    edge_set: HashFinSet<E, S>,
+    vertex_set: OrderedHashFinSet<V, S>,
+    edge_set: OrderedHashFinSet<E, S>,
     src_map: IndexedHashColumn<E, V, S>,
     tgt_map: IndexedHashColumn<E, V, S>,
 }
@@ -438,6 +441,15 @@ where
         self.vertex_set.extend(iter)
     }
 
+    /** Removes a vertex from the graph, returning whether it was present.
+
+    Edges with the vertex as their source or target are left alone, so the
+    graph is not valid until they are updated or removed.
+     */
+    pub fn remove_vertex(&mut self, v: &V) -> bool {
+        self.vertex_set.remove(v)
+    }
+
     /** Adds an edge to the graph, returning whether the edge is new.
 
     If the edge is not new, its source and target are updated.
@@ -452,6 +464,85 @@ where
     pub fn make_edge(&mut self, e: E) -> bool {
         self.edge_set.insert(e)
     }
+
+    /// Removes an edge from the graph, returning whether it was present.
+    pub fn remove_edge(&mut self, e: &E) -> bool {
+        self.src_map.unset(e);
+        self.tgt_map.unset(e);
+        self.edge_set.remove(e)
+    }
+
+    /** Iterates over the edges together with their sources and targets.
+
+    The edges are iterated in the order in which they were added. Edges whose
+    source or target is not set are skipped.
+     */
+    pub fn edge_triples(&self) -> impl Iterator<Item = (E, V, V)> + '_ {
+        self.edge_set.iter().filter_map(|e| {
+            let src = self.src_map.apply(&e)?.clone();
+            let tgt = self.tgt_map.apply(&e)?.clone();
+            Some((e, src, tgt))
+        })
+    }
+
+    /** Adds many edges to the graph at once.
+
+    Edges in the batch whose source or target is not a vertex of the graph are
+    reported by their position in the batch, in which case the graph is left
+    unchanged. Otherwise, this is equivalent to calling
+    [`add_edge`](Self::add_edge) on each edge, except that capacity is reserved
+    up front.
+     */
+    pub fn add_edges<T>(&mut self, iter: T) -> Result<(), NonEmpty<BatchError<InvalidGraphData<E>>>>
+    where
+        T: IntoIterator<Item = (E, V, V)>,
+    {
+        let edges: Vec<_> = iter.into_iter().collect();
+        let errors = edges.iter().enumerate().flat_map(|(index, (e, src, tgt))| {
+            let src_error = (!self.has_vertex(src)).then(|| InvalidGraphData::Src(e.clone()));
+            let tgt_error = (!self.has_vertex(tgt)).then(|| InvalidGraphData::Tgt(e.clone()));
+            src_error
+                .into_iter()
+                .chain(tgt_error)
+                .map(move |error| BatchError { index, error })
+        });
+        validate::wrap_errors(errors)?;
+
+        self.reserve_edges(edges.len());
+        for (e, src, tgt) in edges {
+            self.add_edge(e, src, tgt);
+        }
+        Ok(())
+    }
+
+    /// Reserves capacity for at least `additional` more vertices.
+    pub fn reserve_vertices(&mut self, additional: usize) {
+        self.vertex_set.reserve(additional);
+    }
+
+    /// Reserves capacity for at least `additional` more edges.
+    pub fn reserve_edges(&mut self, additional: usize) {
+        self.edge_set.reserve(additional);
+        self.src_map.reserve(additional);
+        self.tgt_map.reserve(additional);
+    }
+
+    /** Assigns the vertices to layers by the longest path ending at them.
+
+    Requires the graph to be acyclic. See
+    [`graph_algorithms::longest_path_layers`] for details.
+     */
+    pub fn longest_path_layers(&self) -> Result<HashMap<V, usize>, Cycle<V>> {
+        graph_algorithms::longest_path_layers(self)
+    }
+
+    /** Length of the shortest directed cycle, or `None` if the graph is acyclic.
+
+    See [`graph_algorithms::girth`] for details.
+     */
+    pub fn girth(&self) -> Option<usize> {
+        graph_algorithms::girth(self)
+    }
 }
 
 impl<V, E, S> Validate for HashGraph<V, E, S>
@@ -678,6 +769,42 @@ mod tests {
         assert_eq!(g.tgt(&"fg"), 'z');
     }
 
+    #[test]
+    fn edge_triples() {
+        // Generating graph of the schema for symmetric graphs.
+        let mut g: HashGraph<char, char> = Default::default();
+        g.add_vertices(['V', 'E']);
+        g.add_edge('s', 'E', 'V');
+        g.add_edge('t', 'E', 'V');
+        g.add_edge('i', 'E', 'E');
+        assert!(g.make_edge('x')); // No source or target, so skipped.
+        let triples: Vec<_> = g.edge_triples().collect();
+        assert_eq!(triples, vec![('s', 'E', 'V'), ('t', 'E', 'V'), ('i', 'E', 'E')]);
+    }
+
+    #[test]
+    fn bulk_add_edges() {
+        let mut g: HashGraph<char, &str> = Default::default();
+        g.add_vertices(['x', 'y', 'z']);
+        let edges = [("f", 'x', 'y'), ("g", 'y', 'z'), ("h", 'x', 'z')];
+        assert!(g.add_edges(edges).is_ok());
+
+        let mut h: HashGraph<char, &str> = Default::default();
+        h.add_vertices(['x', 'y', 'z']);
+        for (e, src, tgt) in edges {
+            h.add_edge(e, src, tgt);
+        }
+        assert_eq!(g, h);
+        assert_eq!(g.edges().collect::<Vec<_>>(), vec!["f", "g", "h"]);
+
+        // Invalid edges are reported by position in the batch.
+        let errs = g.add_edges([("k", 'z', 'x'), ("l", 'x', 'w')]).unwrap_err();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs.head.index, 1);
+        assert!(matches!(errs.head.error, InvalidGraphData::Tgt("l")));
+        assert!(!g.has_edge(&"k"));
+    }
+
     #[test]
     fn validate_columnar_graph() {
         let mut g = SkelGraph::triangle();
@@ -1,10 +1,15 @@
 //! Algorithms on graphs.
 
-use std::collections::{HashSet, VecDeque};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 
+use thiserror::Error;
+
+use super::components;
 use super::graph::*;
 use super::path::*;
+use crate::zero::HashFinSet;
 
 /** Iterates over all simple paths between two vertices of a finite graph.
 
@@ -71,6 +76,114 @@ where
     maybe_empty_path.into_iter().chain(nonempty_paths)
 }
 
+/** Finds a path of least total weight between two vertices of a finite graph.
+
+This function implements [Dijkstra's
+algorithm](https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm), where the
+weight of each edge is given by a user-supplied function. Since the algorithm is
+only correct for nonnegative weights, the weights of all edges are checked
+before the search begins.
+
+Among paths of least weight, a path with the fewest edges is preferred, and any
+remaining ties are broken by the ordering on edges, so that the result does not
+depend on the order in which the graph enumerates its edges. If there is no path
+between the vertices, `None` is returned.
+ */
+#[allow(clippy::type_complexity)]
+pub fn shortest_path<G, F>(
+    graph: &G,
+    from: &G::V,
+    to: &G::V,
+    weight: F,
+) -> Result<Option<Path<G::V, G::E>>, InvalidWeight<G::E>>
+where
+    G: FinGraph,
+    G::V: Ord + Clone + Hash,
+    G::E: Ord + Clone + Hash,
+    F: Fn(&G::E) -> f64,
+{
+    let mut edges: Vec<_> = graph.edges().collect();
+    edges.sort();
+    let mut weights = HashMap::new();
+    for e in edges {
+        let w = weight(&e);
+        if w.is_nan() || w < 0.0 {
+            return Err(InvalidWeight(e));
+        }
+        weights.insert(e, w);
+    }
+
+    // Least known cost and number of edges for reaching each vertex.
+    let mut costs: HashMap<G::V, (Cost, usize)> = HashMap::new();
+    // Last edge in the best known path to each vertex.
+    let mut preds: HashMap<G::V, G::E> = HashMap::new();
+    let mut visited: HashSet<G::V> = HashSet::new();
+    let mut queue = BinaryHeap::new();
+    costs.insert(from.clone(), (Cost(0.0), 0));
+    queue.push(Reverse((Cost(0.0), 0, from.clone())));
+
+    while let Some(Reverse((cost, len, v))) = queue.pop() {
+        if v == *to {
+            break;
+        }
+        if !visited.insert(v.clone()) {
+            continue;
+        }
+        for e in graph.out_edges(&v) {
+            let w = graph.tgt(&e);
+            if visited.contains(&w) {
+                continue;
+            }
+            let new = (Cost(cost.0 + weights[&e]), len + 1);
+            let better = match costs.get(&w) {
+                None => true,
+                Some(old) => new < *old || (new == *old && preds.get(&w).is_some_and(|f| e < *f)),
+            };
+            if better {
+                costs.insert(w.clone(), new);
+                preds.insert(w.clone(), e);
+                queue.push(Reverse((new.0, new.1, w)));
+            }
+        }
+    }
+
+    if !costs.contains_key(to) {
+        return Ok(None);
+    }
+    let mut path = Vec::new();
+    let mut v = to.clone();
+    while v != *from {
+        let e = preds[&v].clone();
+        v = graph.src(&e);
+        path.push(e);
+    }
+    path.reverse();
+    Ok(Some(Path::from_vec(path).unwrap_or_else(|| Path::Id(from.clone()))))
+}
+
+/// An edge weight that is invalid for a shortest path search.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Weight of edge `{0}` is negative or not a number")]
+pub struct InvalidWeight<E>(pub E);
+
+/// Nonnegative path cost, totally ordered so that it can be used as a priority.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cost(f64);
+
+impl Eq for Cost {}
+
+impl PartialOrd for Cost {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cost {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
 /** Arrange all the elements of a finite graph in specialization order.
 
 The [specialization
@@ -145,12 +258,183 @@ where
     result
 }
 
+/** Computes the weakly connected components of a finite graph.
+
+Two vertices are in the same component if they are connected by a zig-zag of
+edges. The components are returned in order of their first vertex, as it appears
+in the list of vertices followed by the list of edges.
+ */
+pub fn weakly_connected_components<G>(graph: &G) -> Vec<HashFinSet<G::V>>
+where
+    G: FinGraph,
+    G::V: Clone + Hash,
+{
+    let edges = graph.edges().map(|e| (graph.src(&e), graph.tgt(&e)));
+    components::weakly_connected(graph.vertices(), edges)
+}
+
+/** Sorts the vertices of a finite graph topologically.
+
+In a topological ordering, the source of every edge comes before its target.
+Such an ordering exists if and only if the graph is acyclic; otherwise, a cycle
+in the graph is returned. The ordering is computed by Kahn's algorithm, taking
+vertices in the order in which the graph iterates over them when there is a
+choice, so that the result is deterministic.
+ */
+pub fn toposort<G>(graph: &G) -> Result<Vec<G::V>, Cycle<G::V>>
+where
+    G: FinGraph,
+    G::V: Clone + Hash,
+{
+    let mut in_degrees: HashMap<G::V, usize> =
+        graph.vertices().map(|v| (v.clone(), graph.in_degree(&v))).collect();
+    let mut queue: VecDeque<_> = graph.vertices().filter(|v| in_degrees[v] == 0).collect();
+    let mut sorted = Vec::with_capacity(in_degrees.len());
+    while let Some(v) = queue.pop_front() {
+        for e in graph.out_edges(&v) {
+            let w = graph.tgt(&e);
+            let degree = in_degrees.get_mut(&w).expect("Target should be a vertex");
+            *degree -= 1;
+            if *degree == 0 {
+                queue.push_back(w);
+            }
+        }
+        sorted.push(v);
+    }
+    if sorted.len() == in_degrees.len() {
+        return Ok(sorted);
+    }
+
+    // Every unsorted vertex has an in-edge from another unsorted vertex, so
+    // walking backwards along such edges must eventually revisit a vertex.
+    let mut v = graph
+        .vertices()
+        .find(|v| in_degrees[v] > 0)
+        .expect("Some vertex should be unsorted");
+    let mut walk = Vec::new();
+    let mut visited: HashMap<G::V, usize> = HashMap::new();
+    while !visited.contains_key(&v) {
+        visited.insert(v.clone(), walk.len());
+        walk.push(v.clone());
+        let pred = graph.in_edges(&v).map(|e| graph.src(&e)).find(|u| in_degrees[u] > 0);
+        v = pred.expect("Unsorted vertex should have an unsorted predecessor");
+    }
+    let mut cycle = walk.split_off(visited[&v]);
+    cycle[1..].reverse();
+    Err(Cycle(cycle))
+}
+
+/** Assigns vertices of a finite acyclic graph to layers by longest paths.
+
+The layer of a vertex is the length of the longest path ending at it, so that
+sources are in layer zero and the target of every edge is in a higher layer than
+its source. Such a layering is a common first step in drawing a graph in layers,
+as in the Sugiyama method. It is an error if the graph has a cycle.
+ */
+pub fn longest_path_layers<G>(graph: &G) -> Result<HashMap<G::V, usize>, Cycle<G::V>>
+where
+    G: FinGraph,
+    G::V: Clone + Hash,
+{
+    let mut layers = HashMap::new();
+    for v in toposort(graph)? {
+        let layer = graph.in_edges(&v).map(|e| layers[&graph.src(&e)] + 1).max().unwrap_or(0);
+        layers.insert(v, layer);
+    }
+    Ok(layers)
+}
+
+/** Computes the girth of a finite graph, the length of its shortest cycle.
+
+Cycles are directed and a self-loop is a cycle of length one. Returns `None`
+when the graph is acyclic. The girth is found by breadth-first search from each
+vertex, taking quadratic time in the size of the graph.
+ */
+pub fn girth<G>(graph: &G) -> Option<usize>
+where
+    G: FinGraph,
+    G::V: Clone + Hash,
+{
+    let mut girth = None;
+    for s in graph.vertices() {
+        let mut dists: HashMap<G::V, usize> = HashMap::from([(s.clone(), 0)]);
+        let mut queue = VecDeque::from([s.clone()]);
+        while let Some(v) = queue.pop_front() {
+            let d = dists[&v];
+            if girth.is_some_and(|g| d + 1 >= g) {
+                break;
+            }
+            for e in graph.out_edges(&v) {
+                let w = graph.tgt(&e);
+                if w == s {
+                    girth = Some(d + 1);
+                    break;
+                }
+                if !dists.contains_key(&w) {
+                    dists.insert(w.clone(), d + 1);
+                    queue.push_back(w);
+                }
+            }
+        }
+    }
+    girth
+}
+
+/// A cycle in a graph, given by its vertices in order.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Graph has a cycle of length {}", .0.len())]
+pub struct Cycle<V>(pub Vec<V>);
+
 #[cfg(test)]
 mod tests {
     use super::GraphElem::*;
     use super::*;
+    use crate::zero::FinSet;
     use nonempty::nonempty;
 
+    #[test]
+    fn topological_sort() {
+        let g = SkelGraph::triangle();
+        assert_eq!(toposort(&g), Ok(vec![0, 1, 2]));
+        let mut g = SkelGraph::path(4);
+        assert_eq!(toposort(&g), Ok(vec![0, 1, 2, 3]));
+        g.add_edge(2, 1);
+        assert_eq!(toposort(&g), Err(Cycle(vec![1, 2])));
+        assert_eq!(toposort(&SkelGraph::cycle(3)), Err(Cycle(vec![0, 1, 2])));
+    }
+
+    #[test]
+    fn girth_of_graphs() {
+        let mut g: HashGraph<char, &str> = Default::default();
+        g.add_vertices(['x', 'y', 'z']);
+        g.add_edge("xy", 'x', 'y');
+        g.add_edge("yz", 'y', 'z');
+        g.add_edge("xz", 'x', 'z');
+        assert_eq!(g.girth(), None);
+        g.add_edge("zx", 'z', 'x');
+        assert_eq!(g.girth(), Some(2));
+        g.add_edge("yy", 'y', 'y');
+        assert_eq!(g.girth(), Some(1));
+        assert_eq!(girth(&SkelGraph::cycle(5)), Some(5));
+    }
+
+    #[test]
+    fn diamond_layers() {
+        let mut g: HashGraph<char, &str> = Default::default();
+        g.add_vertices(['s', 'a', 'b', 't']);
+        g.add_edge("sa", 's', 'a');
+        g.add_edge("sb", 's', 'b');
+        g.add_edge("at", 'a', 't');
+        g.add_edge("bt", 'b', 't');
+        let layers = g.longest_path_layers().unwrap();
+        assert_eq!(layers[&'s'], 0);
+        assert_eq!((layers[&'a'], layers[&'b']), (1, 1));
+        assert_eq!(layers[&'t'], 2);
+
+        g.add_edge("ts", 't', 's');
+        assert_eq!(g.longest_path_layers().map_err(|cycle| cycle.0.len()), Err(3));
+    }
+
     #[test]
     fn find_simple_paths() {
         let mut g = SkelGraph::triangle();
@@ -170,6 +454,32 @@ mod tests {
         assert_eq!(paths, vec![Path::Seq(nonempty![0, 1])]);
     }
 
+    #[test]
+    fn find_shortest_path() {
+        let g = SkelGraph::triangle();
+        let path = shortest_path(&g, &0, &2, |_| 1.0);
+        assert_eq!(path, Ok(Some(Path::single(2))));
+        let path = shortest_path(&g, &0, &2, |&e| if e == 2 { 3.0 } else { 1.0 });
+        assert_eq!(path, Ok(Some(Path::pair(0, 1))));
+        assert_eq!(shortest_path(&g, &1, &1, |_| 1.0), Ok(Some(Path::Id(1))));
+        assert_eq!(shortest_path(&g, &2, &0, |_| 1.0), Ok(None));
+        assert_eq!(shortest_path(&g, &0, &1, |&e| e as f64 - 1.0), Err(InvalidWeight(0)));
+
+        // Ties are broken first by number of edges, then by the edges themselves.
+        let path = shortest_path(&g, &0, &2, |&e| if e == 2 { 2.0 } else { 1.0 });
+        assert_eq!(path, Ok(Some(Path::single(2))));
+        let mut g = SkelGraph::default();
+        g.add_vertices(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 3);
+        g.add_edge(0, 2);
+        g.add_edge(2, 3);
+        let path = shortest_path(&g, &0, &3, |_| 1.0);
+        assert_eq!(path, Ok(Some(Path::pair(0, 1))));
+        let path = shortest_path(&g, &0, &3, |&e| if e == 0 { 1.0 } else { 0.5 });
+        assert_eq!(path, Ok(Some(Path::pair(2, 3))));
+    }
+
     #[test]
     fn spec_ordering() {
         let g = SkelGraph::path(3);
@@ -190,4 +500,15 @@ mod tests {
         let g = SkelGraph::cycle(1);
         assert_eq!(spec_order_all(&g), vec![Vertex(0), Edge(0)]);
     }
+
+    #[test]
+    fn weakly_connected() {
+        let mut g = SkelGraph::path(3);
+        assert_eq!(weakly_connected_components(&g).len(), 1);
+        g.add_vertices(2);
+        g.add_edge(4, 3);
+        let components = weakly_connected_components(&g);
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[1].len(), 2);
+    }
 }
@@ -0,0 +1,271 @@
+/*! Finite categories with interned string names.
+
+A [`FinCategory`] with `String` objects and morphisms clones its strings
+whenever it stores or returns a generator, which is wasteful when the names are
+long or the category is consulted often. [`UstrFinCategory`] avoids this by
+interning the names globally, but global interning is not always wanted: the
+interned strings live for the rest of the program.
+
+A [`StringInternedCategory`] instead interns the names of its generators into
+an arena owned by the category, numbering them consecutively from zero as they
+are inserted. The category itself is stored over these numbers, while the
+public API is keyed by string slices borrowed from the arena. Only objects and
+morphisms are added under new names: declaring a morphism or a composite looks
+up the names it refers to and fails if they have not been added.
+
+[`UstrFinCategory`]: super::fin_category::UstrFinCategory
+ */
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use super::category::*;
+use super::fin_category::{FinCategory, FinMor};
+use super::graph::{FinGraph, Graph};
+
+/// Arena of names, numbered consecutively from zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Interner {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = u32::try_from(self.names.len()).expect("Too many names to intern");
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    fn get(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+}
+
+/// A name that does not refer to a generator of an interned category.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum UnknownName {
+    /// Name is not that of an object generator.
+    #[error("Object `{0}` is not in the category")]
+    Ob(String),
+
+    /// Name is not that of a morphism generator.
+    #[error("Morphism `{0}` is not in the category")]
+    Mor(String),
+}
+
+/** A finite category with string names, interned into small integers.
+
+Objects and morphism generators are named by strings but stored as `u32`
+identifiers, which can be translated back and forth with
+[`resolve`](Self::resolve) and [`name`](Self::name). The underlying category
+over the identifiers is available through [`category`](Self::category).
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StringInternedCategory {
+    category: FinCategory<u32, u32>,
+    obs: Interner,
+    mors: Interner,
+}
+
+impl StringInternedCategory {
+    /// Underlying category over interned identifiers.
+    pub fn category(&self) -> &FinCategory<u32, u32> {
+        &self.category
+    }
+
+    /// Adds an object generator, returning whether it is new.
+    pub fn add_ob_generator(&mut self, name: &str) -> bool {
+        let v = self.obs.intern(name);
+        self.category.add_ob_generator(v)
+    }
+
+    /** Adds a morphism generator, returning whether it is new.
+
+    The domain and codomain must already be object generators.
+     */
+    pub fn add_mor_generator(
+        &mut self,
+        name: &str,
+        dom: &str,
+        cod: &str,
+    ) -> Result<bool, UnknownName> {
+        let (dom, cod) = (self.lookup_ob(dom)?, self.lookup_ob(cod)?);
+        let e = self.mors.intern(name);
+        Ok(self.category.add_mor_generator(e, dom, cod))
+    }
+
+    /** Sets the composite of two morphism generators.
+
+    The morphisms and the composite must already be in the category.
+     */
+    pub fn set_composite(
+        &mut self,
+        d: &str,
+        e: &str,
+        f: FinMor<&str, &str>,
+    ) -> Result<(), UnknownName> {
+        let (d, e) = (self.lookup_mor(d)?, self.lookup_mor(e)?);
+        let f = match f {
+            FinMor::Id(x) => FinMor::Id(self.lookup_ob(x)?),
+            FinMor::Generator(g) => FinMor::Generator(self.lookup_mor(g)?),
+        };
+        self.category.set_composite(d, e, f);
+        Ok(())
+    }
+
+    fn lookup_ob(&self, x: &str) -> Result<u32, UnknownName> {
+        self.obs
+            .get(x)
+            .filter(|v| self.category.has_ob(v))
+            .ok_or_else(|| UnknownName::Ob(x.to_string()))
+    }
+
+    fn lookup_mor(&self, e: &str) -> Result<u32, UnknownName> {
+        self.mors
+            .get(e)
+            .filter(|e| self.category.generators().has_edge(e))
+            .ok_or_else(|| UnknownName::Mor(e.to_string()))
+    }
+
+    /// Looks up the identifier of a morphism by name, if it has been interned.
+    pub fn resolve(&self, f: FinMor<&str, &str>) -> Option<FinMor<u32, u32>> {
+        Some(match f {
+            FinMor::Id(x) => FinMor::Id(self.obs.get(x)?),
+            FinMor::Generator(e) => FinMor::Generator(self.mors.get(e)?),
+        })
+    }
+
+    /// Name of a morphism given by its identifier.
+    pub fn name(&self, f: &FinMor<u32, u32>) -> FinMor<&str, &str> {
+        match f {
+            FinMor::Id(x) => FinMor::Id(self.obs.name(*x)),
+            FinMor::Generator(e) => FinMor::Generator(self.mors.name(*e)),
+        }
+    }
+
+    /// Does the category contain the object?
+    pub fn has_ob(&self, x: &str) -> bool {
+        self.obs.get(x).is_some_and(|v| self.category.has_ob(&v))
+    }
+
+    /// Does the category contain the morphism?
+    pub fn has_mor(&self, f: FinMor<&str, &str>) -> bool {
+        self.resolve(f).is_some_and(|f| self.category.has_mor(&f))
+    }
+
+    /// Gets the domain of a morphism in the category, if it is one.
+    pub fn dom(&self, f: FinMor<&str, &str>) -> Option<&str> {
+        let f = self.resolve(f).filter(|f| self.category.has_mor(f))?;
+        Some(self.obs.name(self.category.dom(&f)))
+    }
+
+    /// Gets the codomain of a morphism in the category, if it is one.
+    pub fn cod(&self, f: FinMor<&str, &str>) -> Option<&str> {
+        let f = self.resolve(f).filter(|f| self.category.has_mor(f))?;
+        Some(self.obs.name(self.category.cod(&f)))
+    }
+
+    /** Composes a pair of morphisms.
+
+    Returns `None` if either morphism is not in the category, the morphisms are
+    not composable, or their composite is not defined.
+     */
+    pub fn compose2(
+        &self,
+        f: FinMor<&str, &str>,
+        g: FinMor<&str, &str>,
+    ) -> Option<FinMor<&str, &str>> {
+        let f = self.resolve(f).filter(|f| self.category.has_mor(f))?;
+        let g = self.resolve(g).filter(|g| self.category.has_mor(g))?;
+        let h = self.category.try_compose2(f, g).ok()?;
+        Some(self.name(&h))
+    }
+
+    /// Iterates over the names of the object generators.
+    pub fn ob_generators(&self) -> impl Iterator<Item = &str> {
+        self.category.generators().vertices().map(|v| self.obs.name(v))
+    }
+
+    /// Iterates over the names of the morphism generators.
+    pub fn mor_generators(&self) -> impl Iterator<Item = &str> {
+        self.category.generators().edges().map(|e| self.mors.name(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::Validate;
+
+    #[test]
+    fn string_named_schema() {
+        // A split idempotent on edges, collapsing each edge to the reflexive
+        // loop at its source, with long-winded names.
+        let mut sch = StringInternedCategory::default();
+        assert!(sch.add_ob_generator("Edge"));
+        assert!(sch.add_ob_generator("Vertex"));
+        assert!(!sch.add_ob_generator("Edge"));
+        assert_eq!(sch.add_mor_generator("source", "Edge", "Vertex"), Ok(true));
+        assert_eq!(sch.add_mor_generator("reflexive_loop", "Vertex", "Edge"), Ok(true));
+        assert_eq!(sch.add_mor_generator("collapse", "Edge", "Edge"), Ok(true));
+        let composites = [
+            ("reflexive_loop", "source", FinMor::Id("Vertex")),
+            ("source", "reflexive_loop", FinMor::Generator("collapse")),
+            ("collapse", "collapse", FinMor::Generator("collapse")),
+            ("collapse", "source", FinMor::Generator("source")),
+            ("reflexive_loop", "collapse", FinMor::Generator("reflexive_loop")),
+        ];
+        for (d, e, f) in composites {
+            assert!(sch.set_composite(d, e, f).is_ok());
+        }
+        assert!(sch.category().validate().is_ok());
+
+        // Names are looked up, not interned, when referred to.
+        assert_eq!(
+            sch.add_mor_generator("boundary", "Face", "Edge"),
+            Err(UnknownName::Ob("Face".into()))
+        );
+        assert_eq!(
+            sch.set_composite("source", "weight", FinMor::Id("Vertex")),
+            Err(UnknownName::Mor("weight".into()))
+        );
+        assert_eq!(
+            sch.set_composite("source", "reflexive_loop", FinMor::Id("Face")),
+            Err(UnknownName::Ob("Face".into()))
+        );
+        assert!(!sch.has_ob("Face") && !sch.has_mor(FinMor::Generator("boundary")));
+        assert_eq!(sch.mor_generators().count(), 3);
+
+        assert!(sch.has_ob("Vertex"));
+        assert!(!sch.has_ob("Face"));
+        assert!(sch.has_mor(FinMor::Generator("source")));
+        assert!(!sch.has_mor(FinMor::Generator("weight")));
+        assert_eq!(sch.dom(FinMor::Generator("reflexive_loop")), Some("Vertex"));
+        assert_eq!(sch.cod(FinMor::Id("Edge")), Some("Edge"));
+        assert_eq!(sch.cod(FinMor::Generator("weight")), None);
+        let mut obs: Vec<_> = sch.ob_generators().collect();
+        obs.sort();
+        assert_eq!(obs, vec!["Edge", "Vertex"]);
+        assert_eq!(sch.mor_generators().count(), 3);
+
+        let (src, refl) = (FinMor::Generator("source"), FinMor::Generator("reflexive_loop"));
+        assert_eq!(sch.compose2(refl.clone(), src.clone()), Some(FinMor::Id("Vertex")));
+        assert_eq!(sch.compose2(src.clone(), refl.clone()), Some(FinMor::Generator("collapse")));
+        assert_eq!(sch.compose2(src.clone(), src), None);
+        assert_eq!(sch.compose2(FinMor::Id("Vertex"), refl.clone()), Some(refl));
+
+        let id = sch.resolve(FinMor::Generator("reflexive_loop")).unwrap();
+        assert_eq!(id, FinMor::Generator(1));
+        assert_eq!(sch.name(&id), FinMor::Generator("reflexive_loop"));
+    }
+}
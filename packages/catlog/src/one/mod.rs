@@ -1,10 +1,15 @@
 //! Category theory in dimension one.
 
+pub mod canonical;
 pub mod category;
 pub mod fin_category;
 pub mod graph;
 pub mod graph_algorithms;
+pub mod interned;
 pub mod path;
+pub mod typed_graph;
+
+pub(crate) mod components;
 
 pub use self::category::*;
 pub use self::graph::*;
@@ -4,8 +4,11 @@ The central data type is [`Path`]. In addition, this module provides a simple
 data type for [path equations](`PathEq`).
 */
 
+use std::fmt::Display;
+
 use either::Either;
 use nonempty::{nonempty, NonEmpty};
+use thiserror::Error;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -48,6 +51,60 @@ pub enum Path<V, E> {
     Seq(NonEmpty<E>),
 }
 
+/** Convention for writing composites of morphisms.
+
+The same composite, first `f` and then `g`, is written `f; g` in diagrammatic
+order and `g ∘ f` in classical order.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CompositionNotation {
+    /// Diagrammatic order, `f; g`, in which morphisms are written as traversed.
+    #[default]
+    Diagrammatic,
+
+    /// Classical order, `g ∘ f`, as for application of functions.
+    Classical,
+}
+
+/** A path displayed in a given [notation](CompositionNotation).
+
+Returned by [`Path::display`]. The identity path at `x` is shown as `id(x)`.
+ */
+pub struct PathDisplay<'a, V, E> {
+    path: &'a Path<V, E>,
+    notation: CompositionNotation,
+}
+
+impl<V: Display, E: Display> Display for PathDisplay<'_, V, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let edges = match self.path {
+            Path::Id(v) => return write!(f, "id({v})"),
+            Path::Seq(edges) => edges,
+        };
+        let (sep, edges): (_, Vec<_>) = match self.notation {
+            CompositionNotation::Diagrammatic => ("; ", edges.iter().collect()),
+            CompositionNotation::Classical => (" ∘ ", edges.iter().rev().collect()),
+        };
+        for (i, e) in edges.into_iter().enumerate() {
+            if i > 0 {
+                f.write_str(sep)?;
+            }
+            write!(f, "{e}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Displays the path in diagrammatic order.
+impl<V: Display, E: Display> Display for Path<V, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display(CompositionNotation::default()).fmt(f)
+    }
+}
+
 impl<V, E> From<E> for Path<V, E> {
     fn from(e: E) -> Self {
         Path::single(e)
@@ -55,6 +112,14 @@ impl<V, E> From<E> for Path<V, E> {
 }
 
 impl<V, E> Path<V, E> {
+    /// Displays the path as a composite written in the given notation.
+    pub fn display(&self, notation: CompositionNotation) -> PathDisplay<'_, V, E> {
+        PathDisplay {
+            path: self,
+            notation,
+        }
+    }
+
     /// Constructs the empty or identity path.
     pub fn empty(v: V) -> Self {
         Path::Id(v)
@@ -116,6 +181,73 @@ impl<V, E> Path<V, E> {
         }
     }
 
+    /** Iterates over edges in the path, if any.
+
+    Synonym for [`iter`](Path::iter), reading better alongside
+    [`vertices_along`](Path::vertices_along).
+     */
+    pub fn edges(&self) -> impl Iterator<Item = &E> {
+        self.iter()
+    }
+
+    /** Iterates over the vertices visited by the path in the given graph.
+
+    A path of length `n` visits `n+1` vertices, counting repeats, so that an
+    identity path visits just its one vertex. The vertices are computed while
+    walking the path.
+
+    # Panics
+
+    Panics, upon reaching them, if two consecutive edges are not composable in
+    the graph. Like [`src`](Path::src) and [`tgt`](Path::tgt), this method
+    otherwise assumes that the path is [contained in](Path::contained_in) the
+    graph.
+     */
+    pub fn vertices_along<'a, G>(&'a self, graph: &'a G) -> impl Iterator<Item = V> + 'a
+    where
+        V: Eq + Clone,
+        G: Graph<V = V, E = E>,
+    {
+        match self {
+            Path::Id(v) => Either::Left(std::iter::once(v.clone())),
+            Path::Seq(edges) => {
+                let first = std::iter::once(graph.src(edges.first()));
+                let rest = edges.iter().zip(edges.iter().skip(1).map(Some).chain([None])).map(
+                    |(e, next)| {
+                        let v = graph.tgt(e);
+                        if let Some(f) = next {
+                            assert!(v == graph.src(f), "Consecutive edges should be composable");
+                        }
+                        v
+                    },
+                );
+                Either::Right(first.chain(rest))
+            }
+        }
+    }
+
+    /** Iterates over the consecutive subpaths of the given length.
+
+    A path of length `n` has `n-k+1` subpaths of length `k`, when `n >= k`, and
+    none otherwise. In particular, an identity path has no subpaths of positive
+    length. The subpaths are useful for matching rules against paths.
+
+    # Panics
+
+    Panics if `k` is zero, since subpaths of length zero are identities whose
+    vertices cannot be determined without a graph.
+     */
+    pub fn windows(&self, k: usize) -> impl Iterator<Item = Path<V, E>> + '_
+    where
+        E: Clone,
+    {
+        assert!(k > 0, "Length of subpaths should be positive");
+        let n = self.len();
+        (0..(n + 1).saturating_sub(k)).map(move |i| {
+            Path::collect(self.iter().skip(i).take(k).cloned()).expect("Subpath should be nonempty")
+        })
+    }
+
     /** Returns the unique edge in a path of length 1.
 
     This method is a one-sided inverse to [`Path::single`].
@@ -347,18 +479,22 @@ impl<V, E> PathEq<V, E> {
 }
 
 /// A failure of a path equation to be well defined in a graph.
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum InvalidPathEq {
     /// Path in left hand side of equation not contained in the graph.
+    #[error("LHS of path equation is not in the graph")]
     Lhs(),
 
     /// Path in right hand side of equation not contained in the graph.
+    #[error("RHS of path equation is not in the graph")]
     Rhs(),
 
     /// Sources of left and right hand sides of path equation are not equal.
+    #[error("Path equation has sources that are not equal")]
     Src(),
 
     /// Targets of left and right hand sides of path equation are not equal.
+    #[error("Path equation has targets that are not equal")]
     Tgt(),
 }
 
@@ -368,6 +504,16 @@ mod tests {
     use super::*;
     use std::convert::identity;
 
+    #[test]
+    fn display_path() {
+        let path: Path<char, char> = Path::Seq(nonempty!['f', 'g', 'h']);
+        assert_eq!(path.display(CompositionNotation::Diagrammatic).to_string(), "f; g; h");
+        assert_eq!(path.display(CompositionNotation::Classical).to_string(), "h ∘ g ∘ f");
+        assert_eq!(path.to_string(), "f; g; h");
+        let id: Path<char, char> = Path::Id('x');
+        assert_eq!(id.display(CompositionNotation::Classical).to_string(), "id(x)");
+    }
+
     #[test]
     fn path_in_graph() {
         let g = SkelGraph::triangle();
@@ -400,6 +546,32 @@ mod tests {
         assert_eq!(pair.partial_map(Some, |e| Some(e + 1)), Some(Path::pair(1, 2)));
     }
 
+    #[test]
+    fn path_adapters() {
+        let g = SkelGraph::path(5);
+
+        let id = SkelPath::Id(2);
+        assert_eq!(id.edges().count(), 0);
+        assert_eq!(id.vertices_along(&g).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(id.windows(1).count(), 0);
+
+        let single = SkelPath::single(1);
+        assert_eq!(single.edges().collect::<Vec<_>>(), vec![&1]);
+        assert_eq!(single.vertices_along(&g).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(single.windows(1).collect::<Vec<_>>(), vec![Path::single(1)]);
+        assert_eq!(single.windows(2).count(), 0);
+
+        let path = SkelPath::from_vec(vec![0, 1, 2, 3]).unwrap();
+        assert_eq!(path.edges().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(path.vertices_along(&g).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            path.windows(2).collect::<Vec<_>>(),
+            vec![Path::pair(0, 1), Path::pair(1, 2), Path::pair(2, 3)]
+        );
+        assert_eq!(path.windows(4).collect::<Vec<_>>(), vec![path.clone()]);
+        assert_eq!(path.windows(5).count(), 0);
+    }
+
     #[test]
     fn path_eq() {
         let g = SkelGraph::triangle();
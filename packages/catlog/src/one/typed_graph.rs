@@ -0,0 +1,404 @@
+/*! Typed graphs stored compactly by index.
+
+A [`TypedGraph`] is a finite graph whose vertices and edges carry IDs and type
+tags, stored in vectors with adjacency lists in both directions. It is meant for
+analyses that need only the underlying typed graph of a model, such as
+neighborhood queries, connected components, and enumeration of loops, and that
+should not pay for hashing IDs or looking up types in a theory at every step.
+
+As a [graph](FinGraph), a typed graph has vertices and edges given by their
+indices, so that the generic [graph algorithms](super::graph_algorithms) apply
+to it directly. The IDs of vertices and edges are kept alongside to map results
+back to the structure the graph was built from. Since building a typed graph
+from a model takes time linear in its size, a typed graph can be kept in a
+[derived cache](crate::dbl::derived::DerivedCache) and reused until the model
+changes.
+ */
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use derivative::Derivative;
+
+use super::components;
+use super::graph::{FinGraph, Graph};
+use super::path::Path;
+use crate::progress::{Cancelled, NoProgress, Progress};
+use crate::zero::HashFinSet;
+
+/// A finite graph with typed vertices and edges, stored by index.
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct TypedGraph<Id, VType, EType> {
+    vertex_ids: Vec<Id>,
+    vertex_types: Vec<VType>,
+    vertex_index: HashMap<Id, usize>,
+    edge_ids: Vec<Id>,
+    edge_types: Vec<EType>,
+    edge_index: HashMap<Id, usize>,
+    src: Vec<usize>,
+    tgt: Vec<usize>,
+    out_edges: Vec<Vec<usize>>,
+    in_edges: Vec<Vec<usize>>,
+}
+
+impl<Id, VType, EType> TypedGraph<Id, VType, EType>
+where
+    Id: Eq + Clone + Hash,
+{
+    /// Creates an empty typed graph with capacity for vertices and edges.
+    pub fn with_capacity(vertices: usize, edges: usize) -> Self {
+        Self {
+            vertex_ids: Vec::with_capacity(vertices),
+            vertex_types: Vec::with_capacity(vertices),
+            vertex_index: HashMap::with_capacity(vertices),
+            edge_ids: Vec::with_capacity(edges),
+            edge_types: Vec::with_capacity(edges),
+            edge_index: HashMap::with_capacity(edges),
+            src: Vec::with_capacity(edges),
+            tgt: Vec::with_capacity(edges),
+            out_edges: Vec::with_capacity(vertices),
+            in_edges: Vec::with_capacity(vertices),
+        }
+    }
+
+    /** Adds a vertex to the graph, returning its index.
+
+    If a vertex with the ID already exists, its type is updated instead.
+     */
+    pub fn add_vertex(&mut self, id: Id, typ: VType) -> usize {
+        if let Some(&v) = self.vertex_index.get(&id) {
+            self.vertex_types[v] = typ;
+            return v;
+        }
+        let v = self.vertex_ids.len();
+        self.vertex_index.insert(id.clone(), v);
+        self.vertex_ids.push(id);
+        self.vertex_types.push(typ);
+        self.out_edges.push(Vec::new());
+        self.in_edges.push(Vec::new());
+        v
+    }
+
+    /** Adds an edge between two vertices, returning its index.
+
+    Returns `None`, leaving the graph unchanged, if either endpoint is not a
+    vertex or if an edge with the ID already exists.
+     */
+    pub fn add_edge(&mut self, id: Id, src: &Id, tgt: &Id, typ: EType) -> Option<usize> {
+        let (s, t) = (*self.vertex_index.get(src)?, *self.vertex_index.get(tgt)?);
+        if self.edge_index.contains_key(&id) {
+            return None;
+        }
+        let e = self.edge_ids.len();
+        self.edge_index.insert(id.clone(), e);
+        self.edge_ids.push(id);
+        self.edge_types.push(typ);
+        self.src.push(s);
+        self.tgt.push(t);
+        self.out_edges[s].push(e);
+        self.in_edges[t].push(e);
+        Some(e)
+    }
+
+    /// Gets the index of the vertex with the given ID, if any.
+    pub fn vertex_index(&self, id: &Id) -> Option<usize> {
+        self.vertex_index.get(id).copied()
+    }
+
+    /// Gets the index of the edge with the given ID, if any.
+    pub fn edge_index(&self, id: &Id) -> Option<usize> {
+        self.edge_index.get(id).copied()
+    }
+
+    /// Gets the ID of a vertex.
+    pub fn vertex_id(&self, v: usize) -> &Id {
+        &self.vertex_ids[v]
+    }
+
+    /// Gets the ID of an edge.
+    pub fn edge_id(&self, e: usize) -> &Id {
+        &self.edge_ids[e]
+    }
+
+    /// Gets the type of a vertex.
+    pub fn vertex_type(&self, v: usize) -> &VType {
+        &self.vertex_types[v]
+    }
+
+    /// Gets the type of an edge.
+    pub fn edge_type(&self, e: usize) -> &EType {
+        &self.edge_types[e]
+    }
+
+    /** Iterates over the vertices adjacent to a vertex, in either direction.
+
+    Targets of outgoing edges come first, followed by sources of incoming edges.
+    A vertex is repeated once for each edge connecting it to the given vertex.
+     */
+    pub fn neighbors(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        let outs = self.out_edges[v].iter().map(|&e| self.tgt[e]);
+        let ins = self.in_edges[v].iter().map(|&e| self.src[e]);
+        outs.chain(ins)
+    }
+
+    /** Finds the vertices within a given distance of a vertex.
+
+    Edge orientations are ignored, so the result is the ball of the given radius
+    around the vertex in the underlying undirected graph. The vertex itself is
+    always included, provided it belongs to the graph.
+     */
+    pub fn neighborhood(&self, id: &Id, radius: usize) -> HashFinSet<Id> {
+        let mut result = HashFinSet::default();
+        let Some(v) = self.vertex_index(id) else {
+            return result;
+        };
+        let mut dist = vec![usize::MAX; self.vertex_ids.len()];
+        dist[v] = 0;
+        let mut queue = VecDeque::from([v]);
+        while let Some(w) = queue.pop_front() {
+            result.insert(self.vertex_ids[w].clone());
+            if dist[w] == radius {
+                continue;
+            }
+            for u in self.neighbors(w) {
+                if dist[u] == usize::MAX {
+                    dist[u] = dist[w] + 1;
+                    queue.push_back(u);
+                }
+            }
+        }
+        result
+    }
+
+    /** Finds the vertices reachable from a vertex.
+
+    Edges accepted by the `forward` predicate are traversed from source to
+    target and edges accepted by the `backward` predicate from target to source.
+    The predicates are given edge IDs. The vertex itself is always reachable,
+    even if it does not belong to the graph.
+     */
+    pub fn reachable(
+        &self,
+        id: &Id,
+        forward: impl Fn(&Id) -> bool,
+        backward: impl Fn(&Id) -> bool,
+    ) -> HashFinSet<Id> {
+        let mut result = HashFinSet::default();
+        result.insert(id.clone());
+        let Some(v) = self.vertex_index(id) else {
+            return result;
+        };
+        let mut reached = vec![false; self.vertex_ids.len()];
+        reached[v] = true;
+        let mut queue = VecDeque::from([v]);
+        while let Some(w) = queue.pop_front() {
+            result.insert(self.vertex_ids[w].clone());
+            let outs = self.out_edges[w]
+                .iter()
+                .filter(|&&e| forward(&self.edge_ids[e]))
+                .map(|&e| self.tgt[e]);
+            let ins = self.in_edges[w]
+                .iter()
+                .filter(|&&e| backward(&self.edge_ids[e]))
+                .map(|&e| self.src[e]);
+            for u in outs.chain(ins) {
+                if !reached[u] {
+                    reached[u] = true;
+                    queue.push_back(u);
+                }
+            }
+        }
+        result
+    }
+
+    /** Computes the weakly connected components of the graph.
+
+    The components are returned in order of their first vertex, as for
+    [`weakly_connected_components`](super::graph_algorithms::weakly_connected_components).
+     */
+    pub fn connected_components(&self) -> Vec<HashFinSet<Id>> {
+        let edges = self.src.iter().copied().zip(self.tgt.iter().copied());
+        components::weakly_connected(0..self.vertex_ids.len(), edges)
+            .into_iter()
+            .map(|component| {
+                let ids: HashSet<_> =
+                    component.into_iter().map(|v| self.vertex_ids[v].clone()).collect();
+                ids.into()
+            })
+            .collect()
+    }
+
+    /** Enumerates the loops in the graph up to a given length.
+
+    A loop is a nonempty path of edges that starts and ends at the same vertex
+    and otherwise visits no vertex twice. Each loop is reported once, starting
+    from its vertex of least index, so loops are ordered by their starting
+    vertex and then by the order in which edges were added. Parallel edges give
+    distinct loops.
+     */
+    pub fn loops(&self, max_length: usize) -> Vec<Path<Id, Id>> {
+        self.loops_with_progress(max_length, &mut NoProgress)
+            .expect("Enumeration without progress receiver should not be cancelled")
+    }
+
+    /** Enumerates the loops in the graph, reporting progress.
+
+    Progress is reported in the phase `"loops"` after the loops starting at
+    each vertex are enumerated, with the number of vertices done so far.
+     */
+    pub fn loops_with_progress(
+        &self,
+        max_length: usize,
+        progress: &mut impl Progress,
+    ) -> Result<Vec<Path<Id, Id>>, Cancelled> {
+        let n = self.vertex_ids.len();
+        let mut loops = Vec::new();
+        let mut on_path = vec![false; n];
+        let mut path = Vec::new();
+        for start in 0..n {
+            self.extend_loops(start, start, max_length, &mut on_path, &mut path, &mut loops);
+            if progress.report("loops", start + 1, Some(n)).is_break() {
+                return Err(Cancelled);
+            }
+        }
+        Ok(loops)
+    }
+
+    fn extend_loops(
+        &self,
+        start: usize,
+        v: usize,
+        max_length: usize,
+        on_path: &mut [bool],
+        path: &mut Vec<usize>,
+        loops: &mut Vec<Path<Id, Id>>,
+    ) {
+        if path.len() >= max_length {
+            return;
+        }
+        on_path[v] = true;
+        for &e in self.out_edges[v].iter() {
+            let w = self.tgt[e];
+            path.push(e);
+            if w == start {
+                let edges = path.iter().map(|&e| self.edge_ids[e].clone()).collect();
+                loops.push(Path::from_vec(edges).unwrap());
+            } else if w > start && !on_path[w] {
+                self.extend_loops(start, w, max_length, on_path, path, loops);
+            }
+            path.pop();
+        }
+        on_path[v] = false;
+    }
+}
+
+impl<Id, VType, EType> Graph for TypedGraph<Id, VType, EType> {
+    type V = usize;
+    type E = usize;
+
+    fn has_vertex(&self, v: &usize) -> bool {
+        *v < self.vertex_ids.len()
+    }
+    fn has_edge(&self, e: &usize) -> bool {
+        *e < self.edge_ids.len()
+    }
+    fn src(&self, e: &usize) -> usize {
+        self.src[*e]
+    }
+    fn tgt(&self, e: &usize) -> usize {
+        self.tgt[*e]
+    }
+}
+
+impl<Id, VType, EType> FinGraph for TypedGraph<Id, VType, EType> {
+    fn vertices(&self) -> impl Iterator<Item = usize> {
+        0..self.vertex_ids.len()
+    }
+    fn edges(&self) -> impl Iterator<Item = usize> {
+        0..self.edge_ids.len()
+    }
+    fn in_edges(&self, v: &usize) -> impl Iterator<Item = usize> {
+        self.in_edges[*v].iter().copied()
+    }
+    fn out_edges(&self, v: &usize) -> impl Iterator<Item = usize> {
+        self.out_edges[*v].iter().copied()
+    }
+    fn vertex_count(&self) -> usize {
+        self.vertex_ids.len()
+    }
+    fn edge_count(&self) -> usize {
+        self.edge_ids.len()
+    }
+    fn in_degree(&self, v: &usize) -> usize {
+        self.in_edges[*v].len()
+    }
+    fn out_degree(&self, v: &usize) -> usize {
+        self.out_edges[*v].len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zero::{FinSet, Set};
+
+    #[test]
+    fn loops_and_neighborhoods() {
+        // Two triangles sharing the vertex `c`, one with a self-loop, and an
+        // isolated vertex `z`.
+        let mut g: TypedGraph<char, bool, bool> = Default::default();
+        for x in ['a', 'b', 'c', 'd', 'e', 'z'] {
+            g.add_vertex(x, x == 'z');
+        }
+        for (e, s, t) in [
+            ('f', 'a', 'b'),
+            ('g', 'b', 'c'),
+            ('h', 'c', 'a'),
+            ('i', 'c', 'd'),
+            ('j', 'd', 'e'),
+            ('k', 'e', 'c'),
+            ('l', 'e', 'e'),
+        ] {
+            assert!(g.add_edge(e, &s, &t, s == t).is_some());
+        }
+        assert_eq!(g.add_edge('m', &'a', &'y', false), None);
+        assert_eq!(g.add_edge('f', &'a', &'c', false), None);
+        assert_eq!((g.vertex_count(), g.edge_count()), (6, 7));
+        assert_eq!(g.vertex_id(g.src(&g.edge_index(&'k').unwrap())), &'e');
+        assert!(*g.edge_type(g.edge_index(&'l').unwrap()));
+
+        let loops: Vec<Vec<char>> =
+            g.loops(3).into_iter().map(|path| path.iter().copied().collect()).collect();
+        assert_eq!(loops, vec![vec!['f', 'g', 'h'], vec!['i', 'j', 'k'], vec!['l']]);
+        assert_eq!(g.loops(2).len(), 1);
+
+        let ball = g.neighborhood(&'a', 1);
+        assert_eq!(ball.len(), 3);
+        assert!(ball.contains(&'b') && ball.contains(&'c'));
+        assert_eq!(g.neighborhood(&'a', 2).len(), 5);
+        assert_eq!(g.neighborhood(&'y', 2).len(), 0);
+
+        let components = g.connected_components();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].len(), 5);
+        assert!(components[1].contains(&'z'));
+
+        // Following only `f` and `g` forwards and `i` backwards.
+        let reached = g.reachable(&'a', |e| "fg".contains(*e), |e| *e == 'i');
+        assert_eq!(reached.len(), 3);
+        assert!(reached.contains(&'c') && !reached.contains(&'d'));
+        let reached = g.reachable(&'d', |e| "fg".contains(*e), |e| *e == 'i');
+        assert_eq!(reached.len(), 2);
+        assert_eq!(g.reachable(&'y', |_| true, |_| true).len(), 1);
+
+        let mut cancel = |_: &str, done: usize, _: Option<usize>| {
+            if done < 2 {
+                std::ops::ControlFlow::Continue(())
+            } else {
+                std::ops::ControlFlow::Break(())
+            }
+        };
+        assert_eq!(g.loops_with_progress(3, &mut cancel), Err(Cancelled));
+    }
+}
@@ -0,0 +1,126 @@
+/*! Stable, user-facing surface of the package.
+
+Downstream code should import from here rather than from the modules where
+items happen to be defined, so that it is insulated from reorganizations of the
+package internals:
+
+```
+use std::sync::Arc;
+use catlog::prelude::*;
+use catlog::stdlib::th_schema;
+
+let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(Arc::new(th_schema()));
+model.add_ob("x".into(), "Entity".into());
+assert!(model.validate().is_ok());
+```
+
+Unlike the modules themselves, the prelude names each item it re-exports, so
+that implementation details do not leak into it. The prelude alone suffices to
+define a theory, build and validate a model of it, and search the model for
+motifs while reporting progress, as the Wasm bindings do:
+
+```
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use ustr::ustr;
+use catlog::prelude::*;
+use catlog::stdlib::{models::positive_loop, th_signed_category};
+
+let th = Arc::new(th_signed_category());
+let (x, y) = (ustr("x"), ustr("y"));
+let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(th.clone());
+model.add_ob(x, ustr("Object"));
+model.add_ob(y, ustr("Object"));
+model.add_mor(ustr("f"), x, y, FinMor::Id(ustr("Object")));
+model.add_mor(ustr("g"), y, x, FinMor::Id(ustr("Object")));
+let model = Checked::new(model).unwrap();
+
+let mut reports = 0;
+let mut progress = |_: &str, _: usize, _: Option<usize>| {
+    reports += 1;
+    ControlFlow::Continue(())
+};
+let motif = positive_loop(th);
+let loops = DiscreteDblModelMapping::morphisms(&motif, &model)
+    .monic()
+    .find_all_with_progress(&mut progress)
+    .unwrap();
+assert_eq!(loops.len(), 2);
+assert!(reports > 0);
+```
+
+Theories and their morphism types can be described and inspected in the same
+way:
+
+```
+use ustr::ustr;
+use catlog::prelude::*;
+use catlog::stdlib::th_schema;
+
+let th = th_schema();
+let attr = FinMor::Generator(ustr("Attr"));
+assert_eq!(th.src(&attr), ustr("Entity"));
+assert_eq!(th.mor_type_label(&attr, CompositionNotation::Diagrammatic), "Attr");
+assert_eq!(th.kind(), TheoryKind::Discrete);
+```
+ */
+
+pub use crate::zero::{
+    classify, Column, FinSet, HashColumn, HashFinSet, IndexedHashColumn, MapClassification,
+    Mapping, MaybeFinite, OrderedHashFinSet, PredicateSet, Set, SkelFinSet, UstrColumn, UstrFinSet,
+};
+
+pub use crate::one::canonical::{CanonicalCategory, CanonicalMor};
+pub use crate::one::fin_category::{
+    CompositeAuditIssue, CompositionCell, CompositionMatrix, CompositionRepr, CongruenceError,
+    DerivedMorId, FinCategory, FinCategoryBuildError, FinCategoryBuilder, FinMor, FpCategory,
+    InvalidFinCategory, InvalidFpCategory, UstrFinCategory, UstrFpCategory,
+};
+pub use crate::one::interned::{StringInternedCategory, UnknownName};
+pub use crate::one::typed_graph::TypedGraph;
+pub use crate::one::{
+    is_adjunction, AdjunctionWitness, Category, ColumnarGraph, ColumnarGraphMapping, ComposeError,
+    CompositionNotation, EnumerableCategory, FgCategory, FinFunctor, FinGraph, Graph, GraphMapping,
+    GraphMorphism, HashGraph, InvalidGraphMorphism, NotAdjoint, Path, PathEq, SkelGraph, UstrGraph,
+};
+
+pub use crate::dbl::confidence::{
+    CombinationRule, Confidence, ConfidenceColumn, InvalidConfidence, MissingConfidence,
+};
+pub use crate::dbl::derived::DerivedCache;
+pub use crate::dbl::description::{CompositeDescription, MorTypeDescription, TheoryDescription};
+pub use crate::dbl::generator_column::GeneratorColumn;
+pub use crate::dbl::inverse::{InvalidInverse, InverseColumn, InverseError};
+pub use crate::dbl::key::{
+    DuplicateKey, InvalidKeyConstraint, KeyConstraint, KeyConstraints, KeyKind, KeyViolation,
+    SchemaInstance,
+};
+pub use crate::dbl::merge::{
+    MergeConflict, MergeError, MergeValidator, MergeValidatorRegistry, SchemaMergeValidator,
+};
+pub use crate::dbl::model::{
+    DblModel, DiscreteDblModel, DiscreteDblModelBuildError, DiscreteDblModelBuilder, FgDblModel,
+    InvalidDiscreteDblModel, ModelStore, MorSpec, UstrDiscreteDblModel,
+};
+pub use crate::dbl::model_morphism::{
+    DblModelMapping, DiscreteDblModelMapping, DiscreteDblModelMorphism, InvalidDblModelMorphism,
+};
+pub use crate::dbl::namespace::{FolderPath, NamespaceError, NamespaceTree};
+pub use crate::dbl::observer::{ModelObserver, ObFiberIndex};
+pub use crate::dbl::ownership::{CellId, CellOwnership, DanglingRef};
+pub use crate::dbl::provenance::{AuthorId, EditStamp, Provenance, ProvenanceColumn};
+pub use crate::dbl::render::{render_errors, summarize_errors, Severity};
+pub use crate::dbl::selection::{ClosureDirection, Selection, StaleSelection};
+pub use crate::dbl::theory::{
+    DblTheory, Directionality, DirectionalityError, DiscreteDblTheory, DiscreteTabTheory,
+    TabMorType, TabObType, TheoryKind, UstrDiscreteDblTheory, UstrDiscreteTabTheory,
+};
+pub use crate::dbl::theory_morphism::{
+    DiscreteDblTheoryMapping, DiscreteDblTheoryMorphism, InvalidDblTheoryMorphism,
+};
+
+pub use crate::layout::{ClusterNode, ClusterTree, ClusteringOptions};
+
+pub use crate::validate::{unwrap_errors, wrap_errors, BatchError, Checked, Validate};
+
+pub use crate::progress::{Cancelled, NoProgress, Progress, Throttle};
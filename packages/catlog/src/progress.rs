@@ -0,0 +1,124 @@
+/*! Progress reporting and cancellation for long-running operations.
+
+Operations such as searching for morphisms between models can run for a long
+time. Such operations accept a [`Progress`] object, which they notify as they
+work and which can ask them to stop. A cancelled operation returns the
+[`Cancelled`] error and leaves its inputs unmodified. Callers not interested in
+progress can pass [`NoProgress`].
+ */
+
+use std::ops::ControlFlow;
+
+use thiserror::Error;
+
+/** A receiver of progress reports from a long-running operation.
+
+Closures taking the same arguments as [`report`](Progress::report) are
+receivers of progress reports.
+ */
+pub trait Progress {
+    /** Reports progress in a phase of the operation.
+
+    The number of items done so far in the phase is always given, the total
+    number only when it is known in advance. Returning
+    [`Break`](ControlFlow::Break) cancels the operation.
+     */
+    fn report(&mut self, phase: &str, done: usize, total: Option<usize>) -> ControlFlow<()>;
+}
+
+impl<F> Progress for F
+where
+    F: FnMut(&str, usize, Option<usize>) -> ControlFlow<()>,
+{
+    fn report(&mut self, phase: &str, done: usize, total: Option<usize>) -> ControlFlow<()> {
+        self(phase, done, total)
+    }
+}
+
+/// Ignores progress reports and never cancels.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn report(&mut self, _: &str, _: usize, _: Option<usize>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// An operation was cancelled by its [progress receiver](Progress).
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+#[error("Operation was cancelled")]
+pub struct Cancelled;
+
+/** Forwards only some progress reports to another receiver.
+
+Reports can be expensive to deliver, as when they cross into JavaScript, while
+operations may report after every item. A report is forwarded when it starts a
+new phase, when it completes a phase of known total, or when it comes at least
+`every` items after the last report forwarded in its phase. Other reports are
+dropped and never cancel the operation.
+ */
+pub struct Throttle<P> {
+    inner: P,
+    every: usize,
+    last: Option<(String, usize)>,
+}
+
+impl<P: Progress> Throttle<P> {
+    /// Throttles reports to the given receiver.
+    pub fn new(inner: P, every: usize) -> Self {
+        Self {
+            inner,
+            every,
+            last: None,
+        }
+    }
+
+    /// Gets the receiver to which reports are forwarded.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: Progress> Progress for Throttle<P> {
+    fn report(&mut self, phase: &str, done: usize, total: Option<usize>) -> ControlFlow<()> {
+        let forward = match &self.last {
+            Some((last_phase, last_done)) if last_phase == phase => {
+                total == Some(done) || done >= last_done + self.every
+            }
+            _ => true,
+        };
+        if !forward {
+            return ControlFlow::Continue(());
+        }
+        self.last = Some((phase.to_string(), done));
+        self.inner.report(phase, done, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle() {
+        let mut reports = Vec::new();
+        let mut progress = Throttle::new(
+            |phase: &str, done: usize, _: Option<usize>| {
+                reports.push((phase.to_string(), done));
+                ControlFlow::Continue(())
+            },
+            10,
+        );
+        for done in 1..=25 {
+            assert!(progress.report("search", done, Some(25)).is_continue());
+        }
+        assert!(progress.report("sort", 1, None).is_continue());
+        drop(progress);
+        let reports: Vec<_> = reports.iter().map(|(phase, done)| (phase.as_str(), *done)).collect();
+        assert_eq!(
+            reports,
+            vec![("search", 1), ("search", 11), ("search", 21), ("search", 25), ("sort", 1)]
+        );
+    }
+}
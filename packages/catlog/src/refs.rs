@@ -76,3 +76,17 @@ dynamics in biochemical regulatory networks.
 - [arXiv:2301.01445](https://arxiv.org/abs/2301.01445)
  */
 pub const RegNets: () = ();
+
+/** Reference: Sign stability of matrices.
+
+Clark Jeffries, Victor Klee, Pauline van den Driessche, 1977: When is a matrix
+sign stable? Canadian Journal of Mathematics 29(2), 315–326.
+ */
+pub const SignStability: () = ();
+
+/** Reference: Loop analysis of qualitative models.
+
+Charles J. Puccia, Richard Levins, 1985: Qualitative Modeling of Complex Systems:
+An Introduction to Loop Analysis and Time Averaging. Harvard University Press.
+ */
+pub const LoopAnalysis: () = ();
@@ -0,0 +1,3 @@
+//! Analyses of models of theories in the standard library.
+
+pub mod signed;
@@ -0,0 +1,541 @@
+/*! Qualitative analysis of signed models.
+
+A model of the [theory of signed categories](crate::stdlib::theories::th_signed_category)
+is presented by a signed graph, which can equally well be viewed as a matrix of
+signs. Following the ecological literature on loop analysis
+([Puccia & Levins](crate::refs::LoopAnalysis)), the entry of the [`SignMatrix`]
+in row `i` and column `j` is the sign of the effect of object `j` on object `i`,
+so that the matrix is the sign pattern of the Jacobian, or "community matrix,"
+of a dynamical system with the model's causal structure.
+
+Two classical analyses of the sign pattern are provided: whether every system
+with the sign pattern is stable ([sign stability](SignMatrix::is_sign_stable))
+and how the equilibrium responds to a sustained increase in the growth of one
+object ([press perturbation](SignMatrix::predict_press_perturbation)). Both are
+computed combinatorially, with worst-case cost exponential in the number of
+objects, which is fine for the small models typically drawn by hand.
+ */
+
+use std::collections::{BTreeMap, VecDeque};
+use std::hash::Hash;
+use std::ops::{Add, Mul, Neg};
+
+use thiserror::Error;
+use ustr::{ustr, Ustr};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify_next::Tsify;
+
+use crate::one::components;
+use crate::prelude::{Checked, DiscreteDblModel, FinMor, ModelStore, UstrFinCategory};
+
+/** A qualitative sign.
+
+Signs form a semiring under the usual rules for adding and multiplying signs,
+provided that the sum of a positive and a negative sign is taken to be
+ambiguous.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum Sign {
+    /// Zero, or no effect.
+    Zero,
+
+    /// Positive sign.
+    Positive,
+
+    /// Negative sign.
+    Negative,
+
+    /// Sign that cannot be determined qualitatively.
+    Ambiguous,
+}
+
+impl Add for Sign {
+    type Output = Sign;
+
+    fn add(self, other: Sign) -> Sign {
+        match (self, other) {
+            (Sign::Zero, s) | (s, Sign::Zero) => s,
+            (s, t) if s == t => s,
+            _ => Sign::Ambiguous,
+        }
+    }
+}
+
+impl Mul for Sign {
+    type Output = Sign;
+
+    fn mul(self, other: Sign) -> Sign {
+        match (self, other) {
+            (Sign::Zero, _) | (_, Sign::Zero) => Sign::Zero,
+            (Sign::Ambiguous, _) | (_, Sign::Ambiguous) => Sign::Ambiguous,
+            (s, t) if s == t => Sign::Positive,
+            _ => Sign::Negative,
+        }
+    }
+}
+
+impl Neg for Sign {
+    type Output = Sign;
+
+    fn neg(self) -> Sign {
+        match self {
+            Sign::Positive => Sign::Negative,
+            Sign::Negative => Sign::Positive,
+            s => s,
+        }
+    }
+}
+
+/** A sparse square matrix of signs indexed by objects.
+
+The objects are kept in sorted order, so that rows and columns are numbered
+deterministically. Only nonzero entries are stored.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignMatrix<Id> {
+    objects: Vec<Id>,
+    entries: BTreeMap<(usize, usize), Sign>,
+}
+
+impl<Id: Ord> SignMatrix<Id> {
+    /// Creates a zero matrix indexed by the given objects.
+    pub fn new(objects: impl IntoIterator<Item = Id>) -> Self {
+        let mut objects: Vec<_> = objects.into_iter().collect();
+        objects.sort();
+        objects.dedup();
+        Self {
+            objects,
+            entries: Default::default(),
+        }
+    }
+
+    /// Objects indexing the rows and columns, in order.
+    pub fn objects(&self) -> &[Id] {
+        &self.objects
+    }
+
+    /// Number of rows, equivalently of columns, of the matrix.
+    pub fn size(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Gets the index of an object, if it indexes the matrix.
+    pub fn position(&self, x: &Id) -> Option<usize> {
+        self.objects.binary_search(x).ok()
+    }
+
+    /// Gets the entry in a row and column.
+    pub fn get(&self, i: usize, j: usize) -> Sign {
+        self.entries.get(&(i, j)).copied().unwrap_or(Sign::Zero)
+    }
+
+    /// Adds a sign to the entry in a row and column.
+    pub fn add_entry(&mut self, i: usize, j: usize, sign: Sign) {
+        assert!(i < self.size() && j < self.size(), "Entry should be in bounds");
+        let sum = self.get(i, j) + sign;
+        if sum != Sign::Zero {
+            self.entries.insert((i, j), sum);
+        }
+    }
+
+    /// Iterates over the nonzero entries in row-major order.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, usize, Sign)> + '_ {
+        self.entries.iter().map(|((i, j), s)| (*i, *j, *s))
+    }
+
+    /// Iterates over the columns having nonzero entries in a row.
+    fn row(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        self.entries.range((i, 0)..(i + 1, 0)).map(|((_, j), _)| *j)
+    }
+
+    /** Is the matrix sign stable?
+
+    A sign pattern is *sign stable* when every real matrix with that pattern is
+    stable, i.e., has all its eigenvalues in the open left half-plane. This
+    method implements the characterization by [Jeffries, Klee, and van den
+    Driessche](crate::refs::SignStability), comprising the conditions:
+
+    1. no object is self-enhancing: each diagonal entry is zero or negative;
+    2. every pair of objects interacting in both directions does so with
+       opposite signs, as predator and prey;
+    3. there are no cycles of interactions of length three or more;
+    4. some term in the expansion of the determinant is nonzero;
+    5. the matrix fails the *color test*.
+
+    Matrices with ambiguous entries are never sign stable.
+     */
+    pub fn is_sign_stable(&self) -> bool {
+        if self.entries.values().any(|s| *s == Sign::Ambiguous) {
+            return false;
+        }
+        let n = self.size();
+        if (0..n).any(|i| self.get(i, i) == Sign::Positive) {
+            return false;
+        }
+        if self.entries().any(|(i, j, s)| i != j && s * self.get(j, i) == Sign::Positive) {
+            return false;
+        }
+        !self.has_long_cycle() && self.has_nonzero_term() && !self.passes_color_test()
+    }
+
+    /** Does the directed graph of interactions have a cycle of length >= 3?
+
+    This is so if and only if either some interaction lies in a strongly
+    connected component without a reverse interaction or else the undirected
+    graph of two-way interactions is not a forest.
+     */
+    fn has_long_cycle(&self) -> bool {
+        let n = self.size();
+        let mut reachable = vec![vec![false; n]; n];
+        for (j, reached) in reachable.iter_mut().enumerate() {
+            let mut queue = VecDeque::from([j]);
+            while let Some(k) = queue.pop_front() {
+                // Column `k` holds the effects of `k` on other objects.
+                for i in (0..n).filter(|i| self.get(*i, k) != Sign::Zero) {
+                    if !reached[i] {
+                        reached[i] = true;
+                        queue.push_back(i);
+                    }
+                }
+            }
+        }
+        let one_way = self.entries().any(|(i, j, _)| {
+            i != j && reachable[i][j] && reachable[j][i] && self.get(j, i) == Sign::Zero
+        });
+        if one_way {
+            return true;
+        }
+
+        let two_way: Vec<_> = self
+            .entries()
+            .filter(|(i, j, _)| i < j && self.get(*j, *i) != Sign::Zero)
+            .map(|(i, j, _)| (i, j))
+            .collect();
+        let ncomponents = components::weakly_connected(0..n, two_way.iter().copied()).len();
+        two_way.len() > n - ncomponents
+    }
+
+    /// Is some term in the expansion of the determinant nonzero?
+    fn has_nonzero_term(&self) -> bool {
+        // Find a perfect matching of rows with columns by augmenting paths.
+        fn augment<Id: Ord>(
+            m: &SignMatrix<Id>,
+            i: usize,
+            seen: &mut [bool],
+            matched: &mut [Option<usize>],
+        ) -> bool {
+            for j in m.row(i) {
+                if !seen[j] {
+                    seen[j] = true;
+                    let free = match matched[j] {
+                        Some(k) => augment(m, k, seen, matched),
+                        None => true,
+                    };
+                    if free {
+                        matched[j] = Some(i);
+                        return true;
+                    }
+                }
+            }
+            false
+        }
+        let n = self.size();
+        let mut matched = vec![None; n];
+        (0..n).all(|i| augment(self, i, &mut vec![false; n], &mut matched))
+    }
+
+    /** Does the matrix pass the color test?
+
+    The test asks for a coloring of the objects in black and white such that
+    some object is white, every self-regulating object is black, every white
+    object interacts in both directions with another white object, and no black
+    object interacts in both directions with exactly one white object.
+     */
+    fn passes_color_test(&self) -> bool {
+        let n = self.size();
+        let neighbors: Vec<Vec<usize>> = (0..n)
+            .map(|i| self.row(i).filter(|j| *j != i && self.get(*j, i) != Sign::Zero).collect())
+            .collect();
+        let candidates: Vec<_> = (0..n).filter(|i| self.get(*i, *i) == Sign::Zero).collect();
+
+        fn search(neighbors: &[Vec<usize>], candidates: &[usize], white: &mut [bool]) -> bool {
+            let Some((i, rest)) = candidates.split_first() else {
+                return white.iter().any(|w| *w)
+                    && neighbors.iter().enumerate().all(|(i, nbrs)| {
+                        let k = nbrs.iter().filter(|j| white[**j]).count();
+                        if white[i] {
+                            k >= 1
+                        } else {
+                            k != 1
+                        }
+                    });
+            };
+            white[*i] = true;
+            if search(neighbors, rest, white) {
+                return true;
+            }
+            white[*i] = false;
+            search(neighbors, rest, white)
+        }
+        search(&neighbors, &candidates, &mut vec![false; n])
+    }
+
+    /** Sign of the determinant of a square submatrix.
+
+    The rows and columns of the submatrix are given in increasing order.
+     */
+    fn minor_sign(&self, rows: &[usize], cols: &[usize]) -> Sign {
+        fn expand<Id: Ord>(
+            m: &SignMatrix<Id>,
+            rows: &[usize],
+            cols: &[usize],
+            used: &mut [bool],
+            term: Sign,
+            total: &mut Sign,
+        ) {
+            let Some((i, rest)) = rows.split_first() else {
+                *total = *total + term;
+                return;
+            };
+            for (p, j) in cols.iter().enumerate() {
+                let a = m.get(*i, *j);
+                if used[p] || a == Sign::Zero {
+                    continue;
+                }
+                // Each column already used to the right is an inversion.
+                let inversions = used[p + 1..].iter().filter(|u| **u).count();
+                let a = if inversions % 2 == 0 { a } else { -a };
+                used[p] = true;
+                expand(m, rest, cols, used, term * a, total);
+                used[p] = false;
+                if *total == Sign::Ambiguous {
+                    return;
+                }
+            }
+        }
+        let mut total = Sign::Zero;
+        expand(self, rows, cols, &mut vec![false; cols.len()], Sign::Positive, &mut total);
+        total
+    }
+
+    /** Predicts the responses to a press perturbation of an object.
+
+    A *press perturbation* is a sustained increase in the rate of growth of the
+    target object. Assuming the system is at a stable equilibrium, the response
+    of object `i` has the sign of entry `(i, j)` of the matrix `-A⁻¹`, where `j`
+    is the target ([Puccia & Levins](crate::refs::LoopAnalysis)). This entry is
+    computed over the semiring of signs from the adjugate matrix of `A`, so the
+    response is ambiguous when it depends on the magnitudes of the interactions.
+    When the determinant is zero, every response is ambiguous.
+
+    Returns the responses of all the objects, in order, or `None` if the target
+    does not index the matrix.
+     */
+    pub fn predict_press_perturbation(&self, target: &Id) -> Option<Vec<Sign>> {
+        let j = self.position(target)?;
+        let n = self.size();
+        let all: Vec<_> = (0..n).collect();
+        let det = self.minor_sign(&all, &all);
+        let rows: Vec<_> = (0..n).filter(|r| *r != j).collect();
+        Some(
+            (0..n)
+                .map(|i| {
+                    if det == Sign::Zero {
+                        return Sign::Ambiguous;
+                    }
+                    // The adjugate is the transpose of the cofactor matrix.
+                    let cols: Vec<_> = (0..n).filter(|c| *c != i).collect();
+                    let minor = self.minor_sign(&rows, &cols);
+                    let adj = if (i + j) % 2 == 0 { minor } else { -minor };
+                    -(adj * det)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A failure to interpret a model as a signed graph.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SignedModelError<Id> {
+    /// Basic morphism has a type other than positive or negative.
+    #[error("Morphism `{0}` is neither positive nor negative")]
+    MorType(Id),
+
+    /// Basic morphism has undefined or invalid domain or codomain.
+    #[error("Morphism `{0}` does not have a valid domain and codomain")]
+    Endpoints(Id),
+}
+
+/** Signed adjacency matrix of a model of the theory of signed categories.
+
+Each basic morphism `f: x → y` contributes its sign to the entry in row `y` and
+column `x`. Parallel morphisms of opposite signs give an ambiguous entry. The
+model must be [checked](Checked) to be valid.
+ */
+pub fn signed_adjacency_matrix<Id>(
+    model: &Checked<DiscreteDblModel<Id, UstrFinCategory>>,
+) -> Result<SignMatrix<Id>, SignedModelError<Id>>
+where
+    Id: Eq + Clone + Hash + Ord,
+{
+    signed_adjacency_matrix_in_store(&**model)
+}
+
+/** Signed adjacency matrix of a signed model in a [store](ModelStore).
+
+Like [`signed_adjacency_matrix`] but reading the generators from a store, which
+is not checked to be valid beforehand.
+ */
+pub fn signed_adjacency_matrix_in_store<S>(
+    store: &S,
+) -> Result<SignMatrix<S::Id>, SignedModelError<S::Id>>
+where
+    S: ModelStore<MorType = FinMor<Ustr, Ustr>>,
+    S::Id: Hash + Ord,
+{
+    let negative = FinMor::Generator(ustr("Negative"));
+    let mut matrix = SignMatrix::new(store.ob_generators());
+    for f in store.mor_generators() {
+        let sign = match store.lookup_mor_type(&f) {
+            Some(FinMor::Id(_)) => Sign::Positive,
+            Some(t) if t == negative => Sign::Negative,
+            _ => return Err(SignedModelError::MorType(f)),
+        };
+        let dom = store.lookup_dom(&f).and_then(|x| matrix.position(&x));
+        let cod = store.lookup_cod(&f).and_then(|y| matrix.position(&y));
+        let (Some(j), Some(i)) = (dom, cod) else {
+            return Err(SignedModelError::Endpoints(f));
+        };
+        matrix.add_entry(i, j, sign);
+    }
+    Ok(matrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use ustr::{ustr, Ustr};
+
+    use super::*;
+    use crate::dbl::model::UstrDiscreteDblModel;
+    use crate::model;
+    use crate::one::FgCategory;
+    use crate::stdlib::theories::th_signed_category;
+
+    /// Food chain of a plant, an herbivore, and a predator.
+    fn food_chain() -> UstrDiscreteDblModel {
+        let (pos, neg) = (FinMor::Id(ustr("Object")), FinMor::Generator(ustr("Negative")));
+        model! {
+            theory: Arc::new(th_signed_category()),
+            obs: ["1_plant" => "Object", "2_herbivore" => "Object", "3_predator" => "Object"],
+            mors: [
+                "growth" => ("1_plant", "1_plant", neg.clone()),
+                "grazing" => ("2_herbivore", "1_plant", neg.clone()),
+                "forage" => ("1_plant", "2_herbivore", pos.clone()),
+                "predation" => ("3_predator", "2_herbivore", neg),
+                "prey" => ("2_herbivore", "3_predator", pos),
+            ],
+        }
+        .unwrap()
+    }
+
+    #[test]
+    fn sign_semiring() {
+        use Sign::*;
+        assert_eq!(Positive + Negative, Ambiguous);
+        assert_eq!(Zero + Negative, Negative);
+        assert_eq!(Negative * Negative, Positive);
+        assert_eq!(Ambiguous * Zero, Zero);
+        assert_eq!(-Negative, Positive);
+    }
+
+    #[test]
+    fn food_chain_matrix() {
+        use Sign::*;
+        let model = Checked::new(food_chain()).unwrap();
+        let matrix = signed_adjacency_matrix(&model).unwrap();
+        assert_eq!(matrix.size(), 3);
+        assert_eq!(matrix.objects()[1], ustr("2_herbivore"));
+        let entries: Vec<_> = matrix.entries().collect();
+        assert_eq!(
+            entries,
+            vec![
+                (0, 0, Negative),
+                (0, 1, Negative),
+                (1, 0, Positive),
+                (1, 2, Negative),
+                (2, 1, Positive),
+            ]
+        );
+        assert!(matrix.is_sign_stable());
+
+        // Press perturbation table of the food chain, listing the responses to
+        // a press on each object in turn.
+        let table: Vec<_> = matrix
+            .objects()
+            .iter()
+            .map(|x| matrix.predict_press_perturbation(x).unwrap())
+            .collect();
+        assert_eq!(
+            table,
+            vec![
+                vec![Positive, Zero, Positive],
+                vec![Zero, Zero, Positive],
+                vec![Positive, Negative, Positive],
+            ]
+        );
+        assert!(matrix.predict_press_perturbation(&ustr("4_carnivore")).is_none());
+    }
+
+    #[test]
+    fn checked_model() {
+        // An ill-typed morphism makes the model invalid, so it cannot be analyzed.
+        let mut model = food_chain();
+        let unknown = FinMor::Generator(ustr("Unknown"));
+        model.add_mor(ustr("scavenging"), ustr("3_predator"), ustr("1_plant"), unknown);
+        let Err(errs) = Checked::new(model.clone()) else {
+            panic!("Model with ill-typed morphism should not be valid");
+        };
+        assert_eq!(errs.len(), 1);
+        assert!(Checked::check_ref(&model).is_err());
+
+        // Repairing the model makes it valid again.
+        model.make_mor(ustr("scavenging"), FinMor::Id(ustr("Object")));
+        let checked = Checked::check_ref(&model).unwrap();
+        assert_eq!(signed_adjacency_matrix(checked).unwrap().size(), 3);
+
+        // Mutation requires giving up the guarantee of validity.
+        let checked = Checked::new(model).unwrap();
+        assert_eq!(checked.morphism_generators().count(), 6);
+        let mut model = checked.invalidate();
+        model.make_mor(ustr("scavenging"), FinMor::Generator(ustr("Unknown")));
+        assert!(Checked::new(model).is_err());
+    }
+
+    #[test]
+    fn sign_instability() {
+        // Predator and prey without self-regulation pass the color test.
+        let mut matrix: SignMatrix<Ustr> = SignMatrix::new([ustr("x"), ustr("y")]);
+        matrix.add_entry(0, 1, Sign::Negative);
+        matrix.add_entry(1, 0, Sign::Positive);
+        assert!(!matrix.is_sign_stable());
+        matrix.add_entry(0, 0, Sign::Negative);
+        assert!(matrix.is_sign_stable());
+        matrix.add_entry(1, 1, Sign::Positive);
+        assert!(!matrix.is_sign_stable());
+
+        // Cycles of length three are never sign stable.
+        let mut matrix: SignMatrix<Ustr> = SignMatrix::new([ustr("x"), ustr("y"), ustr("z")]);
+        (0..3).for_each(|i| matrix.add_entry(i, i, Sign::Negative));
+        matrix.add_entry(1, 0, Sign::Positive);
+        matrix.add_entry(2, 1, Sign::Positive);
+        matrix.add_entry(0, 2, Sign::Negative);
+        assert!(!matrix.is_sign_stable());
+    }
+}
@@ -0,0 +1,354 @@
+/*! Best-effort inference of models from untyped graphs.
+
+Users sometimes start from a plain list of edges, without saying which types
+the vertices and edges should have under a theory. The function [`infer_model`]
+guesses a typing using [heuristics](TypingHeuristics) specific to the theory
+and records every guess as an [`InferenceNote`], so that the guesses can be
+shown to the user for confirmation.
+
+The inferred model always validates: a vertex whose guessed type is not in the
+theory, or an edge whose guessed type does not match the types guessed for its
+endpoints, is left out of the model, with a note to that effect. Heuristics are provided for the theories of
+[categories](super::theories::th_category),
+[schemas](super::theories::th_schema), and
+[signed categories](super::theories::th_signed_category).
+ */
+
+use std::sync::Arc;
+
+use ustr::{ustr, Ustr};
+
+use crate::prelude::{
+    Category, ColumnarGraph, DblModel, DblTheory, FinGraph, FinMor, UstrDiscreteDblModel,
+    UstrDiscreteDblTheory,
+};
+
+/// A guess made while inferring a model from an untyped graph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InferenceNote {
+    /// Vertex was given an object type.
+    ObType {
+        /// The vertex, now a basic object.
+        ob: Ustr,
+        /// The guessed object type.
+        ob_type: Ustr,
+        /// Why the type was guessed.
+        reason: String,
+    },
+
+    /// Edge was given a morphism type.
+    MorType {
+        /// The edge, now a basic morphism.
+        mor: Ustr,
+        /// The guessed morphism type.
+        mor_type: FinMor<Ustr, Ustr>,
+        /// Why the type was guessed.
+        reason: String,
+    },
+
+    /// Vertex could not be typed and was left out of the model.
+    DroppedOb {
+        /// The vertex.
+        ob: Ustr,
+        /// Why the vertex could not be typed.
+        reason: String,
+    },
+
+    /// Edge could not be typed and was left out of the model.
+    Dropped {
+        /// The edge.
+        mor: Ustr,
+        /// Why the edge could not be typed.
+        reason: String,
+    },
+}
+
+/** Heuristics for typing an untyped graph under a theory.
+
+Each guess comes with a short reason, shown to the user alongside it.
+Implementations should only guess object types belonging to the theory.
+ */
+pub trait TypingHeuristics {
+    /// Guesses the object type of a vertex.
+    fn ob_type<G>(&self, graph: &G, x: &Ustr) -> (Ustr, String)
+    where
+        G: FinGraph<V = Ustr, E = Ustr>;
+
+    /** Guesses the morphism type of an edge.
+
+    The object types already guessed for the source and target of the edge are
+    given. Returns `None` if the edge cannot be typed.
+     */
+    fn mor_type<G>(
+        &self,
+        graph: &G,
+        f: &Ustr,
+        dom_type: &Ustr,
+        cod_type: &Ustr,
+    ) -> Option<(FinMor<Ustr, Ustr>, String)>
+    where
+        G: FinGraph<V = Ustr, E = Ustr>;
+}
+
+/** Heuristics for the [theory of categories](super::theories::th_category).
+
+There is nothing to guess: every vertex is an object and every edge a morphism.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CategoryHeuristics;
+
+impl TypingHeuristics for CategoryHeuristics {
+    fn ob_type<G>(&self, _graph: &G, _x: &Ustr) -> (Ustr, String) {
+        (ustr("Object"), "only object type".into())
+    }
+
+    fn mor_type<G>(
+        &self,
+        _graph: &G,
+        _f: &Ustr,
+        dom_type: &Ustr,
+        _cod_type: &Ustr,
+    ) -> Option<(FinMor<Ustr, Ustr>, String)> {
+        Some((FinMor::Id(*dom_type), "only morphism type".into()))
+    }
+}
+
+/** Heuristics for the [theory of schemas](super::theories::th_schema).
+
+A vertex with incoming but no outgoing edges is taken to be an attribute type
+and every other vertex an entity. Edges into attribute types are then
+attributes and edges between entities are mappings.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SchemaHeuristics;
+
+impl TypingHeuristics for SchemaHeuristics {
+    fn ob_type<G>(&self, graph: &G, x: &Ustr) -> (Ustr, String)
+    where
+        G: FinGraph<V = Ustr, E = Ustr>,
+    {
+        match (graph.in_degree(x), graph.out_degree(x)) {
+            (0, 0) => (ustr("Entity"), "isolated vertex".into()),
+            (_, 0) => (ustr("AttrType"), "has incoming but no outgoing edges".into()),
+            _ => (ustr("Entity"), "has outgoing edges".into()),
+        }
+    }
+
+    fn mor_type<G>(
+        &self,
+        _graph: &G,
+        _f: &Ustr,
+        dom_type: &Ustr,
+        cod_type: &Ustr,
+    ) -> Option<(FinMor<Ustr, Ustr>, String)> {
+        match (dom_type.as_str(), cod_type.as_str()) {
+            ("Entity", "AttrType") => {
+                Some((FinMor::Generator(ustr("Attr")), "target is an attribute type".into()))
+            }
+            ("Entity", "Entity") => {
+                Some((FinMor::Id(ustr("Entity")), "source and target are entities".into()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/** Heuristics for the [theory of signed categories](super::theories::th_signed_category).
+
+An untyped edge carries no sign, so every edge is taken to be positive.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SignedCategoryHeuristics;
+
+impl TypingHeuristics for SignedCategoryHeuristics {
+    fn ob_type<G>(&self, _graph: &G, _x: &Ustr) -> (Ustr, String) {
+        (ustr("Object"), "only object type".into())
+    }
+
+    fn mor_type<G>(
+        &self,
+        _graph: &G,
+        _f: &Ustr,
+        dom_type: &Ustr,
+        _cod_type: &Ustr,
+    ) -> Option<(FinMor<Ustr, Ustr>, String)> {
+        Some((FinMor::Id(*dom_type), "unsigned edge assumed positive".into()))
+    }
+}
+
+/** Infers a model of a theory from an untyped graph.
+
+Vertices become basic objects and edges basic morphisms, typed according to the
+heuristics. The notes list every guess made, in order of vertex and then edge
+ID. Vertices guessed to have a type outside the theory, and edges that cannot be
+typed consistently with their endpoints or that lack a source or target in the
+model, are left out of the model and noted as dropped, so that the model always
+validates.
+ */
+pub fn infer_model<G>(
+    graph: &G,
+    theory: Arc<UstrDiscreteDblTheory>,
+    heuristics: &impl TypingHeuristics,
+) -> (UstrDiscreteDblModel, Vec<InferenceNote>)
+where
+    G: FinGraph<V = Ustr, E = Ustr> + ColumnarGraph<V = Ustr, E = Ustr>,
+{
+    let mut model = UstrDiscreteDblModel::new(theory.clone());
+    let mut notes = Vec::new();
+
+    let mut vertices: Vec<_> = graph.vertices().collect();
+    vertices.sort();
+    for x in vertices {
+        let (ob_type, reason) = heuristics.ob_type(graph, &x);
+        if !theory.has_ob_type(&ob_type) {
+            notes.push(InferenceNote::DroppedOb {
+                ob: x,
+                reason: format!("guessed type {ob_type} is not in the theory"),
+            });
+            continue;
+        }
+        model.add_ob(x, ob_type);
+        notes.push(InferenceNote::ObType {
+            ob: x,
+            ob_type,
+            reason,
+        });
+    }
+
+    let mut edges: Vec<_> = graph.edges().collect();
+    edges.sort();
+    for f in edges {
+        let endpoints = (graph.get_src(&f), graph.get_tgt(&f));
+        let (Some(&dom), Some(&cod)) = endpoints else {
+            notes.push(InferenceNote::Dropped {
+                mor: f,
+                reason: "source or target is not set".into(),
+            });
+            continue;
+        };
+        if !(model.has_ob(&dom) && model.has_ob(&cod)) {
+            notes.push(InferenceNote::Dropped {
+                mor: f,
+                reason: "source or target was left out of the model".into(),
+            });
+            continue;
+        }
+        let (dom_type, cod_type) = (model.ob_type(&dom), model.ob_type(&cod));
+        let guess = heuristics.mor_type(graph, &f, &dom_type, &cod_type);
+        match guess {
+            Some((mor_type, reason))
+                if theory.has_mor_type(&mor_type)
+                    && theory.src(&mor_type) == dom_type
+                    && theory.tgt(&mor_type) == cod_type =>
+            {
+                model.add_mor(f, dom, cod, mor_type.clone());
+                notes.push(InferenceNote::MorType {
+                    mor: f,
+                    mor_type,
+                    reason,
+                });
+            }
+            Some(_) => notes.push(InferenceNote::Dropped {
+                mor: f,
+                reason: "guessed type does not match its endpoints".into(),
+            }),
+            None => notes.push(InferenceNote::Dropped {
+                mor: f,
+                reason: format!("no morphism type from {dom_type} to {cod_type}"),
+            }),
+        }
+    }
+
+    (model, notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbl::model::FgDblModel;
+    use crate::one::{ColumnarGraphMut, FgCategory, UstrGraph};
+    use crate::stdlib::theories::*;
+    use crate::validate::Validate;
+
+    #[test]
+    fn infer_schema() {
+        let mut graph = UstrGraph::default();
+        for x in ["person", "company", "name", "age", "loner"] {
+            graph.add_vertex(ustr(x));
+        }
+        for (f, s, t) in [
+            ("employer", "person", "company"),
+            ("person_name", "person", "name"),
+            ("company_name", "company", "name"),
+            ("person_age", "person", "age"),
+        ] {
+            graph.add_edge(ustr(f), ustr(s), ustr(t));
+        }
+
+        let (model, notes) = infer_model(&graph, Arc::new(th_schema()), &SchemaHeuristics);
+        assert!(model.validate().is_ok());
+        assert_eq!(model.ob_type(&ustr("name")), ustr("AttrType"));
+        assert_eq!(model.ob_type(&ustr("company")), ustr("Entity"));
+        assert_eq!(model.mor_gen_type(&ustr("employer")), FinMor::Id(ustr("Entity")));
+        assert_eq!(notes.len(), 9);
+        assert_eq!(
+            notes[0],
+            InferenceNote::ObType {
+                ob: ustr("age"),
+                ob_type: ustr("AttrType"),
+                reason: "has incoming but no outgoing edges".into(),
+            }
+        );
+        assert_eq!(
+            notes[2],
+            InferenceNote::ObType {
+                ob: ustr("loner"),
+                ob_type: ustr("Entity"),
+                reason: "isolated vertex".into(),
+            }
+        );
+        assert_eq!(
+            notes[5],
+            InferenceNote::MorType {
+                mor: ustr("company_name"),
+                mor_type: FinMor::Generator(ustr("Attr")),
+                reason: "target is an attribute type".into(),
+            }
+        );
+
+        // Edges are positive by default in signed categories.
+        let (model, notes) =
+            infer_model(&graph, Arc::new(th_signed_category()), &SignedCategoryHeuristics);
+        assert!(model.validate().is_ok());
+        assert_eq!(model.mor_gen_type(&ustr("person_age")), FinMor::Id(ustr("Object")));
+        assert!(!notes.iter().any(|note| matches!(note, InferenceNote::Dropped { .. })));
+
+        // Vertices typed outside the theory are dropped with their edges.
+        let (model, notes) = infer_model(&graph, Arc::new(th_category()), &SchemaHeuristics);
+        assert!(model.validate().is_ok());
+        assert_eq!(model.object_generators().count(), 0);
+        assert_eq!(
+            notes[0],
+            InferenceNote::DroppedOb {
+                ob: ustr("age"),
+                reason: "guessed type AttrType is not in the theory".into(),
+            }
+        );
+        assert_eq!(
+            notes
+                .iter()
+                .filter(|note| matches!(note, InferenceNote::Dropped { .. }))
+                .count(),
+            4
+        );
+
+        // Edges without a target are dropped.
+        graph.update_tgt(ustr("employer"), None);
+        let (model, notes) = infer_model(&graph, Arc::new(th_schema()), &SchemaHeuristics);
+        assert!(model.validate().is_ok());
+        assert!(notes.contains(&InferenceNote::Dropped {
+            mor: ustr("employer"),
+            reason: "source or target is not set".into(),
+        }));
+    }
+}
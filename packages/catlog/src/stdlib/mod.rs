@@ -1,5 +1,7 @@
 //! Standard library of double theories and models.
 
+pub mod analyses;
+pub mod inference;
 pub mod models;
 pub mod theories;
 
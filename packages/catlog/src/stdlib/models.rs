@@ -3,9 +3,7 @@
 use std::sync::Arc;
 use ustr::ustr;
 
-use crate::dbl::model::*;
-use crate::dbl::theory::UstrDiscreteDblTheory;
-use crate::one::fin_category::FinMor;
+use crate::prelude::{FinMor, UstrDiscreteDblModel, UstrDiscreteDblTheory};
 
 /** The positive self-loop.
 
@@ -63,7 +61,7 @@ pub fn negative_feedback(th: Arc<UstrDiscreteDblTheory>) -> UstrDiscreteDblModel
 mod tests {
     use super::super::theories::*;
     use super::*;
-    use crate::validate::Validate;
+    use crate::prelude::Validate;
 
     #[test]
     fn signed_categories() {
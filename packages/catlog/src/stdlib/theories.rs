@@ -2,17 +2,23 @@
 
 use ustr::ustr;
 
-use crate::dbl::theory::*;
-use crate::one::fin_category::{FinMor, UstrFinCategory};
+use crate::prelude::{
+    DblTheory, Directionality, TabObType, UstrDiscreteDblTheory, UstrDiscreteTabTheory,
+};
+use crate::theory;
 
 /** The theory of categories, aka the trivial double theory.
 
-As a double category, this is the terminal double category.
+As a double category, this is the terminal double category. The unique morphism
+into it from any other discrete theory is constructed by
+[`unique_morphism_to_terminal`](crate::dbl::theory::DiscreteDblTheory::unique_morphism_to_terminal).
  */
 pub fn th_category() -> UstrDiscreteDblTheory {
-    let mut cat: UstrFinCategory = Default::default();
-    cat.add_ob_generator(ustr("Object"));
-    DiscreteDblTheory::from(cat)
+    theory! {
+        ob_types: [Object],
+        mor_types: [],
+    }
+    .expect("Theory of categories should be well defined")
 }
 
 /** The theory of database schemas with attributes.
@@ -20,12 +26,11 @@ pub fn th_category() -> UstrDiscreteDblTheory {
 As a double category, this is the "walking proarrow".
  */
 pub fn th_schema() -> UstrDiscreteDblTheory {
-    let mut cat: UstrFinCategory = Default::default();
-    let (x, y, p) = (ustr("Entity"), ustr("AttrType"), ustr("Attr"));
-    cat.add_ob_generator(x);
-    cat.add_ob_generator(y);
-    cat.add_mor_generator(p, x, y);
-    DiscreteDblTheory::from(cat)
+    theory! {
+        ob_types: [Entity, AttrType],
+        mor_types: [Attr: Entity -> AttrType],
+    }
+    .expect("Theory of schemas should be well defined")
 }
 
 /** The theory of signed categories.
@@ -34,12 +39,12 @@ A [signed category](crate::refs::RegNets) is a category sliced over the group of
 (nonzero) signs.
  */
 pub fn th_signed_category() -> UstrDiscreteDblTheory {
-    let mut sgn: UstrFinCategory = Default::default();
-    let (x, n) = (ustr("Object"), ustr("Negative"));
-    sgn.add_ob_generator(x);
-    sgn.add_mor_generator(n, x, x);
-    sgn.set_composite(n, n, FinMor::Id(x));
-    DiscreteDblTheory::from(sgn)
+    theory! {
+        ob_types: [Object],
+        mor_types: [Negative: Object -> Object],
+        equations: [Negative; Negative = id(Object)],
+    }
+    .expect("Theory of signed categories should be well defined")
 }
 
 /** The theory of nullable signed categories.
@@ -48,16 +53,36 @@ A nullable signed category is a category sliced over the monoid of signs,
 including zero.
  */
 pub fn th_nullable_signed_category() -> UstrDiscreteDblTheory {
-    let mut sgn: UstrFinCategory = Default::default();
-    let (x, n, z) = (ustr("Object"), ustr("Negative"), ustr("Zero"));
-    sgn.add_ob_generator(x);
-    sgn.add_mor_generator(n, x, x);
-    sgn.add_mor_generator(z, x, x);
-    sgn.set_composite(n, n, FinMor::Id(x));
-    sgn.set_composite(z, z, FinMor::Generator(z));
-    sgn.set_composite(n, z, FinMor::Generator(z));
-    sgn.set_composite(z, n, FinMor::Generator(z));
-    DiscreteDblTheory::from(sgn)
+    theory! {
+        ob_types: [Object],
+        mor_types: [Negative: Object -> Object, Zero: Object -> Object],
+        equations: [
+            Negative; Negative = id(Object),
+            Zero; Zero = Zero,
+            Negative; Zero = Zero,
+            Zero; Negative = Zero,
+        ],
+    }
+    .expect("Theory of nullable signed categories should be well defined")
+}
+
+/** The theory of categories with symmetric links.
+
+A link is an undirected relation between objects, represented in a model by a
+pair of morphisms in opposite directions of the
+[symmetric](Directionality::Symmetric) morphism type `Link`. Any composite
+involving a link is again a link.
+ */
+pub fn th_symmetric_links() -> UstrDiscreteDblTheory {
+    let mut th = theory! {
+        ob_types: [Object],
+        mor_types: [Link: Object -> Object],
+        equations: [Link; Link = Link],
+    }
+    .expect("Theory of symmetric links should be well defined");
+    th.set_directionality(ustr("Link").into(), Directionality::Symmetric)
+        .expect("Link type should be an endomorphism");
+    th
 }
 
 /** The theory of categories with links.
@@ -79,8 +104,10 @@ pub fn th_category_links() -> UstrDiscreteTabTheory {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
-    use crate::validate::Validate;
+    use crate::prelude::{Category, FgCategory, FinMor, TheoryKind, Validate};
 
     #[test]
     fn theories() {
@@ -88,7 +115,111 @@ mod tests {
         assert!(th_schema().validate().is_ok());
         assert!(th_signed_category().validate().is_ok());
         assert!(th_nullable_signed_category().validate().is_ok());
+        assert!(th_symmetric_links().validate().is_ok());
         // TODO: Validate discrete tabulator theories.
         th_category_links();
     }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(th_schema().kind(), TheoryKind::Discrete);
+        assert_eq!(th_category_links().kind(), TheoryKind::DiscreteTab);
+    }
+
+    #[test]
+    fn underlying_category() {
+        let th = th_schema();
+        assert_eq!(th.category().object_generators().count(), 2);
+    }
+
+    #[test]
+    fn sinks_and_sources() {
+        let th = th_schema();
+        assert_eq!(th.category().sink_objects().collect::<Vec<_>>(), vec![ustr("AttrType")]);
+        assert_eq!(th.category().source_objects().collect::<Vec<_>>(), vec![ustr("Entity")]);
+
+        // Every object of a signed category has a loop, so none is a sink.
+        assert_eq!(th_signed_category().category().sink_objects().count(), 0);
+    }
+
+    #[test]
+    fn composite_usages() {
+        let th = th_signed_category();
+        let neg = ustr("Negative");
+        let usages: Vec<_> = th.composites_involving(&neg).collect();
+        assert_eq!(usages, vec![((neg, neg), FinMor::Id(ustr("Object")))]);
+
+        // The pair mentions `Negative` twice as a key and never as a value.
+        let ((d, e), f) = &usages[0];
+        assert_eq!([d, e].into_iter().filter(|g| **g == neg).count(), 2);
+        assert_ne!(*f, FinMor::Generator(neg));
+    }
+
+    #[test]
+    fn full_subcategories() {
+        let th = th_schema();
+        let sub = th.category().full_subcategory(&HashSet::from([ustr("Entity")]).into());
+        assert_eq!(sub.object_generators().collect::<Vec<_>>(), vec![ustr("Entity")]);
+        assert_eq!(sub.morphism_generators().count(), 0);
+
+        let th = th_signed_category();
+        let sub = th.category().full_subcategory(&HashSet::from([ustr("Object")]).into());
+        let neg = FinMor::Generator(ustr("Negative"));
+        assert_eq!(sub.compose2(neg.clone(), neg), FinMor::Id(ustr("Object")));
+    }
+
+    #[test]
+    fn deterministic_order() {
+        let th = th_schema();
+        let obs: Vec<_> = th.category().object_generators().collect();
+        assert_eq!(obs, vec![ustr("Entity"), ustr("AttrType")]);
+        assert_eq!(th.category().object_generators().collect::<Vec<_>>(), obs);
+
+        // Each theory has its own hash state, so compare independent instances.
+        let describe = |th: UstrDiscreteDblTheory| {
+            let cat = th.category();
+            let gens: Vec<_> = cat.object_generators().chain(cat.morphism_generators()).collect();
+            gens.iter().map(|x| x.as_str()).collect::<Vec<_>>().join(",")
+        };
+        let desc = describe(th_nullable_signed_category());
+        assert_eq!(desc, "Object,Negative,Zero");
+        assert!((0..10).all(|_| describe(th_nullable_signed_category()) == desc));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn byte_stable_serialization() {
+        use crate::prelude::{Directionality, UstrFinCategory};
+
+        // Build the same theory with generators and composites inserted in the
+        // opposite order, and with extra types symmetric and then directed again.
+        let (object, negative, zero) = (ustr("Object"), ustr("Negative"), ustr("Zero"));
+        let mut cat = UstrFinCategory::default();
+        cat.add_ob_generator(object);
+        cat.add_mor_generator(zero, object, object);
+        cat.add_mor_generator(negative, object, object);
+        cat.set_composite(zero, negative, FinMor::Generator(zero));
+        cat.set_composite(negative, zero, FinMor::Generator(zero));
+        cat.set_composite(zero, zero, FinMor::Generator(zero));
+        cat.set_composite(negative, negative, FinMor::Id(object));
+        let mut reversed = UstrDiscreteDblTheory::from(cat);
+        for (m, dir) in [
+            (negative, Directionality::Symmetric),
+            (zero, Directionality::Symmetric),
+            (negative, Directionality::Directed),
+        ] {
+            reversed.set_directionality(FinMor::Generator(m), dir).unwrap();
+        }
+        reversed.set_cross_cell_warnings(true);
+
+        let mut th = th_nullable_signed_category();
+        th.set_directionality(FinMor::Generator(zero), Directionality::Symmetric)
+            .unwrap();
+        th.set_cross_cell_warnings(true);
+
+        let bytes = serde_json::to_vec(&th).unwrap();
+        assert_eq!(serde_json::to_vec(&reversed).unwrap(), bytes);
+        let de: UstrDiscreteDblTheory = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(serde_json::to_vec(&de).unwrap(), bytes);
+    }
 }
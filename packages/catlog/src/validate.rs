@@ -6,7 +6,10 @@ use case of compositional validation in a library (rather than an application),
 the validation error type is generic, not string-based.
  */
 
+use std::ops::Deref;
+
 use nonempty::NonEmpty;
+use ref_cast::{ref_cast_custom, RefCastCustom};
 
 /** An object that can validate itself.
 
@@ -37,6 +40,22 @@ pub fn wrap_errors<Error>(iter: impl Iterator<Item = Error>) -> Result<(), NonEm
     }
 }
 
+/** A validation error in an item of a batch.
+
+Operations that add many items at once, such as
+[`add_obs`](crate::dbl::model::DiscreteDblModel::add_obs), validate the whole
+batch before changing anything and report each error together with the
+position of the offending item in the input.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchError<Error> {
+    /// Position of the item in the batch.
+    pub index: usize,
+
+    /// The validation error.
+    pub error: Error,
+}
+
 /**  Unwrap `Result` with validation errors into a list of errors.
  */
 pub fn unwrap_errors<Error>(result: Result<(), NonEmpty<Error>>) -> Vec<Error> {
@@ -45,3 +64,84 @@ pub fn unwrap_errors<Error>(result: Result<(), NonEmpty<Error>>) -> Vec<Error> {
         Err(errs) => errs.into(),
     }
 }
+
+/** An object that is known to be valid.
+
+A value of this type can only be obtained by successfully validating an object,
+so functions that assume validity, such as analyses of models, can take a
+`Checked` value to have the discipline enforced by the type system. Read access
+to the object is through [`Deref`]. To mutate the object, it must first be
+[invalidated](Checked::invalidate) and then validated again.
+
+A model must be checked before it is analyzed or exported:
+
+```
+use std::sync::Arc;
+use catlog::prelude::*;
+use catlog::stdlib::{analyses::signed::signed_adjacency_matrix, theories};
+
+let model: UstrDiscreteDblModel =
+    DiscreteDblModel::new(Arc::new(theories::th_signed_category()));
+let model = Checked::new(model).unwrap();
+assert!(signed_adjacency_matrix(&model).is_ok());
+let json = catlog::export::quiver::export_model_quiver(&model, &Default::default());
+assert_eq!(json, "[0,0]");
+```
+
+An unvalidated model cannot be passed in place of a checked one:
+
+```compile_fail,E0308
+use std::sync::Arc;
+use catlog::prelude::*;
+use catlog::stdlib::{analyses::signed::signed_adjacency_matrix, theories};
+
+let model: UstrDiscreteDblModel =
+    DiscreteDblModel::new(Arc::new(theories::th_signed_category()));
+let _ = signed_adjacency_matrix(&model);
+```
+
+```compile_fail,E0308
+use std::sync::Arc;
+use catlog::prelude::*;
+use catlog::stdlib::theories;
+
+let model: UstrDiscreteDblModel =
+    DiscreteDblModel::new(Arc::new(theories::th_signed_category()));
+catlog::export::quiver::export_model_quiver(&model, &Default::default());
+```
+ */
+#[derive(Clone, Debug, PartialEq, Eq, RefCastCustom)]
+#[repr(transparent)]
+pub struct Checked<T>(T);
+
+impl<T: Validate> Checked<T> {
+    /// Validates the object, returning it as checked if it is valid.
+    pub fn new(x: T) -> Result<Self, NonEmpty<T::ValidationError>> {
+        x.validate()?;
+        Ok(Checked(x))
+    }
+
+    /// Validates a borrowed object, returning a checked view of it if valid.
+    pub fn check_ref(x: &T) -> Result<&Self, NonEmpty<T::ValidationError>> {
+        x.validate()?;
+        Ok(Self::ref_cast(x))
+    }
+
+    #[ref_cast_custom]
+    fn ref_cast(x: &T) -> &Self;
+}
+
+impl<T> Checked<T> {
+    /// Gives up the guarantee of validity, returning the object for mutation.
+    pub fn invalidate(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Checked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
@@ -1,5 +1,6 @@
 //! Data structures for mappings and columns, as found in data tables.
 
+use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::hash::{BuildHasher, BuildHasherDefault, Hash, RandomState};
 use std::marker::PhantomData;
@@ -154,6 +155,82 @@ impl<T> InvalidFunction<T> {
     }
 }
 
+/** Classification of a mapping as injective and/or surjective.
+
+Produced by [`classify`]. Each property that fails to hold comes with a witness
+to its failure.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapClassification<X, Y> {
+    /// Pair of distinct points in the domain with the same image, if any.
+    pub collision: Option<(X, X)>,
+
+    /// Point in the codomain not in the image, if any.
+    pub missed: Option<Y>,
+}
+
+impl<X, Y> MapClassification<X, Y> {
+    /// Is the mapping injective?
+    pub fn is_injective(&self) -> bool {
+        self.collision.is_none()
+    }
+
+    /// Is the mapping surjective?
+    pub fn is_surjective(&self) -> bool {
+        self.missed.is_none()
+    }
+
+    /// Is the mapping bijective?
+    pub fn is_bijective(&self) -> bool {
+        self.is_injective() && self.is_surjective()
+    }
+}
+
+/** Classifies a mapping restricted to a domain as injective and/or surjective.
+
+Surjectivity is relative to the given codomain. Points of the domain at which
+the mapping is undefined are ignored, so the mapping should first be
+[validated](Function) if that is in question.
+
+The witnesses are chosen deterministically given the iteration orders of the
+domain and codomain, as is the case for [skeletal](super::SkelFinSet) sets: the
+collision is the first point in the domain whose image is that of an earlier
+point, paired with that earlier point, and the missed point is the first point
+in the codomain not in the image.
+ */
+pub fn classify<Map, Dom, Cod>(
+    mapping: &Map,
+    dom: &Dom,
+    cod: &Cod,
+) -> MapClassification<Map::Dom, Map::Cod>
+where
+    Map: Mapping,
+    Map::Dom: Clone,
+    Map::Cod: Hash + Clone,
+    Dom: FinSet<Elem = Map::Dom>,
+    Cod: FinSet<Elem = Map::Cod>,
+{
+    let mut preimages: HashMap<Map::Cod, Map::Dom> = HashMap::new();
+    let mut collision = None;
+    for x in dom.iter() {
+        let Some(y) = mapping.apply(&x) else {
+            continue;
+        };
+        match preimages.get(y) {
+            Some(prev) => {
+                if collision.is_none() {
+                    collision = Some((prev.clone(), x));
+                }
+            }
+            None => {
+                preimages.insert(y.clone(), x);
+            }
+        }
+    }
+    let missed = cod.iter().find(|y| !preimages.contains_key(y));
+    MapClassification { collision, missed }
+}
+
 /** An unindexed column backed by a vector.
  */
 #[derive(Clone, Debug, Derivative)]
@@ -227,6 +304,45 @@ pub struct HashColumn<K, V, S = RandomState>(HashMap<K, V, S>);
 /// An unindexed column with keys of type `Ustr`.
 pub type UstrColumn<V> = HashColumn<Ustr, V, BuildHasherDefault<IdentityHasher>>;
 
+impl<K, V, S> HashColumn<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Reserves capacity for at least `additional` more keys.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    /** Gets the value at a key, looking it up by any borrowed form of the key.
+
+    Same as [`apply`](Mapping::apply) but avoids constructing an owned key,
+    like [`HashMap::get`].
+     */
+    pub fn get<Q>(&self, x: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.0.get(x)
+    }
+
+    /// Sets the column at each key-value pair, overwriting existing values.
+    pub fn set_all<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for HashColumn<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(HashMap::from_iter(iter))
+    }
+}
+
 impl<K, V, S> Mapping for HashColumn<K, V, S>
 where
     K: Eq + Hash,
@@ -569,6 +685,21 @@ pub struct IndexedHashColumn<K, V, S = RandomState>(
 #[allow(clippy::type_complexity)]
 pub type IndexedUstrColumn = IndexedHashColumn<Ustr, Ustr, BuildHasherDefault<IdentityHasher>>;
 
+impl<K, V, S> IndexedHashColumn<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /** Reserves capacity for at least `additional` more keys.
+
+    Only the forward mapping is reserved, since the number of distinct values
+    is not known in advance.
+     */
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.mapping.reserve(additional)
+    }
+}
+
 impl<K, V, S> Mapping for IndexedHashColumn<K, V, S>
 where
     K: Eq + Hash + Clone,
@@ -650,6 +781,19 @@ mod tests {
         assert_eq!(preimage, vec!['b', 'c']);
     }
 
+    #[test]
+    fn hash_column_from_iter() {
+        let mut col: HashColumn<char, &str> = [('a', "foo"), ('b', "bar")].into_iter().collect();
+        assert_eq!(col.apply(&'a'), Some(&"foo"));
+        assert_eq!(col.apply(&'b'), Some(&"bar"));
+        assert_eq!(col.apply(&'c'), None);
+
+        col.set_all([('b', "baz"), ('c', "qux")]);
+        assert_eq!(col.apply(&'a'), Some(&"foo"));
+        assert_eq!(col.apply(&'b'), Some(&"baz"));
+        assert_eq!(col.apply(&'c'), Some(&"qux"));
+    }
+
     #[test]
     fn skel_indexed_column() {
         let mut col = SkelIndexedColumn::new(&[1, 3, 5]);
@@ -709,4 +853,16 @@ mod tests {
         assert_eq!(validate(4, 5).unwrap_err(), NonEmpty::new(InvalidFunction::Dom::<usize>(3)));
         assert_eq!(validate(3, 4).unwrap_err(), NonEmpty::new(InvalidFunction::Cod::<usize>(2)));
     }
+
+    #[test]
+    fn classify_mapping() {
+        let col = VecColumn::new(vec![1, 0, 2, 0, 2]);
+        let classified = classify(&col, &SkelFinSet::from(5), &SkelFinSet::from(4));
+        assert_eq!(classified.collision, Some((1, 3)));
+        assert_eq!(classified.missed, Some(3));
+        assert!(!classified.is_injective() && !classified.is_surjective());
+
+        let classified = classify(&col, &SkelFinSet::from(3), &SkelFinSet::from(3));
+        assert!(classified.is_bijective());
+    }
 }
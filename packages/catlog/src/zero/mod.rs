@@ -1,6 +1,8 @@
 //! Sets and functions, aka zero-dimensional category theory.
 
 pub mod column;
+// Experimental and not yet part of the stable surface.
+#[doc(hidden)]
 pub mod directories;
 pub mod set;
 
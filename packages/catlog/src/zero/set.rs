@@ -6,7 +6,8 @@ treated in a generic way.
 
 use std::collections::HashSet;
 use std::hash::{BuildHasher, BuildHasherDefault, Hash, RandomState};
-use std::ops::Range;
+use std::marker::PhantomData;
+use std::ops::{Range, RangeInclusive};
 
 use derivative::Derivative;
 use derive_more::{From, Into};
@@ -57,6 +58,88 @@ pub trait FinSet: Set {
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /** Iterates over the `n`-fold cartesian power of the finite set.
+
+    Yields all tuples of length `n`, with repetition, in lexicographic order
+    with respect to the order of iteration of the set. The zeroth power has a
+    single element, the empty tuple.
+    */
+    fn power(&self, n: usize) -> impl Iterator<Item = Vec<Self::Elem>>
+    where
+        Self::Elem: Clone,
+    {
+        let elems: Vec<_> = self.iter().collect();
+        let size = elems.len();
+        let mut index = if n > 0 && size == 0 {
+            None
+        } else {
+            Some(vec![0; n])
+        };
+        std::iter::from_fn(move || {
+            let current = index.as_mut()?;
+            let tuple = current.iter().map(|i| elems[*i].clone()).collect();
+            // Advance the index like an odometer, stopping after the last tuple.
+            match current.iter().rposition(|i| *i + 1 < size) {
+                Some(k) => {
+                    current[k] += 1;
+                    current[k + 1..].fill(0);
+                }
+                None => index = None,
+            }
+            Some(tuple)
+        })
+    }
+
+    /** Iterates over the elements of the set not belonging to another set.
+
+    The elements are yielded in the order of iteration of this set. Nothing is
+    iterated when this set is empty, and membership in the other set is no
+    longer checked once as many elements have been excluded as it has, since no
+    further element can then belong to it. Thus, removing a small set from a
+    large one costs little more than iterating over the large one.
+    */
+    fn difference_iter<'a>(
+        &'a self,
+        other: &'a impl FinSet<Elem = Self::Elem>,
+    ) -> impl Iterator<Item = Self::Elem> + 'a {
+        let mut remaining = other.len();
+        let elems = (!self.is_empty()).then(|| self.iter()).into_iter().flatten();
+        elems.filter(move |x| {
+            if remaining == 0 || !other.contains(x) {
+                return true;
+            }
+            remaining -= 1;
+            false
+        })
+    }
+
+    /// Iterates over all ordered pairs of elements of the finite set.
+    fn pairs(&self) -> impl Iterator<Item = (Self::Elem, Self::Elem)>
+    where
+        Self::Elem: Clone,
+    {
+        self.power(2).map(|pair| {
+            let [x, y]: [Self::Elem; 2] = pair.try_into().ok().expect("Pair should have length 2");
+            (x, y)
+        })
+    }
+}
+
+/** A set that may or may not be finite.
+
+Generic code that accepts both finite and infinite sets can use this trait to
+query finiteness at runtime. Every [finite set](FinSet) is of this kind.
+ */
+pub trait MaybeFinite: Set {
+    /// The size of the set, if it is finite.
+    fn try_len(&self) -> Option<usize>;
+}
+
+impl<S: FinSet> MaybeFinite for S {
+    fn try_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
 }
 
 /** A skeletal finite set.
@@ -135,6 +218,11 @@ where
     pub fn insert(&mut self, x: T) -> bool {
         self.0.insert(x)
     }
+
+    /// Retains only the elements satisfying the predicate.
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.0.retain(f)
+    }
 }
 
 impl<T, S> Extend<T> for HashFinSet<T, S>
@@ -191,6 +279,140 @@ where
     }
 }
 
+impl<'a, T, S> IntoIterator for &'a HashFinSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = std::collections::hash_set::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/** A finite set backed by a hash set that remembers insertion order.
+
+Membership is checked using a hash set, as in [`HashFinSet`], but iteration
+yields the elements in the order in which they were first inserted. Thus, unlike
+with a hash set, iteration is deterministic. Two such sets are equal when they
+have the same elements, regardless of order.
+ */
+#[derive(Clone, Debug, Derivative)]
+#[derivative(Default(bound = "S: Default"))]
+pub struct OrderedHashFinSet<T, S = RandomState> {
+    elems: Vec<T>,
+    set: HashSet<T, S>,
+}
+
+impl<T, S> OrderedHashFinSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    /// Adds an element to the set, returning whether it is new.
+    pub fn insert(&mut self, x: T) -> bool {
+        let new = self.set.insert(x.clone());
+        if new {
+            self.elems.push(x);
+        }
+        new
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        self.elems.reserve(additional);
+        self.set.reserve(additional);
+    }
+
+    /// Removes an element from the set, returning whether it was present.
+    pub fn remove(&mut self, x: &T) -> bool {
+        let present = self.set.remove(x);
+        if present {
+            self.elems.retain(|y| y != x);
+        }
+        present
+    }
+
+    /// Retains only the elements satisfying the predicate, preserving order.
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let set = &mut self.set;
+        self.elems.retain(|x| {
+            let keep = f(x);
+            if !keep {
+                set.remove(x);
+            }
+            keep
+        });
+    }
+}
+
+impl<T, S> PartialEq for OrderedHashFinSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.set == other.set
+    }
+}
+
+impl<T, S> Eq for OrderedHashFinSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+}
+
+impl<T, S> Extend<T> for OrderedHashFinSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    fn extend<Iter>(&mut self, iter: Iter)
+    where
+        Iter: IntoIterator<Item = T>,
+    {
+        for x in iter {
+            self.insert(x);
+        }
+    }
+}
+
+impl<T, S> Set for OrderedHashFinSet<T, S>
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    type Elem = T;
+
+    fn contains(&self, x: &T) -> bool {
+        self.set.contains(x)
+    }
+}
+
+impl<T, S> FinSet for OrderedHashFinSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    fn iter(&self) -> impl Iterator<Item = T> {
+        self.elems.iter().cloned()
+    }
+    fn len(&self) -> usize {
+        self.elems.len()
+    }
+    fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+}
+
+impl<T, S> IntoIterator for OrderedHashFinSet<T, S> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elems.into_iter()
+    }
+}
+
 /** A skeletal finite set with a data attribute.
 
 The internal representation is simply a vector.
@@ -245,10 +467,117 @@ impl<T> FinSet for AttributedSkelSet<T> {
     }
 }
 
+/// A range of natural numbers is a finite set.
+impl Set for Range<usize> {
+    type Elem = usize;
+
+    fn contains(&self, x: &usize) -> bool {
+        Range::contains(self, x)
+    }
+}
+
+impl FinSet for Range<usize> {
+    fn iter(&self) -> impl Iterator<Item = usize> {
+        self.clone()
+    }
+    fn len(&self) -> usize {
+        ExactSizeIterator::len(self)
+    }
+    fn is_empty(&self) -> bool {
+        Range::is_empty(self)
+    }
+}
+
+/** An inclusive range of natural numbers is a finite set.
+
+The range `0..=usize::MAX` has too many elements for its length to be a `usize`.
+ */
+impl Set for RangeInclusive<usize> {
+    type Elem = usize;
+
+    fn contains(&self, x: &usize) -> bool {
+        RangeInclusive::contains(self, x)
+    }
+}
+
+impl FinSet for RangeInclusive<usize> {
+    fn iter(&self) -> impl Iterator<Item = usize> {
+        self.clone()
+    }
+    fn len(&self) -> usize {
+        if RangeInclusive::is_empty(self) {
+            0
+        } else {
+            self.end() - self.start() + 1
+        }
+    }
+    fn is_empty(&self) -> bool {
+        RangeInclusive::is_empty(self)
+    }
+}
+
+/** A set defined by a predicate.
+
+The elements of the set are the values satisfying the predicate. Such a set is
+in general infinite, so it is not iterable.
+ */
+#[derive(Clone, Copy)]
+pub struct PredicateSet<T, F> {
+    predicate: F,
+    elem_type: PhantomData<T>,
+}
+
+impl<T, F> PredicateSet<T, F>
+where
+    F: Fn(&T) -> bool,
+{
+    /// Creates a set from a predicate on its element type.
+    pub fn new(predicate: F) -> Self {
+        Self {
+            predicate,
+            elem_type: PhantomData,
+        }
+    }
+}
+
+impl<T: Eq, F> Set for PredicateSet<T, F>
+where
+    F: Fn(&T) -> bool,
+{
+    type Elem = T;
+
+    fn contains(&self, x: &T) -> bool {
+        (self.predicate)(x)
+    }
+}
+
+impl<T: Eq, F> MaybeFinite for PredicateSet<T, F>
+where
+    F: Fn(&T) -> bool,
+{
+    fn try_len(&self) -> Option<usize> {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn difference() {
+        let small = HashFinSet::from(HashSet::from([1, 3, 7]));
+        let large = SkelFinSet::from(6);
+        assert_eq!(large.difference_iter(&small).collect::<Vec<_>>(), vec![0, 2, 4, 5]);
+        let mut rest: Vec<_> = small.difference_iter(&large).collect();
+        rest.sort();
+        assert_eq!(rest, vec![7]);
+
+        let empty = SkelFinSet::from(0);
+        assert_eq!(empty.difference_iter(&large).count(), 0);
+        assert_eq!(large.difference_iter(&empty).count(), 6);
+    }
+
     #[test]
     fn skel_fin_set() {
         let mut s: SkelFinSet = Default::default();
@@ -267,6 +596,15 @@ mod tests {
         assert_eq!(sum, 3);
         let elems: Vec<usize> = s.into_iter().collect();
         assert_eq!(elems, vec![0, 1, 2]);
+
+        let s = SkelFinSet::from(3);
+        let square: Vec<_> = s.power(2).collect();
+        assert_eq!(square.len(), 9);
+        assert_eq!(square[..4], [vec![0, 0], vec![0, 1], vec![0, 2], vec![1, 0]]);
+        assert_eq!(square.last(), Some(&vec![2, 2]));
+        assert_eq!(s.pairs().nth(5), Some((1, 2)));
+        assert_eq!(s.power(0).collect::<Vec<_>>(), vec![Vec::<usize>::new()]);
+        assert_eq!(SkelFinSet::from(0).power(2).count(), 0);
     }
 
     #[test]
@@ -285,6 +623,70 @@ mod tests {
         let sum: i32 = s.iter().sum();
         assert_eq!(sum, 15);
         assert_eq!(s.len(), 3);
+        let mut sum = 0;
+        for x in &s {
+            sum += x;
+        }
+        assert_eq!(sum, 15);
+        assert!(s.contains(&5));
+
+        let mut s = HashFinSet::from(HashSet::from([1, 2, 3, 4]));
+        s.retain(|x| x % 2 == 0);
+        assert_eq!(s, HashFinSet::from(HashSet::from([2, 4])));
+        assert_eq!(s.len(), 2);
+    }
+
+    #[test]
+    fn ordered_hash_fin_set() {
+        let mut s: OrderedHashFinSet<char> = Default::default();
+        assert!(s.is_empty());
+        assert!(s.insert('c'));
+        s.extend(['a', 'd', 'b', 'a']);
+        assert!(!s.insert('d'));
+        assert_eq!(s.len(), 4);
+        assert!(s.contains(&'b'));
+        assert_eq!(s.iter().collect::<String>(), "cadb");
+
+        s.retain(|x| *x != 'a');
+        assert!(!s.contains(&'a'));
+        assert_eq!(s.clone().into_iter().collect::<String>(), "cdb");
+
+        let mut t: OrderedHashFinSet<char> = Default::default();
+        t.extend(['b', 'c', 'd']);
+        assert_eq!(s, t);
+    }
+
+    #[test]
+    fn range_fin_set() {
+        let s = 2..5;
+        assert_eq!(FinSet::len(&s), 3);
+        assert!(!FinSet::is_empty(&s));
+        assert!(Set::contains(&s, &2));
+        assert!(!Set::contains(&s, &5));
+        assert_eq!(FinSet::iter(&s).collect::<Vec<_>>(), vec![2, 3, 4]);
+
+        let s = 3..3;
+        assert_eq!(FinSet::len(&s), 0);
+        assert!(FinSet::is_empty(&s));
+        assert!(!Set::contains(&s, &3));
+        assert_eq!(FinSet::iter(&s).count(), 0);
+    }
+
+    #[test]
+    fn range_inclusive_fin_set() {
+        let s = 2..=5;
+        assert_eq!(FinSet::len(&s), 4);
+        assert!(!FinSet::is_empty(&s));
+        assert!(Set::contains(&s, &5));
+        assert!(!Set::contains(&s, &6));
+        assert_eq!(FinSet::iter(&s).collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let s = 3..=2;
+        assert_eq!(FinSet::len(&s), 0);
+        assert!(FinSet::is_empty(&s));
+        assert!(!Set::contains(&s, &3));
+        assert_eq!(FinSet::iter(&s).count(), 0);
     }
 
     #[test]
@@ -299,4 +701,24 @@ mod tests {
         assert!(!s.contains(&3));
         assert_eq!(*s.view(1), 'b');
     }
+
+    #[test]
+    fn maybe_finite() {
+        fn describe<S: MaybeFinite>(s: &S) -> String {
+            match s.try_len() {
+                Some(n) => format!("{n} elements"),
+                None => "infinite".into(),
+            }
+        }
+
+        let s = HashFinSet::from(HashSet::from([1, 2, 3]));
+        assert_eq!(s.try_len(), Some(3));
+        assert_eq!(describe(&s), "3 elements");
+
+        let evens = PredicateSet::new(|n: &usize| n % 2 == 0);
+        assert!(evens.contains(&4));
+        assert!(!evens.contains(&5));
+        assert_eq!(evens.try_len(), None);
+        assert_eq!(describe(&evens), "infinite");
+    }
 }
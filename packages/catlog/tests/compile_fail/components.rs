@@ -0,0 +1,7 @@
+// The connected components of raw graphs are an implementation detail.
+use catlog::one::components::weakly_connected;
+
+fn main() {
+    let components = weakly_connected([1, 2, 3], [(1, 2)]);
+    assert_eq!(components.len(), 2);
+}
@@ -0,0 +1,6 @@
+// Derived caches are exported by the prelude, not by their module.
+use catlog::dbl::derived::DerivedCache;
+
+fn main() {
+    let _cache: DerivedCache<usize> = Default::default();
+}
@@ -0,0 +1,6 @@
+// Columns of generator data are exported by the prelude, not by their module.
+use catlog::dbl::generator_column::GeneratorColumn;
+
+fn main() {
+    let _column: GeneratorColumn<char, usize> = Default::default();
+}
@@ -0,0 +1,7 @@
+// Provenance is exported by the prelude, not by its module.
+use catlog::dbl::provenance::{AuthorId, ProvenanceColumn};
+
+fn main() {
+    let mut column: ProvenanceColumn<char> = Default::default();
+    column.record('x', AuthorId("alice".into()), 0);
+}
@@ -0,0 +1,43 @@
+/*! A consumer of the package in the style of the Wasm bindings.
+
+Like the bindings, it defines a theory, builds and validates a model of it while
+tracking provenance, and renders the validation errors, importing nothing from
+the package except its prelude.
+ */
+
+use std::sync::Arc;
+
+use catlog::prelude::*;
+
+fn main() {
+    let category: UstrFinCategory = FinCategoryBuilder::new()
+        .ob("Entity")
+        .ob("AttrType")
+        .hom("Attr", "Entity", "AttrType")
+        .build()
+        .unwrap();
+    let theory = Arc::new(DiscreteDblTheory::from(category));
+    assert_eq!(theory.kind(), TheoryKind::Discrete);
+    assert_eq!(theory.describe().kind, TheoryKind::Discrete);
+
+    let mut model: UstrDiscreteDblModel = DiscreteDblModel::new(theory);
+    model.add_ob("person".into(), "Entity".into());
+    model.add_ob("name".into(), "AttrType".into());
+    model.add_mor(
+        "has_name".into(),
+        "person".into(),
+        "name".into(),
+        FinMor::Generator("Attr".into()),
+    );
+    model.add_mor("bad".into(), "name".into(), "person".into(), FinMor::Generator("Attr".into()));
+    model.provenance_mut().record("person".into(), AuthorId("alice".into()), 0);
+
+    let errors: Vec<_> = model.validate().unwrap_err().into();
+    assert_eq!(errors.len(), 2);
+    let rendered = render_errors(errors, |id| Some(id.to_string()));
+    assert!(rendered.contains("bad"));
+
+    let mut confidence: ConfidenceColumn<&str> = Default::default();
+    confidence.insert("has_name", Confidence::new(0.5).unwrap());
+    assert!(model.provenance().get(&"person".into()).is_some());
+}
@@ -0,0 +1,8 @@
+//! Tests that the internals of the package are hidden behind its prelude.
+
+#[test]
+fn visibility() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+    t.pass("tests/pass/*.rs");
+}